@@ -0,0 +1,3 @@
+go 1.21
+
+require github.com/pkg/errors v0.9.1
@@ -0,0 +1,34 @@
+// Controller for the example.com custom resources.
+module example.com/platform/operator
+
+go 1.22.1
+
+toolchain go1.22.3
+
+require (
+	github.com/go-logr/logr v1.4.1
+	github.com/prometheus/client_golang v1.19.0
+	k8s.io/api v0.29.2
+	k8s.io/apimachinery v0.29.2
+	k8s.io/client-go v0.29.2
+	sigs.k8s.io/controller-runtime v0.17.2
+)
+
+require (
+	github.com/davecgh/go-spew v1.1.2-0.20180830191138-d8f796af33cc // indirect
+	github.com/docker/distribution v2.8.3+incompatible // indirect
+	github.com/gogo/protobuf v1.3.2 // indirect
+	golang.org/x/net v0.21.0 // indirect
+)
+
+replace (
+	example.com/platform/api => ../api
+	github.com/docker/distribution v2.8.3+incompatible => github.com/distribution/distribution/v3 v3.0.0-alpha.1
+)
+
+exclude k8s.io/client-go v1.5.2
+
+retract (
+	v0.3.0 // published with a broken CRD schema
+	[v0.1.0, v0.1.9]
+)
@@ -0,0 +1,9 @@
+module example.com/shop/payments
+
+go 1.20
+
+require example.com/shop/common v0.0.0-00010101000000-000000000000
+
+require github.com/google/uuid v1.6.0
+
+replace example.com/shop/common => ../common
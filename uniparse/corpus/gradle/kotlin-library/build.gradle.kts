@@ -0,0 +1,22 @@
+plugins {
+    kotlin("jvm") version "1.9.22"
+    `java-library`
+    `maven-publish`
+}
+
+group = "com.example.telemetry"
+version = "2.3.1"
+
+repositories {
+    mavenCentral()
+}
+
+dependencies {
+    api("io.opentelemetry:opentelemetry-api:1.35.0")
+    implementation("org.jetbrains.kotlinx:kotlinx-coroutines-core:1.8.0")
+    testImplementation(kotlin("test"))
+}
+
+kotlin {
+    jvmToolchain(17)
+}
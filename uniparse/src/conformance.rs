@@ -0,0 +1,124 @@
+use serde_json::{Value, json};
+use std::io;
+use std::path::Path;
+use uniparse_core::{Corpus, CorpusReport, Diagnostic, DiagnosticCode};
+
+use crate::facade::{AnyManifest, Format, ParseError, parse_named};
+
+/// The corpus of anonymized real-world manifests shipped with this crate:
+/// `go.mod` files, Groovy and Kotlin Gradle scripts and `build.zig.zon`
+/// files, each next to its golden `<file>.json`.
+pub fn corpus_dir() -> &'static Path {
+    Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/corpus"))
+}
+
+/// Parses every manifest below `root` the way
+/// [`parse_file_lenient`](crate::parse_file_lenient) would and compares the
+/// result, rendered by [`golden_json`] with the statements the lenient
+/// parser left out under `"skipped"`, with its golden file. Files of
+/// unknown or disabled formats are skipped.
+///
+/// Set `UNIPARSE_BLESS=1` to write the golden files instead, then review
+/// the diff before committing it.
+///
+/// ```rust
+/// uniparse::conformance::run_corpus(uniparse::conformance::corpus_dir())
+///     .unwrap()
+///     .assert_ok();
+/// ```
+///
+/// # Errors
+/// Returns an IO error if the corpus can't be read.
+pub fn run_corpus(root: impl AsRef<Path>) -> io::Result<CorpusReport> {
+    Corpus::new(root.as_ref()).run(|path, src| {
+        let format = Format::detect(path).filter(|f| f.is_enabled())?;
+        let golden = match parse_named(format, path, src) {
+            Ok((manifest, skipped)) => render(format, Ok(&manifest), &skipped),
+            Err(e) => render(format, Err(&e), &[]),
+        };
+        Some(golden)
+    })
+}
+
+/// The golden output for a parse: the format and either the model as
+/// JSON or the error code and message. Object keys are sorted, so the
+/// output doesn't depend on hash map order.
+pub fn golden_json(format: Format, parsed: &Result<AnyManifest, ParseError>) -> String {
+    render(format, parsed.as_ref(), &[])
+}
+
+fn render(
+    format: Format,
+    parsed: Result<&AnyManifest, &ParseError>,
+    skipped: &[Diagnostic],
+) -> String {
+    let mut value = match parsed {
+        Ok(manifest) => json!({ "format": format, "manifest": manifest_json(manifest) }),
+        Err(e) => json!({
+            "format": format,
+            "error": { "code": e.code(), "message": e.to_string() },
+        }),
+    };
+    if !skipped.is_empty() {
+        value["skipped"] = skipped
+            .iter()
+            .map(|d| json!({ "code": d.code, "message": d.message }))
+            .collect();
+    }
+    let mut out = serde_json::to_string_pretty(&value).expect("JSON values always serialize");
+    out.push('\n');
+    out
+}
+
+fn manifest_json(manifest: &AnyManifest) -> Value {
+    match *manifest {
+        #[cfg(feature = "go")]
+        AnyManifest::GoMod(ref gomod) => {
+            serde_json::to_value(gomod).expect("manifest models always serialize")
+        }
+        #[cfg(feature = "go")]
+        AnyManifest::GoWork(ref work) => {
+            serde_json::to_value(work).expect("manifest models always serialize")
+        }
+        #[cfg(feature = "go")]
+        AnyManifest::GoSum(ref sum) => {
+            serde_json::to_value(sum).expect("manifest models always serialize")
+        }
+        #[cfg(feature = "gradle")]
+        AnyManifest::Gradle(ref block) => {
+            serde_json::to_value(block).expect("manifest models always serialize")
+        }
+        #[cfg(feature = "zon")]
+        AnyManifest::Zon(ref zon) => {
+            serde_json::to_value(zon).expect("manifest models always serialize")
+        }
+    }
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(all(test, feature = "go"))]
+mod tests {
+    use super::*;
+    use crate::parse_str;
+
+    #[cfg(all(feature = "gradle", feature = "zon"))]
+    #[test]
+    fn test_shipped_corpus_conforms() {
+        let report = run_corpus(corpus_dir()).unwrap();
+        report.assert_ok();
+        assert!(report.passed.len() + report.blessed.len() >= 8);
+    }
+
+    #[test]
+    fn test_golden_json_of_errors() {
+        let err = parse_str(Format::GoMod, "go 1.22");
+        let golden = golden_json(Format::GoMod, &err);
+        let value: Value = serde_json::from_str(&golden).unwrap();
+        assert_eq!(value["format"], "GoMod");
+        assert_eq!(value["error"]["code"], "GOMOD003");
+        assert!(golden.ends_with("}\n"));
+    }
+}
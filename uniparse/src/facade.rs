@@ -0,0 +1,391 @@
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use uniparse_core::{Capabilities, Diagnostic, DiagnosticCode};
+
+/// A manifest format known to `uniparse`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub enum Format {
+    /// Go `go.mod`.
+    GoMod,
+    /// Go workspace `go.work`.
+    GoWork,
+    /// Go checksums `go.sum`.
+    GoSum,
+    /// Gradle build or settings script (Groovy or Kotlin DSL).
+    Gradle,
+    /// Zig `.zon` file, e.g. `build.zig.zon`.
+    Zon,
+}
+
+impl Format {
+    /// Detects the format from a file name.
+    ///
+    /// Detection works for every format, even ones whose feature is disabled;
+    /// parsing such a file returns [`ParseError::Disabled`].
+    pub fn detect(path: impl AsRef<Path>) -> Option<Self> {
+        let name = path.as_ref().file_name()?.to_str()?;
+
+        if name == "go.mod" {
+            Some(Format::GoMod)
+        } else if name == "go.work" {
+            Some(Format::GoWork)
+        } else if name == "go.sum" {
+            Some(Format::GoSum)
+        } else if name.ends_with(".gradle") || name.ends_with(".gradle.kts") {
+            Some(Format::Gradle)
+        } else if name.ends_with(".zon") {
+            Some(Format::Zon)
+        } else {
+            None
+        }
+    }
+
+    /// Whether support for this format was compiled in.
+    pub fn is_enabled(self) -> bool {
+        match self {
+            Format::GoMod | Format::GoWork | Format::GoSum => cfg!(feature = "go"),
+            Format::Gradle => cfg!(feature = "gradle"),
+            Format::Zon => cfg!(feature = "zon"),
+        }
+    }
+
+    /// What the format's crate offers beyond parsing; nothing for formats
+    /// whose feature is disabled, nor for `go.work` and `go.sum`, which are
+    /// only parsed into their models.
+    pub fn capabilities(self) -> Capabilities {
+        match self {
+            #[cfg(feature = "go")]
+            Format::GoMod => uniparse_go::GoMod::CAPABILITIES,
+            Format::GoWork | Format::GoSum => Capabilities::NONE,
+            #[cfg(feature = "gradle")]
+            Format::Gradle => {
+                <uniparse_gradle::DSLBlock as uniparse_core::ParsedFile>::CAPABILITIES
+            }
+            #[cfg(feature = "zon")]
+            Format::Zon => <uniparse_zon::ZonFile as uniparse_core::ParsedFile>::CAPABILITIES,
+            #[allow(unreachable_patterns)]
+            _ => Capabilities::NONE,
+        }
+    }
+}
+
+/// A parsed manifest of any enabled format.
+#[derive(Debug, Clone)]
+pub enum AnyManifest {
+    #[cfg(feature = "go")]
+    GoMod(uniparse_go::GoMod),
+    #[cfg(feature = "go")]
+    GoWork(uniparse_go::GoWork),
+    #[cfg(feature = "go")]
+    GoSum(uniparse_go::GoSum),
+    #[cfg(feature = "gradle")]
+    Gradle(uniparse_gradle::DSLBlock),
+    #[cfg(feature = "zon")]
+    Zon(uniparse_zon::ZonFile),
+}
+
+impl AnyManifest {
+    pub fn format(&self) -> Format {
+        match *self {
+            #[cfg(feature = "go")]
+            AnyManifest::GoMod(_) => Format::GoMod,
+            #[cfg(feature = "go")]
+            AnyManifest::GoWork(_) => Format::GoWork,
+            #[cfg(feature = "go")]
+            AnyManifest::GoSum(_) => Format::GoSum,
+            #[cfg(feature = "gradle")]
+            AnyManifest::Gradle(_) => Format::Gradle,
+            #[cfg(feature = "zon")]
+            AnyManifest::Zon(_) => Format::Zon,
+        }
+    }
+
+    pub fn capabilities(&self) -> Capabilities {
+        self.format().capabilities()
+    }
+}
+
+/// Errors returned by the facade.
+#[derive(Debug, thiserror::Error)]
+pub enum ParseError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The file name doesn't match any known format.
+    #[error("Unrecognized manifest file: {0}")]
+    UnknownFormat(PathBuf),
+
+    /// The format was detected but its cargo feature is disabled.
+    #[error("Support for {0:?} is disabled; enable the matching uniparse feature")]
+    Disabled(Format),
+
+    #[cfg(feature = "go")]
+    #[error(transparent)]
+    Go(#[from] uniparse_go::ParseError),
+
+    #[cfg(feature = "gradle")]
+    #[error(transparent)]
+    Gradle(#[from] uniparse_gradle::ParseError),
+
+    #[cfg(feature = "zon")]
+    #[error(transparent)]
+    Zon(#[from] uniparse_zon::ParseError),
+}
+
+impl DiagnosticCode for ParseError {
+    fn code(&self) -> &'static str {
+        match self {
+            ParseError::Io(_) => "UNIPARSE001",
+            ParseError::UnknownFormat(_) => "UNIPARSE002",
+            ParseError::Disabled(_) => "UNIPARSE003",
+            #[cfg(feature = "go")]
+            ParseError::Go(e) => e.code(),
+            #[cfg(feature = "gradle")]
+            ParseError::Gradle(e) => e.code(),
+            #[cfg(feature = "zon")]
+            ParseError::Zon(e) => e.code(),
+        }
+    }
+}
+
+/// Parses `src` as the given format.
+///
+/// Gradle scripts go through the strict parser; see [`parse_str_lenient`]
+/// for Kotlin DSL scripts it rejects.
+pub fn parse_str(format: Format, src: &str) -> Result<AnyManifest, ParseError> {
+    match format {
+        #[cfg(feature = "go")]
+        Format::GoMod => Ok(AnyManifest::GoMod(uniparse_go::GoMod::parse_str(src)?)),
+        #[cfg(feature = "go")]
+        Format::GoWork => Ok(AnyManifest::GoWork(uniparse_go::GoWork::parse_str(src)?)),
+        #[cfg(feature = "go")]
+        Format::GoSum => Ok(AnyManifest::GoSum(uniparse_go::GoSum::parse_str(src)?)),
+        #[cfg(feature = "gradle")]
+        Format::Gradle => Ok(AnyManifest::Gradle(uniparse_gradle::DSLBlock::parse_str(
+            src,
+        )?)),
+        #[cfg(feature = "zon")]
+        Format::Zon => Ok(AnyManifest::Zon(uniparse_zon::ZonFile::parse_str(src)?)),
+        #[allow(unreachable_patterns)]
+        disabled => {
+            let _ = src;
+            Err(ParseError::Disabled(disabled))
+        }
+    }
+}
+
+/// Parses like [`parse_str`], except that a Gradle script that doesn't
+/// parse but looks like Kotlin DSL (see
+/// [`detect_dialect`](uniparse_gradle::detect_dialect)) is parsed
+/// leniently: statements the model can't represent, such as `val`
+/// declarations, are left out and each one is returned as a diagnostic of
+/// [`Severity::Warning`](uniparse_core::Severity::Warning).
+pub fn parse_str_lenient(
+    format: Format,
+    src: &str,
+) -> Result<(AnyManifest, Vec<Diagnostic>), ParseError> {
+    #[cfg(feature = "gradle")]
+    if format == Format::Gradle {
+        return parse_gradle(src, uniparse_gradle::detect_dialect(src));
+    }
+    parse_str(format, src).map(|manifest| (manifest, Vec::new()))
+}
+
+/// Detects the format of `path` from its file name and parses it.
+pub fn parse_file(path: impl AsRef<Path>) -> Result<AnyManifest, ParseError> {
+    let path = path.as_ref();
+    parse_str(enabled_format(path)?, &fs::read_to_string(path)?)
+}
+
+/// Like [`parse_file`], with the leniency of [`parse_str_lenient`]. The
+/// dialect of a Gradle script comes from its file name, so only
+/// `.gradle.kts` files are parsed leniently.
+pub fn parse_file_lenient(
+    path: impl AsRef<Path>,
+) -> Result<(AnyManifest, Vec<Diagnostic>), ParseError> {
+    let path = path.as_ref();
+    let format = enabled_format(path)?;
+    parse_named(format, path, &fs::read_to_string(path)?)
+}
+
+fn enabled_format(path: &Path) -> Result<Format, ParseError> {
+    let format = Format::detect(path).ok_or_else(|| ParseError::UnknownFormat(path.into()))?;
+    if !format.is_enabled() {
+        return Err(ParseError::Disabled(format));
+    }
+    Ok(format)
+}
+
+/// Parses `src`, read from `path`, as `format` with the leniency of
+/// [`parse_file_lenient`].
+pub(crate) fn parse_named(
+    format: Format,
+    path: &Path,
+    src: &str,
+) -> Result<(AnyManifest, Vec<Diagnostic>), ParseError> {
+    #[cfg(feature = "gradle")]
+    if let Some(dialect) = uniparse_gradle::GradleDialect::from_path(path) {
+        return parse_gradle(src, dialect);
+    }
+    let _ = path;
+    parse_str_lenient(format, src)
+}
+
+#[cfg(feature = "gradle")]
+fn parse_gradle(
+    src: &str,
+    dialect: uniparse_gradle::GradleDialect,
+) -> Result<(AnyManifest, Vec<Diagnostic>), ParseError> {
+    use uniparse_gradle::{DSLBlock, GradleDialect};
+
+    match DSLBlock::parse_str(src) {
+        Ok(block) => Ok((AnyManifest::Gradle(block), Vec::new())),
+        Err(_) if dialect == GradleDialect::Kotlin => {
+            let (block, skipped) = DSLBlock::parse_lenient(src);
+            let warnings = skipped
+                .into_iter()
+                .map(|d| {
+                    let severity = d.severity();
+                    Diagnostic::new(d.code(), d.message).with_severity(severity)
+                })
+                .collect();
+            Ok((AnyManifest::Gradle(block), warnings))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_format() {
+        assert_eq!(Format::detect("go.mod"), Some(Format::GoMod));
+        assert_eq!(Format::detect("go.work"), Some(Format::GoWork));
+        assert_eq!(Format::detect("svc/go.sum"), Some(Format::GoSum));
+        assert_eq!(Format::detect("app/build.gradle"), Some(Format::Gradle));
+        assert_eq!(Format::detect("settings.gradle.kts"), Some(Format::Gradle));
+        assert_eq!(Format::detect("build.zig.zon"), Some(Format::Zon));
+        assert_eq!(Format::detect("Cargo.toml"), None);
+    }
+
+    #[test]
+    fn test_capabilities_per_format() {
+        let gradle = Format::Gradle.capabilities();
+        assert_eq!(gradle.lossless_editing, cfg!(feature = "gradle"));
+
+        let zon = Format::Zon.capabilities();
+        assert_eq!(zon.spans, cfg!(feature = "zon"));
+        assert_eq!(zon.comments, cfg!(feature = "zon"));
+
+        #[cfg(feature = "go")]
+        {
+            let manifest = parse_str(Format::GoMod, "module a.com/b\ngo 1.22\n").unwrap();
+            assert!(manifest.capabilities().spans);
+        }
+        #[cfg(not(feature = "go"))]
+        assert_eq!(Format::GoMod.capabilities(), Capabilities::NONE);
+        assert_eq!(Format::GoSum.capabilities(), Capabilities::NONE);
+    }
+
+    #[cfg(feature = "zon")]
+    #[test]
+    fn test_parse_file_by_name() {
+        let dir = std::env::temp_dir().join("uniparse_facade_parse_file");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("build.zig.zon");
+        fs::write(&path, r#".{ .name = "demo" }"#).unwrap();
+
+        let parsed = parse_file(&path);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(parsed.unwrap().format(), Format::Zon);
+    }
+
+    #[test]
+    fn test_errors_keep_format_codes() {
+        #[cfg(feature = "go")]
+        {
+            let err = parse_str(Format::GoMod, "go 1.22").unwrap_err();
+            assert_eq!(err.code(), "GOMOD003");
+        }
+
+        let err = parse_file("Cargo.toml").unwrap_err();
+        assert_eq!(err.code(), "UNIPARSE002");
+    }
+
+    #[cfg(feature = "gradle")]
+    #[test]
+    fn test_kotlin_scripts_parse_leniently() {
+        let kotlin = "val okhttp = \"4.12.0\"\ndependencies {\n    implementation(\"com.squareup.okhttp3:okhttp:4.12.0\")\n}\n";
+        assert!(parse_str(Format::Gradle, kotlin).is_err());
+        let Ok((AnyManifest::Gradle(block), skipped)) = parse_str_lenient(Format::Gradle, kotlin)
+        else {
+            panic!("Kotlin script didn't parse");
+        };
+        assert!(block.entries.contains_key("dependencies"));
+        assert_eq!(block.entries.len(), 1);
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].severity, uniparse_core::Severity::Warning);
+        assert!(skipped[0].message.contains("val okhttp"));
+
+        let groovy = "def okhttp = '4.12.0'\n";
+        let err = parse_str_lenient(Format::Gradle, groovy).unwrap_err();
+        assert!(err.code().starts_with("GRADLE"));
+    }
+
+    #[cfg(all(feature = "go", feature = "gradle", feature = "zon"))]
+    #[test]
+    fn test_policy_loaded_from_json_checks_every_format() {
+        use std::collections::HashMap;
+        use uniparse_core::{Schema, ViolationKind};
+
+        let policy: HashMap<String, Schema> = serde_json::from_str(
+            r#"{
+                "go": {
+                    "required": ["module"],
+                    "fields": {
+                        "module": { "predicates": [{ "matches": "corp/*" }] },
+                        "go_version": { "predicates": [{ "min_version": "1.21" }] }
+                    }
+                },
+                "gradle": {
+                    "fields": { "version": { "kinds": ["str"], "predicates": [{ "min_version": "2.0" }] } }
+                },
+                "zon": {
+                    "required": ["name", "version"],
+                    "fields": { "minimum_zig_version": { "predicates": [{ "min_version": "0.14.0" }] } }
+                }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(policy["go"].fields[0].0, "module");
+
+        let go = uniparse_go::GoMod::parse_str("module corp/app\n\ngo 1.20\n").unwrap();
+        let gradle = uniparse_gradle::DSLBlock::parse_str("version = '2.1.0'\n").unwrap();
+        let zon = uniparse_zon::ZonFile::parse_str(
+            r#".{ .name = .app, .version = "0.1.0", .minimum_zig_version = "0.14.0-dev.3" }"#,
+        )
+        .unwrap();
+
+        let found = |violations: Vec<uniparse_core::SchemaViolation>| -> Vec<_> {
+            violations.into_iter().map(|v| v.to_string()).collect()
+        };
+        assert_eq!(
+            found(go.validate_schema(&policy["go"])),
+            ["`go_version`: 1.20 is older than the minimum 1.21"]
+        );
+        assert!(gradle.validate_schema(&policy["gradle"]).is_empty());
+        let zon = zon.data.validate_schema(&policy["zon"]);
+        assert_eq!(
+            found(zon.clone()),
+            ["`minimum_zig_version`: 0.14.0-dev.3 is older than the minimum 0.14.0"]
+        );
+        assert!(matches!(zon[0].kind, ViolationKind::OlderThan { .. }));
+
+        let text = serde_json::to_string(&policy["go"]).unwrap();
+        assert_eq!(serde_json::from_str::<Schema>(&text).unwrap(), policy["go"]);
+    }
+}
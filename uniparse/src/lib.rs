@@ -0,0 +1,37 @@
+//! One dependency for every `uniparse` format.
+//!
+//! Each format lives in its own crate and is re-exported here behind a feature
+//! flag (`go`, `gradle`, `zon`, all enabled by default). Disable default
+//! features and pick the formats you need to trim compile time.
+//!
+//! ```rust
+//! use uniparse::{AnyManifest, Format};
+//!
+//! assert_eq!(Format::detect("services/api/go.mod"), Some(Format::GoMod));
+//!
+//! # #[cfg(feature = "go")] {
+//! let manifest = uniparse::parse_str(Format::GoMod, "module a.com/b\ngo 1.22\n").unwrap();
+//! assert!(matches!(manifest, AnyManifest::GoMod(_)));
+//! # }
+//! ```
+
+#[cfg(feature = "conformance")]
+pub mod conformance;
+mod facade;
+mod report;
+
+pub use facade::{
+    AnyManifest, Format, ParseError, parse_file, parse_file_lenient, parse_str, parse_str_lenient,
+};
+pub use report::{ManifestSummary, ParseFailure, RepoReport, ReportDependency, ReportDiagnostic};
+pub use uniparse_core::{
+    CancellationToken, Capabilities, Diagnostic, DiagnosticCode, Rule, RuleSet, Severity,
+    SourceEdit, sort_by_severity,
+};
+
+#[cfg(feature = "go")]
+pub use uniparse_go as go;
+#[cfg(feature = "gradle")]
+pub use uniparse_gradle as gradle;
+#[cfg(feature = "zon")]
+pub use uniparse_zon as zon;
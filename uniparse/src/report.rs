@@ -0,0 +1,441 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use uniparse_core::{CancellationToken, DiagnosticCode, RuleSet, Severity, walk_files};
+
+use crate::facade::{AnyManifest, Format, parse_file_lenient};
+
+/// Directories that hold build output, caches or VCS data rather than
+/// sources, and are not searched for manifests.
+const SKIPPED_DIRS: &[&str] = &["target", "node_modules", "build", "zig-out", "vendor"];
+
+/// Everything `uniparse` can tell about the manifests of a repository.
+///
+/// Paths are relative to the scanned directory and everything is sorted by
+/// path, so two reports of the same tree compare and serialize equal.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RepoReport {
+    /// Manifests that parsed.
+    pub manifests: Vec<ManifestSummary>,
+    /// Number of dependencies declared in the manifests of each format.
+    pub dependency_counts: BTreeMap<Format, usize>,
+    /// Manifests that didn't parse.
+    pub failures: Vec<ParseFailure>,
+    /// The dependencies of every manifest, in one list.
+    pub dependencies: Vec<ReportDependency>,
+    /// Problems found by the rules passed to
+    /// [`RepoReport::generate_with_rules`], and warnings for statements
+    /// left out of Kotlin DSL scripts (see
+    /// [`parse_file_lenient`](crate::parse_file_lenient)).
+    pub diagnostics: Vec<ReportDiagnostic>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ManifestSummary {
+    pub path: PathBuf,
+    pub format: Format,
+    /// Number of dependencies declared in this manifest.
+    pub dependencies: usize,
+}
+
+/// A manifest that was found but couldn't be read or parsed.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ParseFailure {
+    pub path: PathBuf,
+    pub format: Format,
+    /// Diagnostic code of the error, e.g. `GOMOD002`.
+    pub code: String,
+    pub message: String,
+}
+
+/// One dependency of one manifest.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReportDependency {
+    /// The manifest declaring it.
+    pub manifest: PathBuf,
+    pub format: Format,
+    /// As written: a module path, `group:name`, a catalog reference such as
+    /// `libs.okhttp`, or a `.zon` dependency key.
+    pub name: String,
+    pub version: Option<String>,
+    /// Canonical identity for matching the same dependency across
+    /// manifests; see [`normalize_dependency_id`](uniparse_core::normalize_dependency_id).
+    pub id: String,
+    /// Gradle configuration, e.g. `implementation`.
+    pub scope: Option<String>,
+}
+
+/// A [`Diagnostic`](uniparse_core::Diagnostic) a rule or the lenient
+/// parser reported for one manifest.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReportDiagnostic {
+    pub manifest: PathBuf,
+    pub format: Format,
+    pub code: String,
+    pub severity: Severity,
+    pub message: String,
+    /// Keys down to the offending value; empty for the whole file.
+    pub path: Vec<String>,
+}
+
+impl RepoReport {
+    /// Finds every manifest under `dir` whose format [`Format::detect`]
+    /// recognises and parses it. Hidden directories, build output
+    /// (`target`, `build`, `node_modules`, ...) and symbolic links to
+    /// directories are skipped.
+    ///
+    /// Manifests are parsed with [`parse_file_lenient`](crate::parse_file_lenient).
+    /// Those that fail to parse, or whose format is disabled, end up in
+    /// `failures` instead of aborting the scan.
+    ///
+    /// # Errors
+    /// Returns the IO error if a directory can't be listed.
+    pub fn generate(dir: impl AsRef<Path>) -> io::Result<Self> {
+        Self::generate_with_rules(dir, &RuleSet::default())
+    }
+
+    /// Like [`RepoReport::generate`], also running `rules` over every
+    /// manifest that parsed.
+    ///
+    /// # Errors
+    /// Returns the IO error if a directory can't be listed.
+    pub fn generate_with_rules(
+        dir: impl AsRef<Path>,
+        rules: &RuleSet<AnyManifest>,
+    ) -> io::Result<Self> {
+        Self::generate_cancellable(dir, rules, &CancellationToken::default())
+    }
+
+    /// Like [`RepoReport::generate_with_rules`], checking `cancel` before
+    /// each directory and manifest.
+    ///
+    /// # Errors
+    /// Returns an [`io::ErrorKind::Interrupted`] error once `cancel` is
+    /// cancelled, or the IO error if a directory can't be listed.
+    pub fn generate_cancellable(
+        dir: impl AsRef<Path>,
+        rules: &RuleSet<AnyManifest>,
+        cancel: &CancellationToken,
+    ) -> io::Result<Self> {
+        let dir = dir.as_ref();
+        let mut files = Vec::new();
+        find_manifests(dir, cancel, &mut files)?;
+        files.sort();
+
+        let mut report = RepoReport::default();
+        for (path, format) in files {
+            cancel.check()?;
+            let relative = path.strip_prefix(dir).unwrap_or(&path).to_path_buf();
+            match parse_file_lenient(&path) {
+                Ok((manifest, skipped)) => {
+                    let dependencies = dependencies(&manifest, &relative);
+                    report.diagnostics.extend(
+                        skipped.into_iter().chain(rules.check(&manifest)).map(|d| {
+                            ReportDiagnostic {
+                                manifest: relative.clone(),
+                                format,
+                                code: d.code.to_string(),
+                                severity: d.severity,
+                                message: d.message,
+                                path: d.path,
+                            }
+                        }),
+                    );
+                    *report.dependency_counts.entry(format).or_default() += dependencies.len();
+                    report.manifests.push(ManifestSummary {
+                        path: relative,
+                        format,
+                        dependencies: dependencies.len(),
+                    });
+                    report.dependencies.extend(dependencies);
+                }
+                Err(e) => report.failures.push(ParseFailure {
+                    path: relative,
+                    format,
+                    code: e.code().to_string(),
+                    message: e.to_string(),
+                }),
+            }
+        }
+        Ok(report)
+    }
+
+    /// Rule diagnostics at least as serious as `min`, e.g. only errors to
+    /// decide whether CI fails.
+    pub fn diagnostics_at_least(&self, min: Severity) -> impl Iterator<Item = &ReportDiagnostic> {
+        self.diagnostics.iter().filter(move |d| d.severity >= min)
+    }
+}
+
+fn find_manifests(
+    dir: &Path,
+    cancel: &CancellationToken,
+    out: &mut Vec<(PathBuf, Format)>,
+) -> io::Result<()> {
+    walk_files(
+        dir,
+        cancel,
+        |dir| {
+            let name = dir.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+            !name.starts_with('.') && !SKIPPED_DIRS.contains(&name)
+        },
+        |path| {
+            if let Some(format) = Format::detect(&path) {
+                out.push((path, format));
+            }
+        },
+    )
+}
+
+// `entry` is unused when every format is disabled
+#[allow(unused_variables)]
+fn dependencies(manifest: &AnyManifest, path: &Path) -> Vec<ReportDependency> {
+    let entry = |name: String, version: Option<String>, id: String, scope: Option<String>| {
+        ReportDependency {
+            manifest: path.to_path_buf(),
+            format: manifest.format(),
+            name,
+            version,
+            id,
+            scope,
+        }
+    };
+
+    match *manifest {
+        #[cfg(feature = "go")]
+        AnyManifest::GoMod(ref gomod) => gomod
+            .requires
+            .iter()
+            .map(|dep| {
+                entry(
+                    dep.name.clone(),
+                    Some(dep.version.clone()),
+                    dep.dependency_id(),
+                    None,
+                )
+            })
+            .collect(),
+        // a workspace's modules are reported through their own go.mod, and
+        // go.sum only pins the hashes of what those require
+        #[cfg(feature = "go")]
+        AnyManifest::GoWork(_) | AnyManifest::GoSum(_) => Vec::new(),
+        #[cfg(feature = "gradle")]
+        AnyManifest::Gradle(ref build) => {
+            use uniparse_gradle::{DependencyNotation, Resolver};
+
+            let resolver = Resolver::default();
+            build
+                .dependencies()
+                .into_iter()
+                .map(|dep| {
+                    let scope = Some(dep.configuration.clone());
+                    match (dep.resolve(&resolver), &dep.notation) {
+                        (Some(coords), _) => entry(
+                            format!("{}:{}", coords.group, coords.name),
+                            coords.version.clone(),
+                            coords.dependency_id(),
+                            scope,
+                        ),
+                        (
+                            None,
+                            DependencyNotation::Literal(s) | DependencyNotation::Reference(s),
+                        ) => entry(
+                            s.clone(),
+                            None,
+                            uniparse_core::normalize_dependency_id(s),
+                            scope,
+                        ),
+                    }
+                })
+                .collect()
+        }
+        #[cfg(feature = "zon")]
+        AnyManifest::Zon(ref zon) => {
+            use uniparse_zon::ZonValue;
+
+            let Some(ZonValue::Object(deps)) = zon.get(&["dependencies"]) else {
+                return Vec::new();
+            };
+            let mut out: Vec<_> = deps
+                .iter()
+                .map(|(name, dep)| {
+                    let url = dep.get_path(&["url"]).and_then(ZonValue::as_str);
+                    let id = uniparse_core::normalize_dependency_id(url.unwrap_or(name));
+                    entry(name.clone(), None, id, None)
+                })
+                .collect();
+            out.sort_by(|a, b| a.name.cmp(&b.name));
+            out
+        }
+    }
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(all(test, feature = "go", feature = "gradle", feature = "zon"))]
+mod tests {
+    use super::*;
+    use std::fs;
+    use uniparse_core::{Diagnostic, Rule};
+
+    #[test]
+    fn test_generate_report() {
+        let dir = std::env::temp_dir().join("uniparse_facade_report");
+        let _ = fs::remove_dir_all(&dir);
+        for sub in ["svc", "app", "lib", "zig", "broken", "app/build", ".git"] {
+            fs::create_dir_all(dir.join(sub)).unwrap();
+        }
+        fs::write(
+            dir.join("svc/go.mod"),
+            "module a.com/svc\ngo 1.22\nrequire github.com/pkg/errors v0.9.1\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("app/build.gradle"),
+            "dependencies {\n    implementation 'com.squareup.okhttp3:okhttp:4.12.0'\n    testImplementation libs.junit\n}\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("zig/build.zig.zon"),
+            r#".{ .name = "demo", .dependencies = .{ .zigimg = .{ .url = "https://github.com/zigimg/zigimg/archive/1.tar.gz", .hash = "1220ab" } } }"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("lib/build.gradle.kts"),
+            "val release = \"1.0\"\nversion = \"1.0\"\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("svc/go.sum"),
+            "github.com/pkg/errors v0.9.1 h1:FEBLx1zS214owpjy7qsBeixbURkuhQAwrK5UwLGTwt4=\n",
+        )
+        .unwrap();
+        fs::write(dir.join("broken/go.mod"), "go 1.22\n").unwrap();
+        fs::write(dir.join("app/build/go.mod"), "not scanned").unwrap();
+        fs::write(dir.join(".git/go.mod"), "not scanned").unwrap();
+
+        let report = RepoReport::generate(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+        let report = report.unwrap();
+
+        assert_eq!(
+            report
+                .manifests
+                .iter()
+                .map(|m| (m.path.to_str().unwrap(), m.dependencies))
+                .collect::<Vec<_>>(),
+            [
+                ("app/build.gradle", 2),
+                ("lib/build.gradle.kts", 0),
+                ("svc/go.mod", 1),
+                ("svc/go.sum", 0),
+                ("zig/build.zig.zon", 1)
+            ]
+        );
+        assert_eq!(
+            report.dependency_counts,
+            BTreeMap::from([
+                (Format::GoMod, 1),
+                (Format::GoSum, 0),
+                (Format::Gradle, 2),
+                (Format::Zon, 1)
+            ])
+        );
+
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures[0].path, Path::new("broken/go.mod"));
+        assert_eq!(report.failures[0].code, "GOMOD003");
+
+        let okhttp = &report.dependencies[0];
+        assert_eq!(okhttp.name, "com.squareup.okhttp3:okhttp");
+        assert_eq!(okhttp.version.as_deref(), Some("4.12.0"));
+        assert_eq!(okhttp.scope.as_deref(), Some("implementation"));
+        assert_eq!(report.dependencies[1].name, "libs.junit");
+
+        // the Kotlin script parsed leniently, without its `val`
+        assert_eq!(report.diagnostics.len(), 1);
+        let skipped = &report.diagnostics[0];
+        assert_eq!(skipped.manifest, Path::new("lib/build.gradle.kts"));
+        assert_eq!(
+            (skipped.code.as_str(), skipped.severity),
+            ("GRADLE012", Severity::Warning)
+        );
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["dependency_counts"]["Gradle"], 2);
+        assert_eq!(
+            json["dependencies"][3]["id"],
+            "github.com/zigimg/zigimg/archive/1.tar.gz"
+        );
+    }
+
+    struct NoPseudoVersions;
+
+    impl Rule<AnyManifest> for NoPseudoVersions {
+        fn name(&self) -> &str {
+            "no-pseudo-versions"
+        }
+
+        fn applies_to(&self, manifest: &AnyManifest) -> bool {
+            manifest.format() == Format::GoMod
+        }
+
+        fn check(&self, manifest: &AnyManifest) -> Vec<Diagnostic> {
+            let AnyManifest::GoMod(gomod) = manifest else {
+                unreachable!("only applies to go.mod")
+            };
+            (gomod.requires.iter().enumerate())
+                .filter(|(_, dep)| dep.is_pseudo_version())
+                .map(|(i, dep)| {
+                    Diagnostic::new("ACME001", format!("{} is an untagged commit", dep.name))
+                        .with_severity(Severity::Warning)
+                        .at(["requires".to_string(), i.to_string()])
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_generate_with_rules() {
+        let dir = std::env::temp_dir().join("uniparse_facade_rules");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("svc")).unwrap();
+        fs::write(
+            dir.join("svc/go.mod"),
+            "module a.com/svc\ngo 1.22\nrequire (\n\
+             \tgithub.com/pkg/errors v0.9.1\n\
+             \tcorp.com/auth v0.0.0-20240101120000-abcdef123456\n)\n",
+        )
+        .unwrap();
+        fs::write(dir.join("build.gradle"), "apply plugin: 'java'\n").unwrap();
+
+        let mut rules = RuleSet::default();
+        rules.register(NoPseudoVersions);
+        let report = RepoReport::generate_with_rules(&dir, &rules);
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let cancelled = RepoReport::generate_cancellable(&dir, &rules, &cancel);
+        fs::remove_dir_all(&dir).unwrap();
+        let report = report.unwrap();
+        assert_eq!(cancelled.unwrap_err().kind(), io::ErrorKind::Interrupted);
+
+        assert_eq!(
+            report.diagnostics,
+            [ReportDiagnostic {
+                manifest: PathBuf::from("svc/go.mod"),
+                format: Format::GoMod,
+                code: "ACME001".into(),
+                severity: Severity::Warning,
+                message: "corp.com/auth is an untagged commit".into(),
+                path: vec!["requires".into(), "1".into()],
+            }]
+        );
+        assert_eq!(report.diagnostics_at_least(Severity::Warning).count(), 1);
+        assert_eq!(report.diagnostics_at_least(Severity::Error).count(), 0);
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["diagnostics"][0]["severity"], "warning");
+    }
+}
@@ -0,0 +1,74 @@
+use std::io;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Lets a host abort a scan over many files from another thread, e.g. when
+/// an editor closes the workspace being indexed.
+///
+/// Clones share one flag. Scans check it between files, so cancelling
+/// takes effect once the file being parsed is done.
+///
+/// ```rust
+/// use uniparse_core::CancellationToken;
+///
+/// let token = CancellationToken::new();
+/// let scan = token.clone();
+/// token.cancel();
+/// assert_eq!(scan.check().unwrap_err().kind(), std::io::ErrorKind::Interrupted);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// An [`io::ErrorKind::Interrupted`] error once cancelled, so scans
+    /// that already return IO errors can stop with `?`.
+    pub fn check(&self) -> io::Result<()> {
+        if self.is_cancelled() {
+            Err(io::Error::new(io::ErrorKind::Interrupted, "scan cancelled"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Shares a flag the host already has.
+impl From<Arc<AtomicBool>> for CancellationToken {
+    fn from(cancelled: Arc<AtomicBool>) -> Self {
+        CancellationToken { cancelled }
+    }
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel_from_another_thread() {
+        let flag = Arc::new(AtomicBool::new(false));
+        let token = CancellationToken::from(flag.clone());
+        assert!(token.check().is_ok());
+
+        let remote = token.clone();
+        std::thread::spawn(move || remote.cancel()).join().unwrap();
+        assert!(token.is_cancelled());
+        assert!(flag.load(Ordering::Relaxed));
+    }
+}
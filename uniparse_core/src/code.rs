@@ -0,0 +1,55 @@
+use crate::severity::Severity;
+
+/// A stable, machine-readable identifier for an error or diagnostic.
+///
+/// Codes are made of an uppercase format prefix followed by a three digit
+/// number, e.g. `GOMOD001`, `GRADLE004` or `ZON002`. Once published a code is
+/// never reused for a different condition, so downstream tools can key their
+/// own documentation or severity policies on it instead of on message text.
+pub trait DiagnosticCode {
+    /// Returns the stable code for this value.
+    fn code(&self) -> &'static str;
+
+    /// How serious the problem is. [`Severity::Error`] unless the type says
+    /// otherwise, which is right for parse errors.
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+}
+
+impl<T: DiagnosticCode + ?Sized> DiagnosticCode for &T {
+    fn code(&self) -> &'static str {
+        (**self).code()
+    }
+
+    fn severity(&self) -> Severity {
+        (**self).severity()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    enum Sample {
+        First,
+        Second,
+    }
+
+    impl DiagnosticCode for Sample {
+        fn code(&self) -> &'static str {
+            match self {
+                Sample::First => "TEST001",
+                Sample::Second => "TEST002",
+            }
+        }
+    }
+
+    #[test]
+    fn test_code_through_reference() {
+        let items = [Sample::First, Sample::Second];
+        let codes: Vec<_> = items.iter().map(|s| (&s).code()).collect();
+        assert_eq!(codes, vec!["TEST001", "TEST002"]);
+        assert_eq!((&&items[0]).severity(), Severity::Error);
+    }
+}
@@ -0,0 +1,96 @@
+//! Support for keeping old signatures alive while an API is redesigned.
+//!
+//! When a public function changes shape, e.g. a `Result<T, String>` becoming
+//! a `Result<T, SomeError>` or a model gaining fields for lossless editing,
+//! the old version moves to a `compat` module of its crate instead of
+//! disappearing:
+//!
+//! - it keeps its name and exact signature,
+//! - it is marked `#[deprecated(since = "...", note = "use ... instead")]`,
+//!   naming the release that introduced the replacement,
+//! - it is a thin wrapper around the new function, using [`IntoLegacy`] to
+//!   turn structured errors back into the message it used to return,
+//! - it is removed no earlier than the second minor release after it was
+//!   deprecated.
+//!
+//! Downstream code can then switch `use` paths to `compat` first and move
+//! to the new API one call site at a time.
+
+use std::fmt::Display;
+
+/// Turns a structured error back into the `String` error of an old
+/// signature.
+///
+/// The message is the error's `Display` output, which is what the old
+/// functions returned, so code matching on message text keeps working.
+///
+/// ```rust
+/// use uniparse_core::compat::IntoLegacy;
+///
+/// #[derive(Debug)]
+/// struct NotAnObject;
+///
+/// impl std::fmt::Display for NotAnObject {
+///     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+///         f.write_str("Target is not an object")
+///     }
+/// }
+///
+/// let result: Result<(), NotAnObject> = Err(NotAnObject);
+/// assert_eq!(result.into_legacy(), Err("Target is not an object".to_string()));
+/// ```
+pub trait IntoLegacy<T> {
+    fn into_legacy(self) -> Result<T, String>;
+}
+
+impl<T, E: Display> IntoLegacy<T> for Result<T, E> {
+    fn into_legacy(self) -> Result<T, String> {
+        self.map_err(|e| e.to_string())
+    }
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DiagnosticCode;
+
+    #[derive(Debug)]
+    enum EditError {
+        NotFound(String),
+    }
+
+    impl Display for EditError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                EditError::NotFound(path) => write!(f, "Path not found: {}", path),
+            }
+        }
+    }
+
+    impl DiagnosticCode for EditError {
+        fn code(&self) -> &'static str {
+            "TEST001"
+        }
+    }
+
+    fn remove(path: &str) -> Result<(), EditError> {
+        Err(EditError::NotFound(path.to_string()))
+    }
+
+    #[deprecated(note = "use `remove` instead")]
+    fn legacy_remove(path: &str) -> Result<(), String> {
+        remove(path).into_legacy()
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_legacy_wrapper_keeps_message() {
+        assert_eq!(remove("a.b").unwrap_err().code(), "TEST001");
+        assert_eq!(legacy_remove("a.b"), Err("Path not found: a.b".to_string()));
+        assert_eq!(Ok::<_, EditError>(1).into_legacy(), Ok(1));
+    }
+}
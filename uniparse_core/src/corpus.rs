@@ -0,0 +1,235 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Environment variable that makes [`Corpus::run`] write golden files
+/// instead of comparing against them.
+const BLESS_VAR: &str = "UNIPARSE_BLESS";
+
+/// A directory of fixture files with their expected output, for
+/// conformance tests over real-world inputs.
+///
+/// Each fixture `<name>` has its golden output next to it in
+/// `<name>.json`. A run renders every fixture and compares the result with
+/// the golden file; with `UNIPARSE_BLESS=1` set (or [`Corpus::bless`]) it
+/// writes the golden files instead, for new fixtures or intended changes.
+///
+/// ```rust
+/// use uniparse_core::Corpus;
+///
+/// let dir = std::env::temp_dir().join("uniparse_corpus_doc");
+/// std::fs::create_dir_all(&dir).unwrap();
+/// std::fs::write(dir.join("a.txt"), "Hello").unwrap();
+/// std::fs::write(dir.join("a.txt.json"), "\"hello\"\n").unwrap();
+///
+/// let report = Corpus::new(&dir)
+///     .run(|_, src| Some(format!("\"{}\"\n", src.to_lowercase())))
+///     .unwrap();
+/// std::fs::remove_dir_all(&dir).unwrap();
+/// report.assert_ok();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Corpus {
+    root: PathBuf,
+    bless: bool,
+}
+
+/// What a [`Corpus::run`] found, with fixture paths relative to the corpus
+/// root.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CorpusReport {
+    pub passed: Vec<PathBuf>,
+    /// Fixtures whose golden file was written by a blessing run.
+    pub blessed: Vec<PathBuf>,
+    pub failures: Vec<CorpusFailure>,
+}
+
+/// A fixture whose output doesn't match its golden file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorpusFailure {
+    pub fixture: PathBuf,
+    /// The golden file contents, or `None` if the fixture has none yet.
+    pub expected: Option<String>,
+    pub actual: String,
+}
+
+impl Corpus {
+    /// A corpus rooted at `root`, blessing if `UNIPARSE_BLESS` is set to
+    /// anything but `0`.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        let bless = std::env::var(BLESS_VAR).is_ok_and(|v| !v.is_empty() && v != "0");
+        Corpus {
+            root: root.into(),
+            bless,
+        }
+    }
+
+    pub fn bless(mut self, bless: bool) -> Self {
+        self.bless = bless;
+        self
+    }
+
+    /// Renders every fixture below the root, in path order. `render` gets
+    /// the fixture path and contents and returns `None` for files that
+    /// aren't fixtures, e.g. notes or formats the caller doesn't handle.
+    /// Golden files and hidden entries are never passed to it.
+    ///
+    /// # Errors
+    /// Returns an IO error if the corpus can't be read, or a golden file
+    /// can't be written while blessing.
+    pub fn run(
+        &self,
+        mut render: impl FnMut(&Path, &str) -> Option<String>,
+    ) -> io::Result<CorpusReport> {
+        let mut fixtures = Vec::new();
+        collect(&self.root, &mut fixtures)?;
+        fixtures.sort();
+
+        let mut report = CorpusReport::default();
+        for path in fixtures {
+            let src = fs::read_to_string(&path)?;
+            let Some(actual) = render(&path, &src) else {
+                continue;
+            };
+            let golden = golden_path(&path);
+            let fixture = path.strip_prefix(&self.root).unwrap_or(&path).to_path_buf();
+            let expected = fs::read_to_string(&golden).ok();
+
+            if expected.as_deref() == Some(actual.as_str()) {
+                report.passed.push(fixture);
+            } else if self.bless {
+                fs::write(&golden, &actual)?;
+                report.blessed.push(fixture);
+            } else {
+                report.failures.push(CorpusFailure {
+                    fixture,
+                    expected,
+                    actual,
+                });
+            }
+        }
+        Ok(report)
+    }
+}
+
+impl CorpusReport {
+    pub fn is_ok(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// Panics with one line per failing fixture, for use in tests.
+    pub fn assert_ok(&self) {
+        if self.is_ok() {
+            return;
+        }
+        let lines: Vec<String> = self.failures.iter().map(|f| f.to_string()).collect();
+        panic!(
+            "{} of {} corpus fixtures don't match their golden files \
+             (rerun with {}=1 to update them):\n{}",
+            self.failures.len(),
+            self.failures.len() + self.passed.len(),
+            BLESS_VAR,
+            lines.join("\n")
+        );
+    }
+}
+
+impl std::fmt::Display for CorpusFailure {
+    /// The fixture and its first differing line.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Some(expected) = &self.expected else {
+            return write!(f, "{}: no golden file", self.fixture.display());
+        };
+        let mut expected_lines = expected.lines();
+        let mut actual_lines = self.actual.lines();
+        let mut line = 1;
+        loop {
+            match (expected_lines.next(), actual_lines.next()) {
+                (Some(e), Some(a)) if e == a => line += 1,
+                (e, a) => {
+                    return write!(
+                        f,
+                        "{}:{}: expected `{}`, got `{}`",
+                        self.fixture.display(),
+                        line,
+                        e.unwrap_or("<end>"),
+                        a.unwrap_or("<end>")
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn golden_path(fixture: &Path) -> PathBuf {
+    let mut name = fixture.as_os_str().to_owned();
+    name.push(".json");
+    PathBuf::from(name)
+}
+
+fn is_golden(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "json")
+}
+
+fn collect(dir: &Path, out: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let hidden = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.starts_with('.'));
+        if hidden {
+            continue;
+        }
+        if path.is_dir() {
+            collect(&path, out)?;
+        } else if !is_golden(&path) {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_compares_and_blesses() {
+        let dir = std::env::temp_dir().join("uniparse_core_corpus");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        fs::write(dir.join("ok.txt"), "a").unwrap();
+        fs::write(dir.join("ok.txt.json"), "A").unwrap();
+        fs::write(dir.join("nested/changed.txt"), "b\nc").unwrap();
+        fs::write(dir.join("nested/changed.txt.json"), "B\nX").unwrap();
+        fs::write(dir.join("new.txt"), "d").unwrap();
+        fs::write(dir.join("notes.md"), "skipped").unwrap();
+
+        let render =
+            |path: &Path, src: &str| (path.extension()? == "txt").then(|| src.to_uppercase());
+        let report = Corpus::new(&dir).bless(false).run(render).unwrap();
+        assert_eq!(report.passed, [PathBuf::from("ok.txt")]);
+        assert_eq!(report.failures.len(), 2);
+        assert_eq!(
+            report.failures[0].to_string(),
+            format!(
+                "{}:2: expected `X`, got `C`",
+                Path::new("nested/changed.txt").display()
+            )
+        );
+        assert_eq!(report.failures[1].to_string(), "new.txt: no golden file");
+        assert!(!report.is_ok());
+
+        let blessed = Corpus::new(&dir).bless(true).run(render).unwrap();
+        assert_eq!(blessed.blessed.len(), 2);
+        let again = Corpus::new(&dir).bless(false).run(render).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        again.assert_ok();
+        assert_eq!(again.passed.len(), 3);
+    }
+}
@@ -0,0 +1,727 @@
+//! Lossless syntax trees shared by the format crates.
+//!
+//! A tree comes in two layers, as in rust-analyzer's rowan:
+//!
+//! - The *green* tree ([`GreenNode`], [`GreenToken`]) is immutable and holds
+//!   every byte of the source, whitespace and comments included, so
+//!   printing it gives back the input exactly. Nodes don't know their
+//!   position or parent, which lets unchanged subtrees be shared between the
+//!   old and the edited tree.
+//! - The *red* tree ([`SyntaxNode`], [`SyntaxToken`]) is a cursor over a
+//!   green tree that adds byte offsets and parent links, created on demand
+//!   while navigating.
+//!
+//! Edits never mutate a tree: [`SyntaxNode::replace_with`] and
+//! [`SyntaxNode::splice_children`] return a new green root that shares
+//! everything outside the edited path, and printing it changes only the
+//! edited text.
+//!
+//! Conventions for the format crates:
+//!
+//! - Each format defines its own kind enum implementing [`CstKind`].
+//! - Whitespace and comments are tokens of their own (trivia) and sit as
+//!   siblings inside the innermost node that encloses them; they are never
+//!   folded into the text of a meaningful token.
+//! - Comments on the lines directly above an item belong inside that
+//!   item's node, so moving or deleting the node takes them along, and
+//!   [`SyntaxNode::leading_comments`] can find them.
+
+use std::fmt::{self, Debug, Display, Formatter};
+use std::ops::Range;
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// The kind of a node or token in a format's syntax tree.
+pub trait CstKind: Copy + Eq + Debug {
+    /// Whitespace, line breaks and comments: tokens without meaning that are
+    /// kept only so the tree prints back to its source.
+    fn is_trivia(self) -> bool;
+
+    /// Whether this trivia is a comment.
+    fn is_comment(self) -> bool {
+        false
+    }
+
+    /// Whether this trivia is a line break.
+    fn is_newline(self) -> bool {
+        false
+    }
+}
+
+/// A leaf of the green tree: a kind and the exact source text.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GreenToken<K> {
+    kind: K,
+    text: String,
+}
+
+/// An inner node of the green tree.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GreenNode<K> {
+    kind: K,
+    children: Vec<GreenElement<K>>,
+    text_len: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GreenElement<K> {
+    Node(Arc<GreenNode<K>>),
+    Token(Arc<GreenToken<K>>),
+}
+
+impl<K: CstKind> GreenToken<K> {
+    pub fn new(kind: K, text: impl Into<String>) -> Self {
+        GreenToken {
+            kind,
+            text: text.into(),
+        }
+    }
+
+    pub fn kind(&self) -> K {
+        self.kind
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+impl<K: CstKind> GreenNode<K> {
+    pub fn new(kind: K, children: Vec<GreenElement<K>>) -> Self {
+        let text_len = children.iter().map(GreenElement::text_len).sum();
+        GreenNode {
+            kind,
+            children,
+            text_len,
+        }
+    }
+
+    pub fn kind(&self) -> K {
+        self.kind
+    }
+
+    pub fn children(&self) -> &[GreenElement<K>] {
+        &self.children
+    }
+
+    /// Length of the node's text in bytes.
+    pub fn text_len(&self) -> usize {
+        self.text_len
+    }
+
+    /// A copy of this node with `range` of its children replaced.
+    pub fn splice_children(
+        &self,
+        range: Range<usize>,
+        replacement: impl IntoIterator<Item = GreenElement<K>>,
+    ) -> GreenNode<K> {
+        let mut children = self.children.clone();
+        children.splice(range, replacement);
+        GreenNode::new(self.kind, children)
+    }
+}
+
+impl<K: CstKind> GreenElement<K> {
+    pub fn kind(&self) -> K {
+        match self {
+            GreenElement::Node(node) => node.kind(),
+            GreenElement::Token(token) => token.kind(),
+        }
+    }
+
+    pub fn text_len(&self) -> usize {
+        match self {
+            GreenElement::Node(node) => node.text_len(),
+            GreenElement::Token(token) => token.text.len(),
+        }
+    }
+}
+
+impl<K> From<GreenNode<K>> for GreenElement<K> {
+    fn from(node: GreenNode<K>) -> Self {
+        GreenElement::Node(Arc::new(node))
+    }
+}
+
+impl<K> From<GreenToken<K>> for GreenElement<K> {
+    fn from(token: GreenToken<K>) -> Self {
+        GreenElement::Token(Arc::new(token))
+    }
+}
+
+impl<K> Display for GreenToken<K> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl<K> Display for GreenNode<K> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.children.iter().try_for_each(|child| child.fmt(f))
+    }
+}
+
+impl<K> Display for GreenElement<K> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            GreenElement::Node(node) => node.fmt(f),
+            GreenElement::Token(token) => token.fmt(f),
+        }
+    }
+}
+
+/// Builds a green tree bottom-up while a parser walks the source.
+///
+/// ```rust
+/// use uniparse_core::{CstKind, GreenNodeBuilder, SyntaxNode};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// enum Kind { File, Entry, Key, Value, Space }
+///
+/// impl CstKind for Kind {
+///     fn is_trivia(self) -> bool {
+///         self == Kind::Space
+///     }
+/// }
+///
+/// let mut builder = GreenNodeBuilder::new();
+/// builder.start_node(Kind::File);
+/// builder.start_node(Kind::Entry);
+/// builder.token(Kind::Key, "a");
+/// builder.token(Kind::Space, " ");
+/// builder.token(Kind::Value, "1");
+/// builder.finish_node();
+/// builder.finish_node();
+///
+/// let root = SyntaxNode::new_root(builder.finish());
+/// assert_eq!(root.to_string(), "a 1");
+/// ```
+#[derive(Debug)]
+pub struct GreenNodeBuilder<K> {
+    /// Open nodes with the index of their first child in `children`.
+    parents: Vec<(K, usize)>,
+    children: Vec<GreenElement<K>>,
+}
+
+/// A position in a [`GreenNodeBuilder`] that a node can later be started
+/// at, for constructs recognised only after their first tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checkpoint(usize);
+
+impl<K: CstKind> Default for GreenNodeBuilder<K> {
+    fn default() -> Self {
+        GreenNodeBuilder {
+            parents: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+}
+
+impl<K: CstKind> GreenNodeBuilder<K> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn start_node(&mut self, kind: K) {
+        self.parents.push((kind, self.children.len()));
+    }
+
+    pub fn token(&mut self, kind: K, text: &str) {
+        self.children.push(GreenToken::new(kind, text).into());
+    }
+
+    pub fn finish_node(&mut self) {
+        let (kind, first) = self
+            .parents
+            .pop()
+            .expect("finish_node called without a matching start_node");
+        let children = self.children.split_off(first);
+        self.children.push(GreenNode::new(kind, children).into());
+    }
+
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint(self.children.len())
+    }
+
+    /// Starts a node that wraps everything added since `checkpoint`.
+    pub fn start_node_at(&mut self, checkpoint: Checkpoint, kind: K) {
+        let Checkpoint(first) = checkpoint;
+        assert!(
+            first <= self.children.len()
+                && self.parents.last().is_none_or(|&(_, start)| start <= first),
+            "checkpoint is no longer valid"
+        );
+        self.parents.push((kind, first));
+    }
+
+    /// Returns the root node.
+    ///
+    /// # Panics
+    /// Panics unless exactly one node was started and finished at the top
+    /// level.
+    pub fn finish(mut self) -> GreenNode<K> {
+        assert!(self.parents.is_empty(), "unfinished nodes in builder");
+        match (self.children.pop(), self.children.is_empty()) {
+            (Some(GreenElement::Node(root)), true) => Arc::unwrap_or_clone(root),
+            _ => panic!("builder must hold exactly one root node"),
+        }
+    }
+}
+
+/// The index of the first comment on the lines directly above the token at
+/// `first`, or `first` if there is none, for parsers attaching comments to
+/// the item below them.
+///
+/// `kind(i)` is the kind of the `i`th token of the source. Tokens before
+/// `floor`, e.g. ones already added to the tree, are never included.
+/// Comments after code on their line don't count, and neither do comments
+/// separated from the item by a blank line.
+pub fn comments_above<K: CstKind>(
+    kind: impl Fn(usize) -> Option<K>,
+    floor: usize,
+    first: usize,
+) -> usize {
+    let is = |j: usize, f: fn(K) -> bool| kind(j).is_some_and(f);
+    let whitespace = |k: K| k.is_trivia() && !k.is_comment() && !k.is_newline();
+    let skip_whitespace = |mut j: usize| {
+        while j > floor && is(j - 1, whitespace) {
+            j -= 1;
+        }
+        j
+    };
+    let starts_line = |mut j: usize| {
+        while j > 0 && is(j - 1, whitespace) {
+            j -= 1;
+        }
+        j == 0 || is(j - 1, K::is_newline)
+    };
+
+    let mut start = first;
+    let mut j = skip_whitespace(first);
+    while j > floor && is(j - 1, K::is_newline) {
+        j = skip_whitespace(j - 1);
+        if j > floor && is(j - 1, K::is_comment) && starts_line(j - 1) {
+            j -= 1;
+            start = j;
+            j = skip_whitespace(j);
+        } else {
+            break;
+        }
+    }
+    start
+}
+
+/// A node of the red tree: a green node with its position and parent.
+///
+/// Cloning is cheap. Two `SyntaxNode`s are equal when they are the same
+/// node of the same tree.
+#[derive(Clone)]
+pub struct SyntaxNode<K> {
+    data: Rc<NodeData<K>>,
+}
+
+struct NodeData<K> {
+    green: Arc<GreenNode<K>>,
+    offset: usize,
+    /// Parent and our index among its children.
+    parent: Option<(SyntaxNode<K>, usize)>,
+}
+
+/// A token of the red tree.
+#[derive(Clone)]
+pub struct SyntaxToken<K> {
+    green: Arc<GreenToken<K>>,
+    offset: usize,
+    parent: SyntaxNode<K>,
+    index: usize,
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub enum SyntaxElement<K> {
+    Node(SyntaxNode<K>),
+    Token(SyntaxToken<K>),
+}
+
+impl<K: CstKind> SyntaxNode<K> {
+    pub fn new_root(green: GreenNode<K>) -> Self {
+        SyntaxNode {
+            data: Rc::new(NodeData {
+                green: Arc::new(green),
+                offset: 0,
+                parent: None,
+            }),
+        }
+    }
+
+    pub fn kind(&self) -> K {
+        self.data.green.kind
+    }
+
+    pub fn green(&self) -> &GreenNode<K> {
+        &self.data.green
+    }
+
+    /// Byte range of the node's text in the source.
+    pub fn text_range(&self) -> Range<usize> {
+        self.data.offset..self.data.offset + self.data.green.text_len
+    }
+
+    pub fn parent(&self) -> Option<SyntaxNode<K>> {
+        self.data.parent.as_ref().map(|(parent, _)| parent.clone())
+    }
+
+    /// Index of this node among its parent's children, tokens included.
+    pub fn index(&self) -> usize {
+        self.data.parent.as_ref().map_or(0, |&(_, index)| index)
+    }
+
+    pub fn ancestors(&self) -> impl Iterator<Item = SyntaxNode<K>> {
+        std::iter::successors(Some(self.clone()), SyntaxNode::parent)
+    }
+
+    pub fn children_with_tokens(&self) -> Vec<SyntaxElement<K>> {
+        let mut offset = self.data.offset;
+        self.data
+            .green
+            .children
+            .iter()
+            .enumerate()
+            .map(|(index, child)| {
+                let element = match child {
+                    GreenElement::Node(green) => SyntaxElement::Node(SyntaxNode {
+                        data: Rc::new(NodeData {
+                            green: Arc::clone(green),
+                            offset,
+                            parent: Some((self.clone(), index)),
+                        }),
+                    }),
+                    GreenElement::Token(green) => SyntaxElement::Token(SyntaxToken {
+                        green: Arc::clone(green),
+                        offset,
+                        parent: self.clone(),
+                        index,
+                    }),
+                };
+                offset += child.text_len();
+                element
+            })
+            .collect()
+    }
+
+    pub fn children(&self) -> impl Iterator<Item = SyntaxNode<K>> {
+        self.children_with_tokens()
+            .into_iter()
+            .filter_map(SyntaxElement::into_node)
+    }
+
+    /// This node and every node below it, in source order.
+    pub fn descendants(&self) -> impl Iterator<Item = SyntaxNode<K>> {
+        let mut stack = vec![self.clone()];
+        std::iter::from_fn(move || {
+            let node = stack.pop()?;
+            let mut children: Vec<_> = node.children().collect();
+            children.reverse();
+            stack.extend(children);
+            Some(node)
+        })
+    }
+
+    /// Every token below this node, in source order.
+    pub fn tokens(&self) -> impl Iterator<Item = SyntaxToken<K>> {
+        let mut stack = vec![SyntaxElement::Node(self.clone())];
+        std::iter::from_fn(move || {
+            loop {
+                match stack.pop()? {
+                    SyntaxElement::Token(token) => return Some(token),
+                    SyntaxElement::Node(node) => {
+                        stack.extend(node.children_with_tokens().into_iter().rev())
+                    }
+                }
+            }
+        })
+    }
+
+    /// The comment tokens at the start of this node, before its first
+    /// token that isn't trivia.
+    pub fn leading_comments(&self) -> Vec<SyntaxToken<K>> {
+        self.tokens()
+            .take_while(|t| t.kind().is_trivia())
+            .filter(|t| t.kind().is_comment())
+            .collect()
+    }
+
+    /// The root of a tree in which this node is replaced by `replacement`.
+    /// Everything else is shared with the current tree.
+    pub fn replace_with(&self, replacement: GreenNode<K>) -> GreenNode<K> {
+        let mut green = replacement;
+        let mut node = self.clone();
+        while let Some((parent, index)) = node.data.parent.clone() {
+            green = parent
+                .data
+                .green
+                .splice_children(index..index + 1, [green.into()]);
+            node = parent;
+        }
+        green
+    }
+
+    /// The root of a tree in which `range` of this node's children (tokens
+    /// included) is replaced by `replacement`; an empty range inserts.
+    pub fn splice_children(
+        &self,
+        range: Range<usize>,
+        replacement: impl IntoIterator<Item = GreenElement<K>>,
+    ) -> GreenNode<K> {
+        self.replace_with(self.data.green.splice_children(range, replacement))
+    }
+}
+
+impl<K: CstKind> SyntaxToken<K> {
+    pub fn kind(&self) -> K {
+        self.green.kind
+    }
+
+    pub fn text(&self) -> &str {
+        &self.green.text
+    }
+
+    pub fn text_range(&self) -> Range<usize> {
+        self.offset..self.offset + self.green.text.len()
+    }
+
+    pub fn parent(&self) -> SyntaxNode<K> {
+        self.parent.clone()
+    }
+
+    /// Index of this token among its parent's children, nodes included.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// The root of a tree in which this token is replaced by `replacement`.
+    pub fn replace_with(&self, replacement: GreenToken<K>) -> GreenNode<K> {
+        self.parent
+            .splice_children(self.index..self.index + 1, [replacement.into()])
+    }
+}
+
+impl<K: CstKind> SyntaxElement<K> {
+    pub fn kind(&self) -> K {
+        match self {
+            SyntaxElement::Node(node) => node.kind(),
+            SyntaxElement::Token(token) => token.kind(),
+        }
+    }
+
+    pub fn text_range(&self) -> Range<usize> {
+        match self {
+            SyntaxElement::Node(node) => node.text_range(),
+            SyntaxElement::Token(token) => token.text_range(),
+        }
+    }
+
+    pub fn into_node(self) -> Option<SyntaxNode<K>> {
+        match self {
+            SyntaxElement::Node(node) => Some(node),
+            SyntaxElement::Token(_) => None,
+        }
+    }
+
+    pub fn into_token(self) -> Option<SyntaxToken<K>> {
+        match self {
+            SyntaxElement::Token(token) => Some(token),
+            SyntaxElement::Node(_) => None,
+        }
+    }
+}
+
+impl<K> PartialEq for SyntaxNode<K> {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.data.green, &other.data.green) && self.data.offset == other.data.offset
+    }
+}
+
+impl<K> Eq for SyntaxNode<K> {}
+
+impl<K> PartialEq for SyntaxToken<K> {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.green, &other.green) && self.offset == other.offset
+    }
+}
+
+impl<K> Eq for SyntaxToken<K> {}
+
+impl<K: CstKind> Debug for SyntaxNode<K> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}@{:?}", self.kind(), self.text_range())
+    }
+}
+
+impl<K: CstKind> Debug for SyntaxToken<K> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?}@{:?} {:?}",
+            self.kind(),
+            self.text_range(),
+            self.text()
+        )
+    }
+}
+
+impl<K: CstKind> Debug for SyntaxElement<K> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SyntaxElement::Node(node) => Debug::fmt(node, f),
+            SyntaxElement::Token(token) => Debug::fmt(token, f),
+        }
+    }
+}
+
+impl<K> Display for SyntaxNode<K> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.data.green.fmt(f)
+    }
+}
+
+impl<K> Display for SyntaxToken<K> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.green.text)
+    }
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Kind {
+        File,
+        Entry,
+        Key,
+        Equals,
+        Value,
+        Space,
+        Newline,
+        Comment,
+    }
+
+    impl CstKind for Kind {
+        fn is_trivia(self) -> bool {
+            matches!(self, Kind::Space | Kind::Newline | Kind::Comment)
+        }
+
+        fn is_comment(self) -> bool {
+            self == Kind::Comment
+        }
+
+        fn is_newline(self) -> bool {
+            self == Kind::Newline
+        }
+    }
+
+    /// A lossless parser for `key = value` lines with `#` comments, where a
+    /// comment line belongs to the entry below it.
+    fn parse(src: &str) -> SyntaxNode<Kind> {
+        let mut builder = GreenNodeBuilder::new();
+        builder.start_node(Kind::File);
+        let mut pending = builder.checkpoint();
+        for line in src.split_inclusive('\n') {
+            let (text, newline) = match line.strip_suffix('\n') {
+                Some(text) => (text, "\n"),
+                None => (line, ""),
+            };
+            if let Some(comment) = text.strip_prefix('#') {
+                builder.token(Kind::Comment, &format!("#{comment}"));
+            } else if let Some((key, value)) = text.split_once(" = ") {
+                builder.start_node_at(pending, Kind::Entry);
+                builder.token(Kind::Key, key);
+                builder.token(Kind::Space, " ");
+                builder.token(Kind::Equals, "=");
+                builder.token(Kind::Space, " ");
+                builder.token(Kind::Value, value);
+                builder.finish_node();
+            }
+            if !newline.is_empty() {
+                builder.token(Kind::Newline, newline);
+            }
+            if !text.starts_with('#') {
+                pending = builder.checkpoint();
+            }
+        }
+        builder.finish_node();
+        SyntaxNode::new_root(builder.finish())
+    }
+
+    const SRC: &str = "a = 1\n# about b\nb = 2\n";
+
+    #[test]
+    fn test_round_trip_and_ranges() {
+        let root = parse(SRC);
+        assert_eq!(root.to_string(), SRC);
+        assert_eq!(root.text_range(), 0..SRC.len());
+
+        let entries: Vec<_> = root.children().collect();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(&SRC[entries[1].text_range()], "# about b\nb = 2");
+        assert_eq!(
+            entries[1]
+                .leading_comments()
+                .iter()
+                .map(SyntaxToken::text)
+                .collect::<Vec<_>>(),
+            ["# about b"]
+        );
+        assert!(entries[0].leading_comments().is_empty());
+
+        let value = entries[1]
+            .tokens()
+            .find(|t| t.kind() == Kind::Value)
+            .unwrap();
+        assert_eq!(value.text_range(), 20..21);
+        assert_eq!(value.parent(), entries[1]);
+        assert_eq!(
+            root.descendants().map(|n| n.kind()).collect::<Vec<_>>(),
+            [Kind::File, Kind::Entry, Kind::Entry]
+        );
+    }
+
+    #[test]
+    fn test_edits_share_untouched_subtrees() {
+        let root = parse(SRC);
+        let value = root.tokens().find(|t| t.text() == "2").unwrap();
+        let edited = SyntaxNode::new_root(value.replace_with(GreenToken::new(Kind::Value, "20")));
+        assert_eq!(edited.to_string(), "a = 1\n# about b\nb = 20\n");
+
+        let (GreenElement::Node(before), GreenElement::Node(after)) =
+            (&root.green().children()[0], &edited.green().children()[0])
+        else {
+            panic!("Expected entry nodes");
+        };
+        assert!(Arc::ptr_eq(before, after));
+
+        // drop the first entry and its line break
+        let removed = SyntaxNode::new_root(root.splice_children(0..2, []));
+        assert_eq!(removed.to_string(), "# about b\nb = 2\n");
+    }
+
+    #[test]
+    fn test_comments_above() {
+        use Kind::*;
+        // "# a\n\n  # b\n  # c\n  k = v" and "x # d\nk = v"
+        let kinds = [
+            Comment, Newline, Newline, Space, Comment, Newline, Space, Comment, Newline, Space, Key,
+        ];
+        let kind = |i: usize| kinds.get(i).copied();
+        assert_eq!(comments_above(kind, 0, 10), 4);
+        assert_eq!(comments_above(kind, 5, 10), 7);
+        assert_eq!(comments_above(kind, 0, 0), 0);
+
+        let trailing = [Value, Space, Comment, Newline, Key];
+        assert_eq!(comments_above(|i| trailing.get(i).copied(), 0, 4), 4);
+    }
+}
@@ -0,0 +1,186 @@
+use std::fmt::{Display, Formatter};
+use std::ops::Deref;
+use std::sync::Arc;
+
+use crate::{Capabilities, ParsedFile, SemanticEq};
+
+/// A cheaply cloneable, immutable handle to a parsed model.
+///
+/// Cloning shares the underlying tree through an [`Arc`], so a manifest can
+/// be handed to many worker threads without copying it. Edits go through
+/// [`Document::make_mut`] (or the [`ParsedFile`] methods) and are
+/// copy-on-write: the tree is only cloned if another handle still refers to
+/// it, and other handles never observe the change.
+#[derive(Debug, Default)]
+pub struct Document<T>(Arc<T>);
+
+impl<T> Document<T> {
+    pub fn new(model: T) -> Self {
+        Document(Arc::new(model))
+    }
+
+    /// Whether both handles share the same tree, i.e. neither has been
+    /// edited since one was cloned from the other.
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        Arc::ptr_eq(&this.0, &other.0)
+    }
+}
+
+impl<T: Clone> Document<T> {
+    /// Mutable access to the model, cloning it first if it is shared.
+    pub fn make_mut(&mut self) -> &mut T {
+        Arc::make_mut(&mut self.0)
+    }
+
+    /// Unwraps the model, cloning it if it is shared.
+    pub fn into_inner(self) -> T {
+        Arc::unwrap_or_clone(self.0)
+    }
+}
+
+impl<T> Clone for Document<T> {
+    fn clone(&self) -> Self {
+        Document(Arc::clone(&self.0))
+    }
+}
+
+impl<T> Deref for Document<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> From<T> for Document<T> {
+    fn from(model: T) -> Self {
+        Document::new(model)
+    }
+}
+
+impl<T: PartialEq> PartialEq for Document<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<T: SemanticEq> SemanticEq for Document<T> {
+    fn semantic_eq(&self, other: &Self) -> bool {
+        Document::ptr_eq(self, other) || self.0.semantic_eq(&other.0)
+    }
+}
+
+impl<T: Display> Display for Document<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<T: ParsedFile + Clone> ParsedFile for Document<T> {
+    type Error = T::Error;
+    type Value = T::Value;
+    type OwnedValue = T::OwnedValue;
+
+    const CAPABILITIES: Capabilities = T::CAPABILITIES;
+
+    fn parse_str(src: &str) -> Result<Self, Self::Error> {
+        T::parse_str(src).map(Document::new)
+    }
+
+    fn to_string_pretty(&self) -> String {
+        self.0.to_string_pretty()
+    }
+
+    fn get(&self, path: &[&str]) -> Option<&Self::Value> {
+        self.0.get(path)
+    }
+
+    fn set(&mut self, path: &[&str], value: Self::OwnedValue) -> Result<(), String> {
+        self.make_mut().set(path, value)
+    }
+
+    fn remove(&mut self, path: &[&str]) -> Result<(), String> {
+        self.make_mut().remove(path)
+    }
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Props(BTreeMap<String, String>);
+
+    impl ParsedFile for Props {
+        type Error = String;
+        type Value = String;
+        type OwnedValue = String;
+
+        fn parse_str(src: &str) -> Result<Self, String> {
+            src.lines()
+                .map(|line| match line.split_once('=') {
+                    Some((k, v)) => Ok((k.to_string(), v.to_string())),
+                    None => Err(format!("bad line {line}")),
+                })
+                .collect::<Result<_, _>>()
+                .map(Props)
+        }
+
+        fn to_string_pretty(&self) -> String {
+            self.0.iter().map(|(k, v)| format!("{k}={v}\n")).collect()
+        }
+
+        fn get(&self, path: &[&str]) -> Option<&String> {
+            self.0.get(*path.first()?)
+        }
+
+        fn set(&mut self, path: &[&str], value: String) -> Result<(), String> {
+            let key = path.first().ok_or("empty path")?;
+            self.0.insert(key.to_string(), value);
+            Ok(())
+        }
+
+        fn remove(&mut self, path: &[&str]) -> Result<(), String> {
+            let key = path.first().ok_or("empty path")?;
+            self.0.remove(*key);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_edits_are_copy_on_write() {
+        let shared: Document<Props> = Document::parse_str("a=1\nb=2").unwrap();
+        let mut edited = shared.clone();
+        assert!(Document::ptr_eq(&shared, &edited));
+
+        edited.set(&["a"], "3".into()).unwrap();
+        assert!(!Document::ptr_eq(&shared, &edited));
+        assert_eq!(shared.get(&["a"]).map(String::as_str), Some("1"));
+        assert_eq!(edited.to_string_pretty(), "a=3\nb=2\n");
+
+        // sole owner: edited in place
+        let before = Arc::as_ptr(&edited.0);
+        edited.remove(&["b"]).unwrap();
+        assert_eq!(Arc::as_ptr(&edited.0), before);
+        assert!(!edited.supports_comments());
+    }
+
+    #[test]
+    fn test_handles_cross_threads() {
+        let doc: Document<Props> = Document::parse_str("k=v").unwrap();
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let doc = doc.clone();
+                std::thread::spawn(move || doc.get(&["k"]).cloned())
+            })
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap().as_deref(), Some("v"));
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use std::ops::Range;
+
+/// One replacement in a source text: the bytes at `range` become
+/// `replacement`.
+///
+/// The `patch_edits` methods of the format crates return these, in source
+/// order, so an editor can apply a change as a workspace edit instead of
+/// rewriting the whole file. Ranges are byte offsets into the original text.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SourceEdit {
+    pub range: Range<usize>,
+    pub replacement: String,
+}
+
+impl SourceEdit {
+    pub fn new(range: Range<usize>, replacement: impl Into<String>) -> Self {
+        SourceEdit {
+            range,
+            replacement: replacement.into(),
+        }
+    }
+
+    /// Whether the edit only adds text.
+    pub fn is_insertion(&self) -> bool {
+        self.range.is_empty()
+    }
+
+    /// Applies `edits` to `source`. Insertions at the same offset end up in
+    /// the order they are given.
+    ///
+    /// ```rust
+    /// use uniparse_core::SourceEdit;
+    ///
+    /// let edits = [
+    ///     SourceEdit::new(4..5, "y"),
+    ///     SourceEdit::new(0..0, "// "),
+    ///     SourceEdit::new(0..0, "let "),
+    /// ];
+    /// assert_eq!(SourceEdit::apply("a = x;", &edits), "// let a = y;");
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if two edits overlap or a range is outside `source` or not on
+    /// a char boundary.
+    pub fn apply(source: &str, edits: &[SourceEdit]) -> String {
+        let mut sorted: Vec<_> = edits.iter().collect();
+        sorted.sort_by_key(|e| (e.range.start, e.range.end));
+
+        let mut out = String::with_capacity(source.len());
+        let mut at = 0;
+        for edit in sorted {
+            assert!(
+                edit.range.start >= at,
+                "overlapping edit at {:?}",
+                edit.range
+            );
+            out.push_str(&source[at..edit.range.start]);
+            out.push_str(&edit.replacement);
+            at = edit.range.end;
+        }
+        out.push_str(&source[at..]);
+        out
+    }
+}
+
+/// `range` widened to its whole lines, line break included, if nothing but
+/// whitespace is before it on its first line and nothing but whitespace or
+/// a `//` comment after it on its last. Otherwise `range` itself, so
+/// removing it leaves the rest of those lines alone.
+///
+/// The `patch_edits` methods delete entries through this, so a removed
+/// entry takes its line with it in every format.
+///
+/// ```rust
+/// use uniparse_core::whole_lines;
+///
+/// let src = "a = 1\n  b = 2 // two\nc = 3, d = 4\n";
+/// assert_eq!(whole_lines(src, 8..13), 6..21);
+/// assert_eq!(whole_lines(src, 28..33), 28..33);
+/// ```
+pub fn whole_lines(src: &str, range: Range<usize>) -> Range<usize> {
+    let start = src[..range.start].rfind('\n').map_or(0, |i| i + 1);
+    let end = match src[range.clone()].ends_with('\n') {
+        true => range.end,
+        false => src[range.end..]
+            .find('\n')
+            .map_or(src.len(), |i| range.end + i + 1),
+    };
+    let after = src[range.end..end].trim();
+    if src[start..range.start].trim().is_empty() && (after.is_empty() || after.starts_with("//")) {
+        start..end
+    } else {
+        range
+    }
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_in_any_order() {
+        let edits = vec![
+            SourceEdit::new(11..11, "\n.c"),
+            SourceEdit::new(3..7, ""),
+            SourceEdit::new(0..1, "{"),
+        ];
+        assert_eq!(SourceEdit::apply(".a .b .x .y", &edits), "{a x .y\n.c");
+        assert_eq!(SourceEdit::apply("unchanged", &[]), "unchanged");
+        assert!(edits[0].is_insertion());
+    }
+
+    #[test]
+    fn test_whole_lines() {
+        let src = "a\n  b = 2\r\nc, d // e\nf";
+        assert_eq!(whole_lines(src, 4..9), 2..11);
+        assert_eq!(whole_lines(src, 11..12), 11..12);
+        assert_eq!(whole_lines(src, 14..15), 14..15);
+        assert_eq!(whole_lines(src, 21..22), 21..22);
+        assert_eq!(whole_lines(src, 0..1), 0..2);
+        assert_eq!(whole_lines(src, 0..2), 0..2);
+    }
+
+    #[test]
+    #[should_panic(expected = "overlapping edit")]
+    fn test_apply_rejects_overlaps() {
+        SourceEdit::apply(
+            "abcdef",
+            &[SourceEdit::new(0..3, "x"), SourceEdit::new(2..4, "y")],
+        );
+    }
+}
@@ -0,0 +1,41 @@
+//! Shared building blocks for the `uniparse` parser crates.
+//!
+//! Every format crate (`uniparse_go`, `uniparse_gradle`, `uniparse_zon`) depends
+//! on this crate so that generic tooling can treat their errors, diagnostics
+//! and models uniformly.
+
+mod cancel;
+mod code;
+pub mod compat;
+mod corpus;
+mod cst;
+mod document;
+mod edit;
+mod normalize;
+mod parsed;
+mod rule;
+mod schema;
+mod semantic;
+mod severity;
+mod value;
+mod version;
+mod walk;
+
+pub use cancel::CancellationToken;
+pub use code::DiagnosticCode;
+pub use corpus::{Corpus, CorpusFailure, CorpusReport};
+pub use cst::{
+    Checkpoint, CstKind, GreenElement, GreenNode, GreenNodeBuilder, GreenToken, SyntaxElement,
+    SyntaxNode, SyntaxToken, comments_above,
+};
+pub use document::Document;
+pub use edit::{SourceEdit, whole_lines};
+pub use normalize::normalize_dependency_id;
+pub use parsed::{Capabilities, ParsedFile};
+pub use rule::{Diagnostic, Rule, RuleSet, sort_by_severity};
+pub use schema::{Predicate, Schema, SchemaViolation, ValueKind, ViolationKind};
+pub use semantic::{SemanticEq, unordered_eq};
+pub use severity::Severity;
+pub use value::{AsValueRef, ValueRef};
+pub use version::compare_prerelease;
+pub use walk::walk_files;
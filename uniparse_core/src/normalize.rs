@@ -0,0 +1,99 @@
+/// Maps a dependency identifier to a canonical form for cross-manifest
+/// matching.
+///
+/// URLs and module paths become `host/path`: the scheme (`https://`,
+/// `git+https://`, `ssh://`, ...), user info, `#ref` fragment, trailing `/`
+/// and `.git` suffix are dropped and the host is lowercased, so
+/// `git+https://GitHub.com/foo/bar.git#v1` and `github.com/foo/bar` compare
+/// equal. scp-style git remotes (`git@github.com:foo/bar.git`) are read the
+/// same way. Paths keep their case, since Go module paths and most hosts are
+/// case-sensitive there.
+///
+/// Maven coordinates (`group:name[:version]`) only have whitespace around
+/// their parts removed.
+///
+/// ```
+/// use uniparse_core::normalize_dependency_id;
+///
+/// assert_eq!(
+///     normalize_dependency_id("https://GitHub.com/ziglibs/known-folders.git"),
+///     "github.com/ziglibs/known-folders"
+/// );
+/// assert_eq!(normalize_dependency_id(" org.example : lib "), "org.example:lib");
+/// ```
+pub fn normalize_dependency_id(id: &str) -> String {
+    let id = id.trim();
+
+    let rest = match id.split_once("://") {
+        Some((_, rest)) => rest,
+        None if is_coordinates(id) => {
+            return id.split(':').map(str::trim).collect::<Vec<_>>().join(":");
+        }
+        None => id,
+    };
+    let rest = rest.split('#').next().unwrap_or_default();
+
+    let (authority, path) = match rest.find('/') {
+        Some(i) => rest.split_at(i),
+        None => (rest, ""),
+    };
+    // `git@github.com:foo/bar` keeps its path after the colon
+    let (authority, path) = match authority.rsplit_once('@') {
+        Some((_, host)) => match host.split_once(':') {
+            Some((host, first)) if !first.chars().all(|c| c.is_ascii_digit()) => {
+                (host, format!("/{}{}", first, path))
+            }
+            _ => (host, path.to_string()),
+        },
+        None => (authority, path.to_string()),
+    };
+
+    let path = path.trim_end_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    format!("{}{}", authority.to_ascii_lowercase(), path)
+}
+
+/// `group:name[:version]`, as opposed to a URL, module path or scp remote.
+fn is_coordinates(id: &str) -> bool {
+    match id.split_once(':') {
+        Some((group, _)) => !id.contains('/') && !group.contains('@'),
+        None => false,
+    }
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_equivalent_spellings_normalize_to_the_same_id() {
+        let spellings = [
+            "github.com/foo/Bar",
+            "https://github.com/foo/Bar",
+            "http://GITHUB.com/foo/Bar/",
+            "git+https://github.com/foo/Bar.git#v1.2.0",
+            "ssh://git@github.com/foo/Bar.git",
+            "git@github.com:foo/Bar.git",
+        ];
+        for id in spellings {
+            assert_eq!(normalize_dependency_id(id), "github.com/foo/Bar", "{}", id);
+        }
+
+        assert_eq!(
+            normalize_dependency_id("https://example.com:8443/pkg.tar.gz"),
+            "example.com:8443/pkg.tar.gz"
+        );
+        assert_eq!(
+            normalize_dependency_id("org.example:lib:1.0"),
+            "org.example:lib:1.0"
+        );
+        assert_eq!(
+            normalize_dependency_id("golang.org/x/text"),
+            "golang.org/x/text"
+        );
+    }
+}
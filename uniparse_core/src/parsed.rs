@@ -0,0 +1,83 @@
+use crate::value::{AsValueRef, ValueRef};
+
+/// A parsed configuration file that can be printed back and edited by path.
+///
+/// Implemented by the top-level model of each format crate, so tools can be
+/// written once against the trait and run over `.zon`, Gradle and `go.mod`
+/// files alike. Paths are sequences of keys from the document root, e.g.
+/// `["dependencies", "zigimg", "url"]`.
+pub trait ParsedFile: Sized {
+    /// Error returned when the source can't be parsed.
+    type Error;
+    /// Value stored at a path.
+    type Value: ?Sized;
+    /// Value [`set`](ParsedFile::set) takes, usually `Self::Value`, or its
+    /// owned form where that is unsized, e.g. `String` for `str`.
+    type OwnedValue;
+
+    /// What the format's crate offers beyond this trait.
+    const CAPABILITIES: Capabilities = Capabilities::NONE;
+
+    /// Whether the format's crate can edit a file while keeping the rest of
+    /// its text unchanged.
+    fn supports_lossless(&self) -> bool {
+        Self::CAPABILITIES.lossless_editing
+    }
+
+    /// Whether the format's crate keeps comments.
+    fn supports_comments(&self) -> bool {
+        Self::CAPABILITIES.comments
+    }
+
+    /// Whether the format's crate reports source positions.
+    fn supports_spans(&self) -> bool {
+        Self::CAPABILITIES.spans
+    }
+
+    fn parse_str(src: &str) -> Result<Self, Self::Error>;
+
+    /// Renders the document in the format's canonical style.
+    fn to_string_pretty(&self) -> String;
+
+    fn get(&self, path: &[&str]) -> Option<&Self::Value>;
+
+    /// Like [`get`](ParsedFile::get), as a format-agnostic [`ValueRef`].
+    fn get_ref(&self, path: &[&str]) -> Option<ValueRef<'_>>
+    where
+        Self::Value: AsValueRef,
+    {
+        self.get(path).map(AsValueRef::as_value_ref)
+    }
+
+    /// Sets the value at `path`, creating intermediate containers where the
+    /// format allows it.
+    fn set(&mut self, path: &[&str], value: Self::OwnedValue) -> Result<(), String>;
+
+    fn remove(&mut self, path: &[&str]) -> Result<(), String>;
+}
+
+/// Optional features of a format crate, so generic tools can pick a code
+/// path at runtime instead of matching on format names.
+///
+/// The flags describe the crate as a whole, not the [`ParsedFile`] model:
+/// the Gradle crate edits losslessly through `GradleBuild`, for example,
+/// while its `DSLBlock` model doesn't.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Capabilities {
+    /// Edits that leave the text outside the edited item unchanged,
+    /// comments and layout included.
+    pub lossless_editing: bool,
+    /// Line, column and byte positions on parsed items or diagnostics.
+    pub spans: bool,
+    /// Comments are kept instead of being dropped while parsing.
+    pub comments: bool,
+}
+
+impl Capabilities {
+    /// No optional features.
+    pub const NONE: Capabilities = Capabilities {
+        lossless_editing: false,
+        spans: false,
+        comments: false,
+    };
+}
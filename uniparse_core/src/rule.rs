@@ -0,0 +1,249 @@
+use std::fmt::{self, Display, Formatter};
+
+use crate::code::DiagnosticCode;
+use crate::severity::Severity;
+
+/// A problem reported by a [`Rule`], built with [`Diagnostic::new`] and
+/// the `with_*` methods.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub struct Diagnostic {
+    /// Stable code chosen by the rule's author, e.g. `ACME001`.
+    pub code: &'static str,
+    pub severity: Severity,
+    pub message: String,
+    /// Keys from the manifest root down to the offending value, e.g.
+    /// `["requires", "3"]`; empty when the problem is about the whole file.
+    pub path: Vec<String>,
+}
+
+impl Diagnostic {
+    /// A diagnostic of [`Severity::Error`]; see
+    /// [`with_severity`](Self::with_severity) for others.
+    pub fn new(code: &'static str, message: impl Into<String>) -> Self {
+        Diagnostic {
+            code,
+            severity: Severity::Error,
+            message: message.into(),
+            path: Vec::new(),
+        }
+    }
+
+    pub fn with_severity(mut self, severity: Severity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    /// Whether the diagnostic is at least as serious as `min`.
+    pub fn is_at_least(&self, min: Severity) -> bool {
+        self.severity >= min
+    }
+
+    /// Sets the path of the offending value.
+    pub fn at<S: Into<String>>(mut self, path: impl IntoIterator<Item = S>) -> Self {
+        self.path = path.into_iter().map(Into::into).collect();
+        self
+    }
+}
+
+impl DiagnosticCode for Diagnostic {
+    fn code(&self) -> &'static str {
+        self.code
+    }
+
+    fn severity(&self) -> Severity {
+        self.severity
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if !self.path.is_empty() {
+            write!(f, "`{}`: ", self.path.join("."))?;
+        }
+        f.write_str(&self.message)
+    }
+}
+
+/// Sorts `diagnostics` most serious first, keeping the order of those of
+/// the same severity.
+///
+/// ```rust
+/// use uniparse_core::{Diagnostic, Severity, sort_by_severity};
+///
+/// let mut found = vec![
+///     Diagnostic::new("ACME002", "consider pinning").with_severity(Severity::Hint),
+///     Diagnostic::new("ACME001", "snapshot dependency"),
+/// ];
+/// sort_by_severity(&mut found);
+/// assert_eq!(found[0].code, "ACME001");
+///
+/// // fail CI on errors only
+/// let errors = found.iter().filter(|d| d.is_at_least(Severity::Error)).count();
+/// assert_eq!(errors, 1);
+/// ```
+pub fn sort_by_severity(diagnostics: &mut [Diagnostic]) {
+    diagnostics.sort_by_key(|d| std::cmp::Reverse(d.severity));
+}
+
+/// A custom check over manifests of type `M`, such as the facade's
+/// `AnyManifest` or a single format's model.
+///
+/// ```rust
+/// use uniparse_core::{Diagnostic, Rule, RuleSet};
+///
+/// struct NoEmptyNames;
+///
+/// impl Rule<Vec<String>> for NoEmptyNames {
+///     fn name(&self) -> &str {
+///         "no-empty-names"
+///     }
+///
+///     fn check(&self, names: &Vec<String>) -> Vec<Diagnostic> {
+///         (names.iter().enumerate())
+///             .filter(|(_, name)| name.is_empty())
+///             .map(|(i, _)| Diagnostic::new("ACME001", "name is empty").at([i.to_string()]))
+///             .collect()
+///     }
+/// }
+///
+/// let mut rules = RuleSet::default();
+/// rules.register(NoEmptyNames);
+/// let found = rules.check(&vec!["a".to_string(), String::new()]);
+/// assert_eq!(found[0].to_string(), "`1`: name is empty");
+/// ```
+pub trait Rule<M: ?Sized>: Send + Sync {
+    /// Short identifier of the rule, e.g. `no-snapshot-dependencies`.
+    fn name(&self) -> &str;
+
+    /// Whether the rule should run on `manifest`, e.g. only on one format.
+    /// Every manifest by default.
+    fn applies_to(&self, manifest: &M) -> bool {
+        let _ = manifest;
+        true
+    }
+
+    fn check(&self, manifest: &M) -> Vec<Diagnostic>;
+}
+
+/// Rules run together, in the order they were registered.
+pub struct RuleSet<M: ?Sized> {
+    rules: Vec<Box<dyn Rule<M>>>,
+}
+
+impl<M: ?Sized> Default for RuleSet<M> {
+    fn default() -> Self {
+        RuleSet { rules: Vec::new() }
+    }
+}
+
+impl<M: ?Sized> fmt::Debug for RuleSet<M> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.rules.iter().map(|rule| rule.name()))
+            .finish()
+    }
+}
+
+impl<M: ?Sized> RuleSet<M> {
+    pub fn register(&mut self, rule: impl Rule<M> + 'static) -> &mut Self {
+        self.rules.push(Box::new(rule));
+        self
+    }
+
+    /// Names of the registered rules.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.rules.iter().map(|rule| rule.name())
+    }
+
+    /// Runs every rule that applies to `manifest`.
+    pub fn check(&self, manifest: &M) -> Vec<Diagnostic> {
+        self.rules
+            .iter()
+            .filter(|rule| rule.applies_to(manifest))
+            .flat_map(|rule| rule.check(manifest))
+            .collect()
+    }
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct MaxLen(usize);
+
+    impl Rule<str> for MaxLen {
+        fn name(&self) -> &str {
+            "max-len"
+        }
+
+        fn check(&self, text: &str) -> Vec<Diagnostic> {
+            (text.len() > self.0)
+                .then(|| Diagnostic::new("TEST001", format!("longer than {}", self.0)))
+                .into_iter()
+                .collect()
+        }
+    }
+
+    struct NoSpaces;
+
+    impl Rule<str> for NoSpaces {
+        fn name(&self) -> &str {
+            "no-spaces"
+        }
+
+        fn applies_to(&self, text: &str) -> bool {
+            !text.starts_with('#')
+        }
+
+        fn check(&self, text: &str) -> Vec<Diagnostic> {
+            (text.char_indices())
+                .filter(|(_, c)| *c == ' ')
+                .map(|(i, _)| Diagnostic::new("TEST002", "space").at([i.to_string()]))
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_rules_run_in_registration_order() {
+        let mut rules = RuleSet::default();
+        rules.register(NoSpaces).register(MaxLen(4));
+        assert_eq!(rules.names().collect::<Vec<_>>(), ["no-spaces", "max-len"]);
+        assert_eq!(format!("{:?}", rules), r#"["no-spaces", "max-len"]"#);
+
+        let found = rules.check("a b c");
+        assert_eq!(
+            found.iter().map(|d| d.to_string()).collect::<Vec<_>>(),
+            ["`1`: space", "`3`: space", "longer than 4"]
+        );
+        assert_eq!(found[2].code(), "TEST001");
+
+        // `NoSpaces` doesn't apply to comments
+        assert_eq!(rules.check("# a b").len(), 1);
+    }
+
+    #[test]
+    fn test_severity_filter_and_sort() {
+        let mut found = vec![
+            Diagnostic::new("TEST001", "a").with_severity(Severity::Info),
+            Diagnostic::new("TEST002", "b"),
+            Diagnostic::new("TEST003", "c").with_severity(Severity::Warning),
+            Diagnostic::new("TEST004", "d").with_severity(Severity::Info),
+        ];
+        let shown: Vec<_> = found
+            .iter()
+            .filter(|d| d.is_at_least(Severity::Warning))
+            .map(|d| d.code)
+            .collect();
+        assert_eq!(shown, ["TEST002", "TEST003"]);
+
+        sort_by_severity(&mut found);
+        let codes: Vec<_> = found.iter().map(|d| d.code).collect();
+        assert_eq!(codes, ["TEST002", "TEST003", "TEST001", "TEST004"]);
+    }
+}
@@ -0,0 +1,682 @@
+use std::cmp::Ordering;
+use std::fmt::{self, Display, Formatter};
+
+use crate::code::DiagnosticCode;
+use crate::value::ValueRef;
+use crate::version::compare_prerelease;
+
+/// The variant of a [`ValueRef`], without its contents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ValueKind {
+    Str,
+    Bool,
+    Number,
+    Ident,
+    Seq,
+    Map,
+    Opaque,
+}
+
+impl Display for ValueKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ValueKind::Str => "string",
+            ValueKind::Bool => "bool",
+            ValueKind::Number => "number",
+            ValueKind::Ident => "identifier",
+            ValueKind::Seq => "list",
+            ValueKind::Map => "map",
+            ValueKind::Opaque => "expression",
+        })
+    }
+}
+
+impl ValueRef<'_> {
+    pub fn kind(&self) -> ValueKind {
+        match self {
+            ValueRef::Str(_) => ValueKind::Str,
+            ValueRef::Bool(_) => ValueKind::Bool,
+            ValueRef::Number(_) => ValueKind::Number,
+            ValueRef::Ident(_) => ValueKind::Ident,
+            ValueRef::Seq(_) => ValueKind::Seq,
+            ValueRef::Map(_) => ValueKind::Map,
+            ValueRef::Opaque => ValueKind::Opaque,
+        }
+    }
+}
+
+/// The expected shape of a value, checked against any format through its
+/// [`ValueRef`] view.
+///
+/// ```rust
+/// use uniparse_core::{Schema, ValueKind, ValueRef};
+///
+/// let schema = Schema::default()
+///     .required("name", Schema::of(ValueKind::Str))
+///     .field("paths", Schema::of(ValueKind::Seq).items(Schema::of(ValueKind::Str)));
+///
+/// let value = ValueRef::Map(vec![("paths", ValueRef::Seq(vec![ValueRef::Bool(true)]))]);
+/// let violations = schema.validate(&value);
+/// assert_eq!(violations[0].to_string(), "`name`: required key is missing");
+/// assert_eq!(violations[1].to_string(), "`paths.0`: expected string, found bool");
+/// ```
+///
+/// With the `serde` feature a schema is plain data that policies can be
+/// loaded from, e.g. as JSON. Every key is optional, `fields` is a map in
+/// the order its keys should be checked and predicates are named by their
+/// snake-case variant:
+///
+/// ```json
+/// {
+///   "required": ["name"],
+///   "fields": {
+///     "name": { "kinds": ["str"], "predicates": [{ "matches": "corp/*" }] },
+///     "go_version": { "predicates": [{ "min_version": "1.21" }] }
+///   }
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Schema {
+    /// Kinds the value may have; any kind if empty.
+    pub kinds: Vec<ValueKind>,
+    /// Schemas of map entries, checked when the key is present.
+    #[cfg_attr(feature = "serde", serde(with = "fields_map"))]
+    pub fields: Vec<(String, Schema)>,
+    /// Keys a map must have.
+    pub required: Vec<String>,
+    /// Reject map keys that aren't listed in `fields`.
+    pub deny_unknown_keys: bool,
+    /// Schema of every item of a sequence.
+    pub items: Option<Box<Schema>>,
+    /// Smallest value a number may have.
+    pub minimum: Option<f64>,
+    /// Tests a string must pass.
+    pub predicates: Vec<Predicate>,
+    /// Schemas the value must not match. A value matches a schema when
+    /// validating it against that schema finds nothing.
+    pub forbidden: Vec<Schema>,
+    /// What a value matching this schema is, reported when it is
+    /// forbidden.
+    pub description: Option<String>,
+}
+
+/// A test of a string value.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Predicate {
+    /// The string matches this glob, where `*` stands for any run of
+    /// characters.
+    Matches(String),
+    /// The string is this version or newer, compared by numeric
+    /// components, so `1.9` is older than `1.21` and `v1.2` equals
+    /// `1.2.0`, then by semver pre-release precedence, so `1.21.0-rc.1` is
+    /// older than `1.21`.
+    MinVersion(String),
+    /// A test written in code, for checks the others can't express, such as
+    /// "is a Go pseudo-version". Failures are shown as "`{found}` is not a
+    /// {name}". Being code, it is skipped by `serde` and a schema holding
+    /// one can't be serialized.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Test {
+        // skipped too, or serde would borrow it from the input
+        #[cfg_attr(feature = "serde", serde(skip))]
+        name: &'static str,
+        test: fn(&str) -> bool,
+    },
+}
+
+impl fmt::Debug for Predicate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Predicate::Matches(glob) => f.debug_tuple("Matches").field(glob).finish(),
+            Predicate::MinVersion(minimum) => f.debug_tuple("MinVersion").field(minimum).finish(),
+            Predicate::Test { name, .. } => f.debug_tuple("Test").field(name).finish(),
+        }
+    }
+}
+
+/// [`Predicate::Test`]s are equal when their names are.
+impl PartialEq for Predicate {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Predicate::Matches(a), Predicate::Matches(b)) => a == b,
+            (Predicate::MinVersion(a), Predicate::MinVersion(b)) => a == b,
+            (Predicate::Test { name: a, .. }, Predicate::Test { name: b, .. }) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Schema {
+    /// A schema accepting only values of `kind`.
+    pub fn of(kind: ValueKind) -> Self {
+        Schema {
+            kinds: vec![kind],
+            ..Schema::default()
+        }
+    }
+
+    /// A schema accepting strings that match `glob`.
+    pub fn matching(glob: impl Into<String>) -> Self {
+        Schema::of(ValueKind::Str).satisfies(Predicate::Matches(glob.into()))
+    }
+
+    /// Adds an optional map entry; `self` must then be a map.
+    pub fn field(mut self, key: impl Into<String>, schema: Schema) -> Self {
+        if self.kinds.is_empty() {
+            self.kinds.push(ValueKind::Map);
+        }
+        self.fields.push((key.into(), schema));
+        self
+    }
+
+    /// Adds a map entry that must be present.
+    pub fn required(mut self, key: impl Into<String>, schema: Schema) -> Self {
+        let key = key.into();
+        self.required.push(key.clone());
+        self.field(key, schema)
+    }
+
+    /// Sets the schema of sequence items.
+    pub fn items(mut self, schema: Schema) -> Self {
+        self.items = Some(Box::new(schema));
+        self
+    }
+
+    /// Sets the smallest value a number may have.
+    pub fn at_least(mut self, minimum: f64) -> Self {
+        self.minimum = Some(minimum);
+        self
+    }
+
+    /// Adds the smallest version a string may be.
+    pub fn at_least_version(self, minimum: impl Into<String>) -> Self {
+        self.satisfies(Predicate::MinVersion(minimum.into()))
+    }
+
+    /// Adds a test a string must pass.
+    pub fn satisfies(mut self, predicate: Predicate) -> Self {
+        self.predicates.push(predicate);
+        self
+    }
+
+    /// Rejects values that match `schema`.
+    pub fn forbid(mut self, schema: Schema) -> Self {
+        self.forbidden.push(schema);
+        self
+    }
+
+    /// Describes what a value matching this schema is.
+    pub fn describe(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Checks `value`, returning every violation in the order the schema
+    /// lists its fields. Nothing below a value of the wrong kind is
+    /// checked.
+    pub fn validate(&self, value: &ValueRef<'_>) -> Vec<SchemaViolation> {
+        let mut violations = Vec::new();
+        self.check(value, &mut Vec::new(), &mut violations);
+        violations
+    }
+
+    fn check(&self, value: &ValueRef<'_>, path: &mut Vec<String>, out: &mut Vec<SchemaViolation>) {
+        let kind = value.kind();
+        if !self.kinds.is_empty() && !self.kinds.contains(&kind) {
+            out.push(SchemaViolation {
+                path: path.clone(),
+                kind: ViolationKind::WrongKind {
+                    expected: self.kinds.clone(),
+                    found: kind,
+                },
+            });
+            return;
+        }
+
+        for schema in &self.forbidden {
+            if schema.validate(value).is_empty() {
+                out.push(SchemaViolation {
+                    path: path.clone(),
+                    kind: ViolationKind::Forbidden {
+                        description: schema.description.clone(),
+                    },
+                });
+            }
+        }
+
+        if let ValueRef::Str(text) | ValueRef::Ident(text) = value {
+            self.check_text(text, path, out);
+        }
+
+        match value {
+            ValueRef::Map(entries) => {
+                let child = |key: &str, path: &[String]| {
+                    let mut path = path.to_vec();
+                    path.push(key.to_string());
+                    path
+                };
+                for key in &self.required {
+                    if value.get(key).is_none() {
+                        out.push(SchemaViolation {
+                            path: child(key, path),
+                            kind: ViolationKind::MissingKey,
+                        });
+                    }
+                }
+                for (key, schema) in &self.fields {
+                    if let Some(field) = value.get(key) {
+                        path.push(key.clone());
+                        schema.check(field, path, out);
+                        path.pop();
+                    }
+                }
+                if self.deny_unknown_keys {
+                    for (key, _) in entries {
+                        if !self.fields.iter().any(|(k, _)| k == key) {
+                            out.push(SchemaViolation {
+                                path: child(key, path),
+                                kind: ViolationKind::UnknownKey,
+                            });
+                        }
+                    }
+                }
+            }
+            ValueRef::Number(raw) => {
+                if let Some(minimum) = self.minimum
+                    && number_value(raw).is_some_and(|n| n < minimum)
+                {
+                    out.push(SchemaViolation {
+                        path: path.clone(),
+                        kind: ViolationKind::BelowMinimum {
+                            minimum,
+                            found: raw.to_string(),
+                        },
+                    });
+                }
+            }
+            ValueRef::Seq(items) => {
+                if let Some(schema) = &self.items {
+                    for (i, item) in items.iter().enumerate() {
+                        path.push(i.to_string());
+                        schema.check(item, path, out);
+                        path.pop();
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn check_text(&self, text: &str, path: &[String], out: &mut Vec<SchemaViolation>) {
+        let mut push = |kind| {
+            out.push(SchemaViolation {
+                path: path.to_vec(),
+                kind,
+            })
+        };
+        for predicate in &self.predicates {
+            match predicate {
+                Predicate::Matches(glob) if !glob_match(glob, text) => {
+                    push(ViolationKind::NoMatch {
+                        pattern: glob.clone(),
+                        found: text.to_string(),
+                    })
+                }
+                Predicate::MinVersion(minimum) if compare_versions(text, minimum).is_lt() => {
+                    push(ViolationKind::OlderThan {
+                        minimum: minimum.clone(),
+                        found: text.to_string(),
+                    })
+                }
+                Predicate::Test { name, test } if !test(text) => push(ViolationKind::Unsatisfied {
+                    predicate: name,
+                    found: text.to_string(),
+                }),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Whether `text` matches `glob`, where `*` matches any run of characters
+/// and everything else matches itself.
+fn glob_match(glob: &str, text: &str) -> bool {
+    let mut parts = glob.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<_> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(at) => rest = &rest[at + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+/// Orders versions such as `v1.21.3-rc.1` by their numeric components,
+/// with trailing zeros dropped so `1.2` equals `1.2.0`, then by
+/// [`compare_prerelease`]. Build metadata after `+` is ignored.
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let (release_a, pre_a) = version_parts(a);
+    let (release_b, pre_b) = version_parts(b);
+    release_a
+        .cmp(&release_b)
+        .then_with(|| compare_prerelease(pre_a, pre_b))
+}
+
+/// The numeric components of a version and its pre-release part.
+fn version_parts(version: &str) -> (Vec<u64>, Option<&str>) {
+    let version = version.strip_prefix('v').unwrap_or(version);
+    let version = version.split('+').next().unwrap_or_default();
+    let (release, pre) = match version.split_once('-') {
+        Some((release, pre)) => (release, Some(pre)),
+        None => (version, None),
+    };
+    let mut parts: Vec<u64> = release
+        .split('.')
+        .map(|part| {
+            let digits = part.len() - part.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+            part[..digits].parse().unwrap_or(0)
+        })
+        .collect();
+    while parts.last() == Some(&0) {
+        parts.pop();
+    }
+    (parts, pre)
+}
+
+/// The value of a number literal such as `34`, `1.8`, `0x1F`, `1_000` or
+/// `10L`.
+fn number_value(raw: &str) -> Option<f64> {
+    let digits = raw.replace('_', "");
+    let radix = [("0x", 16), ("0X", 16), ("0o", 8), ("0b", 2), ("0B", 2)]
+        .into_iter()
+        .find_map(|(prefix, radix)| Some((digits.strip_prefix(prefix)?, radix)));
+    match radix {
+        Some((digits, radix)) => i64::from_str_radix(digits.trim_end_matches(['L', 'l']), radix)
+            .ok()
+            .map(|n| n as f64),
+        None => digits
+            .trim_end_matches(['L', 'l', 'f', 'F', 'd', 'D'])
+            .parse()
+            .ok(),
+    }
+}
+
+/// A place where a value doesn't match its [`Schema`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaViolation {
+    /// Map keys and sequence indices from the validated value down to the
+    /// offending one. For a missing key, the path it should be at.
+    pub path: Vec<String>,
+    pub kind: ViolationKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ViolationKind {
+    MissingKey,
+    WrongKind {
+        expected: Vec<ValueKind>,
+        found: ValueKind,
+    },
+    /// A key the schema doesn't list, with `deny_unknown_keys` set.
+    UnknownKey,
+    /// A number smaller than the schema's `minimum`, as written.
+    BelowMinimum {
+        minimum: f64,
+        found: String,
+    },
+    /// A string that doesn't match a [`Predicate::Matches`] glob.
+    NoMatch {
+        pattern: String,
+        found: String,
+    },
+    /// A value matching one of the schema's `forbidden` schemas, with that
+    /// schema's description.
+    Forbidden {
+        description: Option<String>,
+    },
+    /// A version older than a [`Predicate::MinVersion`].
+    OlderThan {
+        minimum: String,
+        found: String,
+    },
+    /// A string failing a [`Predicate::Test`].
+    Unsatisfied {
+        predicate: &'static str,
+        found: String,
+    },
+}
+
+impl DiagnosticCode for SchemaViolation {
+    fn code(&self) -> &'static str {
+        match self.kind {
+            ViolationKind::MissingKey => "SCHEMA001",
+            ViolationKind::WrongKind { .. } => "SCHEMA002",
+            ViolationKind::UnknownKey => "SCHEMA003",
+            ViolationKind::BelowMinimum { .. } => "SCHEMA004",
+            ViolationKind::NoMatch { .. } => "SCHEMA005",
+            ViolationKind::Forbidden { .. } => "SCHEMA006",
+            ViolationKind::OlderThan { .. } => "SCHEMA007",
+            ViolationKind::Unsatisfied { .. } => "SCHEMA008",
+        }
+    }
+}
+
+impl Display for SchemaViolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if !self.path.is_empty() {
+            write!(f, "`{}`: ", self.path.join("."))?;
+        }
+        match &self.kind {
+            ViolationKind::MissingKey => f.write_str("required key is missing"),
+            ViolationKind::WrongKind { expected, found } => {
+                let expected: Vec<_> = expected.iter().map(ValueKind::to_string).collect();
+                write!(f, "expected {}, found {}", expected.join(" or "), found)
+            }
+            ViolationKind::UnknownKey => f.write_str("key is not allowed here"),
+            ViolationKind::BelowMinimum { minimum, found } => {
+                write!(f, "{} is less than the minimum {}", found, minimum)
+            }
+            ViolationKind::NoMatch { pattern, found } => {
+                write!(f, "`{}` doesn't match `{}`", found, pattern)
+            }
+            ViolationKind::Forbidden {
+                description: Some(description),
+            } => write!(f, "{} is not allowed", description),
+            ViolationKind::Forbidden { description: None } => f.write_str("value is not allowed"),
+            ViolationKind::OlderThan { minimum, found } => {
+                write!(f, "{} is older than the minimum {}", found, minimum)
+            }
+            ViolationKind::Unsatisfied { predicate, found } => {
+                write!(f, "`{}` is not a {}", found, predicate)
+            }
+        }
+    }
+}
+
+/// [`Schema::fields`] as a map, keeping the order of its keys.
+#[cfg(feature = "serde")]
+mod fields_map {
+    use std::fmt::{self, Formatter};
+
+    use serde::de::{MapAccess, Visitor};
+    use serde::{Deserializer, Serializer};
+
+    use super::Schema;
+
+    pub fn serialize<S: Serializer>(
+        fields: &[(String, Schema)],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(fields.iter().map(|(key, schema)| (key, schema)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<(String, Schema)>, D::Error> {
+        struct Fields;
+
+        impl<'de> Visitor<'de> for Fields {
+            type Value = Vec<(String, Schema)>;
+
+            fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                f.write_str("a map from keys to schemas")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut fields = Vec::new();
+                while let Some(field) = map.next_entry()? {
+                    fields.push(field);
+                }
+                Ok(fields)
+            }
+        }
+
+        deserializer.deserialize_map(Fields)
+    }
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nested_violations() {
+        let dependency = Schema::of(ValueKind::Map)
+            .required("url", Schema::of(ValueKind::Str))
+            .field("lazy", Schema::of(ValueKind::Bool));
+        let schema = Schema {
+            deny_unknown_keys: true,
+            ..Schema::default()
+                .required("name", Schema::of(ValueKind::Ident))
+                .field("dependencies", Schema::of(ValueKind::Map))
+                .field("deps", Schema::of(ValueKind::Seq).items(dependency))
+        };
+
+        let value = ValueRef::Map(vec![
+            ("name", ValueRef::Str("demo")),
+            (
+                "deps",
+                ValueRef::Seq(vec![
+                    ValueRef::Map(vec![("url", ValueRef::Str("https://x"))]),
+                    ValueRef::Map(vec![("lazy", ValueRef::Number("1"))]),
+                ]),
+            ),
+            ("extra", ValueRef::Bool(true)),
+        ]);
+
+        let violations = schema.validate(&value);
+        assert_eq!(
+            violations
+                .iter()
+                .map(|v| (v.path.join("."), v.code()))
+                .collect::<Vec<_>>(),
+            [
+                ("name".to_string(), "SCHEMA002"),
+                ("deps.1.url".to_string(), "SCHEMA001"),
+                ("deps.1.lazy".to_string(), "SCHEMA002"),
+                ("extra".to_string(), "SCHEMA003"),
+            ]
+        );
+        assert_eq!(
+            violations[0].to_string(),
+            "`name`: expected identifier, found string"
+        );
+        assert!(Schema::default().validate(&ValueRef::Opaque).is_empty());
+    }
+
+    #[test]
+    fn test_minimum() {
+        let schema = Schema::of(ValueKind::Number).at_least(34.0);
+        let below: Vec<_> = ["33", "0x21", "1_0", "20L", "34", "0x22", "35.5"]
+            .into_iter()
+            .filter(|raw| !schema.validate(&ValueRef::Number(raw)).is_empty())
+            .collect();
+        assert_eq!(below, ["33", "0x21", "1_0", "20L"]);
+
+        let violation = &schema.validate(&ValueRef::Number("21"))[0];
+        assert_eq!(violation.code(), "SCHEMA004");
+        assert_eq!(violation.to_string(), "21 is less than the minimum 34");
+    }
+
+    #[test]
+    fn test_string_constraints() {
+        let versions = Schema::of(ValueKind::Str).at_least_version("1.21");
+        let older: Vec<_> = [
+            "1.9",
+            "v1.20.14",
+            "1.21.0-rc.1",
+            "1.21",
+            "1.21.0",
+            "1.21rc1",
+            "v1.22-rc.1",
+            "2",
+        ]
+        .into_iter()
+        .filter(|raw| !versions.validate(&ValueRef::Str(raw)).is_empty())
+        .collect();
+        assert_eq!(older, ["1.9", "v1.20.14", "1.21.0-rc.1"]);
+
+        let globs = ["corp/*", "*.internal/*/v2", "*", "exact"];
+        let matching = |text| globs.iter().filter(|glob| glob_match(glob, text)).count();
+        assert_eq!(matching("corp/tools"), 2);
+        assert_eq!(matching("git.internal/x/v2"), 2);
+        assert_eq!(matching("git.internal/x/v2/y"), 1);
+        assert_eq!(matching("exact"), 2);
+
+        const LOWERCASE: Predicate = Predicate::Test {
+            name: "lowercase name",
+            test: |s| !s.chars().any(char::is_uppercase),
+        };
+        let internal = Schema::default()
+            .required("name", Schema::matching("corp/*"))
+            .required("version", Schema::default().satisfies(LOWERCASE))
+            .describe("lowercase internal version");
+        let schema = Schema::of(ValueKind::Seq).items(Schema::default().forbid(internal));
+        let value = ValueRef::Seq(vec![
+            ValueRef::Map(vec![
+                ("name", ValueRef::Str("corp/a")),
+                ("version", ValueRef::Str("dev")),
+            ]),
+            ValueRef::Map(vec![
+                ("name", ValueRef::Str("corp/b")),
+                ("version", ValueRef::Str("DEV")),
+            ]),
+            ValueRef::Map(vec![
+                ("name", ValueRef::Str("other")),
+                ("version", ValueRef::Str("dev")),
+            ]),
+        ]);
+        let violations = schema.validate(&value);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].code(), "SCHEMA006");
+        assert_eq!(
+            violations[0].to_string(),
+            "`0`: lowercase internal version is not allowed"
+        );
+        assert_eq!(
+            Schema::default()
+                .satisfies(LOWERCASE)
+                .validate(&ValueRef::Str("Up"))[0]
+                .to_string(),
+            "`Up` is not a lowercase name"
+        );
+    }
+}
@@ -0,0 +1,61 @@
+/// Equality that ignores differences with no meaning in the source format.
+///
+/// Two values are semantically equal when a build tool would treat the files
+/// they came from the same way, even if statements were reordered or
+/// literals spelled differently. Use it for change detection and in tests
+/// where `==` would report false positives.
+pub trait SemanticEq {
+    /// Returns `true` if `self` and `other` mean the same thing.
+    fn semantic_eq(&self, other: &Self) -> bool;
+}
+
+impl<T: SemanticEq> SemanticEq for [T] {
+    fn semantic_eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().zip(other).all(|(a, b)| a.semantic_eq(b))
+    }
+}
+
+impl<T: SemanticEq> SemanticEq for Option<T> {
+    fn semantic_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Some(a), Some(b)) => a.semantic_eq(b),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Compares two slices as multisets, using `semantic_eq` for the elements.
+pub fn unordered_eq<T: SemanticEq>(a: &[T], b: &[T]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let mut used = vec![false; b.len()];
+    a.iter().all(|x| {
+        let found = (0..b.len()).find(|&i| !used[i] && x.semantic_eq(&b[i]));
+        found.map(|i| used[i] = true).is_some()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct Word(&'static str);
+
+    impl SemanticEq for Word {
+        fn semantic_eq(&self, other: &Self) -> bool {
+            self.0.eq_ignore_ascii_case(other.0)
+        }
+    }
+
+    #[test]
+    fn test_unordered_eq_counts_duplicates() {
+        let a = [Word("a"), Word("B"), Word("a")];
+        assert!(unordered_eq(&a, &[Word("b"), Word("A"), Word("a")]));
+        assert!(!unordered_eq(&a, &[Word("a"), Word("b"), Word("b")]));
+        assert!(!a[..].semantic_eq(&[Word("b"), Word("a"), Word("a")][..]));
+    }
+}
@@ -0,0 +1,94 @@
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// How serious a [`Diagnostic`](crate::Diagnostic) is, ordered from
+/// [`Hint`](Severity::Hint) up to [`Error`](Severity::Error), so
+/// `severity >= Severity::Warning` keeps warnings and errors.
+///
+/// ```rust
+/// use uniparse_core::Severity;
+///
+/// let fail_on: Severity = "warning".parse().unwrap();
+/// assert!(Severity::Error >= fail_on);
+/// assert!(Severity::Info < fail_on);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Severity {
+    /// A suggestion, e.g. a shorter way to write something.
+    Hint,
+    /// Worth knowing, but nothing to fix.
+    Info,
+    /// Likely a mistake, but the manifest still works.
+    Warning,
+    /// The manifest is wrong or breaks a policy; CI should fail.
+    Error,
+}
+
+impl Severity {
+    /// All severities, least serious first.
+    pub const ALL: [Severity; 4] = [
+        Severity::Hint,
+        Severity::Info,
+        Severity::Warning,
+        Severity::Error,
+    ];
+
+    /// Lowercase name, as used by `Display` and `FromStr`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Severity::Hint => "hint",
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Severity {
+    type Err = String;
+
+    /// Parses a name as printed by `Display`, ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Severity::ALL
+            .into_iter()
+            .find(|severity| severity.as_str().eq_ignore_ascii_case(s))
+            .ok_or_else(|| {
+                format!(
+                    "unknown severity `{}`, expected one of error, warning, info, hint",
+                    s
+                )
+            })
+    }
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_order_and_names() {
+        let mut severities = vec![Severity::Info, Severity::Error, Severity::Hint];
+        severities.sort();
+        assert_eq!(
+            severities,
+            [Severity::Hint, Severity::Info, Severity::Error]
+        );
+        for severity in Severity::ALL {
+            assert_eq!(severity.to_string().parse::<Severity>(), Ok(severity));
+        }
+        assert_eq!("Warning".parse::<Severity>(), Ok(Severity::Warning));
+        assert!("fatal".parse::<Severity>().is_err());
+    }
+}
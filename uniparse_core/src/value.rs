@@ -0,0 +1,118 @@
+/// A borrowed, format-agnostic view of a value in a parsed file.
+///
+/// Returned by [`ParsedFile::get_ref`](crate::ParsedFile::get_ref) and the
+/// `get_ref` methods of the format crates, so read-only tooling can walk
+/// `.zon`, Gradle and `go.mod` models without cloning them or matching on
+/// each format's own value enum. Strings borrow from the model; only the
+/// `Seq` and `Map` vectors are allocated.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValueRef<'a> {
+    Str(&'a str),
+    Bool(bool),
+    /// A number literal as written, e.g. `0x1f`.
+    Number(&'a str),
+    /// A bare name: a `.zon` enum literal or a Gradle reference such as
+    /// `libs.retrofit`.
+    Ident(&'a str),
+    Seq(Vec<ValueRef<'a>>),
+    /// Entries in source order. Keys may repeat where the format allows it.
+    Map(Vec<(&'a str, ValueRef<'a>)>),
+    /// A value with no format-agnostic reading, e.g. an unevaluated Gradle
+    /// expression.
+    Opaque,
+}
+
+impl<'a> ValueRef<'a> {
+    pub fn as_str(&self) -> Option<&'a str> {
+        match self {
+            ValueRef::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            ValueRef::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_seq(&self) -> Option<&[ValueRef<'a>]> {
+        match self {
+            ValueRef::Seq(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    /// The value of `key` in a map, the last one if it repeats.
+    pub fn get(&self, key: &str) -> Option<&ValueRef<'a>> {
+        match self {
+            ValueRef::Map(entries) => entries
+                .iter()
+                .rev()
+                .find(|(k, _)| *k == key)
+                .map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Follows `path` down from this value: map keys by name, sequence items
+    /// by index.
+    pub fn lookup(self, path: &[&str]) -> Option<ValueRef<'a>> {
+        let Some((first, rest)) = path.split_first() else {
+            return Some(self);
+        };
+        let next = match self {
+            ValueRef::Map(entries) => entries.into_iter().rev().find(|(k, _)| k == first)?.1,
+            ValueRef::Seq(items) => items.into_iter().nth(first.parse().ok()?)?,
+            _ => return None,
+        };
+        next.lookup(rest)
+    }
+}
+
+/// Conversion of a format's value type into a [`ValueRef`].
+pub trait AsValueRef {
+    fn as_value_ref(&self) -> ValueRef<'_>;
+}
+
+impl AsValueRef for str {
+    fn as_value_ref(&self) -> ValueRef<'_> {
+        ValueRef::Str(self)
+    }
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accessors() {
+        let value = ValueRef::Map(vec![
+            ("name", ValueRef::Str("a")),
+            ("paths", ValueRef::Seq(vec![ValueRef::Str("src")])),
+            ("name", ValueRef::Str("b")),
+        ]);
+
+        assert_eq!(value.get("name").and_then(ValueRef::as_str), Some("b"));
+        assert_eq!(
+            value
+                .get("paths")
+                .and_then(ValueRef::as_seq)
+                .map(<[_]>::len),
+            Some(1)
+        );
+        assert_eq!(value.get("missing"), None);
+        assert_eq!(ValueRef::Bool(true).as_bool(), Some(true));
+        assert_eq!(ValueRef::Ident("x").as_str(), None);
+        assert_eq!(
+            value.clone().lookup(&["paths", "0"]),
+            Some(ValueRef::Str("src"))
+        );
+        assert_eq!(value.lookup(&["paths", "1"]), None);
+    }
+}
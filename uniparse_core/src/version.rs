@@ -0,0 +1,76 @@
+use std::cmp::Ordering;
+
+/// Orders the pre-release parts of two versions that share their release,
+/// by semver precedence: `None`, a release, sorts after any pre-release,
+/// and dot-separated identifiers compare numerically when both are numbers
+/// and otherwise as text, with numbers below text.
+///
+/// Go modules, Zig versions and schema minimums all order their
+/// pre-releases through this, so `1.0.0-rc.2` is older than `1.0.0-rc.10`
+/// everywhere.
+///
+/// ```
+/// use std::cmp::Ordering;
+/// use uniparse_core::compare_prerelease;
+///
+/// assert_eq!(compare_prerelease(Some("rc.2"), Some("rc.10")), Ordering::Less);
+/// assert_eq!(compare_prerelease(Some("dev.2"), None), Ordering::Less);
+/// ```
+pub fn compare_prerelease(a: Option<&str>, b: Option<&str>) -> Ordering {
+    let (a, b) = match (a, b) {
+        (None, None) => return Ordering::Equal,
+        (None, Some(_)) => return Ordering::Greater,
+        (Some(_), None) => return Ordering::Less,
+        (Some(a), Some(b)) => (a, b),
+    };
+    let mut a = a.split('.');
+    let mut b = b.split('.');
+    loop {
+        let ordering = match (a.next(), b.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) => match (x.parse::<u64>(), y.parse::<u64>()) {
+                (Ok(x), Ok(y)) => x.cmp(&y),
+                (Ok(_), Err(_)) => Ordering::Less,
+                (Err(_), Ok(_)) => Ordering::Greater,
+                (Err(_), Err(_)) => x.cmp(y),
+            },
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_prerelease_follows_semver() {
+        // the precedence example of the semver spec
+        let ordered = [
+            Some("alpha"),
+            Some("alpha.1"),
+            Some("alpha.beta"),
+            Some("beta"),
+            Some("beta.2"),
+            Some("beta.11"),
+            Some("rc.1"),
+            None,
+        ];
+        for pair in ordered.windows(2) {
+            assert_eq!(compare_prerelease(pair[0], pair[1]), Ordering::Less);
+            assert_eq!(compare_prerelease(pair[1], pair[0]), Ordering::Greater);
+        }
+        assert_eq!(
+            compare_prerelease(Some("1.x"), Some("1.x")),
+            Ordering::Equal
+        );
+    }
+}
@@ -0,0 +1,99 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::cancel::CancellationToken;
+
+/// Calls `visit` with every file below `dir`, descending only into the
+/// subdirectories `descend` accepts. Checks `cancel` before each directory.
+///
+/// Symbolic links are not followed into directories, so a link back to an
+/// ancestor can't make the walk loop forever; links to files are visited
+/// like files.
+///
+/// ```rust
+/// use uniparse_core::{CancellationToken, walk_files};
+///
+/// let mut sources = Vec::new();
+/// walk_files(
+///     env!("CARGO_MANIFEST_DIR"),
+///     &CancellationToken::new(),
+///     |dir| !dir.ends_with("target"),
+///     |file| {
+///         if file.extension().is_some_and(|ext| ext == "rs") {
+///             sources.push(file);
+///         }
+///     },
+/// )
+/// .unwrap();
+/// assert!(sources.iter().any(|file| file.ends_with("walk.rs")));
+/// ```
+///
+/// # Errors
+/// The first IO error reading a directory, or [`CancellationToken::check`]'s
+/// once cancelled.
+pub fn walk_files(
+    dir: impl AsRef<Path>,
+    cancel: &CancellationToken,
+    mut descend: impl FnMut(&Path) -> bool,
+    mut visit: impl FnMut(PathBuf),
+) -> io::Result<()> {
+    walk(dir.as_ref(), cancel, &mut descend, &mut visit)
+}
+
+fn walk(
+    dir: &Path,
+    cancel: &CancellationToken,
+    descend: &mut dyn FnMut(&Path) -> bool,
+    visit: &mut dyn FnMut(PathBuf),
+) -> io::Result<()> {
+    cancel.check()?;
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        // unlike `Path::is_dir`, doesn't follow symbolic links
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            if descend(&path) {
+                walk(&path, cancel, descend, visit)?;
+            }
+        } else if !file_type.is_symlink() || path.is_file() {
+            visit(path);
+        }
+    }
+    Ok(())
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_does_not_follow_symlinked_dirs() {
+        let root = std::env::temp_dir().join("uniparse_core_walk_symlinks");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("a")).unwrap();
+        fs::write(root.join("a/go.mod"), "module a\n").unwrap();
+        // a loop back to the root and a link to a file
+        std::os::unix::fs::symlink(&root, root.join("a/loop")).unwrap();
+        std::os::unix::fs::symlink(root.join("a/go.mod"), root.join("linked.mod")).unwrap();
+
+        let mut found = Vec::new();
+        walk_files(
+            &root,
+            &CancellationToken::new(),
+            |_| true,
+            |file| found.push(file.strip_prefix(&root).unwrap().to_path_buf()),
+        )
+        .unwrap();
+        found.sort();
+        assert_eq!(found, [Path::new("a/go.mod"), Path::new("linked.mod")]);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
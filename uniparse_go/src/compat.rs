@@ -0,0 +1,20 @@
+//! The signatures this crate had before the filesystem moved behind the
+//! `fs` feature, kept as deprecated wrappers so callers can migrate one
+//! call site at a time. See [`uniparse_core::compat`] for the policy.
+
+use std::fs;
+use std::path::Path;
+
+use crate::model::{GoMod, ParseError};
+
+/// Parses a `go.mod` file from the given path.
+///
+/// # Errors
+/// Returns a [`ParseError`] if the file can't be read or parsed.
+#[deprecated(
+    since = "0.1.0",
+    note = "use `GoMod::parse_file`, or read the file and call `GoMod::parse_str`"
+)]
+pub fn parse_file(path: impl AsRef<Path>) -> Result<GoMod, ParseError> {
+    GoMod::parse_str(&fs::read_to_string(path)?)
+}
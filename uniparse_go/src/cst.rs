@@ -0,0 +1,656 @@
+//! Lossless syntax tree of a `go.mod` file, for edits that must leave the
+//! rest of the file exactly as it was.
+//!
+//! [`GoMod`] keeps only the data. [`GoModCst`] keeps every byte on top of
+//! the [`uniparse_core`] tree types: each directive line becomes a
+//! [`SyntaxKind::Directive`] node and each `( ... )` block a
+//! [`SyntaxKind::Block`] node with one [`SyntaxKind::Entry`] per line. The
+//! comment lines directly above a line and a comment at its end belong to
+//! its node, so deleting an entry takes its comments along.
+
+use std::fmt::{self, Display, Formatter};
+
+use uniparse_core::{
+    CstKind, GreenElement, GreenNode, GreenNodeBuilder, GreenToken, SyntaxElement, SyntaxNode,
+    SyntaxToken,
+};
+
+use crate::model::{GoMod, ParseError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SyntaxKind {
+    /// The whole file.
+    File,
+    /// A one-line directive such as `go 1.21`, with its comments.
+    Directive,
+    /// `require ( ... )`, from the keyword to the `)`.
+    Block,
+    /// One line of a block with its comments.
+    Entry,
+    /// The directive name, e.g. `require`.
+    Keyword,
+    /// A module path, version or file path, quotes included if quoted.
+    Word,
+    /// `=>`
+    Arrow,
+    OpenParen,
+    CloseParen,
+    /// `[` of a retracted version range.
+    OpenBracket,
+    CloseBracket,
+    Comma,
+    Whitespace,
+    Newline,
+    /// `// ...`
+    Comment,
+}
+
+impl CstKind for SyntaxKind {
+    fn is_trivia(self) -> bool {
+        matches!(
+            self,
+            SyntaxKind::Whitespace | SyntaxKind::Newline | SyntaxKind::Comment
+        )
+    }
+
+    fn is_comment(self) -> bool {
+        self == SyntaxKind::Comment
+    }
+}
+
+/// A `go.mod` file as a lossless syntax tree, for tools that bump a
+/// dependency without touching anything else.
+///
+/// ```rust
+/// use uniparse_go::GoModCst;
+///
+/// let src = "module example.com/m\n\ngo 1.21\n\nrequire (\n\t// pinned, see #12\n\tgithub.com/a/x v1.0.0\n)\n";
+/// let mut cst = GoModCst::parse(src).unwrap();
+/// assert_eq!(cst.to_string(), src);
+///
+/// cst.bump_version("github.com/a/x", "v1.0.1");
+/// assert_eq!(
+///     cst.to_string(),
+///     "module example.com/m\n\ngo 1.21\n\nrequire (\n\t// pinned, see #12\n\tgithub.com/a/x v1.0.1\n)\n"
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct GoModCst {
+    root: SyntaxNode<SyntaxKind>,
+}
+
+impl GoModCst {
+    /// Parses `source`, which must also be valid for
+    /// [`GoMod::parse_str`]. Printing the result gives back `source`
+    /// unchanged.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(len = source.len()), err)
+    )]
+    pub fn parse(source: &str) -> Result<Self, ParseError> {
+        GoMod::parse_str(source)?;
+        let lines: Vec<_> = source.split_inclusive('\n').map(Line::lex).collect();
+        let mut builder = GreenNodeBuilder::new();
+        builder.start_node(SyntaxKind::File);
+
+        let mut in_block = false;
+        let mut n = 0;
+        while n < lines.len() {
+            let line = &lines[n];
+            if !line.is_code() {
+                // comment lines directly above a line belong to its node
+                let end = (n..lines.len())
+                    .find(|&k| !lines[k].is_comment())
+                    .unwrap_or(lines.len());
+                let attached = line.is_comment()
+                    && lines
+                        .get(end)
+                        .is_some_and(|next| next.is_code() && !(in_block && next.closes_block()));
+                if !attached {
+                    line.emit(&mut builder, 0);
+                    n += 1;
+                    continue;
+                }
+            }
+
+            let code = (n..lines.len()).find(|&k| lines[k].is_code()).unwrap_or(n);
+            let kind = match &lines[code] {
+                l if in_block && l.closes_block() => None,
+                _ if in_block => Some(SyntaxKind::Entry),
+                l if l.opens_block() => Some(SyntaxKind::Block),
+                _ => Some(SyntaxKind::Directive),
+            };
+            let Some(kind) = kind else {
+                // `)`, which ends the block node
+                let close = &lines[code];
+                close.emit_content(&mut builder, 0, false);
+                builder.finish_node();
+                close.emit_newline(&mut builder);
+                in_block = false;
+                n = code + 1;
+                continue;
+            };
+
+            let indent = lines[n].indent_len();
+            lines[n].emit_range(&mut builder, 0, indent);
+            builder.start_node(kind);
+            for (k, line) in lines.iter().enumerate().take(code).skip(n) {
+                line.emit(&mut builder, if k == n { indent } else { 0 });
+            }
+            let first = if code == n { indent } else { 0 };
+            lines[code].emit_content(&mut builder, first, !in_block);
+            if kind == SyntaxKind::Block {
+                lines[code].emit_newline(&mut builder);
+                in_block = true;
+            } else {
+                builder.finish_node();
+                lines[code].emit_newline(&mut builder);
+            }
+            n = code + 1;
+        }
+        if in_block {
+            builder.finish_node();
+        }
+
+        builder.finish_node();
+        Ok(GoModCst {
+            root: SyntaxNode::new_root(builder.finish()),
+        })
+    }
+
+    pub fn syntax(&self) -> &SyntaxNode<SyntaxKind> {
+        &self.root
+    }
+
+    /// Sets the version of the required module `path`, changing only that
+    /// word of the file. Returns `false` if `path` isn't required.
+    pub fn bump_version(&mut self, path: &str, version: &str) -> bool {
+        let Some(node) = self.find("require", path) else {
+            return false;
+        };
+        let Some(old) = words(&node).nth(1) else {
+            return false;
+        };
+        self.root = SyntaxNode::new_root(old.replace_with(word(version)));
+        true
+    }
+
+    /// Sets the `go` line's version. Returns `false` if there is no `go`
+    /// line.
+    pub fn set_go_version(&mut self, version: &str) -> bool {
+        let Some(old) = self.nodes("go").last().and_then(|node| words(&node).next()) else {
+            return false;
+        };
+        self.root = SyntaxNode::new_root(old.replace_with(word(version)));
+        true
+    }
+
+    /// Requires `path` at `version`. A module that is already required gets
+    /// its version set; otherwise the line goes after the last entry of
+    /// the last `require` block, copying its indentation, or after the last
+    /// `require` line. A file without requirements gets the directive
+    /// appended. New lines end in `\r\n` if the file's lines do.
+    pub fn add_require(&mut self, path: &str, version: &str) {
+        if self.bump_version(path, version) {
+            return;
+        }
+        let text = self.root.to_string();
+        // the lexer keeps the `\r` of a `\r\n` at the end of the line
+        let cr = || text.contains("\r\n").then(|| whitespace("\r"));
+        let entry = |kind| {
+            let mut children = Vec::new();
+            if kind == SyntaxKind::Directive {
+                children.extend([token(SyntaxKind::Keyword, "require"), whitespace(" ")]);
+            }
+            children.extend([word(path).into(), whitespace(" "), word(version).into()]);
+            children.extend(cr());
+            GreenElement::from(GreenNode::new(kind, children))
+        };
+
+        let requires: Vec<_> = self.nodes("require").collect();
+        let block = requires.iter().rfind(|n| n.kind() == SyntaxKind::Block);
+        let green = if let Some(block) = block {
+            let last = block
+                .children()
+                .filter(|n| n.kind() == SyntaxKind::Entry)
+                .last();
+            match last {
+                Some(last) => {
+                    let indent = indent_before(&last).unwrap_or_else(|| "\t".into());
+                    let at = last.index() + 1;
+                    block.splice_children(
+                        at..at,
+                        [newline(), whitespace(&indent), entry(SyntaxKind::Entry)],
+                    )
+                }
+                None => {
+                    // `require (\n)`: right after the first line break
+                    let at = block
+                        .children_with_tokens()
+                        .iter()
+                        .position(|c| c.kind() == SyntaxKind::Newline)
+                        .map_or(block.green().children().len(), |i| i + 1);
+                    block.splice_children(
+                        at..at,
+                        [whitespace("\t"), entry(SyntaxKind::Entry), newline()],
+                    )
+                }
+            }
+        } else if let Some(line) = requires.last() {
+            let at = line.index() + 1;
+            self.root
+                .splice_children(at..at, [newline(), entry(SyntaxKind::Directive)])
+        } else {
+            let mut elements = Vec::new();
+            let blank_line = text.ends_with("\n\n") || text.ends_with("\n\r\n");
+            if !text.is_empty() && !blank_line {
+                if !text.ends_with('\n') {
+                    elements.extend(cr());
+                    elements.push(newline());
+                }
+                elements.extend(cr());
+                elements.push(newline());
+            }
+            elements.extend([entry(SyntaxKind::Directive), newline()]);
+            let end = self.root.green().children().len();
+            self.root.splice_children(end..end, elements)
+        };
+        self.root = SyntaxNode::new_root(green);
+    }
+
+    /// Deletes the requirement of `path` with the comments attached to it,
+    /// and its block if that ends up empty. Returns `false` if `path` isn't
+    /// required.
+    pub fn drop_require(&mut self, path: &str) -> bool {
+        let Some(node) = self.find("require", path) else {
+            return false;
+        };
+        let target = match node.parent() {
+            Some(block)
+                if block.kind() == SyntaxKind::Block
+                    && block
+                        .children()
+                        .filter(|n| n.kind() == SyntaxKind::Entry)
+                        .count()
+                        == 1 =>
+            {
+                block
+            }
+            _ => node,
+        };
+        self.root = SyntaxNode::new_root(remove_line(&target));
+        true
+    }
+
+    /// Directive lines, blocks and block entries of `directive`, in source
+    /// order.
+    fn nodes(&self, directive: &str) -> impl Iterator<Item = SyntaxNode<SyntaxKind>> {
+        self.root.descendants().filter(move |node| {
+            let owner = match node.kind() {
+                SyntaxKind::Directive | SyntaxKind::Block => Some(node.clone()),
+                SyntaxKind::Entry => node.parent(),
+                _ => None,
+            };
+            owner.is_some_and(|owner| {
+                owner
+                    .tokens()
+                    .find(|t| t.kind() == SyntaxKind::Keyword)
+                    .is_some_and(|t| t.text() == directive)
+            })
+        })
+    }
+
+    /// The last line of `directive` for module `path`.
+    fn find(&self, directive: &str, path: &str) -> Option<SyntaxNode<SyntaxKind>> {
+        self.nodes(directive)
+            .filter(|node| node.kind() != SyntaxKind::Block)
+            .filter(|node| {
+                words(node)
+                    .next()
+                    .is_some_and(|w| unquote(w.text()) == path)
+            })
+            .last()
+    }
+}
+
+impl Display for GoModCst {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.root.fmt(f)
+    }
+}
+
+/// The words directly in `node`, without the directive keyword.
+fn words(node: &SyntaxNode<SyntaxKind>) -> impl Iterator<Item = SyntaxToken<SyntaxKind>> {
+    node.children_with_tokens()
+        .into_iter()
+        .filter_map(|child| match child {
+            SyntaxElement::Token(t) if t.kind() == SyntaxKind::Word => Some(t),
+            _ => None,
+        })
+}
+
+fn unquote(text: &str) -> &str {
+    text.trim_matches(['"', '`'])
+}
+
+/// The indentation on the line of `node`, if it starts the line.
+fn indent_before(node: &SyntaxNode<SyntaxKind>) -> Option<String> {
+    let parent = node.parent()?;
+    let children = parent.children_with_tokens();
+    let index = node.index();
+    match index.checked_sub(1).map(|i| &children[i]) {
+        Some(SyntaxElement::Token(t)) if t.kind() == SyntaxKind::Whitespace => {
+            Some(t.text().to_string())
+        }
+        _ => None,
+    }
+}
+
+/// The tree without the line(s) of `node`: its indentation, the node and
+/// its line break, plus a blank line after it if there is one before it too.
+fn remove_line(node: &SyntaxNode<SyntaxKind>) -> GreenNode<SyntaxKind> {
+    let Some(parent) = node.parent() else {
+        return node.green().clone();
+    };
+    let children = parent.children_with_tokens();
+    let kind = |i: usize| children.get(i).map(|c| c.kind());
+
+    let mut start = node.index();
+    if start > 0 && kind(start - 1) == Some(SyntaxKind::Whitespace) {
+        start -= 1;
+    }
+    let mut end = node.index() + 1;
+    if kind(end) == Some(SyntaxKind::Newline) {
+        end += 1;
+    }
+    let blank_before = start == 0
+        || (kind(start - 1) == Some(SyntaxKind::Newline)
+            && (start == 1 || kind(start - 2) == Some(SyntaxKind::Newline)));
+    if blank_before && kind(end) == Some(SyntaxKind::Newline) {
+        end += 1;
+    }
+    parent.splice_children(start..end, [])
+}
+
+fn token(kind: SyntaxKind, text: &str) -> GreenElement<SyntaxKind> {
+    GreenToken::new(kind, text).into()
+}
+
+fn word(text: &str) -> GreenToken<SyntaxKind> {
+    GreenToken::new(SyntaxKind::Word, text)
+}
+
+fn whitespace(text: &str) -> GreenElement<SyntaxKind> {
+    token(SyntaxKind::Whitespace, text)
+}
+
+fn newline() -> GreenElement<SyntaxKind> {
+    token(SyntaxKind::Newline, "\n")
+}
+
+/// One line of the source split into tokens, the line break apart.
+struct Line<'a> {
+    leaves: Vec<(SyntaxKind, &'a str)>,
+    newline: bool,
+}
+
+impl<'a> Line<'a> {
+    fn lex(line: &'a str) -> Self {
+        let (text, newline) = match line.strip_suffix('\n') {
+            Some(text) => (text, true),
+            None => (line, false),
+        };
+        let mut leaves = Vec::new();
+        let mut rest = text;
+        while !rest.is_empty() {
+            let (kind, len) = if rest.starts_with("//") {
+                (
+                    SyntaxKind::Comment,
+                    rest.trim_end_matches([' ', '\t', '\r']).len(),
+                )
+            } else if rest.starts_with("=>") {
+                (SyntaxKind::Arrow, 2)
+            } else if let Some(c) = rest.chars().next()
+                && c.is_whitespace()
+            {
+                let len = rest
+                    .find(|c: char| !c.is_whitespace())
+                    .unwrap_or(rest.len());
+                (SyntaxKind::Whitespace, len)
+            } else {
+                match rest.as_bytes()[0] {
+                    b'(' => (SyntaxKind::OpenParen, 1),
+                    b')' => (SyntaxKind::CloseParen, 1),
+                    b'[' => (SyntaxKind::OpenBracket, 1),
+                    b']' => (SyntaxKind::CloseBracket, 1),
+                    b',' => (SyntaxKind::Comma, 1),
+                    quote @ (b'"' | b'`') => {
+                        let close = rest[1..].find(quote as char).map_or(rest.len(), |i| i + 2);
+                        (SyntaxKind::Word, close)
+                    }
+                    _ => {
+                        let len = rest
+                            .find(|c: char| c.is_whitespace() || "()[],\"`".contains(c))
+                            .unwrap_or(rest.len());
+                        let len = rest[..len].find("//").unwrap_or(len).max(1);
+                        (SyntaxKind::Word, len)
+                    }
+                }
+            };
+            leaves.push((kind, &rest[..len]));
+            rest = &rest[len..];
+        }
+        Line { leaves, newline }
+    }
+
+    fn significant(&self) -> impl Iterator<Item = &(SyntaxKind, &'a str)> {
+        self.leaves.iter().filter(|(kind, _)| !kind.is_trivia())
+    }
+
+    fn is_code(&self) -> bool {
+        self.significant().next().is_some()
+    }
+
+    fn is_comment(&self) -> bool {
+        !self.is_code() && self.leaves.iter().any(|(kind, _)| kind.is_comment())
+    }
+
+    fn opens_block(&self) -> bool {
+        self.significant().last().map(|(kind, _)| *kind) == Some(SyntaxKind::OpenParen)
+    }
+
+    fn closes_block(&self) -> bool {
+        self.significant().next().map(|(kind, _)| *kind) == Some(SyntaxKind::CloseParen)
+    }
+
+    /// Number of leading leaves that are indentation: 0 or 1.
+    fn indent_len(&self) -> usize {
+        match self.leaves.first() {
+            Some((SyntaxKind::Whitespace, _)) => 1,
+            _ => 0,
+        }
+    }
+
+    fn emit_range(&self, builder: &mut GreenNodeBuilder<SyntaxKind>, from: usize, to: usize) {
+        for (kind, text) in &self.leaves[from..to] {
+            builder.token(*kind, text);
+        }
+    }
+
+    /// The leaves from `from` on, without the line break. With `keyword`,
+    /// the first word is the directive.
+    fn emit_content(&self, builder: &mut GreenNodeBuilder<SyntaxKind>, from: usize, keyword: bool) {
+        let mut keyword = keyword;
+        for (kind, text) in &self.leaves[from..] {
+            let kind = match kind {
+                SyntaxKind::Word if keyword => SyntaxKind::Keyword,
+                kind => *kind,
+            };
+            keyword &= kind.is_trivia();
+            builder.token(kind, text);
+        }
+    }
+
+    fn emit_newline(&self, builder: &mut GreenNodeBuilder<SyntaxKind>) {
+        if self.newline {
+            builder.token(SyntaxKind::Newline, "\n");
+        }
+    }
+
+    fn emit(&self, builder: &mut GreenNodeBuilder<SyntaxKind>, from: usize) {
+        self.emit_content(builder, from, false);
+        self.emit_newline(builder);
+    }
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SRC: &str = "// Code generated by hand.
+
+module example.com/app
+
+go 1.21 // keep in sync with CI
+
+require (
+\tgithub.com/a/x v1.0.0 // indirect
+\t// pinned until the API settles
+\tgithub.com/a/y v0.3.0
+)
+
+require \"github.com/a/z\" v2.0.0+incompatible
+
+replace github.com/a/x => ../x
+
+retract [v1.0.0, v1.0.1] // published too early
+";
+
+    #[test]
+    fn test_round_trip_and_nodes() {
+        let cst = GoModCst::parse(SRC).unwrap();
+        assert_eq!(cst.to_string(), SRC);
+
+        let text = |kind| {
+            cst.syntax()
+                .descendants()
+                .filter(|n| n.kind() == kind)
+                .map(|n| n.to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            text(SyntaxKind::Entry),
+            [
+                "github.com/a/x v1.0.0 // indirect",
+                "// pinned until the API settles\n\tgithub.com/a/y v0.3.0"
+            ]
+        );
+        assert_eq!(text(SyntaxKind::Block).len(), 1);
+        assert!(text(SyntaxKind::Block)[0].ends_with(")"));
+        assert_eq!(
+            text(SyntaxKind::Directive),
+            [
+                "module example.com/app",
+                "go 1.21 // keep in sync with CI",
+                "require \"github.com/a/z\" v2.0.0+incompatible",
+                "replace github.com/a/x => ../x",
+                "retract [v1.0.0, v1.0.1] // published too early",
+            ]
+        );
+        assert!(GoModCst::parse("not a go.mod").is_err());
+    }
+
+    #[test]
+    fn test_edits_touch_only_their_line() {
+        let mut cst = GoModCst::parse(SRC).unwrap();
+        assert!(cst.bump_version("github.com/a/z", "v2.1.0+incompatible"));
+        assert!(cst.set_go_version("1.22"));
+        assert!(cst.drop_require("github.com/a/y"));
+        cst.add_require("github.com/a/w", "v0.1.0");
+        assert!(!cst.bump_version("github.com/a/missing", "v1.0.0"));
+        assert!(!cst.drop_require("github.com/a/missing"));
+
+        let text = cst.to_string();
+        assert_eq!(
+            text,
+            "// Code generated by hand.
+
+module example.com/app
+
+go 1.22 // keep in sync with CI
+
+require (
+\tgithub.com/a/x v1.0.0 // indirect
+\tgithub.com/a/w v0.1.0
+)
+
+require \"github.com/a/z\" v2.1.0+incompatible
+
+replace github.com/a/x => ../x
+
+retract [v1.0.0, v1.0.1] // published too early
+"
+        );
+        let gomod = GoMod::parse_str(&text).unwrap();
+        assert_eq!(gomod.go_version, "1.22");
+        assert_eq!(gomod.requires.len(), 3);
+    }
+
+    #[test]
+    fn test_emptied_block_and_new_directives() {
+        let src = "module m\n\ngo 1.21\n\nrequire (\n\t// only one\n\ta.com/x v1.0.0\n)\n\nexclude a.com/x v0.9.0\n";
+        let mut cst = GoModCst::parse(src).unwrap();
+        assert!(cst.drop_require("a.com/x"));
+        assert_eq!(
+            cst.to_string(),
+            "module m\n\ngo 1.21\n\nexclude a.com/x v0.9.0\n"
+        );
+
+        cst.add_require("a.com/y", "v1.2.0");
+        cst.add_require("a.com/z", "v0.1.0");
+        assert_eq!(
+            cst.to_string(),
+            "module m\n\ngo 1.21\n\nexclude a.com/x v0.9.0\n\nrequire a.com/y v1.2.0\nrequire a.com/z v0.1.0\n"
+        );
+
+        let mut empty = GoModCst::parse("module m\ngo 1.21\nrequire (\n)").unwrap();
+        empty.add_require("a.com/x", "v1.0.0");
+        assert_eq!(
+            empty.to_string(),
+            "module m\ngo 1.21\nrequire (\n\ta.com/x v1.0.0\n)"
+        );
+    }
+
+    #[test]
+    fn test_new_lines_keep_crlf() {
+        let src = "module m\r\ngo 1.21\r\n\r\nrequire (\r\n\ta.com/x v1.0.0\r\n)\r\n";
+        let mut cst = GoModCst::parse(src).unwrap();
+        cst.add_require("a.com/y", "v0.1.0");
+        assert_eq!(
+            cst.to_string(),
+            "module m\r\ngo 1.21\r\n\r\nrequire (\r\n\ta.com/x v1.0.0\r\n\ta.com/y v0.1.0\r\n)\r\n"
+        );
+
+        let mut single =
+            GoModCst::parse("module m\r\ngo 1.21\r\nrequire a.com/x v1.0.0\r\n").unwrap();
+        single.add_require("a.com/y", "v0.1.0");
+        assert_eq!(
+            single.to_string(),
+            "module m\r\ngo 1.21\r\nrequire a.com/x v1.0.0\r\nrequire a.com/y v0.1.0\r\n"
+        );
+
+        let mut none = GoModCst::parse("module m\r\ngo 1.21").unwrap();
+        none.add_require("a.com/x", "v1.0.0");
+        let text = none.to_string();
+        assert_eq!(
+            text,
+            "module m\r\ngo 1.21\r\n\r\nrequire a.com/x v1.0.0\r\n"
+        );
+        assert_eq!(GoModCst::parse(&text).unwrap().to_string(), text);
+    }
+}
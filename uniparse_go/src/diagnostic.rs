@@ -0,0 +1,152 @@
+use serde::{Deserialize, Serialize};
+use uniparse_core::{DiagnosticCode, Severity};
+
+use crate::model::{GoMod, ParseError};
+use crate::span::{GoModSpans, Span};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DiagnosticKind {
+    /// A malformed line, see [`ParseError::Syntax`].
+    Syntax,
+    /// The `module` or `go` directive is missing.
+    MissingField,
+    /// The input is a `go.sum` or `go.work` rather than a `go.mod`.
+    WrongFileType,
+}
+
+impl DiagnosticCode for DiagnosticKind {
+    fn code(&self) -> &'static str {
+        match self {
+            DiagnosticKind::Syntax => "GOMOD002",
+            DiagnosticKind::MissingField => "GOMOD003",
+            DiagnosticKind::WrongFileType => "GOMOD012",
+        }
+    }
+}
+
+/// A problem found by [`GoMod::parse_str_with_diagnostics`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub kind: DiagnosticKind,
+    pub message: String,
+    /// The line the problem is on, without its comment. Problems of the
+    /// whole file, such as a missing `module`, point at its start.
+    pub span: Span,
+    /// How to fix it, when there's an obvious way.
+    pub suggestion: Option<String>,
+}
+
+impl DiagnosticCode for Diagnostic {
+    fn code(&self) -> &'static str {
+        self.kind.code()
+    }
+
+    fn severity(&self) -> Severity {
+        self.kind.severity()
+    }
+}
+
+impl GoMod {
+    /// Parses like [`GoMod::parse_recovering`], reporting each problem as a
+    /// [`Diagnostic`] with the span of its line, for editors and linters.
+    ///
+    /// ```rust
+    /// use uniparse_go::{DiagnosticCode, GoMod};
+    ///
+    /// let src = "module example.com/m\n\nrequire (\n\tgithub.com/a/b\n\tgithub.com/c/d v1.0.0\n)\n";
+    /// let (gomod, diagnostics) = GoMod::parse_str_with_diagnostics(src);
+    /// assert_eq!(gomod.requires.len(), 1);
+    ///
+    /// let found: Vec<_> = diagnostics.iter().map(|d| (d.code(), d.span.line)).collect();
+    /// assert_eq!(found, [("GOMOD002", 4), ("GOMOD003", 1)]);
+    /// assert_eq!(&src[diagnostics[0].span.byte_range.clone()], "github.com/a/b");
+    /// ```
+    pub fn parse_str_with_diagnostics(content: &str) -> (GoMod, Vec<Diagnostic>) {
+        let mut errors = Vec::new();
+        let gomod = GoMod::parse_spanned(content, &mut GoModSpans::default(), &mut errors);
+        let diagnostics = errors
+            .into_iter()
+            .map(|err| diagnostic(content, err))
+            .collect();
+        (gomod, diagnostics)
+    }
+}
+
+fn diagnostic(content: &str, err: ParseError) -> Diagnostic {
+    let file_start = || Span::from_range(content, 0..0);
+    match err {
+        ParseError::Syntax {
+            line,
+            msg,
+            suggestion,
+        } => Diagnostic {
+            kind: DiagnosticKind::Syntax,
+            message: msg,
+            span: Span::of_line(content, line),
+            suggestion,
+        },
+        ParseError::MissingField(_) => Diagnostic {
+            kind: DiagnosticKind::MissingField,
+            message: err.to_string(),
+            span: file_start(),
+            suggestion: None,
+        },
+        // the suggestion has its own field, so leave it out of the message
+        ParseError::WrongFileType {
+            detected,
+            suggestion,
+        } => Diagnostic {
+            kind: DiagnosticKind::WrongFileType,
+            message: format!(
+                "Expected a go.mod file, but this looks like a {} file",
+                detected
+            ),
+            span: file_start(),
+            suggestion: Some(suggestion.to_string()),
+        },
+        // parsing a string never reads a file
+        ParseError::Io(_) => unreachable!("no IO while parsing a string"),
+        ParseError::SumEntry { .. } => unreachable!("a go.mod is never parsed as go.sum"),
+    }
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diagnostics_point_at_lines() {
+        let src = "module example.com/app\ngo 1.22\n\nrequire (\n    github.com/a/x v1.0.0 v2 // indirect\n)\nreplce github.com/a/x => ../x\n";
+        let (gomod, diagnostics) = GoMod::parse_str_with_diagnostics(src);
+        assert_eq!(gomod.module, "example.com/app");
+        assert!(gomod.requires.is_empty());
+
+        let found: Vec<_> = diagnostics
+            .iter()
+            .map(|d| (d.code(), d.span.line, d.span.column))
+            .collect();
+        assert_eq!(found, [("GOMOD002", 5, 5), ("GOMOD002", 7, 1)]);
+        assert_eq!(
+            &src[diagnostics[0].span.byte_range.clone()],
+            "github.com/a/x v1.0.0 v2"
+        );
+        assert_eq!(diagnostics[1].message, "unknown directive `replce`");
+        assert_eq!(
+            diagnostics[1].suggestion.as_deref(),
+            Some("did you mean `replace`?")
+        );
+
+        let (_, diagnostics) = GoMod::parse_str_with_diagnostics("a v1 h1:x=\n");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code(), "GOMOD012");
+        assert_eq!(diagnostics[0].span.byte_range, 0..0);
+        assert_eq!(diagnostics[0].severity(), Severity::Error);
+
+        let (_, diagnostics) = GoMod::parse_str_with_diagnostics("module m\ngo 1.22\n");
+        assert!(diagnostics.is_empty());
+    }
+}
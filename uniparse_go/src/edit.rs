@@ -0,0 +1,334 @@
+use crate::model::{GoDependency, GoMod, GoReplace};
+
+/// A batch of edits mirroring the flags of `go mod edit`.
+///
+/// ```rust
+/// use uniparse_go::{EditSpec, GoMod};
+///
+/// let mut gomod = GoMod::parse_str("module a.com/m\ngo 1.20\n").unwrap();
+/// let spec = EditSpec::from_flags(["-go=1.22", "-require=github.com/x/y@v1.2.3"]).unwrap();
+/// gomod.edit(spec);
+///
+/// assert_eq!(gomod.go_version, "1.22");
+/// assert_eq!(gomod.requires[0].version, "v1.2.3");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EditSpec {
+    /// `-module=path`
+    pub module: Option<String>,
+    /// `-go=version`
+    pub go: Option<String>,
+    /// `-require=path@version`: adds or updates a requirement.
+    pub require: Vec<GoDependency>,
+    /// `-droprequire=path`
+    pub drop_require: Vec<String>,
+    /// `-replace=old[@v]=new[@v]`: adds or overwrites a replacement. Without
+    /// `@v` on the left it overwrites the replacements of every version of
+    /// `old`, as `go mod edit` does.
+    pub replace: Vec<GoReplace>,
+    /// `-dropreplace=old[@v]`: `(path, version)` of the replacement to drop.
+    /// Like `go mod edit`, a missing version drops only a replacement
+    /// without one, e.g. `replace old => new`.
+    pub drop_replace: Vec<(String, Option<String>)>,
+}
+
+impl EditSpec {
+    /// Builds a spec from `go mod edit` style flags, e.g.
+    /// `-replace=example.com/a=../a`. Both `-flag` and `--flag` are accepted.
+    ///
+    /// # Errors
+    /// Returns `Err` for unknown flags or malformed values.
+    pub fn from_flags<I, S>(flags: I) -> Result<Self, String>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut spec = EditSpec::default();
+
+        for flag in flags {
+            let flag = flag.as_ref();
+            let (name, value) = flag
+                .trim_start_matches('-')
+                .split_once('=')
+                .ok_or_else(|| format!("Expected -flag=value, got `{}`", flag))?;
+            if value.is_empty() {
+                return Err(format!("Missing value for `{}`", flag));
+            }
+
+            match name {
+                "module" => spec.module = Some(value.to_string()),
+                "go" => spec.go = Some(value.to_string()),
+                "require" => {
+                    let (name, version) = split_path_version(value);
+                    let version = version
+                        .ok_or_else(|| format!("-require needs path@version, got `{}`", value))?;
+                    spec.require.push(GoDependency {
+                        name,
+                        version,
+                        indirect: false,
+                    });
+                }
+                "droprequire" => spec.drop_require.push(value.to_string()),
+                "replace" => {
+                    let (old, new) = value
+                        .split_once('=')
+                        .ok_or_else(|| format!("-replace needs old=new, got `{}`", value))?;
+                    let (old_path, old_version) = split_path_version(old);
+                    let (new_path, new_version) = split_path_version(new);
+                    spec.replace.push(GoReplace {
+                        old_path,
+                        old_version,
+                        new_path,
+                        new_version,
+                    });
+                }
+                "dropreplace" => spec.drop_replace.push(split_path_version(value)),
+                _ => return Err(format!("Unknown flag `{}`", flag)),
+            }
+        }
+
+        Ok(spec)
+    }
+}
+
+impl GoMod {
+    /// Applies an [`EditSpec`], the in-process equivalent of running
+    /// `go mod edit` with the same flags.
+    ///
+    /// Edits are applied in a fixed order: `module`, `go`, dropped
+    /// requirements, added requirements, dropped replacements, then added
+    /// replacements.
+    pub fn edit(&mut self, spec: EditSpec) {
+        if let Some(module) = spec.module {
+            self.module = module;
+        }
+        if let Some(go) = spec.go {
+            self.go_version = go;
+        }
+
+        for name in &spec.drop_require {
+            self.drop_require(name);
+        }
+        for dep in spec.require {
+            self.add_require(&dep.name, &dep.version);
+        }
+
+        self.replaces.retain(|r| {
+            !spec
+                .drop_replace
+                .iter()
+                .any(|(path, version)| *path == r.old_path && *version == r.old_version)
+        });
+        for replace in spec.replace {
+            // no version on the left covers every version of the module
+            let covers = |r: &GoReplace| {
+                r.old_path == replace.old_path
+                    && (replace.old_version.is_none() || r.old_version == replace.old_version)
+            };
+            match self.replaces.iter().position(covers) {
+                Some(first) => {
+                    let mut index = 0;
+                    self.replaces.retain(|r| {
+                        index += 1;
+                        index - 1 == first || !covers(r)
+                    });
+                    self.replaces[first] = replace;
+                }
+                None => self.replaces.push(replace),
+            }
+        }
+    }
+
+    /// Requires `name` at `version`, like `go mod edit -require`. An
+    /// existing requirement is updated in place and keeps its `// indirect`
+    /// mark; duplicate lines for the module are dropped.
+    ///
+    /// ```rust
+    /// use uniparse_go::GoMod;
+    ///
+    /// let mut gomod = GoMod::parse_str("module m\ngo 1.22\n").unwrap();
+    /// gomod.add_require("github.com/a/x", "v1.0.0");
+    /// gomod.add_require("github.com/a/x", "v1.1.0");
+    /// assert_eq!(gomod.requires.len(), 1);
+    /// assert!(gomod.bump_version("github.com/a/x", "v1.2.0"));
+    /// assert!(gomod.drop_require("github.com/a/x"));
+    /// assert!(!gomod.bump_version("github.com/a/x", "v1.3.0"));
+    /// ```
+    pub fn add_require(&mut self, name: &str, version: &str) {
+        if !self.bump_version(name, version) {
+            self.requires.push(GoDependency {
+                name: name.to_string(),
+                version: version.to_string(),
+                indirect: false,
+            });
+        }
+    }
+
+    /// Removes every requirement of `name`, like `go mod edit -droprequire`.
+    /// Returns whether the module was required.
+    pub fn drop_require(&mut self, name: &str) -> bool {
+        let len = self.requires.len();
+        self.requires.retain(|dep| dep.name != name);
+        self.requires.len() != len
+    }
+
+    /// Sets the version `name` is required at, keeping its place and its
+    /// `// indirect` mark; duplicate lines for the module are dropped.
+    /// Returns `false`, changing nothing, if `name` isn't required.
+    pub fn bump_version(&mut self, name: &str, new_version: &str) -> bool {
+        let Some(first) = self.requires.iter().position(|dep| dep.name == name) else {
+            return false;
+        };
+        let dep = &mut self.requires[first];
+        dep.version = new_version.to_string();
+
+        let mut index = 0;
+        self.requires.retain(|dep| {
+            index += 1;
+            index - 1 <= first || dep.name != name
+        });
+        true
+    }
+}
+
+fn split_path_version(s: &str) -> (String, Option<String>) {
+    match s.split_once('@') {
+        Some((path, version)) => (path.to_string(), Some(version.to_string())),
+        None => (s.to_string(), None),
+    }
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GO_MOD: &str = r#"
+    module example.com/app
+    go 1.20
+
+    require (
+        github.com/one/lib v1.0.0
+        github.com/two/lib v2.3.4
+    )
+
+    replace github.com/one/lib => ../one
+    replace github.com/two/lib v2.3.4 => github.com/fork/two v2.3.5
+    "#;
+
+    #[test]
+    fn test_edit_mirrors_go_mod_edit() {
+        let mut gomod = GoMod::parse_str(GO_MOD).unwrap();
+        let spec = EditSpec::from_flags([
+            "-module=example.com/renamed",
+            "-go=1.22",
+            "-require=github.com/one/lib@v1.1.0",
+            "-require=github.com/three/lib@v0.3.0",
+            "-droprequire=github.com/two/lib",
+            "-dropreplace=github.com/two/lib@v2.3.4",
+            "--replace=github.com/one/lib=github.com/fork/one@v1.1.1",
+        ])
+        .unwrap();
+        gomod.edit(spec);
+
+        assert_eq!(gomod.module, "example.com/renamed");
+        assert_eq!(gomod.go_version, "1.22");
+        let requires: Vec<_> = gomod
+            .requires
+            .iter()
+            .map(|d| format!("{}@{}", d.name, d.version))
+            .collect();
+        assert_eq!(
+            requires,
+            vec!["github.com/one/lib@v1.1.0", "github.com/three/lib@v0.3.0"]
+        );
+        assert_eq!(
+            gomod.replaces,
+            vec![GoReplace {
+                old_path: "github.com/one/lib".into(),
+                old_version: None,
+                new_path: "github.com/fork/one".into(),
+                new_version: Some("v1.1.1".into()),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_replace_without_version_covers_every_version() {
+        let mut gomod = GoMod::parse_str(
+            "module m\ngo 1.22\nreplace (\n\ta.com/x v1.0.0 => ../x1\n\tb.com/y => ../y\n\ta.com/x v2.0.0 => ../x2\n\ta.com/x => ../x\n)\n",
+        )
+        .unwrap();
+        let spec =
+            EditSpec::from_flags(["-dropreplace=a.com/x", "-replace=a.com/x=../fork"]).unwrap();
+        gomod.edit(spec);
+
+        let replaces: Vec<_> = gomod
+            .replaces
+            .iter()
+            .map(|r| {
+                (
+                    r.old_path.as_str(),
+                    r.old_version.as_deref(),
+                    r.new_path.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            replaces,
+            [("a.com/x", None, "../fork"), ("b.com/y", None, "../y")]
+        );
+    }
+
+    #[test]
+    fn test_require_edits_by_module_path() {
+        let mut gomod = GoMod::parse_str(
+            "module m
+go 1.22
+require (
+\tgithub.com/one/lib v1.0.0
+\tgithub.com/two/lib v2.0.0 // indirect
+\tgithub.com/one/lib v1.0.1
+)
+",
+        )
+        .unwrap();
+
+        assert!(gomod.bump_version("github.com/two/lib", "v2.1.0+incompatible"));
+        assert!(gomod.requires[1].indirect);
+        assert!(gomod.requires[1].is_incompatible());
+
+        // the duplicate line goes, the first keeps its place
+        gomod.add_require("github.com/one/lib", "v1.2.0");
+        gomod.add_require("github.com/three/lib", "v0.1.0");
+        let requires: Vec<_> = gomod
+            .requires
+            .iter()
+            .map(|d| format!("{}@{}", d.name, d.version))
+            .collect();
+        assert_eq!(
+            requires,
+            [
+                "github.com/one/lib@v1.2.0",
+                "github.com/two/lib@v2.1.0+incompatible",
+                "github.com/three/lib@v0.1.0"
+            ]
+        );
+
+        assert!(gomod.drop_require("github.com/two/lib"));
+        assert!(!gomod.drop_require("github.com/two/lib"));
+        assert!(!gomod.bump_version("github.com/four/lib", "v1.0.0"));
+        assert_eq!(gomod.requires.len(), 2);
+    }
+
+    #[test]
+    fn test_from_flags_errors() {
+        assert!(EditSpec::from_flags(["-require=github.com/x/y"]).is_err());
+        assert!(EditSpec::from_flags(["-replace=github.com/x/y"]).is_err());
+        assert!(EditSpec::from_flags(["-fmt"]).is_err());
+        assert!(EditSpec::from_flags(["-exclude=github.com/x/y@v1.0.0"]).is_err());
+    }
+}
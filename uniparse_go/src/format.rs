@@ -0,0 +1,398 @@
+use std::fmt::{self, Display, Formatter, Write};
+
+use crate::model::{GoDependency, GoMod, GoReplace, GoRetract};
+use crate::toolchain::GoRelease;
+
+/// Columns a leading tab takes up when measuring line width, as in gofmt.
+const TAB_WIDTH: usize = 8;
+
+/// Options for [`GoMod::to_string_with`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// Pad the old side of the entries of a `replace ( ... )` block so their
+    /// `=>` arrows line up.
+    pub align_replace_arrows: bool,
+    /// Preferred maximum line width: entries of a `replace` block that would
+    /// exceed it once padded are left unaligned. Lines too long unpadded
+    /// are kept, as `go.mod` has no way to break them. `None` means no
+    /// limit.
+    pub max_width: Option<usize>,
+}
+
+impl GoMod {
+    /// Writes the file in canonical go.mod syntax, the way `go mod tidy`
+    /// lays it out: one blank line between directives, a list directive
+    /// with more than one entry grouped into a `( ... )` block, and from
+    /// `go 1.17` on, the `// indirect` requirements in a `require` of their
+    /// own after the direct ones. Same as [`Display`].
+    ///
+    /// Comments other than `// indirect` and retraction rationales aren't
+    /// kept; [`GoMod::patch_source`] writes edits into the original text
+    /// instead.
+    ///
+    /// ```rust
+    /// use uniparse_go::GoMod;
+    ///
+    /// let mut gomod =
+    ///     GoMod::parse_str("module m\ngo 1.21\nrequire (\n\ta.com/x v1.0.0\n\ta.com/y v0.2.0\n)\n")
+    ///         .unwrap();
+    /// gomod.remove(&["requires", "0"]).unwrap();
+    /// gomod.set(&["excludes", "0"], "a.com/x@v1.0.0").unwrap();
+    /// assert_eq!(
+    ///     gomod.to_gomod_string(),
+    ///     "module m\n\ngo 1.21\n\nrequire a.com/y v0.2.0\n\nexclude a.com/x v1.0.0\n"
+    /// );
+    /// ```
+    pub fn to_gomod_string(&self) -> String {
+        self.to_string()
+    }
+
+    /// Writes the file in go.mod syntax using `options`; [`Display`] uses the
+    /// defaults.
+    ///
+    /// ```rust
+    /// use uniparse_go::{FormatOptions, GoMod};
+    ///
+    /// let gomod = GoMod::parse_str(
+    ///     "module m\ngo 1.21\nreplace a.com/x => ../x\nreplace example.com/long/path v1.0.0 => ../y\n",
+    /// )
+    /// .unwrap();
+    /// let options = FormatOptions {
+    ///     align_replace_arrows: true,
+    ///     ..FormatOptions::default()
+    /// };
+    /// assert!(gomod.to_string_with(&options).contains(
+    ///     "\ta.com/x                      => ../x\n\texample.com/long/path v1.0.0 => ../y\n"
+    /// ));
+    /// ```
+    pub fn to_string_with(&self, options: &FormatOptions) -> String {
+        let mut out = String::new();
+        // writing to a String can't fail
+        let _ = write_gomod(&mut out, self, options);
+        out
+    }
+}
+
+impl Display for GoMod {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write_gomod(f, self, &FormatOptions::default())
+    }
+}
+
+fn write_gomod(out: &mut impl Write, gomod: &GoMod, options: &FormatOptions) -> fmt::Result {
+    writeln!(out, "module {}", gomod.module)?;
+    writeln!(out, "\ngo {}", gomod.go_version)?;
+    if let Some(toolchain) = &gomod.toolchain {
+        writeln!(out, "\ntoolchain {}", toolchain)?;
+    }
+
+    // since 1.17 the go command lists indirect requirements separately
+    let split = GoRelease::parse(&gomod.go_version)
+        .zip(GoRelease::parse("1.17"))
+        .is_some_and(|(go, split_since)| go >= split_since);
+    if split {
+        let (indirect, direct): (Vec<_>, Vec<_>) = gomod.requires.iter().partition(|d| d.indirect);
+        write_requires(out, &direct)?;
+        write_requires(out, &indirect)?;
+    } else {
+        write_requires(out, &gomod.requires.iter().collect::<Vec<_>>())?;
+    }
+
+    match gomod.excludes.as_slice() {
+        [] => {}
+        [exclude] => writeln!(out, "\nexclude {} {}", exclude.name, exclude.version)?,
+        excludes => {
+            writeln!(out, "\nexclude (")?;
+            for exclude in excludes {
+                writeln!(out, "\t{} {}", exclude.name, exclude.version)?;
+            }
+            writeln!(out, ")")?;
+        }
+    }
+
+    write_replaces(out, &gomod.replaces, options)?;
+
+    match gomod.retracts.as_slice() {
+        [] => Ok(()),
+        [retract] => {
+            writeln!(out)?;
+            write_retract(out, retract, "", "retract ")
+        }
+        retracts => {
+            writeln!(out, "\nretract (")?;
+            for retract in retracts {
+                write_retract(out, retract, "\t", "")?;
+            }
+            writeln!(out, ")")
+        }
+    }
+}
+
+fn write_requires(out: &mut impl Write, deps: &[&GoDependency]) -> fmt::Result {
+    match deps {
+        [] => Ok(()),
+        [dep] => {
+            writeln!(out)?;
+            write_require(out, dep, "", "require ")
+        }
+        deps => {
+            writeln!(out, "\nrequire (")?;
+            for dep in deps {
+                write_require(out, dep, "\t", "")?;
+            }
+            writeln!(out, ")")
+        }
+    }
+}
+
+fn write_require(
+    out: &mut impl Write,
+    dep: &GoDependency,
+    indent: &str,
+    directive: &str,
+) -> fmt::Result {
+    let entry = format!("{} {}", dep.name, dep.version);
+    write_entry(
+        out,
+        indent,
+        directive,
+        &entry,
+        dep.indirect.then_some("indirect"),
+    )
+}
+
+fn write_retract(
+    out: &mut impl Write,
+    retract: &GoRetract,
+    indent: &str,
+    directive: &str,
+) -> fmt::Result {
+    write_entry(out, indent, directive, retract, retract.rationale())
+}
+
+/// Writes one directive line, `directive` being empty inside a block. A
+/// one-line `comment` goes at the end of the line and a longer one on
+/// comment lines above it, where the parser picks them up again.
+pub(crate) fn write_entry(
+    out: &mut impl Write,
+    indent: &str,
+    directive: &str,
+    entry: &dyn Display,
+    comment: Option<&str>,
+) -> fmt::Result {
+    let comment = comment.unwrap_or_default();
+    let multiline = comment.contains('\n');
+    if multiline {
+        for line in comment.lines() {
+            match line {
+                "" => writeln!(out, "{}//", indent)?,
+                line => writeln!(out, "{}// {}", indent, line)?,
+            }
+        }
+    }
+    write!(out, "{}{}{}", indent, directive, entry)?;
+    if !multiline && !comment.is_empty() {
+        write!(out, " // {}", comment)?;
+    }
+    writeln!(out)
+}
+
+pub(crate) fn write_replaces(
+    out: &mut impl Write,
+    replaces: &[GoReplace],
+    options: &FormatOptions,
+) -> fmt::Result {
+    let sides: Vec<(String, String)> = replaces
+        .iter()
+        .map(|r| {
+            let side = |path: &str, version: &Option<String>| match version {
+                Some(version) => format!("{} {}", path, version),
+                None => path.to_string(),
+            };
+            (
+                side(&r.old_path, &r.old_version),
+                side(&r.new_path, &r.new_version),
+            )
+        })
+        .collect();
+    let fits = |width: usize| options.max_width.is_none_or(|max| width <= max);
+
+    match sides.as_slice() {
+        [] => Ok(()),
+        [(old, new)] => writeln!(out, "\nreplace {} => {}", old, new),
+        sides => {
+            let column = sides
+                .iter()
+                .filter(|(old, new)| options.align_replace_arrows && fits(width(old, new)))
+                .map(|(old, _)| old.chars().count())
+                .max()
+                .unwrap_or(0);
+
+            writeln!(out, "\nreplace (")?;
+            for (old, new) in sides {
+                let padded = column.max(old.chars().count());
+                if fits(TAB_WIDTH + padded + " => ".len() + new.chars().count()) {
+                    writeln!(out, "\t{:<width$} => {}", old, new, width = padded)?;
+                } else {
+                    writeln!(out, "\t{} => {}", old, new)?;
+                }
+            }
+            writeln!(out, ")")
+        }
+    }
+}
+
+/// Width of the block entry `\told => new`, counting the tab as
+/// [`TAB_WIDTH`].
+fn width(old: &str, new: &str) -> usize {
+    TAB_WIDTH + old.chars().count() + " => ".len() + new.chars().count()
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SRC: &str = "module example.com/app
+
+go 1.21
+
+require (
+\tgithub.com/one/lib v1.0.0
+\tgithub.com/two/lib v2.0.0
+)
+
+replace (
+\tgithub.com/one/lib => ../one
+\tgithub.com/two/lib v2.0.0 => github.com/fork/two-with-a-long-name v2.0.1
+)
+";
+
+    #[test]
+    fn test_display_round_trips() {
+        let gomod = GoMod::parse_str(SRC).unwrap();
+        assert_eq!(gomod.to_string(), SRC);
+        assert_eq!(GoMod::parse_str(&gomod.to_string()).unwrap(), gomod);
+
+        let single = GoMod::parse_str("module m\ngo 1.22\nrequire a.com/x v1.0.0\n").unwrap();
+        assert_eq!(
+            single.to_string(),
+            "module m\n\ngo 1.22\n\nrequire a.com/x v1.0.0\n"
+        );
+    }
+
+    #[test]
+    fn test_indirect_requires_split_from_go_1_17() {
+        let requires =
+            "require (\n\ta.com/x v1.0.0 // indirect\n\ta.com/y v1.0.0\n\ta.com/z v1.0.0\n)\n";
+        let old = GoMod::parse_str(&format!("module m\n\ngo 1.16\n\n{}", requires)).unwrap();
+        assert_eq!(
+            old.to_string(),
+            format!("module m\n\ngo 1.16\n\n{}", requires)
+        );
+
+        let new = GoMod::parse_str(&format!("module m\ngo 1.22.1\n{}", requires)).unwrap();
+        assert_eq!(
+            new.to_string(),
+            "module m
+
+go 1.22.1
+
+require (
+\ta.com/y v1.0.0
+\ta.com/z v1.0.0
+)
+
+require a.com/x v1.0.0 // indirect
+"
+        );
+    }
+
+    #[test]
+    fn test_to_gomod_string_after_edits() {
+        let mut gomod = GoMod::parse_str(
+            "module m\ngo 1.21\nrequire a.com/x v1.0.0 // indirect\nexclude a.com/x v0.9.0\nretract v0.1.0 // oops\n",
+        )
+        .unwrap();
+        gomod.set(&["toolchain"], "go1.22.3").unwrap();
+        gomod.set(&["excludes", "1"], "a.com/x@v0.9.1").unwrap();
+        gomod.set(&["retracts", "1"], "[v0.2.0, v0.3.0]").unwrap();
+        gomod.remove(&["retracts", "0"]).unwrap();
+
+        let text = gomod.to_gomod_string();
+        assert_eq!(
+            text,
+            "module m
+
+go 1.21
+
+toolchain go1.22.3
+
+require a.com/x v1.0.0 // indirect
+
+exclude (
+\ta.com/x v0.9.0
+\ta.com/x v0.9.1
+)
+
+retract [v0.2.0, v0.3.0]
+"
+        );
+        assert_eq!(GoMod::parse_str(&text).unwrap(), gomod);
+    }
+
+    #[test]
+    fn test_aligned_arrows_respect_max_width() {
+        let mut gomod = GoMod::parse_str(SRC).unwrap();
+        gomod.replaces.insert(
+            0,
+            GoReplace {
+                old_path: "a.io/x".into(),
+                old_version: None,
+                new_path: "../x".into(),
+                new_version: None,
+            },
+        );
+
+        let aligned = gomod.to_string_with(&FormatOptions {
+            align_replace_arrows: true,
+            max_width: None,
+        });
+        assert!(aligned.contains(
+            "\ta.io/x                    => ../x\n\tgithub.com/one/lib        => ../one\n\tgithub.com/two/lib v2.0.0 => github.com/fork/two-with-a-long-name v2.0.1\n"
+        ));
+
+        // the long entry doesn't fit in 60 columns, so the others align
+        // among themselves
+        let narrow = gomod.to_string_with(&FormatOptions {
+            align_replace_arrows: true,
+            max_width: Some(60),
+        });
+        assert!(narrow.contains(
+            "\ta.io/x             => ../x\n\tgithub.com/one/lib => ../one\n\tgithub.com/two/lib v2.0.0 => github.com/fork/two-with-a-long-name v2.0.1\n"
+        ));
+    }
+
+    #[test]
+    fn test_long_single_replace_stays_on_its_line() {
+        let gomod = GoMod::parse_str(
+            "module m\ngo 1.21\nreplace github.com/some/module => github.com/another/module v1.2.3\n",
+        )
+        .unwrap();
+
+        // a block would be just as wide
+        let narrow = gomod.to_string_with(&FormatOptions {
+            max_width: Some(60),
+            ..FormatOptions::default()
+        });
+        assert_eq!(narrow, gomod.to_string());
+        assert!(
+            narrow.ends_with(
+                "\nreplace github.com/some/module => github.com/another/module v1.2.3\n"
+            )
+        );
+    }
+}
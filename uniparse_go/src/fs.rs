@@ -0,0 +1,129 @@
+//! Reading `go.mod`, `go.sum` and `go.work` from disk, behind the `fs` feature.
+//!
+//! Everything else in the crate works on strings only, so it builds for
+//! targets without a filesystem such as `wasm32-unknown-unknown`.
+
+use std::path::Path;
+use std::{fs, io};
+
+use crate::model::{GoMod, ParseError};
+use crate::sum::GoSum;
+use crate::verified::VerifiedModuleSet;
+use crate::work::GoWork;
+
+impl GoMod {
+    /// Parses a `go.mod` file from the given path.
+    ///
+    /// # Errors
+    /// Returns a [`ParseError`] if the file can't be read or parsed.
+    pub fn parse_file(path: impl AsRef<Path>) -> Result<GoMod, ParseError> {
+        let content = fs::read_to_string(path)?;
+        Self::parse_str(&content)
+    }
+}
+
+impl GoSum {
+    /// Parses a `go.sum` file from the given path.
+    ///
+    /// # Errors
+    /// Returns a [`ParseError`] if the file can't be read or parsed.
+    pub fn parse_file(path: impl AsRef<Path>) -> Result<GoSum, ParseError> {
+        let content = fs::read_to_string(path)?;
+        Self::parse_str(&content)
+    }
+}
+
+impl GoWork {
+    /// Parses a `go.work` file from the given path.
+    ///
+    /// # Errors
+    /// Returns a [`ParseError`] if the file can't be read or parsed.
+    pub fn parse_file(path: impl AsRef<Path>) -> Result<GoWork, ParseError> {
+        let content = fs::read_to_string(path)?;
+        Self::parse_str(&content)
+    }
+}
+
+impl VerifiedModuleSet {
+    /// Parses `go_mod_path` and the `go.sum` in the same directory.
+    ///
+    /// A missing `go.sum` is read as empty, as for a module without
+    /// dependencies.
+    ///
+    /// # Errors
+    /// Returns a [`ParseError`] if either file can't be read or parsed.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            fields(path = %go_mod_path.as_ref().display()),
+            err
+        )
+    )]
+    pub fn load(go_mod_path: impl AsRef<Path>) -> Result<Self, ParseError> {
+        let go_mod_path = go_mod_path.as_ref();
+        let go_mod = GoMod::parse_file(go_mod_path)?;
+
+        let sum_path = go_mod_path.with_file_name("go.sum");
+        let sum_src = match fs::read_to_string(&sum_path) {
+            Ok(src) => src,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        Self::from_parts(go_mod, &sum_src)
+    }
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GO_MOD: &str = "module example.com/app
+go 1.21
+require (
+\tgithub.com/one/lib v1.0.0
+\tgithub.com/two/lib v2.0.0
+)
+";
+
+    #[test]
+    fn test_parse_file_ok() {
+        let path = std::env::temp_dir().join("uniparse_go_parse_file.mod");
+        fs::write(&path, GO_MOD).unwrap();
+
+        let parsed = GoMod::parse_file(&path).unwrap();
+        assert_eq!(parsed.module, "example.com/app");
+        assert_eq!(parsed.requires[0].version, "v1.0.0");
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_parse_file_io_error() {
+        let result = GoMod::parse_file("nonexistent_path.go.mod");
+        assert!(matches!(result, Err(ParseError::Io(_))));
+    }
+
+    #[test]
+    fn test_load_reads_sibling_go_sum() {
+        let dir = std::env::temp_dir().join("uniparse_go_verified");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("go.mod"), GO_MOD).unwrap();
+        fs::write(dir.join("go.sum"), "github.com/one/lib v1.0.0\n").unwrap();
+
+        let err = VerifiedModuleSet::load(dir.join("go.mod")).unwrap_err();
+        assert!(matches!(err, ParseError::SumEntry { line: 1, .. }));
+
+        fs::remove_file(dir.join("go.sum")).unwrap();
+        let set = VerifiedModuleSet::load(dir.join("go.mod")).unwrap();
+        assert_eq!(set.missing.len(), 2);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
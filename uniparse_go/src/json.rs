@@ -0,0 +1,250 @@
+use serde::Serialize;
+use serde_json::ser::{PrettyFormatter, Serializer};
+
+use crate::model::GoMod;
+
+/// The document `go mod edit -json` prints; field names, order and
+/// `omitempty` rules follow `cmd/go`.
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct FileJson<'a> {
+    module: ModuleJson<'a>,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    go: &'a str,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    toolchain: &'a str,
+    require: Option<Vec<RequireJson<'a>>>,
+    exclude: Option<Vec<VersionJson<'a>>>,
+    replace: Option<Vec<ReplaceJson<'a>>>,
+    retract: Option<Vec<RetractJson<'a>>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct ModuleJson<'a> {
+    path: &'a str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct RequireJson<'a> {
+    path: &'a str,
+    version: &'a str,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    indirect: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct VersionJson<'a> {
+    path: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct ReplaceJson<'a> {
+    old: VersionJson<'a>,
+    new: VersionJson<'a>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct RetractJson<'a> {
+    #[serde(skip_serializing_if = "str::is_empty")]
+    low: &'a str,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    high: &'a str,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    rationale: &'a str,
+}
+
+impl GoMod {
+    /// The file as `go mod edit -json` prints it: tab-indented, with empty
+    /// lists as `null`, so tools reading that output can use this instead.
+    ///
+    /// ```rust
+    /// use uniparse_go::GoMod;
+    ///
+    /// let gomod = GoMod::parse_str("module example.com/m\ngo 1.21\n").unwrap();
+    /// assert_eq!(
+    ///     gomod.to_go_json(),
+    ///     "{\n\t\"Module\": {\n\t\t\"Path\": \"example.com/m\"\n\t},\n\t\"Go\": \"1.21\",\n\t\"Require\": null,\n\t\"Exclude\": null,\n\t\"Replace\": null,\n\t\"Retract\": null\n}\n"
+    /// );
+    /// ```
+    pub fn to_go_json(&self) -> String {
+        let file = FileJson {
+            module: ModuleJson { path: &self.module },
+            go: &self.go_version,
+            toolchain: self.toolchain.as_deref().unwrap_or_default(),
+            require: non_empty(
+                self.requires
+                    .iter()
+                    .map(|r| RequireJson {
+                        path: &r.name,
+                        version: &r.version,
+                        indirect: r.indirect,
+                    })
+                    .collect(),
+            ),
+            exclude: non_empty(
+                self.excludes
+                    .iter()
+                    .map(|e| VersionJson {
+                        path: &e.name,
+                        version: Some(&e.version),
+                    })
+                    .collect(),
+            ),
+            replace: non_empty(
+                self.replaces
+                    .iter()
+                    .map(|r| ReplaceJson {
+                        old: VersionJson {
+                            path: &r.old_path,
+                            version: r.old_version.as_deref(),
+                        },
+                        new: VersionJson {
+                            path: &r.new_path,
+                            version: r.new_version.as_deref(),
+                        },
+                    })
+                    .collect(),
+            ),
+            retract: non_empty(
+                self.retracts
+                    .iter()
+                    .map(|r| {
+                        let (low, high) = r.bounds();
+                        RetractJson {
+                            low,
+                            high,
+                            rationale: r.rationale().unwrap_or_default(),
+                        }
+                    })
+                    .collect(),
+            ),
+        };
+
+        let mut out = Vec::new();
+        let mut serializer =
+            Serializer::with_formatter(&mut out, PrettyFormatter::with_indent(b"\t"));
+        file.serialize(&mut serializer)
+            .expect("serializing strings to memory can't fail");
+        let json = String::from_utf8(out).expect("serde_json writes UTF-8");
+        escape_like_go(&json) + "\n"
+    }
+}
+
+/// Go encodes an empty (nil) slice as `null`.
+fn non_empty<T>(items: Vec<T>) -> Option<Vec<T>> {
+    (!items.is_empty()).then_some(items)
+}
+
+/// Applies the escapes Go's `encoding/json` adds on top of serde_json's:
+/// `<`, `>` and `&` for embedding in HTML, and the JavaScript line
+/// separators. They can only occur inside strings, so a plain replace is
+/// safe.
+fn escape_like_go(json: &str) -> String {
+    json.replace('<', "\\u003c")
+        .replace('>', "\\u003e")
+        .replace('&', "\\u0026")
+        .replace('\u{2028}', "\\u2028")
+        .replace('\u{2029}', "\\u2029")
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_go_mod_edit_json() {
+        let gomod = GoMod::parse_str(
+            "module example.com/app
+go 1.21
+toolchain go1.22.3
+require (
+\tgithub.com/one/lib v1.0.0
+\tgolang.org/x/text v0.14.0 // indirect
+)
+exclude golang.org/x/text v0.13.0
+replace github.com/one/lib => ../one
+replace golang.org/x/text v0.14.0 => golang.org/x/text v0.15.0
+retract (
+\tv1.0.0 // published too early
+\t[v1.1.0, v1.1.3]
+)
+",
+        )
+        .unwrap();
+
+        // output of `go mod edit -json` for the same file
+        let expected = r#"{
+	"Module": {
+		"Path": "example.com/app"
+	},
+	"Go": "1.21",
+	"Toolchain": "go1.22.3",
+	"Require": [
+		{
+			"Path": "github.com/one/lib",
+			"Version": "v1.0.0"
+		},
+		{
+			"Path": "golang.org/x/text",
+			"Version": "v0.14.0",
+			"Indirect": true
+		}
+	],
+	"Exclude": [
+		{
+			"Path": "golang.org/x/text",
+			"Version": "v0.13.0"
+		}
+	],
+	"Replace": [
+		{
+			"Old": {
+				"Path": "github.com/one/lib"
+			},
+			"New": {
+				"Path": "../one"
+			}
+		},
+		{
+			"Old": {
+				"Path": "golang.org/x/text",
+				"Version": "v0.14.0"
+			},
+			"New": {
+				"Path": "golang.org/x/text",
+				"Version": "v0.15.0"
+			}
+		}
+	],
+	"Retract": [
+		{
+			"Low": "v1.0.0",
+			"High": "v1.0.0",
+			"Rationale": "published too early"
+		},
+		{
+			"Low": "v1.1.0",
+			"High": "v1.1.3"
+		}
+	]
+}
+"#;
+        assert_eq!(gomod.to_go_json(), expected);
+    }
+
+    #[test]
+    fn test_escapes_html_characters_like_go() {
+        assert_eq!(escape_like_go(r#""a<b>&c""#), r#""a\u003cb\u003e\u0026c""#);
+    }
+}
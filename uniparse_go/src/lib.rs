@@ -4,6 +4,9 @@
 //! It supports reading from strings and files, and validates essential fields such as
 //! `module`, `go` version, and `require` entries.
 //!
+//! Reading files needs the `fs` feature, which is on by default. Without it
+//! the crate does no IO, so it also builds for `wasm32-unknown-unknown`.
+//!
 //! # Example
 //!
 //! ```rust
@@ -18,6 +21,47 @@
 //! assert_eq!(gomod.module, "example.com/m");
 //! ```
 
+#[cfg(feature = "fs")]
+pub mod compat;
+mod cst;
+mod diagnostic;
+mod edit;
+mod format;
+#[cfg(feature = "fs")]
+mod fs;
+mod json;
+#[cfg(feature = "macros")]
+mod macros;
 mod model;
+mod patch;
+#[cfg(feature = "fs")]
+mod peek;
+mod query;
+mod replace;
+mod sort;
+mod span;
+mod sum;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+mod toolchain;
+mod verified;
+mod version;
+mod work;
 
-pub use model::{GoDependency, GoMod, ParseError};
+pub use cst::{GoModCst, SyntaxKind};
+pub use diagnostic::{Diagnostic, DiagnosticKind};
+pub use edit::EditSpec;
+pub use format::FormatOptions;
+pub use model::{GoDependency, GoExclude, GoMod, GoReplace, GoRetract, ParseError};
+pub use query::{ModuleQuery, QueryError, VersionQuery, parse_module_query, split_path_version};
+pub use replace::ReplaceIssue;
+pub use span::{GoModSpans, Span};
+pub use sum::{GoSum, GoSumEntry, HashKind, SumIssue};
+pub use toolchain::VersionIssue;
+pub use uniparse_core::{
+    AsValueRef, Capabilities, DiagnosticCode, Document, ParsedFile, Predicate, Schema,
+    SchemaViolation, SemanticEq, Severity, SourceEdit, ValueKind, ValueRef, ViolationKind,
+};
+pub use verified::{ModuleSum, VerifiedModuleSet};
+pub use version::{GoVersion, PseudoVersion, Timestamp, parse_version};
+pub use work::GoWork;
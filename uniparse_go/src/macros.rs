@@ -0,0 +1,186 @@
+/// Builds a [`GoMod`](crate::GoMod) from go.mod-like statements, each ended
+/// by `;`. Needs the `macros` feature.
+///
+/// Paths and versions are string literals, or any Rust expression in
+/// parentheses that converts into a `String`:
+///
+/// - `module "example.com/m";`, `go "1.22";`, `toolchain "go1.22.3";`
+/// - `require "path" "version";`, with `indirect` before the `;` for a
+///   `// indirect` requirement
+/// - `exclude "path" "version";`
+/// - `replace "old" ["version"] => "new" ["version"];`
+/// - `retract "version";` or `retract ["low", "high"];`
+///
+/// ```rust
+/// use uniparse_go::{GoMod, gomod};
+///
+/// let gomod = gomod! {
+///     module "example.com/m";
+///     go "1.22";
+///     require "github.com/a/b" "v1.0.0";
+///     require "golang.org/x/sys" "v0.20.0" indirect;
+///     replace "github.com/a/b" => "../b";
+/// };
+/// assert_eq!(
+///     gomod,
+///     GoMod::parse_str(
+///         "module example.com/m\ngo 1.22\nrequire (\n\tgithub.com/a/b v1.0.0\n\tgolang.org/x/sys v0.20.0 // indirect\n)\nreplace github.com/a/b => ../b\n"
+///     )
+///     .unwrap()
+/// );
+/// ```
+#[macro_export]
+macro_rules! gomod {
+    (@require $path:tt $version:tt $indirect:literal) => {
+        $crate::GoDependency {
+            name: ::std::string::String::from($path),
+            version: ::std::string::String::from($version),
+            indirect: $indirect,
+        }
+    };
+    (@replace $gomod:ident ($old:tt) ($($old_version:tt)?) ($new:tt) ($($new_version:tt)?)) => {
+        $gomod.replaces.push($crate::GoReplace {
+            old_path: ::std::string::String::from($old),
+            old_version: $crate::gomod!(@optional $($old_version)?),
+            new_path: ::std::string::String::from($new),
+            new_version: $crate::gomod!(@optional $($new_version)?),
+        })
+    };
+    (@optional) => {
+        ::std::option::Option::None
+    };
+    (@optional $value:tt) => {
+        ::std::option::Option::Some(::std::string::String::from($value))
+    };
+
+    // statements, pushed onto `$gomod`
+    (@ $gomod:ident) => {};
+    (@ $gomod:ident module $path:tt ; $($rest:tt)*) => {
+        $gomod.module = ::std::string::String::from($path);
+        $crate::gomod!(@ $gomod $($rest)*);
+    };
+    (@ $gomod:ident go $version:tt ; $($rest:tt)*) => {
+        $gomod.go_version = ::std::string::String::from($version);
+        $crate::gomod!(@ $gomod $($rest)*);
+    };
+    (@ $gomod:ident toolchain $name:tt ; $($rest:tt)*) => {
+        $gomod.toolchain = ::std::option::Option::Some(::std::string::String::from($name));
+        $crate::gomod!(@ $gomod $($rest)*);
+    };
+    (@ $gomod:ident require $path:tt $version:tt indirect ; $($rest:tt)*) => {
+        $gomod.requires.push($crate::gomod!(@require $path $version true));
+        $crate::gomod!(@ $gomod $($rest)*);
+    };
+    (@ $gomod:ident require $path:tt $version:tt ; $($rest:tt)*) => {
+        $gomod.requires.push($crate::gomod!(@require $path $version false));
+        $crate::gomod!(@ $gomod $($rest)*);
+    };
+    (@ $gomod:ident exclude $path:tt $version:tt ; $($rest:tt)*) => {
+        $gomod.excludes.push($crate::GoExclude {
+            name: ::std::string::String::from($path),
+            version: ::std::string::String::from($version),
+        });
+        $crate::gomod!(@ $gomod $($rest)*);
+    };
+    (@ $gomod:ident replace $old:tt => $new:tt ; $($rest:tt)*) => {
+        $crate::gomod!(@replace $gomod ($old) () ($new) ());
+        $crate::gomod!(@ $gomod $($rest)*);
+    };
+    (@ $gomod:ident replace $old:tt => $new:tt $new_version:tt ; $($rest:tt)*) => {
+        $crate::gomod!(@replace $gomod ($old) () ($new) ($new_version));
+        $crate::gomod!(@ $gomod $($rest)*);
+    };
+    (@ $gomod:ident replace $old:tt $old_version:tt => $new:tt ; $($rest:tt)*) => {
+        $crate::gomod!(@replace $gomod ($old) ($old_version) ($new) ());
+        $crate::gomod!(@ $gomod $($rest)*);
+    };
+    (@ $gomod:ident replace $old:tt $old_version:tt => $new:tt $new_version:tt ; $($rest:tt)*) => {
+        $crate::gomod!(@replace $gomod ($old) ($old_version) ($new) ($new_version));
+        $crate::gomod!(@ $gomod $($rest)*);
+    };
+    (@ $gomod:ident retract [$low:tt, $high:tt] ; $($rest:tt)*) => {
+        $gomod.retracts.push($crate::GoRetract::Range {
+            low: ::std::string::String::from($low),
+            high: ::std::string::String::from($high),
+            rationale: ::std::option::Option::None,
+        });
+        $crate::gomod!(@ $gomod $($rest)*);
+    };
+    (@ $gomod:ident retract $version:tt ; $($rest:tt)*) => {
+        $gomod.retracts.push($crate::GoRetract::Version {
+            version: ::std::string::String::from($version),
+            rationale: ::std::option::Option::None,
+        });
+        $crate::gomod!(@ $gomod $($rest)*);
+    };
+    (@ $gomod:ident $($unexpected:tt)+) => {
+        compile_error!(concat!(
+            "expected a go.mod directive ended by `;`, found `",
+            stringify!($($unexpected)+),
+            "`"
+        ))
+    };
+
+    ($($statements:tt)*) => {{
+        let mut gomod = $crate::GoMod {
+            module: ::std::string::String::new(),
+            go_version: ::std::string::String::new(),
+            toolchain: ::std::option::Option::None,
+            requires: ::std::vec::Vec::new(),
+            replaces: ::std::vec::Vec::new(),
+            excludes: ::std::vec::Vec::new(),
+            retracts: ::std::vec::Vec::new(),
+        };
+        $crate::gomod!(@ gomod $($statements)*);
+        gomod
+    }};
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use crate::GoMod;
+
+    #[test]
+    fn test_gomod_matches_parsed_file() {
+        let fork = "github.com/me/x";
+        let built = gomod! {
+            module "example.com/app";
+            go "1.22";
+            toolchain "go1.22.3";
+            require "github.com/a/x" "v1.0.0";
+            require "github.com/a/y" "v2.0.0+incompatible" indirect;
+            exclude "github.com/a/x" "v0.9.0";
+            replace "github.com/a/x" "v1.0.0" => (fork) "v1.0.1";
+            replace "github.com/a/y" => "../y";
+            retract "v0.1.0";
+            retract ["v0.2.0", "v0.3.0"];
+        };
+
+        let parsed = GoMod::parse_str(
+            "module example.com/app
+go 1.22
+toolchain go1.22.3
+require (
+\tgithub.com/a/x v1.0.0
+\tgithub.com/a/y v2.0.0+incompatible // indirect
+)
+exclude github.com/a/x v0.9.0
+replace (
+\tgithub.com/a/x v1.0.0 => github.com/me/x v1.0.1
+\tgithub.com/a/y => ../y
+)
+retract (
+\tv0.1.0
+\t[v0.2.0, v0.3.0]
+)
+",
+        )
+        .unwrap();
+        assert_eq!(built, parsed);
+        assert!(built.requires[1].is_incompatible());
+    }
+}
@@ -1,5 +1,10 @@
 use serde::{Deserialize, Serialize};
-use std::{fs, path::Path, usize};
+use uniparse_core::{
+    AsValueRef, Capabilities, DiagnosticCode, ParsedFile, Schema, SchemaViolation, SemanticEq,
+    ValueRef, normalize_dependency_id, unordered_eq,
+};
+
+use crate::span::{GoModSpans, Span};
 
 /// Represents a parsed `go.mod` file.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -8,10 +13,20 @@ pub struct GoMod {
     pub module: String,
     /// The Go version specified, e.g., `1.20`.
     pub go_version: String,
+    /// The toolchain to build with, e.g., `go1.22.3`, when declared via
+    /// `toolchain`.
+    #[serde(default)]
+    pub toolchain: Option<String>,
     /// List of dependencies declared via `require` in `go.mod`.
     pub requires: Vec<GoDependency>,
-    // pub replaces: Vec<GoReplace>,
-    // pub excludes: Vec<GoExclude>,
+    /// Module replacements declared via `replace`.
+    pub replaces: Vec<GoReplace>,
+    /// Module versions excluded via `exclude`.
+    #[serde(default)]
+    pub excludes: Vec<GoExclude>,
+    /// Versions of this module withdrawn via `retract`.
+    #[serde(default)]
+    pub retracts: Vec<GoRetract>,
 }
 
 /// Represents a single `require` dependency entry in a `go.mod` file.
@@ -19,21 +34,326 @@ pub struct GoMod {
 pub struct GoDependency {
     /// Name of the module, e.g., `github.com/foo/bar`.
     pub name: String,
-    /// Required version, e.g., `v1.2.3`.
+    /// Required version, e.g., `v1.2.3`, including any `+incompatible`
+    /// suffix; see [`GoDependency::is_incompatible`].
     pub version: String,
+    /// Marked `// indirect`: needed by other dependencies rather than
+    /// imported by this module.
+    #[serde(default)]
+    pub indirect: bool,
+}
+
+/// Represents a single `replace` directive in a `go.mod` file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GoReplace {
+    /// Module being replaced, e.g., `github.com/foo/bar`.
+    pub old_path: String,
+    /// Only this version is replaced when set; otherwise all versions are.
+    pub old_version: Option<String>,
+    /// Replacement module path or local directory, e.g., `../bar`.
+    pub new_path: String,
+    /// Version of the replacement module; `None` for local directories.
+    pub new_version: Option<String>,
+}
+
+/// A single `exclude` entry: a module version the build must not use.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GoExclude {
+    /// Name of the module, e.g., `github.com/foo/bar`.
+    pub name: String,
+    /// Excluded version, e.g., `v1.2.3`.
+    pub version: String,
+}
+
+/// A single `retract` entry: versions of this module that shouldn't be
+/// used, with the reason given in the comment next to or above it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum GoRetract {
+    /// `retract v1.0.0`
+    Version {
+        version: String,
+        rationale: Option<String>,
+    },
+    /// `retract [v1.0.0, v1.2.0]`, both ends included.
+    Range {
+        low: String,
+        high: String,
+        rationale: Option<String>,
+    },
+}
+
+impl GoRetract {
+    /// The lowest and highest retracted version; the same version twice
+    /// for a single one.
+    pub fn bounds(&self) -> (&str, &str) {
+        match self {
+            GoRetract::Version { version, .. } => (version, version),
+            GoRetract::Range { low, high, .. } => (low, high),
+        }
+    }
+
+    /// Why the versions were retracted, one line per comment line.
+    pub fn rationale(&self) -> Option<&str> {
+        match self {
+            GoRetract::Version { rationale, .. } | GoRetract::Range { rationale, .. } => {
+                rationale.as_deref()
+            }
+        }
+    }
+
+    fn rationale_mut(&mut self) -> &mut Option<String> {
+        match self {
+            GoRetract::Version { rationale, .. } | GoRetract::Range { rationale, .. } => rationale,
+        }
+    }
+
+    /// Parses `v1.0.0` or `[v1.0.0, v1.2.0]`, optionally quoted as in
+    /// go.mod.
+    fn parse(spec: &str, rationale: Option<String>) -> Option<GoRetract> {
+        let version = |v: &str| {
+            let v = unquote(v.trim());
+            (!v.is_empty() && !v.contains(char::is_whitespace)).then(|| v.to_string())
+        };
+        match spec.trim().strip_prefix('[') {
+            Some(range) => {
+                let (low, high) = range.strip_suffix(']')?.split_once(',')?;
+                Some(GoRetract::Range {
+                    low: version(low)?,
+                    high: version(high)?,
+                    rationale,
+                })
+            }
+            None => Some(GoRetract::Version {
+                version: version(spec)?,
+                rationale,
+            }),
+        }
+    }
+}
+
+impl std::fmt::Display for GoRetract {
+    /// The versions as written after `retract`, without the rationale.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GoRetract::Version { version, .. } => write!(f, "{}", version),
+            GoRetract::Range { low, high, .. } => write!(f, "[{}, {}]", low, high),
+        }
+    }
+}
+
+impl GoDependency {
+    /// The module path in canonical form, for matching it against
+    /// dependencies of other manifests; see
+    /// [`normalize_dependency_id`](uniparse_core::normalize_dependency_id).
+    pub fn dependency_id(&self) -> String {
+        normalize_dependency_id(&self.name)
+    }
+}
+
+impl GoReplace {
+    /// Whether the replacement points at a directory on disk rather than a
+    /// module version.
+    pub fn is_local(&self) -> bool {
+        self.new_version.is_none()
+    }
+}
+
+impl AsValueRef for GoMod {
+    /// A map with the keys used by [`GoMod::get`] paths; `toolchain` is left
+    /// out when there is none.
+    fn as_value_ref(&self) -> ValueRef<'_> {
+        let mut entries = vec![
+            ("module", ValueRef::Str(&self.module)),
+            ("go_version", ValueRef::Str(&self.go_version)),
+        ];
+        if let Some(toolchain) = &self.toolchain {
+            entries.push(("toolchain", ValueRef::Str(toolchain)));
+        }
+        entries.extend([
+            ("requires", seq(&self.requires)),
+            ("replaces", seq(&self.replaces)),
+            ("excludes", seq(&self.excludes)),
+            ("retracts", seq(&self.retracts)),
+        ]);
+        ValueRef::Map(entries)
+    }
+}
+
+impl AsValueRef for GoDependency {
+    fn as_value_ref(&self) -> ValueRef<'_> {
+        ValueRef::Map(vec![
+            ("name", ValueRef::Str(&self.name)),
+            ("version", ValueRef::Str(&self.version)),
+            ("incompatible", ValueRef::Bool(self.is_incompatible())),
+            ("indirect", ValueRef::Bool(self.indirect)),
+        ])
+    }
+}
+
+impl AsValueRef for GoExclude {
+    fn as_value_ref(&self) -> ValueRef<'_> {
+        ValueRef::Map(vec![
+            ("name", ValueRef::Str(&self.name)),
+            ("version", ValueRef::Str(&self.version)),
+        ])
+    }
+}
+
+impl AsValueRef for GoRetract {
+    /// `version` for a single version, `low` and `high` for a range, and
+    /// `rationale` when one is given.
+    fn as_value_ref(&self) -> ValueRef<'_> {
+        let mut entries = match self {
+            GoRetract::Version { version, .. } => vec![("version", ValueRef::Str(version))],
+            GoRetract::Range { low, high, .. } => {
+                vec![("low", ValueRef::Str(low)), ("high", ValueRef::Str(high))]
+            }
+        };
+        if let Some(rationale) = self.rationale() {
+            entries.push(("rationale", ValueRef::Str(rationale)));
+        }
+        ValueRef::Map(entries)
+    }
+}
+
+impl AsValueRef for GoReplace {
+    /// Versions that aren't given are left out of the map.
+    fn as_value_ref(&self) -> ValueRef<'_> {
+        let mut entries = vec![("old_path", ValueRef::Str(&self.old_path))];
+        if let Some(version) = &self.old_version {
+            entries.push(("old_version", ValueRef::Str(version)));
+        }
+        entries.push(("new_path", ValueRef::Str(&self.new_path)));
+        if let Some(version) = &self.new_version {
+            entries.push(("new_version", ValueRef::Str(version)));
+        }
+        ValueRef::Map(entries)
+    }
+}
+
+fn seq<T: AsValueRef>(items: &[T]) -> ValueRef<'_> {
+    ValueRef::Seq(items.iter().map(AsValueRef::as_value_ref).collect())
+}
+
+/// The list `items` if `path` is empty, else `path` looked up in the item
+/// its first segment indexes.
+fn item_ref<'a, T: AsValueRef>(items: &'a [T], path: &[&str]) -> Option<ValueRef<'a>> {
+    match path.split_first() {
+        None => Some(seq(items)),
+        Some((index, rest)) => items
+            .get(index.parse::<usize>().ok()?)?
+            .as_value_ref()
+            .lookup(rest),
+    }
+}
+
+impl SemanticEq for GoDependency {
+    fn semantic_eq(&self, other: &Self) -> bool {
+        self == other
+    }
+}
+
+impl SemanticEq for GoExclude {
+    fn semantic_eq(&self, other: &Self) -> bool {
+        self == other
+    }
+}
+
+impl SemanticEq for GoRetract {
+    fn semantic_eq(&self, other: &Self) -> bool {
+        self == other
+    }
+}
+
+impl SemanticEq for GoReplace {
+    fn semantic_eq(&self, other: &Self) -> bool {
+        self == other
+    }
+}
+
+impl SemanticEq for GoMod {
+    /// The order of `require` and `replace` entries, and whether they were
+    /// written as single lines or blocks, has no effect on the build.
+    fn semantic_eq(&self, other: &Self) -> bool {
+        self.module == other.module
+            && self.go_version == other.go_version
+            && self.toolchain == other.toolchain
+            && unordered_eq(&self.requires, &other.requires)
+            && unordered_eq(&self.replaces, &other.replaces)
+            && unordered_eq(&self.excludes, &other.excludes)
+            && unordered_eq(&self.retracts, &other.retracts)
+    }
+}
+
+impl std::str::FromStr for GoMod {
+    type Err = ParseError;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        GoMod::parse_str(src)
+    }
+}
+
+impl ParsedFile for GoMod {
+    type Error = ParseError;
+    /// Every leaf of a `go.mod` is a string, `indirect` included (`"true"`
+    /// or `"false"`); see [`GoMod::get`] for the paths.
+    type Value = str;
+    type OwnedValue = String;
+
+    const CAPABILITIES: Capabilities = GoMod::CAPABILITIES;
+
+    fn parse_str(src: &str) -> Result<Self, ParseError> {
+        GoMod::parse_str(src)
+    }
+
+    fn to_string_pretty(&self) -> String {
+        self.to_gomod_string()
+    }
+
+    fn get(&self, path: &[&str]) -> Option<&str> {
+        GoMod::get(self, path)
+    }
+
+    /// Whole lists and entries too, as [`GoMod::get_ref`] does.
+    fn get_ref(&self, path: &[&str]) -> Option<ValueRef<'_>> {
+        GoMod::get_ref(self, path)
+    }
+
+    fn set(&mut self, path: &[&str], value: String) -> Result<(), String> {
+        GoMod::set(self, path, &value)
+    }
+
+    fn remove(&mut self, path: &[&str]) -> Result<(), String> {
+        GoMod::remove(self, path)
+    }
 }
 
 impl GoMod {
+    /// What this crate offers beyond parsing and path access: through
+    /// [`GoModCst`](crate::GoModCst), [`GoMod::patch_source`] and
+    /// [`GoMod::parse_with_spans`] rather than this model.
+    pub const CAPABILITIES: Capabilities = Capabilities {
+        lossless_editing: true,
+        spans: true,
+        comments: true,
+    };
+
     /// Get a string field from the `GoMod` by path.
     ///
     /// Supported paths:
     /// - `["module"]`
     /// - `["go_version"]`
-    /// - `["requires", "<index>", "name" | "version"]`
+    /// - `["toolchain"]`
+    /// - `["requires", "<index>", "name" | "version" | "indirect"]`
+    /// - `["replaces", "<index>", "old_path" | "old_version" | "new_path" | "new_version"]`
+    /// - `["excludes", "<index>", "name" | "version"]`
+    /// - `["retracts", "<index>", "version" | "low" | "high" | "rationale"]`,
+    ///   where `low` and `high` of a single version are that version
     pub fn get(&self, path: &[&str]) -> Option<&str> {
         match path {
             ["module"] => Some(&self.module),
             ["go_version"] => Some(&self.go_version),
+            ["toolchain"] => self.toolchain.as_deref(),
             ["requires", idx_str, field] => {
                 let idx = idx_str.parse::<usize>().ok()?;
                 let dep = self.requires.get(idx)?;
@@ -41,6 +361,41 @@ impl GoMod {
                 match *field {
                     "name" => Some(&dep.name),
                     "version" => Some(&dep.version),
+                    "indirect" => Some(if dep.indirect { "true" } else { "false" }),
+                    _ => None,
+                }
+            }
+            ["replaces", idx_str, field] => {
+                let idx = idx_str.parse::<usize>().ok()?;
+                let replace = self.replaces.get(idx)?;
+
+                match *field {
+                    "old_path" => Some(&replace.old_path),
+                    "old_version" => replace.old_version.as_deref(),
+                    "new_path" => Some(&replace.new_path),
+                    "new_version" => replace.new_version.as_deref(),
+                    _ => None,
+                }
+            }
+            ["excludes", idx_str, field] => {
+                let idx = idx_str.parse::<usize>().ok()?;
+                let exclude = self.excludes.get(idx)?;
+
+                match *field {
+                    "name" => Some(&exclude.name),
+                    "version" => Some(&exclude.version),
+                    _ => None,
+                }
+            }
+            ["retracts", idx_str, field] => {
+                let idx = idx_str.parse::<usize>().ok()?;
+                let retract = self.retracts.get(idx)?;
+
+                match (*field, retract) {
+                    ("version", GoRetract::Version { version, .. }) => Some(version),
+                    ("low", _) => Some(retract.bounds().0),
+                    ("high", _) => Some(retract.bounds().1),
+                    ("rationale", _) => retract.rationale(),
                     _ => None,
                 }
             }
@@ -48,12 +403,72 @@ impl GoMod {
         }
     }
 
+    /// Like [`GoMod::get`], but as a [`ValueRef`], so whole lists and entries
+    /// can be read too, e.g. `["requires"]` or `["replaces", "0"]`.
+    pub fn get_ref(&self, path: &[&str]) -> Option<ValueRef<'_>> {
+        // only the entry the path leads to is converted, not the whole file
+        let Some((first, rest)) = path.split_first() else {
+            return Some(self.as_value_ref());
+        };
+        match *first {
+            "module" => ValueRef::Str(&self.module).lookup(rest),
+            "go_version" => ValueRef::Str(&self.go_version).lookup(rest),
+            "toolchain" => ValueRef::Str(self.toolchain.as_deref()?).lookup(rest),
+            "requires" => item_ref(&self.requires, rest),
+            "replaces" => item_ref(&self.replaces, rest),
+            "excludes" => item_ref(&self.excludes, rest),
+            "retracts" => item_ref(&self.retracts, rest),
+            _ => None,
+        }
+    }
+
+    /// Checks the file against a [`Schema`], with the same keys as
+    /// [`GoMod::get_ref`], so one policy can cover `go.mod` alongside the
+    /// other formats.
+    ///
+    /// ```rust
+    /// use uniparse_go::{GoMod, PseudoVersion, Schema};
+    ///
+    /// let internal_commit = Schema::default()
+    ///     .required("name", Schema::matching("corp-internal/*"))
+    ///     .required("version", Schema::default().satisfies(PseudoVersion::PREDICATE))
+    ///     .describe("untagged internal module");
+    /// let policy = Schema::default()
+    ///     .field("go_version", Schema::default().at_least_version("1.21"))
+    ///     .field("requires", Schema::default().items(Schema::default().forbid(internal_commit)));
+    ///
+    /// let gomod = GoMod::parse_str(
+    ///     "module m\ngo 1.20\nrequire corp-internal/auth v0.0.0-20240101000000-abcdef123456\n",
+    /// ).unwrap();
+    /// let found: Vec<_> = gomod.validate_schema(&policy).iter().map(|v| v.to_string()).collect();
+    /// assert_eq!(found, [
+    ///     "`go_version`: 1.20 is older than the minimum 1.21",
+    ///     "`requires.0`: untagged internal module is not allowed",
+    /// ]);
+    /// ```
+    pub fn validate_schema(&self, schema: &Schema) -> Vec<SchemaViolation> {
+        schema.validate(&self.as_value_ref())
+    }
+
     /// Set a string field in the `GoMod` by path.
     ///
     /// Supported paths:
     /// - `["module"]`
     /// - `["go_version"]`
-    /// - `["requires", "<index>", "name" | "version"]`
+    /// - `["toolchain"]`
+    /// - `["requires", "<index>", "name" | "version" | "indirect"]`
+    /// - `["replaces", "<index>", "old_path" | "old_version" | "new_path" | "new_version"]`
+    /// - `["excludes", "<index>", "name" | "version"]`
+    /// - `["excludes", "<index>"]` with a `path@version` value, which
+    ///   replaces that exclusion, or adds one when `<index>` is the number
+    ///   of exclusions
+    /// - `["retracts", "<index>", "version" | "low" | "high" | "rationale"]`
+    /// - `["retracts", "<index>"]` with `v1.0.0` or `[v1.0.0, v1.2.0]`,
+    ///   which replaces those versions, keeping the rationale, or adds a
+    ///   retraction when `<index>` is the number of retractions
+    ///
+    /// Setting the toolchain, a replace version or a rationale to `""`
+    /// removes it, e.g. to point the replacement at a local directory.
     ///
     /// # Errors
     /// Returns `Err` if the path is unsupported or index is invalid.
@@ -67,6 +482,10 @@ impl GoMod {
                 self.go_version = value.to_string();
                 Ok(())
             }
+            ["toolchain"] => {
+                self.toolchain = (!value.is_empty()).then(|| value.to_string());
+                Ok(())
+            }
             ["requires", idx_str, field] => {
                 let idx = idx_str.parse::<usize>().map_err(|_| "Invalid index")?;
                 let dep = self.requires.get_mut(idx).ok_or("Index out of bounds")?;
@@ -80,21 +499,114 @@ impl GoMod {
                         dep.version = value.to_string();
                         Ok(())
                     }
+                    "indirect" => {
+                        dep.indirect = value
+                            .parse()
+                            .map_err(|_| format!("Expected true or false, got `{}`", value))?;
+                        Ok(())
+                    }
                     _ => Err("Unknown field".into()),
                 }
             }
+            ["replaces", idx_str, field] => {
+                let idx = idx_str.parse::<usize>().map_err(|_| "Invalid index")?;
+                let replace = self.replaces.get_mut(idx).ok_or("Index out of bounds")?;
+                let version = (!value.is_empty()).then(|| value.to_string());
+
+                match *field {
+                    "old_path" => replace.old_path = value.to_string(),
+                    "old_version" => replace.old_version = version,
+                    "new_path" => replace.new_path = value.to_string(),
+                    "new_version" => replace.new_version = version,
+                    _ => return Err("Unknown field".into()),
+                }
+                Ok(())
+            }
+            ["excludes", idx_str] => {
+                let idx = idx_str.parse::<usize>().map_err(|_| "Invalid index")?;
+                let (name, version) = value
+                    .split_once('@')
+                    .filter(|(name, version)| !name.is_empty() && !version.is_empty())
+                    .ok_or_else(|| format!("Expected path@version, got `{}`", value))?;
+                let exclude = GoExclude {
+                    name: name.to_string(),
+                    version: version.to_string(),
+                };
+                match idx.cmp(&self.excludes.len()) {
+                    std::cmp::Ordering::Less => self.excludes[idx] = exclude,
+                    std::cmp::Ordering::Equal => self.excludes.push(exclude),
+                    std::cmp::Ordering::Greater => return Err("Index out of bounds".into()),
+                }
+                Ok(())
+            }
+            ["excludes", idx_str, field] => {
+                let idx = idx_str.parse::<usize>().map_err(|_| "Invalid index")?;
+                let exclude = self.excludes.get_mut(idx).ok_or("Index out of bounds")?;
+
+                match *field {
+                    "name" => exclude.name = value.to_string(),
+                    "version" => exclude.version = value.to_string(),
+                    _ => return Err("Unknown field".into()),
+                }
+                Ok(())
+            }
+            ["retracts", idx_str] => {
+                let idx = idx_str.parse::<usize>().map_err(|_| "Invalid index")?;
+                let rationale = self
+                    .retracts
+                    .get(idx)
+                    .and_then(|r| r.rationale().map(str::to_string));
+                let retract = GoRetract::parse(value, rationale)
+                    .ok_or_else(|| format!("Expected a version or [low, high], got `{}`", value))?;
+                match idx.cmp(&self.retracts.len()) {
+                    std::cmp::Ordering::Less => self.retracts[idx] = retract,
+                    std::cmp::Ordering::Equal => self.retracts.push(retract),
+                    std::cmp::Ordering::Greater => return Err("Index out of bounds".into()),
+                }
+                Ok(())
+            }
+            ["retracts", idx_str, field] => {
+                let idx = idx_str.parse::<usize>().map_err(|_| "Invalid index")?;
+                let retract = self.retracts.get_mut(idx).ok_or("Index out of bounds")?;
+
+                match (*field, retract) {
+                    ("rationale", retract) => {
+                        *retract.rationale_mut() = (!value.is_empty()).then(|| value.to_string())
+                    }
+                    ("version", GoRetract::Version { version, .. })
+                    | ("low", GoRetract::Range { low: version, .. })
+                    | ("high", GoRetract::Range { high: version, .. }) => {
+                        *version = value.to_string()
+                    }
+                    _ => return Err("Unknown field".into()),
+                }
+                Ok(())
+            }
             _ => Err("Unsupported path".into()),
         }
     }
 
-    /// Remove a dependency by index from the `requires` list.
+    /// Remove an entry by path.
     ///
-    /// Only supports paths in the format `["requires", "<index>"]`.
+    /// Supported paths:
+    /// - `["toolchain"]`
+    /// - `["requires", "<index>"]`
+    /// - `["replaces", "<index>"]`
+    /// - `["excludes", "<index>"]`
+    /// - `["retracts", "<index>"]`
+    /// - `["retracts", "<index>", "rationale"]`
+    /// - `["replaces", "<index>", "old_version" | "new_version"]`, which
+    ///   makes the replace apply to every version or point at a local
+    ///   directory
     ///
     /// # Errors
     /// Returns `Err` if the path is invalid or index is out of bounds.
     pub fn remove(&mut self, path: &[&str]) -> Result<(), String> {
         match path {
+            ["toolchain"] => {
+                self.toolchain = None;
+                Ok(())
+            }
             ["requires", idx_str] => {
                 let idx = idx_str.parse::<usize>().map_err(|_| "Invalid index")?;
                 if idx >= self.requires.len() {
@@ -103,73 +615,461 @@ impl GoMod {
                 self.requires.remove(idx);
                 Ok(())
             }
-            _ => Err("Remove only supports ['requires', idx]".into()),
+            ["replaces", idx_str] => {
+                let idx = idx_str.parse::<usize>().map_err(|_| "Invalid index")?;
+                if idx >= self.replaces.len() {
+                    return Err("Index out of bounds".into());
+                }
+                self.replaces.remove(idx);
+                Ok(())
+            }
+            ["excludes", idx_str] => {
+                let idx = idx_str.parse::<usize>().map_err(|_| "Invalid index")?;
+                if idx >= self.excludes.len() {
+                    return Err("Index out of bounds".into());
+                }
+                self.excludes.remove(idx);
+                Ok(())
+            }
+            ["retracts", idx_str] => {
+                let idx = idx_str.parse::<usize>().map_err(|_| "Invalid index")?;
+                if idx >= self.retracts.len() {
+                    return Err("Index out of bounds".into());
+                }
+                self.retracts.remove(idx);
+                Ok(())
+            }
+            ["retracts", idx_str, "rationale"] => {
+                let idx = idx_str.parse::<usize>().map_err(|_| "Invalid index")?;
+                let retract = self.retracts.get_mut(idx).ok_or("Index out of bounds")?;
+                *retract.rationale_mut() = None;
+                Ok(())
+            }
+            ["replaces", idx_str, field] => {
+                let idx = idx_str.parse::<usize>().map_err(|_| "Invalid index")?;
+                let replace = self.replaces.get_mut(idx).ok_or("Index out of bounds")?;
+                match *field {
+                    "old_version" => replace.old_version = None,
+                    "new_version" => replace.new_version = None,
+                    _ => return Err("Only replace versions can be removed".into()),
+                }
+                Ok(())
+            }
+            _ => Err(
+                "Remove only supports ['toolchain'], ['requires', idx], ['excludes', idx], ['retracts', idx, ...] and ['replaces', idx, ...]"
+                    .into(),
+            ),
         }
     }
 
-    /// Parses a `go.mod` file from the given path.
-    ///
-    /// # Errors
-    /// Returns a [`ParseError`] if the file can't be read or parsed.
-    pub fn parse_file(path: impl AsRef<Path>) -> Result<GoMod, ParseError> {
-        let content = fs::read_to_string(path)?;
-        Self::parse_str(&content)
-    }
-
     /// Parses the contents of a `go.mod` file from a string.
     ///
     /// # Errors
     /// Returns a [`ParseError`] if required fields are missing or the syntax is invalid.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(len = content.len()), err)
+    )]
     pub fn parse_str(content: &str) -> Result<GoMod, ParseError> {
+        let mut errors = Vec::new();
+        let gomod = GoMod::parse_spanned(content, &mut GoModSpans::default(), &mut errors);
+        match errors.into_iter().next() {
+            Some(err) => Err(err),
+            None => Ok(gomod),
+        }
+    }
+
+    /// Parses the contents of a `go.mod` file without stopping at the first
+    /// problem: a malformed line is reported and skipped, and parsing goes
+    /// on with the next one. Returns every error, in line order, with the
+    /// best-effort model built from the lines that did parse; `module` and
+    /// `go_version` are empty if their directive is missing.
+    ///
+    /// The errors are empty exactly when [`GoMod::parse_str`] succeeds,
+    /// and otherwise start with the one it returns.
+    ///
+    /// ```rust
+    /// use uniparse_go::GoMod;
+    ///
+    /// let (gomod, errors) = GoMod::parse_recovering(
+    ///     "module example.com/m\ngo 1.22\nrequire (\n\tgithub.com/a/b\n\tgithub.com/c/d v1.0.0\n\tgithub.com/e/f v1 v2\n)\n",
+    /// );
+    /// assert_eq!(gomod.requires.len(), 1);
+    /// assert_eq!(errors.len(), 2);
+    /// assert_eq!(errors[0].to_string(), "Unexpected token on line 4: Invalid require entry: `\tgithub.com/a/b`");
+    /// ```
+    pub fn parse_recovering(content: &str) -> (GoMod, Vec<ParseError>) {
+        let mut errors = Vec::new();
+        let gomod = GoMod::parse_spanned(content, &mut GoModSpans::default(), &mut errors);
+        (gomod, errors)
+    }
+
+    /// Parses `content` as far as possible, adding every problem to
+    /// `errors` and recording where directives are in `spans`.
+    pub(crate) fn parse_spanned(
+        content: &str,
+        spans: &mut GoModSpans,
+        errors: &mut Vec<ParseError>,
+    ) -> GoMod {
+        let mut gomod = GoMod {
+            module: String::new(),
+            go_version: String::new(),
+            toolchain: None,
+            requires: Vec::new(),
+            replaces: Vec::new(),
+            excludes: Vec::new(),
+            retracts: Vec::new(),
+        };
+        if let Some(err) = wrong_file_type(content) {
+            errors.push(err);
+            return gomod;
+        }
+
         let mut module = None;
         let mut go_version = None;
+        let mut toolchain = None;
         let mut requires = Vec::new();
-        let mut in_require_block = false;
+        let mut replaces = Vec::new();
+        let mut excludes = Vec::new();
+        let mut retracts = Vec::new();
+        // directive of the open `(` block and the line it was opened on
+        let mut block: Option<(&str, usize)> = None;
+        // comment lines right above the current line and above the open block
+        let mut above = Vec::new();
+        let mut above_block = Vec::new();
+        let mut last_directive: Option<&str> = None;
+        let mut module_too_late = false;
 
-        for (i, line) in content.lines().enumerate() {
-            let trimmed = line.trim();
+        let mut offset = 0;
+        for (i, raw) in content.split_inclusive('\n').enumerate() {
+            let line = raw.strip_suffix('\n').unwrap_or(raw);
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            let line_start = offset;
+            offset += raw.len();
+            // module paths and versions can't contain `//`
+            let code = line.split("//").next().unwrap_or_default();
+            let trimmed = code.trim();
+            let start = line_start + code.len() - code.trim_start().len();
+            let span = || Span::from_range(content, start..start + trimmed.len());
+            let syntax = |msg: String, suggestion: &str| ParseError::Syntax {
+                line: i + 1,
+                msg,
+                suggestion: Some(suggestion.to_string()),
+            };
 
-            if trimmed.is_empty() || trimmed.starts_with("//") {
+            if trimmed.is_empty() {
+                // a blank line detaches the comments above it
+                match line.split_once("//") {
+                    Some((_, comment)) => above.push(comment.trim()),
+                    None => above.clear(),
+                }
                 continue;
             }
+            let comments = std::mem::take(&mut above);
 
-            match trimmed {
-                l if l.starts_with("module ") => {
-                    module = Some(l["module ".len()..].trim().to_string())
+            // directive of the current line, either from an open `(` block
+            // or the first word
+            let (directive, rest) = match block {
+                Some(_) if trimmed == ")" => {
+                    block = None;
+                    continue;
                 }
-                l if l.starts_with("go ") => go_version = Some(l["go ".len()..].trim().to_string()),
-                "require (" => in_require_block = true,
-                ")" if in_require_block => in_require_block = false,
-                l if in_require_block || l.starts_with("require ") => {
-                    let cleaned = l.strip_prefix("require").unwrap_or(l).trim();
-                    let parts: Vec<&str> = cleaned.split_whitespace().collect();
-                    if parts.len() >= 2 {
+                Some((directive, _)) => (directive, trimmed),
+                None if trimmed == ")" => {
+                    errors.push(match last_directive {
+                        Some(d) => syntax(
+                            format!("`{}` block closed but never opened at line {}", d, i + 1),
+                            &format!("open the block with `{} (` or remove the `)`", d),
+                        ),
+                        None => syntax(
+                            format!("block closed but never opened at line {}", i + 1),
+                            "remove the `)`",
+                        ),
+                    });
+                    continue;
+                }
+                None => match trimmed.strip_suffix('(').map(str::trim_end) {
+                    // `require (`, or `require(` without a space
+                    Some(directive)
+                        if !directive.is_empty() && !directive.contains(char::is_whitespace) =>
+                    {
+                        block = Some((directive, i + 1));
+                        last_directive = Some(directive);
+                        above_block = comments;
+                        continue;
+                    }
+                    _ => match trimmed.split_once(char::is_whitespace) {
+                        Some((directive, rest)) => (directive, rest.trim()),
+                        None => (trimmed, ""),
+                    },
+                },
+            };
+            last_directive = Some(directive);
+
+            if module.is_none()
+                && !module_too_late
+                && matches!(directive, "require" | "replace" | "exclude" | "retract")
+            {
+                // reported once, the entries themselves are still read
+                errors.push(syntax(
+                    format!("`{}` appears before the `module` directive", directive),
+                    "move the `module` line to the top of the file",
+                ));
+                module_too_late = true;
+            }
+
+            match directive {
+                "module" => {
+                    module = Some(rest.to_string());
+                    spans.module = Some(span());
+                }
+                "go" => {
+                    go_version = Some(rest.to_string());
+                    spans.go_version = Some(span());
+                }
+                "toolchain" => toolchain = Some(rest.to_string()),
+                "require" => {
+                    let parts: Vec<&str> = rest.split_whitespace().collect();
+                    if let [name, version] = parts[..] {
+                        let version = unquote(version);
                         requires.push(GoDependency {
-                            name: parts[0].to_string(),
-                            version: parts[1].to_string(),
+                            name: unquote(name).to_string(),
+                            version: version.to_string(),
+                            indirect: is_indirect(line),
                         });
+                        spans.requires.push(span());
                     } else {
-                        return Err(ParseError::Syntax {
-                            line: i + 1,
-                            msg: format!("Invalid require entry: `{}`", line),
+                        errors.push(syntax(
+                            format!("Invalid require entry: `{}`", line),
+                            "write it as `<module path> <version>`",
+                        ));
+                    }
+                }
+                "exclude" => {
+                    let parts: Vec<&str> = rest.split_whitespace().collect();
+                    if let [name, version] = parts[..] {
+                        excludes.push(GoExclude {
+                            name: unquote(name).to_string(),
+                            version: unquote(version).to_string(),
                         });
+                    } else {
+                        errors.push(syntax(
+                            format!("Invalid exclude entry: `{}`", line),
+                            "write it as `<module path> <version>`",
+                        ));
                     }
                 }
-                _ => {}
+                "retract" => {
+                    // the trailing comment, else the ones above the entry,
+                    // else the ones above its block, as in cmd/go
+                    let rationale = match line.split_once("//") {
+                        Some((_, comment)) if !comment.trim().is_empty() => vec![comment.trim()],
+                        _ if !comments.is_empty() || block.is_none() => comments,
+                        _ => above_block.clone(),
+                    };
+                    let rationale = (!rationale.is_empty()).then(|| rationale.join("\n"));
+                    match GoRetract::parse(rest, rationale) {
+                        Some(retract) => retracts.push(retract),
+                        None => errors.push(syntax(
+                            format!("Invalid retract entry: `{}`", line),
+                            "write it as `<version>` or `[<low>, <high>]`",
+                        )),
+                    }
+                }
+                "replace" => {
+                    match rest
+                        .split_once("=>")
+                        .and_then(|(old, new)| parse_replace(old, new))
+                    {
+                        Some(replace) => replaces.push(replace),
+                        None => errors.push(syntax(
+                            format!("Invalid replace entry: `{}`", line),
+                            "write it as `<old path> [version] => <new path> [version]`",
+                        )),
+                    }
+                }
+                _ => {
+                    if let Some(err) = misspelled_directive(directive, GO_MOD_DIRECTIVES, i + 1) {
+                        errors.push(err);
+                        continue;
+                    }
+                    #[cfg(feature = "tracing")]
+                    tracing::trace!(line = i + 1, directive, "skipping directive");
+                }
             }
         }
 
-        let module = module.ok_or(ParseError::MissingField("module"))?;
-        let go_version = go_version.ok_or(ParseError::MissingField("go version"))?;
+        if let Some((directive, opened)) = block {
+            errors.push(ParseError::Syntax {
+                line: opened,
+                msg: format!(
+                    "`{}` block opened at line {} is never closed",
+                    directive, opened
+                ),
+                suggestion: Some("add a closing `)`".to_string()),
+            });
+        }
+        // the unclosed block is only found at the end but reported at its
+        // opening line; errors without a line go last
+        errors.sort_by_key(|err| match err {
+            ParseError::Syntax { line, .. } => *line,
+            _ => usize::MAX,
+        });
 
-        Ok(GoMod {
-            module,
-            go_version,
-            requires,
-            // replaces,
-            // excludes,
-        })
+        match module {
+            Some(module) => gomod.module = module,
+            None => errors.push(ParseError::MissingField("module")),
+        }
+        match go_version {
+            Some(go_version) => gomod.go_version = go_version,
+            None => errors.push(ParseError::MissingField("go version")),
+        }
+        gomod.toolchain = toolchain;
+        gomod.requires = requires;
+        gomod.replaces = replaces;
+        gomod.excludes = excludes;
+        gomod.retracts = retracts;
+        gomod
+    }
+}
+
+/// Directives of `go.mod`, including those the model skips.
+const GO_MOD_DIRECTIVES: &[&str] = &[
+    "module",
+    "go",
+    "toolchain",
+    "godebug",
+    "require",
+    "exclude",
+    "replace",
+    "retract",
+    "tool",
+    "ignore",
+];
+
+/// A [`ParseError::Syntax`] suggesting the closest of `known` if
+/// `directive` looks like a typo of one, e.g. `requir`. Directives too far
+/// from any known one are left alone, so files using a newer directive
+/// still parse.
+pub(crate) fn misspelled_directive(
+    directive: &str,
+    known: &[&str],
+    line: usize,
+) -> Option<ParseError> {
+    let max = if directive.chars().count() <= 4 { 1 } else { 2 };
+    let mut candidates: Vec<(usize, &str)> = known
+        .iter()
+        .filter(|k| **k != directive)
+        .map(|k| (edit_distance(directive, k), *k))
+        .filter(|(distance, _)| *distance <= max)
+        .collect();
+    // closest first, ties in the order of `known`
+    candidates.sort_by_key(|(distance, _)| *distance);
+    let names: Vec<String> = candidates
+        .iter()
+        .take(3)
+        .map(|(_, k)| format!("`{}`", k))
+        .collect();
+    let suggestion = match names.as_slice() {
+        [] => return None,
+        [one] => format!("did you mean {}?", one),
+        [rest @ .., last] => format!("did you mean {} or {}?", rest.join(", "), last),
+    };
+    Some(ParseError::Syntax {
+        line,
+        msg: format!("unknown directive `{}`", directive),
+        suggestion: Some(suggestion),
+    })
+}
+
+/// Edits (insertions, deletions, substitutions and swaps of adjacent
+/// characters) needed to turn `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    // rows i-2, i-1 and i of the distance table
+    let mut before: Vec<usize> = Vec::new();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        let mut current = vec![i; b.len() + 1];
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            current[j] = (previous[j] + 1)
+                .min(current[j - 1] + 1)
+                .min(previous[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = current[j].min(before[j - 2] + 1);
+            }
+        }
+        before = std::mem::replace(&mut previous, current);
+    }
+    previous[b.len()]
+}
+
+/// Whether the comment of `line` marks a requirement as indirect, like
+/// `// indirect` or `// indirect; needed by tests`.
+fn is_indirect(line: &str) -> bool {
+    line.split_once("//").is_some_and(|(_, comment)| {
+        let comment = comment.trim();
+        comment == "indirect" || comment.starts_with("indirect;")
+    })
+}
+
+/// A [`ParseError::WrongFileType`] if `content` is clearly a `go.sum` (its
+/// first line is `<module> <version> h1:<hash>`) or a `go.work` (it has a
+/// `use` directive and no `module`), rather than a `go.mod`.
+fn wrong_file_type(content: &str) -> Option<ParseError> {
+    let lines = content
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with("//"));
+
+    let first: Vec<_> = lines.clone().next()?.split_whitespace().collect();
+    if first.len() == 3 && first[2].starts_with("h1:") {
+        return Some(ParseError::WrongFileType {
+            detected: "go.sum",
+            suggestion: "parse it with `GoSum::parse_str`, or read it with `VerifiedModuleSet::from_parts` together with the go.mod next to it",
+        });
+    }
+
+    let mut uses = false;
+    for line in lines {
+        match line.split(|c: char| c.is_whitespace() || c == '(').next() {
+            Some("module") => return None,
+            Some("use") => uses = true,
+            _ => {}
+        }
     }
+    uses.then_some(ParseError::WrongFileType {
+        detected: "go.work",
+        suggestion: "parse it with `GoWork::parse_str`, then the go.mod of each module listed in its `use` directives",
+    })
+}
+
+/// Strips the quotes of a module path or version written as a Go string,
+/// e.g. `"example.com/m"`.
+pub(crate) fn unquote(s: &str) -> &str {
+    ['"', '`']
+        .iter()
+        .find_map(|q| s.strip_prefix(*q)?.strip_suffix(*q))
+        .unwrap_or(s)
+}
+
+/// Splits both sides of `old [version] => new [version]`.
+pub(crate) fn parse_replace(old: &str, new: &str) -> Option<GoReplace> {
+    let mut old = old.split_whitespace();
+    let mut new = new.split_whitespace();
+    let replace = GoReplace {
+        old_path: old.next()?.to_string(),
+        old_version: old.next().map(str::to_string),
+        new_path: new.next()?.to_string(),
+        new_version: new.next().map(str::to_string),
+    };
+
+    if old.next().is_some() || new.next().is_some() {
+        return None;
+    }
+    Some(replace)
 }
 
 /// Errors returned by `go.mod` parsing routines.
@@ -186,18 +1086,51 @@ pub enum ParseError {
         line: usize,
         /// Details of the error.
         msg: String,
+        /// How to fix it, for tools to show next to the error.
+        suggestion: Option<String>,
     },
 
     /// A required field (e.g. `module`, `go`) was not found.
     #[error("Missing required field: {0}")]
     MissingField(&'static str),
+
+    /// The input is another kind of Go file, e.g. `go.sum` passed where a
+    /// `go.mod` was expected.
+    #[error("Expected a go.mod file, but this looks like a {detected} file; {suggestion}")]
+    WrongFileType {
+        /// The file the input looks like, e.g. `go.work`.
+        detected: &'static str,
+        /// What to do instead.
+        suggestion: &'static str,
+    },
+
+    /// A line of a `go.sum` file that isn't
+    /// `<module> <version>[/go.mod] <hash>`.
+    #[error("Invalid go.sum entry on line {line}: `{entry}`")]
+    SumEntry {
+        /// Line number (starting at 1).
+        line: usize,
+        /// The line as written.
+        entry: String,
+    },
+}
+
+impl DiagnosticCode for ParseError {
+    fn code(&self) -> &'static str {
+        match self {
+            ParseError::Io(_) => "GOMOD001",
+            ParseError::Syntax { .. } => "GOMOD002",
+            ParseError::MissingField(_) => "GOMOD003",
+            ParseError::WrongFileType { .. } => "GOMOD012",
+            ParseError::SumEntry { .. } => "GOMOD016",
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs;
-    use std::path::PathBuf;
+    use crate::version::PseudoVersion;
 
     fn fixture_go_mod() -> &'static str {
         r#"
@@ -225,6 +1158,232 @@ mod tests {
         assert_eq!(parsed.requires[2].name, "github.com/three/lib");
     }
 
+    #[test]
+    fn test_parse_replaces() {
+        let content = r#"
+        module example.com/test
+        go 1.21
+
+        replace github.com/one/lib => ../lib // local checkout
+
+        replace (
+            github.com/two/lib v2.3.4 => github.com/fork/lib v2.3.5
+            github.com/three/lib => github.com/fork/three v0.1.0
+        )
+    "#;
+        let parsed = GoMod::parse_str(content).unwrap();
+
+        assert_eq!(parsed.replaces.len(), 3);
+        assert_eq!(parsed.replaces[0].new_path, "../lib");
+        assert!(parsed.replaces[0].is_local());
+        assert_eq!(
+            parsed.replaces[1],
+            GoReplace {
+                old_path: "github.com/two/lib".into(),
+                old_version: Some("v2.3.4".into()),
+                new_path: "github.com/fork/lib".into(),
+                new_version: Some("v2.3.5".into()),
+            }
+        );
+        assert_eq!(parsed.replaces[2].old_version, None);
+
+        let bad = "module a\ngo 1.20\nreplace github.com/foo/bar ../bar";
+        assert!(matches!(
+            GoMod::parse_str(bad),
+            Err(ParseError::Syntax { line: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn test_excludes() {
+        let content = "module m\ngo 1.21\n\
+                       exclude github.com/a/x v1.0.0 // broken release\n\
+                       exclude (\n\
+                       \tgithub.com/a/y v0.3.0\n\
+                       \tgithub.com/a/y v0.3.1\n\
+                       )\n";
+        let mut parsed = GoMod::parse_str(content).unwrap();
+        assert_eq!(parsed.excludes.len(), 3);
+        assert_eq!(
+            parsed.excludes[0],
+            GoExclude {
+                name: "github.com/a/x".into(),
+                version: "v1.0.0".into(),
+            }
+        );
+        assert_eq!(parsed.get(&["excludes", "2", "version"]), Some("v0.3.1"));
+
+        parsed
+            .set(&["excludes", "3"], "github.com/a/z@v2.0.0")
+            .unwrap();
+        parsed.set(&["excludes", "1", "version"], "v0.3.2").unwrap();
+        parsed.remove(&["excludes", "0"]).unwrap();
+        let found: Vec<_> = parsed
+            .excludes
+            .iter()
+            .map(|e| format!("{}@{}", e.name, e.version))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                "github.com/a/y@v0.3.2",
+                "github.com/a/y@v0.3.1",
+                "github.com/a/z@v2.0.0"
+            ]
+        );
+        assert!(parsed.set(&["excludes", "9"], "a.com/x@v1.0.0").is_err());
+        assert!(parsed.set(&["excludes", "0"], "a.com/x").is_err());
+        assert_eq!(GoMod::parse_str(&parsed.to_string()).unwrap(), parsed);
+
+        let bad = "module m\ngo 1.21\nexclude github.com/a/x\n";
+        assert!(matches!(
+            GoMod::parse_str(bad),
+            Err(ParseError::Syntax { line: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn test_toolchain() {
+        let content = "module m\ngo 1.21\ntoolchain go1.22.3 // pinned for CI\n";
+        let mut parsed = GoMod::parse_str(content).unwrap();
+        assert_eq!(parsed.toolchain.as_deref(), Some("go1.22.3"));
+        assert_eq!(parsed.get(&["toolchain"]), Some("go1.22.3"));
+        assert_eq!(
+            parsed.to_string(),
+            "module m\n\ngo 1.21\n\ntoolchain go1.22.3\n"
+        );
+
+        parsed.set(&["toolchain"], "go1.23.0").unwrap();
+        assert_eq!(
+            parsed.patch_source(content),
+            "module m\ngo 1.21\ntoolchain go1.23.0 // pinned for CI\n"
+        );
+        parsed.remove(&["toolchain"]).unwrap();
+        assert_eq!(parsed.get(&["toolchain"]), None);
+        assert_eq!(parsed.patch_source(content), "module m\ngo 1.21\n");
+
+        let without = GoMod::parse_str("module m\n\ngo 1.21\n\nrequire a.com/x v1.0.0\n").unwrap();
+        assert_eq!(without.toolchain, None);
+        let mut with = without.clone();
+        with.set(&["toolchain"], "go1.22.3").unwrap();
+        assert_eq!(
+            with.patch_source(&without.to_string()),
+            "module m\n\ngo 1.21\n\ntoolchain go1.22.3\n\nrequire a.com/x v1.0.0\n"
+        );
+    }
+
+    #[test]
+    fn test_retracts() {
+        let content = "module example.com/m\ngo 1.21\n\
+                       retract v1.0.0 // published too early\n\
+                       \n\
+                       // the cache layer corrupts data;\n\
+                       // fixed in v1.2.1\n\
+                       retract [v1.1.0, \"v1.2.0\"]\n\
+                       // found by the fuzzer\n\
+                       retract (\n\
+                       \tv1.3.0\n\
+                       \t// see #42\n\
+                       \tv1.3.1\n\
+                       )\n";
+        let mut parsed = GoMod::parse_str(content).unwrap();
+        assert_eq!(
+            parsed.retracts,
+            [
+                GoRetract::Version {
+                    version: "v1.0.0".into(),
+                    rationale: Some("published too early".into()),
+                },
+                GoRetract::Range {
+                    low: "v1.1.0".into(),
+                    high: "v1.2.0".into(),
+                    rationale: Some("the cache layer corrupts data;\nfixed in v1.2.1".into()),
+                },
+                GoRetract::Version {
+                    version: "v1.3.0".into(),
+                    rationale: Some("found by the fuzzer".into()),
+                },
+                GoRetract::Version {
+                    version: "v1.3.1".into(),
+                    rationale: Some("see #42".into()),
+                },
+            ]
+        );
+        assert_eq!(GoMod::parse_str(&parsed.to_string()).unwrap(), parsed);
+
+        assert_eq!(parsed.get(&["retracts", "1", "high"]), Some("v1.2.0"));
+        assert_eq!(parsed.get(&["retracts", "0", "low"]), Some("v1.0.0"));
+        assert_eq!(parsed.get(&["retracts", "1", "version"]), None);
+        parsed.set(&["retracts", "0"], "[v1.0.0, v1.0.2]").unwrap();
+        parsed.set(&["retracts", "4"], "v1.4.0").unwrap();
+        parsed
+            .set(&["retracts", "4", "rationale"], "leaks tokens")
+            .unwrap();
+        parsed.remove(&["retracts", "2", "rationale"]).unwrap();
+        parsed.remove(&["retracts", "3"]).unwrap();
+        assert!(parsed.set(&["retracts", "0", "version"], "v1.0.1").is_err());
+        assert!(parsed.set(&["retracts", "0"], "[v1.0.0]").is_err());
+        assert_eq!(
+            parsed.to_string(),
+            "module example.com/m\n\ngo 1.21\n\nretract (\n\
+             \t[v1.0.0, v1.0.2] // published too early\n\
+             \t// the cache layer corrupts data;\n\
+             \t// fixed in v1.2.1\n\
+             \t[v1.1.0, v1.2.0]\n\
+             \tv1.3.0\n\
+             \tv1.4.0 // leaks tokens\n\
+             )\n"
+        );
+
+        let bad = "module m\ngo 1.21\nretract [v1.0.0 v1.1.0]\n";
+        assert!(matches!(
+            GoMod::parse_str(bad),
+            Err(ParseError::Syntax { line: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn test_replace_paths() {
+        let mut parsed = GoMod::parse_str(
+            "module m\ngo 1.21\nreplace (\n\ta.com/x v1.0.0 => b.com/x v1.0.1\n\ta.com/y => ../y\n)\n",
+        )
+        .unwrap();
+        assert_eq!(
+            parsed.get(&["replaces", "0", "old_version"]),
+            Some("v1.0.0")
+        );
+        assert_eq!(parsed.get(&["replaces", "1", "new_path"]), Some("../y"));
+        assert_eq!(parsed.get(&["replaces", "1", "new_version"]), None);
+
+        // point the first replace at a local checkout for every version
+        parsed.set(&["replaces", "0", "new_path"], "../x").unwrap();
+        parsed.set(&["replaces", "0", "new_version"], "").unwrap();
+        parsed.remove(&["replaces", "0", "old_version"]).unwrap();
+        assert_eq!(
+            parsed.replaces[0],
+            GoReplace {
+                old_path: "a.com/x".into(),
+                old_version: None,
+                new_path: "../x".into(),
+                new_version: None,
+            }
+        );
+
+        parsed
+            .set(&["replaces", "1", "new_path"], "c.com/y")
+            .unwrap();
+        parsed
+            .set(&["replaces", "1", "new_version"], "v0.2.0")
+            .unwrap();
+        assert!(!parsed.replaces[1].is_local());
+
+        parsed.remove(&["replaces", "0"]).unwrap();
+        assert_eq!(parsed.replaces.len(), 1);
+        assert!(parsed.set(&["replaces", "1", "new_path"], "x").is_err());
+        assert!(parsed.set(&["replaces", "0", "path"], "x").is_err());
+        assert!(parsed.remove(&["replaces", "0", "new_path"]).is_err());
+    }
+
     #[test]
     fn test_get_paths() {
         let parsed = GoMod::parse_str(fixture_go_mod()).unwrap();
@@ -234,6 +1393,30 @@ mod tests {
         assert_eq!(parsed.get(&["requires", "1", "version"]), Some("v2.3.4"));
         assert_eq!(parsed.get(&["requires", "99", "name"]), None);
         assert_eq!(parsed.get(&["invalid"]), None);
+
+        let requires = parsed.get_ref(&["requires"]).unwrap();
+        assert_eq!(requires.as_seq().map(<[_]>::len), Some(3));
+        assert_eq!(
+            parsed.get_ref(&["requires", "1", "version"]),
+            Some(ValueRef::Str("v2.3.4"))
+        );
+        assert_eq!(
+            parsed.get_ref(&["requires", "1", "indirect"]),
+            Some(ValueRef::Bool(false))
+        );
+        assert_eq!(parsed.get_ref(&["requires", "1", "sum"]), None);
+        // the same as looking the path up in the whole tree
+        for path in [
+            &[][..],
+            &["module"],
+            &["toolchain"],
+            &["replaces", "0"],
+            &["retracts", "0", "rationale"],
+            &["requires", "x"],
+            &["module", "name"],
+        ] {
+            assert_eq!(parsed.get_ref(path), parsed.as_value_ref().lookup(path));
+        }
     }
 
     #[test]
@@ -290,26 +1473,312 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_file_ok() {
-        let content = r#"
-        module local.test/foo
-        go 1.19
-        require github.com/test/lib v1.2.3
-    "#;
+    fn test_require_edge_cases() {
+        // spacing, quoting and comments the parser has to tolerate
+        let content = "module a.com/b // the service\n\
+                       go 1.21 // bumped\n\
+                       require(\n\
+                       \tgithub.com/a/x\t \tv1.0.0//indirect\n\
+                       \t\"github.com/a/y\" \"v1.1.0\"\n\
+                       \tgithub.com/a/z v2.0.0+incompatible // no go.mod\n\
+                       ) // end of requires\n\
+                       require ( // tools\n\
+                       \tgolang.org/x/tools v0.21.0\n\
+                       )\n";
+        let parsed = GoMod::parse_str(content).unwrap();
+        assert_eq!(parsed.module, "a.com/b");
+        assert_eq!(parsed.go_version, "1.21");
+        let found: Vec<_> = parsed
+            .requires
+            .iter()
+            .map(|d| {
+                (
+                    d.name.as_str(),
+                    d.version.as_str(),
+                    d.is_incompatible(),
+                    d.indirect,
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("github.com/a/x", "v1.0.0", false, true),
+                ("github.com/a/y", "v1.1.0", false, false),
+                ("github.com/a/z", "v2.0.0+incompatible", true, false),
+                ("golang.org/x/tools", "v0.21.0", false, false),
+            ]
+        );
 
-        let path = PathBuf::from("tmp_test.mod");
-        fs::write(&path, content).unwrap();
+        let mut go_mod = parsed;
+        go_mod
+            .set(&["requires", "0", "version"], "v3.0.0+incompatible")
+            .unwrap();
+        assert!(go_mod.requires[0].is_incompatible());
+        // the only indirect requirement moves to a `require` of its own
+        let written = go_mod.to_string();
+        assert!(written.ends_with("\nrequire github.com/a/x v3.0.0+incompatible // indirect\n"));
+        let reparsed = GoMod::parse_str(&written).unwrap();
+        assert_eq!(reparsed.requires.last(), Some(&go_mod.requires[0]));
+        assert_eq!(reparsed.to_string(), written);
 
-        let parsed = GoMod::parse_file(&path).unwrap();
-        assert_eq!(parsed.module, "local.test/foo");
-        assert_eq!(parsed.requires[0].version, "v1.2.3");
+        for extra in [
+            "require a.com/x v1.0.0 v1.1.0\n",
+            "require a.com/x v1.0.0 indirect\n",
+        ] {
+            let content = format!("module a.com/b\ngo 1.21\n{}", extra);
+            assert!(matches!(
+                GoMod::parse_str(&content),
+                Err(ParseError::Syntax { line: 3, .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_misplaced_directives_have_suggestions() {
+        let errors = [
+            "module a.com/b\ngo 1.20\nrequire x.com/y v1.0.0\n)\n",
+            "require x.com/y v1.0.0\nmodule a.com/b\ngo 1.20\n",
+            "module a.com/b\ngo 1.20\nrequire (\n\tx.com/y v1.0.0\n",
+        ]
+        .map(|src| match GoMod::parse_str(src) {
+            Err(ParseError::Syntax {
+                line,
+                msg,
+                suggestion,
+            }) => (line, msg, suggestion.unwrap()),
+            other => panic!("Expected a syntax error, got {:?}", other),
+        });
+
+        assert_eq!(
+            errors[0],
+            (
+                4,
+                "`require` block closed but never opened at line 4".to_string(),
+                "open the block with `require (` or remove the `)`".to_string()
+            )
+        );
+        assert_eq!(errors[1].0, 1);
+        assert_eq!(errors[1].2, "move the `module` line to the top of the file");
+        assert_eq!(
+            (errors[2].0, errors[2].1.as_str()),
+            (3, "`require` block opened at line 3 is never closed")
+        );
+    }
+
+    #[test]
+    fn test_typo_directives_suggest_the_closest() {
+        let suggestion = |src: &str| match GoMod::parse_str(src) {
+            Err(ParseError::Syntax {
+                line,
+                msg,
+                suggestion,
+            }) => (line, msg, suggestion.unwrap()),
+            other => panic!("Expected a syntax error, got {:?}", other),
+        };
+
+        assert_eq!(
+            suggestion("modul a.com/b\ngo 1.22\n"),
+            (
+                1,
+                "unknown directive `modul`".to_string(),
+                "did you mean `module`?".to_string()
+            )
+        );
+        let (line, _, hint) =
+            suggestion("module a.com/b\ngo 1.22\nrequrie (\n\tx.com/y v1.0.0\n)\n");
+        assert_eq!((line, hint.as_str()), (4, "did you mean `require`?"));
+        let (_, _, hint) = suggestion("module a.com/b\ngo 1.22\nreplac x.com/y => ../y\n");
+        assert_eq!(hint, "did you mean `replace`?");
+        let (_, _, hint) = suggestion("module a.com/b\ngo 1.22\nretrace v1.0.0\n");
+        assert_eq!(hint, "did you mean `retract` or `replace`?");
+
+        // far from every known directive: skipped as before
+        let gomod =
+            GoMod::parse_str("module a.com/b\ngo 1.22\nfrobnicate x\ngodebug default=go1.21\n");
+        assert!(gomod.is_ok());
+        assert_eq!(edit_distance("toolchian", "toolchain"), 1);
+    }
+
+    #[test]
+    fn test_wrong_file_type() {
+        let sum = "github.com/pkg/errors v0.9.1 h1:FEBLx1zS214owpjy7qsBeixbURkuhQAwrK5UwLGTwt4=\n\
+                   github.com/pkg/errors v0.9.1/go.mod h1:bwawxfHBFNV+L2hUp1rHADufV3IMtnDRdf1r5NINEl0=\n";
+        let work =
+            "// workspace\ngo 1.22\n\nuse (\n\t./api\n\t./web\n)\n\nreplace a.com/x => ../x\n";
+
+        for (src, detected) in [(sum, "go.sum"), (work, "go.work")] {
+            let err = GoMod::parse_str(src).unwrap_err();
+            assert_eq!(err.code(), "GOMOD012");
+            assert!(matches!(err, ParseError::WrongFileType { detected: d, .. } if d == detected));
+        }
+        assert_eq!(
+            GoMod::parse_str(work).unwrap_err().to_string(),
+            "Expected a go.mod file, but this looks like a go.work file; parse it with \
+             `GoWork::parse_str`, then the go.mod of each module listed in its `use` directives"
+        );
+
+        // `use` isn't a go.mod directive, but a module line makes it one
+        assert!(GoMod::parse_str("module m\ngo 1.22\nuse ./x\n").is_ok());
+    }
+
+    #[test]
+    fn test_error_codes() {
+        let err = GoMod::parse_str("go 1.18").unwrap_err();
+        assert_eq!(err.code(), "GOMOD003");
+
+        let err = GoMod::parse_str("module a\ngo 1.20\nrequire github.com/foo/bar").unwrap_err();
+        assert_eq!(err.code(), "GOMOD002");
+    }
+
+    #[test]
+    fn test_semantic_eq_ignores_require_order() {
+        let reordered = GoMod::parse_str(
+            "module example.com/test\ngo 1.20\n\
+             require github.com/three/lib v0.9.1\n\
+             require (\n\tgithub.com/two/lib v2.3.4\n\tgithub.com/one/lib v1.0.0\n)\n",
+        )
+        .unwrap();
+        let original = GoMod::parse_str(fixture_go_mod()).unwrap();
+        assert_ne!(original, reordered);
+        assert!(original.semantic_eq(&reordered));
 
-        fs::remove_file(&path).unwrap();
+        let mut bumped = reordered.clone();
+        bumped.requires[0].version = "v0.9.2".into();
+        assert!(!original.semantic_eq(&bumped));
     }
 
     #[test]
-    fn test_parse_file_io_error() {
-        let result = GoMod::parse_file("nonexistent_path.go.mod");
-        assert!(matches!(result, Err(ParseError::Io(_))));
+    fn test_validate_schema_policy() {
+        let gomod = GoMod::parse_str(
+            "module example.com/app\ngo 1.21.4\nrequire (\n\
+             \tcorp-internal/auth v1.4.0\n\
+             \tcorp-internal/billing v1.2.4-0.20240101120000-abcdef123456\n\
+             \tgithub.com/pkg/errors v0.0.0-20240101120000-abcdef123456\n\
+             )\n",
+        )
+        .unwrap();
+        let internal_commit = Schema::default()
+            .required("name", Schema::matching("corp-internal/*"))
+            .required(
+                "version",
+                Schema::default().satisfies(PseudoVersion::PREDICATE),
+            );
+        let policy = Schema::default()
+            .required("go_version", Schema::default().at_least_version("1.21"))
+            .field(
+                "requires",
+                Schema::default().items(Schema::default().forbid(internal_commit)),
+            );
+
+        let violations = gomod.validate_schema(&policy);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].path, ["requires", "1"]);
+        assert_eq!(violations[0].code(), "SCHEMA006");
+
+        let old = GoMod {
+            go_version: "1.20".into(),
+            ..gomod
+        };
+        assert_eq!(
+            old.validate_schema(&policy)[0].kind,
+            uniparse_core::ViolationKind::OlderThan {
+                minimum: "1.21".into(),
+                found: "1.20".into()
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_recovering_reports_every_error() {
+        let src = "require github.com/early/x v1.0.0
+module example.com/app
+requir github.com/typo/x v1.0.0
+require (
+\tgithub.com/a/x v1.0.0
+\tgithub.com/a/y
+\tgithub.com/a/z v1.2.0
+)
+)
+exclude github.com/a/x
+replace github.com/a/x => ../x
+retract [v1.0.0
+";
+        let (gomod, errors) = GoMod::parse_recovering(src);
+        let lines: Vec<_> = errors
+            .iter()
+            .map(|e| match e {
+                ParseError::Syntax { line, .. } => Some(*line),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            lines,
+            [Some(1), Some(3), Some(6), Some(9), Some(10), Some(12), None]
+        );
+        assert!(matches!(
+            errors.last(),
+            Some(ParseError::MissingField("go version"))
+        ));
+
+        // the first error is the one parse_str stops at
+        assert_eq!(
+            GoMod::parse_str(src).unwrap_err().to_string(),
+            errors[0].to_string()
+        );
+
+        assert_eq!(gomod.module, "example.com/app");
+        assert_eq!(gomod.go_version, "");
+        let requires: Vec<_> = gomod.requires.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(
+            requires,
+            ["github.com/early/x", "github.com/a/x", "github.com/a/z"]
+        );
+        assert_eq!(gomod.replaces.len(), 1);
+        assert!(gomod.excludes.is_empty());
+
+        // an unclosed block is reported before the entries inside it
+        let (_, errors) =
+            GoMod::parse_recovering("module m\ngo 1.22\nrequire (\n\ta.com/x v1.0.0\n\ta.com/y\n");
+        let lines: Vec<_> = errors
+            .iter()
+            .map(|e| match e {
+                ParseError::Syntax { line, .. } => *line,
+                _ => 0,
+            })
+            .collect();
+        assert_eq!(lines, [3, 5]);
+
+        let (gomod, errors) = GoMod::parse_recovering(fixture_go_mod());
+        assert!(errors.is_empty());
+        assert_eq!(gomod, GoMod::parse_str(fixture_go_mod()).unwrap());
+    }
+
+    #[test]
+    fn test_parsed_file_impl() {
+        fn bump<F: ParsedFile<OwnedValue = String>>(
+            src: &str,
+            path: &[&str],
+            value: &str,
+        ) -> String {
+            let mut file = F::parse_str(src).ok().unwrap();
+            file.set(path, value.to_string()).unwrap();
+            file.to_string_pretty()
+        }
+
+        let src = "module example.com/m\ngo 1.21\nrequire github.com/a/x v1.0.0\n";
+        let gomod: GoMod = src.parse().unwrap();
+        assert_eq!(ParsedFile::get(&gomod, &["go_version"]), Some("1.21"));
+        assert_eq!(
+            ParsedFile::get_ref(&gomod, &["requires", "0", "indirect"]),
+            Some(ValueRef::Bool(false))
+        );
+        assert!(gomod.supports_spans());
+        assert_eq!(
+            bump::<GoMod>(src, &["requires", "0", "version"], "v1.1.0"),
+            "module example.com/m\n\ngo 1.21\n\nrequire github.com/a/x v1.1.0\n"
+        );
+        let shared = uniparse_core::Document::<GoMod>::parse_str(src).unwrap();
+        assert_eq!(shared.get(&["module"]), Some("example.com/m"));
     }
 }
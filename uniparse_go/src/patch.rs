@@ -0,0 +1,572 @@
+use std::fmt::Display;
+use std::ops::Range;
+
+use uniparse_core::{CstKind, SourceEdit, SyntaxNode, whole_lines};
+
+use crate::cst::{GoModCst, SyntaxKind};
+use crate::format::write_entry;
+use crate::model::{GoDependency, GoExclude, GoMod, GoReplace, GoRetract};
+
+type Edit = (Range<usize>, String);
+
+impl GoMod {
+    /// Writes this file into `original`, the source it was parsed from,
+    /// rewriting only the directive lines that changed.
+    ///
+    /// Entries are matched by module path (and version, for `exclude`,
+    /// `replace` and `retract`). A changed entry has its line rewritten,
+    /// keeping its trailing comment. Removed entries are deleted together
+    /// with the comment lines right above them, and so are blocks that end
+    /// up empty. New entries are inserted after the entry before them, in
+    /// the same block or as the same kind of single line. Directives the
+    /// file didn't have yet are appended at the end. Every other byte stays
+    /// as it was. If `original` doesn't parse, the result is the
+    /// [`Display`] output.
+    ///
+    /// ```rust
+    /// use uniparse_go::GoMod;
+    ///
+    /// let src = "module example.com/m\n\ngo 1.21\n\nrequire (\n\tgithub.com/a/x v1.0.0 // indirect\n\tgithub.com/a/y v0.3.0\n)\n";
+    /// let mut gomod = GoMod::parse_str(src).unwrap();
+    /// gomod.set(&["requires", "0", "version"], "v1.0.1").unwrap();
+    /// assert_eq!(
+    ///     gomod.patch_source(src),
+    ///     "module example.com/m\n\ngo 1.21\n\nrequire (\n\tgithub.com/a/x v1.0.1 // indirect\n\tgithub.com/a/y v0.3.0\n)\n"
+    /// );
+    /// ```
+    pub fn patch_source(&self, original: &str) -> String {
+        SourceEdit::apply(original, &self.patch_edits(original))
+    }
+
+    /// The edits [`GoMod::patch_source`] makes, in source order, e.g. to
+    /// send to an editor. If `original` doesn't parse, that is a single edit
+    /// replacing all of it.
+    pub fn patch_edits(&self, original: &str) -> Vec<SourceEdit> {
+        let (Ok(old), Ok(cst)) = (GoMod::parse_str(original), GoModCst::parse(original)) else {
+            return vec![SourceEdit::new(0..original.len(), self.to_string())];
+        };
+        let layout = Layout::new(original, &cst);
+
+        let mut edits = Vec::new();
+        for (at, old, new) in [
+            (&layout.module, &old.module, &self.module),
+            (&layout.go, &old.go_version, &self.go_version),
+        ] {
+            if let Some(at) = at
+                && old != new
+            {
+                edits.push((at.text.clone(), new.clone()));
+            }
+        }
+        match (&layout.toolchain, &self.toolchain) {
+            (Some(at), Some(new)) if old.toolchain.as_ref() != Some(new) => {
+                edits.push((at.text.clone(), new.clone()))
+            }
+            (Some(at), None) => {
+                edits.push((with_blank_line(original, at.span.clone()), String::new()))
+            }
+            (None, Some(new)) => {
+                // right after the `go` line, as `go mod tidy` puts it
+                let pos = layout.go.as_ref().map_or(original.len(), |go| go.span.end);
+                let mut text = if pos == original.len() && !original.ends_with('\n') {
+                    "\n\n".to_string()
+                } else {
+                    "\n".to_string()
+                };
+                text.push_str(&format!("toolchain {}\n", new));
+                edits.push((pos..pos, text));
+            }
+            _ => {}
+        }
+        let patched = patch_list(
+            original,
+            &layout,
+            &layout.requires,
+            &old.requires,
+            &self.requires,
+            &mut edits,
+        ) && patch_list(
+            original,
+            &layout,
+            &layout.excludes,
+            &old.excludes,
+            &self.excludes,
+            &mut edits,
+        ) && patch_list(
+            original,
+            &layout,
+            &layout.replaces,
+            &old.replaces,
+            &self.replaces,
+            &mut edits,
+        ) && patch_list(
+            original,
+            &layout,
+            &layout.retracts,
+            &old.retracts,
+            &self.retracts,
+            &mut edits,
+        );
+        if !patched {
+            return vec![SourceEdit::new(0..original.len(), self.to_string())];
+        }
+
+        // stable, so insertions at the same offset stay in order
+        edits.sort_by_key(|(range, _)| (range.start, range.end));
+        edits
+            .into_iter()
+            .map(|(range, text)| SourceEdit::new(range, text))
+            .collect()
+    }
+}
+
+/// An entry of one of the list directives.
+trait Entry: PartialEq {
+    const DIRECTIVE: &'static str;
+    /// Whether the comment lines above the entry belong to [`Entry::comment`]
+    /// rather than to the reader.
+    const OWNS_COMMENTS_ABOVE: bool = false;
+
+    /// What identifies the entry across edits.
+    fn key(&self) -> (&str, Option<&str>);
+
+    /// The entry as written after the directive, without comments.
+    fn text(&self) -> String;
+
+    /// The comment the model keeps for the entry.
+    fn comment(&self) -> Option<&str> {
+        None
+    }
+}
+
+impl Entry for GoDependency {
+    const DIRECTIVE: &'static str = "require";
+
+    fn key(&self) -> (&str, Option<&str>) {
+        (&self.name, None)
+    }
+
+    fn text(&self) -> String {
+        format!("{} {}", self.name, self.version)
+    }
+
+    fn comment(&self) -> Option<&str> {
+        self.indirect.then_some("indirect")
+    }
+}
+
+impl Entry for GoExclude {
+    const DIRECTIVE: &'static str = "exclude";
+
+    fn key(&self) -> (&str, Option<&str>) {
+        (&self.name, Some(&self.version))
+    }
+
+    fn text(&self) -> String {
+        format!("{} {}", self.name, self.version)
+    }
+}
+
+impl Entry for GoReplace {
+    const DIRECTIVE: &'static str = "replace";
+
+    fn key(&self) -> (&str, Option<&str>) {
+        (&self.old_path, self.old_version.as_deref())
+    }
+
+    fn text(&self) -> String {
+        let side = |path: &str, version: &Option<String>| match version {
+            Some(version) => format!("{} {}", path, version),
+            None => path.to_string(),
+        };
+        format!(
+            "{} => {}",
+            side(&self.old_path, &self.old_version),
+            side(&self.new_path, &self.new_version)
+        )
+    }
+}
+
+impl Entry for GoRetract {
+    const DIRECTIVE: &'static str = "retract";
+    const OWNS_COMMENTS_ABOVE: bool = true;
+
+    fn key(&self) -> (&str, Option<&str>) {
+        let (low, high) = self.bounds();
+        (low, Some(high))
+    }
+
+    fn text(&self) -> String {
+        self.to_string()
+    }
+
+    fn comment(&self) -> Option<&str> {
+        self.rationale()
+    }
+}
+
+/// Where the directives of a file are, in source order, as the nodes of its
+/// [`GoModCst`] place them.
+#[derive(Default)]
+struct Layout {
+    /// The last `module`, `go` and `toolchain` line.
+    module: Option<EntryLines>,
+    go: Option<EntryLines>,
+    toolchain: Option<EntryLines>,
+    requires: Vec<EntryLines>,
+    excludes: Vec<EntryLines>,
+    replaces: Vec<EntryLines>,
+    retracts: Vec<EntryLines>,
+    /// From the `(` line to after the `)` line.
+    blocks: Vec<Range<usize>>,
+}
+
+/// Where one entry is.
+struct EntryLines {
+    /// The comment lines right above the entry, its own line and its line
+    /// break.
+    span: Range<usize>,
+    /// The entry itself, without directive and comments.
+    text: Range<usize>,
+    /// Its leading whitespace.
+    indent: Range<usize>,
+    /// Index into [`Layout::blocks`] if it's inside one.
+    block: Option<usize>,
+}
+
+impl Layout {
+    /// Finds the directives of `cst`, parsed from `src`.
+    fn new(src: &str, cst: &GoModCst) -> Layout {
+        let mut layout = Layout::default();
+        for node in cst.syntax().descendants() {
+            let (owner, block) = match node.kind() {
+                SyntaxKind::Block => {
+                    layout.blocks.push(whole_lines(src, node.text_range()));
+                    continue;
+                }
+                SyntaxKind::Directive => (node.clone(), None),
+                SyntaxKind::Entry => match node.parent() {
+                    Some(parent) => (parent, layout.blocks.len().checked_sub(1)),
+                    None => continue,
+                },
+                _ => continue,
+            };
+            let Some(entry) = EntryLines::new(src, &node, block) else {
+                continue;
+            };
+            let keyword = owner.tokens().find(|t| t.kind() == SyntaxKind::Keyword);
+            match keyword.as_ref().map(|t| t.text()) {
+                Some("module") => layout.module = Some(entry),
+                Some("go") => layout.go = Some(entry),
+                Some("toolchain") => layout.toolchain = Some(entry),
+                Some("require") => layout.requires.push(entry),
+                Some("exclude") => layout.excludes.push(entry),
+                Some("replace") => layout.replaces.push(entry),
+                Some("retract") => layout.retracts.push(entry),
+                _ => {}
+            }
+        }
+        layout
+    }
+}
+
+impl EntryLines {
+    /// Where the directive or block entry `node` is. `None` if its line
+    /// holds nothing but the directive.
+    fn new(src: &str, node: &SyntaxNode<SyntaxKind>, block: Option<usize>) -> Option<Self> {
+        // the comment lines above belong to the node too
+        let tokens: Vec<_> = node.tokens().collect();
+        let line = tokens
+            .iter()
+            .rposition(|t| t.kind() == SyntaxKind::Newline)
+            .map_or(0, |i| i + 1);
+        let mut code = tokens[line..].iter().filter(|t| !t.kind().is_trivia());
+        let first = code.clone().next()?.text_range().start;
+        let start = code
+            .clone()
+            .find(|t| t.kind() != SyntaxKind::Keyword)?
+            .text_range()
+            .start;
+        let end = code.next_back()?.text_range().end;
+        Some(EntryLines {
+            span: whole_lines(src, node.text_range()),
+            text: start..end,
+            indent: line_start(src, first)..first,
+            block,
+        })
+    }
+}
+
+fn line_start(src: &str, pos: usize) -> usize {
+    src[..pos].rfind('\n').map_or(0, |i| i + 1)
+}
+
+/// Edits turning the entries at `lines`, which hold `old`, into `new`.
+/// Returns `false` if `lines` doesn't match `old`.
+fn patch_list<T: Entry>(
+    src: &str,
+    layout: &Layout,
+    lines: &[EntryLines],
+    old: &[T],
+    new: &[T],
+    edits: &mut Vec<Edit>,
+) -> bool {
+    if lines.len() != old.len() {
+        return false;
+    }
+    if old.is_empty() {
+        if !new.is_empty() {
+            let mut section = if src.is_empty() || src.ends_with('\n') {
+                "\n".to_string()
+            } else {
+                "\n\n".to_string()
+            };
+            match new {
+                [entry] => render(&mut section, entry, "", &format!("{} ", T::DIRECTIVE)),
+                entries => {
+                    section.push_str(&format!("{} (\n", T::DIRECTIVE));
+                    for entry in entries {
+                        render(&mut section, entry, "\t", "");
+                    }
+                    section.push_str(")\n");
+                }
+            }
+            edits.push((src.len()..src.len(), section));
+        }
+        return true;
+    }
+
+    let mut removed = Vec::new();
+    // blocks that keep or gain an entry
+    let mut used = vec![false; layout.blocks.len()];
+    // entry new ones go next to and where, before the first entry if none
+    // yet; in place of removed ones, whose blank line may go with them
+    let mut anchor: Option<(&EntryLines, usize)> = None;
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        let same_key = i < old.len() && j < new.len() && old[i].key() == new[j].key();
+        if same_key {
+            let at = &lines[i];
+            if old[i].comment() != new[j].comment() {
+                let mut text = String::new();
+                render(
+                    &mut text,
+                    &new[j],
+                    &src[at.indent.clone()],
+                    &prefix::<T>(src, at),
+                );
+                let range = match T::OWNS_COMMENTS_ABOVE {
+                    true => at.span.clone(),
+                    false => at.indent.start..at.span.end,
+                };
+                edits.push((range, text));
+            } else if old[i] != new[j] {
+                edits.push((at.text.clone(), new[j].text()));
+            }
+            if let Some(b) = at.block {
+                used[b] = true;
+            }
+            anchor = Some((at, at.span.end));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && !new[j..].iter().any(|e| e.key() == old[i].key()) {
+            removed.push(&lines[i]);
+            anchor = Some((&lines[i], lines[i].span.start));
+            i += 1;
+        } else {
+            let (at, pos) = anchor.unwrap_or((&lines[0], lines[0].span.start));
+            let mut text = String::new();
+            if pos == src.len() && !src.ends_with('\n') {
+                text.push('\n');
+            }
+            render(
+                &mut text,
+                &new[j],
+                &src[at.indent.clone()],
+                &prefix::<T>(src, at),
+            );
+            edits.push((pos..pos, text));
+            if let Some(b) = at.block {
+                used[b] = true;
+            }
+            j += 1;
+        }
+    }
+
+    for (b, block) in layout.blocks.iter().enumerate() {
+        let emptied = !used[b] && removed.iter().any(|e| e.block == Some(b));
+        if emptied {
+            edits.push((with_blank_line(src, block.clone()), String::new()));
+        }
+    }
+    for entry in removed {
+        match entry.block {
+            Some(b) if !used[b] => {}
+            Some(_) => edits.push((entry.span.clone(), String::new())),
+            None => edits.push((with_blank_line(src, entry.span.clone()), String::new())),
+        }
+    }
+    true
+}
+
+/// `range`, a group of whole lines, plus the blank line after it if there
+/// is one before it too, so deleting it doesn't leave two in a row.
+fn with_blank_line(src: &str, range: Range<usize>) -> Range<usize> {
+    let before = src[..range.start].trim_end_matches([' ', '\t', '\r']);
+    let after = src[range.end..].trim_start_matches([' ', '\t', '\r']);
+    if (before.is_empty() || before.ends_with("\n\n")) && after.starts_with('\n') {
+        range.start..src.len() - after.len() + 1
+    } else {
+        range
+    }
+}
+
+/// What goes between the indentation and the entry on new lines next to
+/// `at`: the directive unless it's in a block.
+fn prefix<T: Entry>(src: &str, at: &EntryLines) -> String {
+    match at.block {
+        Some(_) => String::new(),
+        None => src[at.indent.end..at.text.start].to_string(),
+    }
+}
+
+/// Appends `entry` as a line, with its comment.
+fn render<T: Entry>(out: &mut String, entry: &T, indent: &str, prefix: &str) {
+    let text: &dyn Display = &entry.text();
+    // writing to a String can't fail
+    let _ = write_entry(out, indent, prefix, text, entry.comment());
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SRC: &str = "// Code generated by hand.
+
+module example.com/app
+
+go 1.21
+
+require (
+\tgithub.com/a/x v1.0.0 // indirect
+\t// pinned until the API settles
+\tgithub.com/a/y v0.3.0
+)
+
+require github.com/a/z v2.0.0+incompatible
+
+replace (
+\tgithub.com/a/x => ../x
+)
+
+retract v1.0.0 // published too early
+";
+
+    #[test]
+    fn test_patch_source_unchanged_is_identical() {
+        let gomod = GoMod::parse_str(SRC).unwrap();
+        assert_eq!(gomod.patch_source(SRC), SRC);
+    }
+
+    #[test]
+    fn test_patch_source_only_touches_changes() {
+        let mut gomod = GoMod::parse_str(SRC).unwrap();
+        gomod.set(&["go_version"], "1.22").unwrap();
+        gomod.set(&["requires", "0", "version"], "v1.1.0").unwrap();
+        gomod.set(&["requires", "2", "indirect"], "true").unwrap();
+        gomod.remove(&["requires", "1"]).unwrap();
+        gomod.requires.insert(
+            1,
+            GoDependency {
+                name: "github.com/a/w".into(),
+                version: "v0.1.0".into(),
+                indirect: false,
+            },
+        );
+        gomod.remove(&["replaces", "0"]).unwrap();
+        gomod
+            .set(&["excludes", "0"], "github.com/a/y@v0.2.0")
+            .unwrap();
+        gomod
+            .set(&["retracts", "0", "rationale"], "leaks credentials")
+            .unwrap();
+
+        let patched = gomod.patch_source(SRC);
+        assert_eq!(
+            patched,
+            "// Code generated by hand.
+
+module example.com/app
+
+go 1.22
+
+require (
+\tgithub.com/a/x v1.1.0 // indirect
+\tgithub.com/a/w v0.1.0
+)
+
+require github.com/a/z v2.0.0+incompatible // indirect
+
+retract v1.0.0 // leaks credentials
+
+exclude github.com/a/y v0.2.0
+"
+        );
+        assert_eq!(GoMod::parse_str(&patched).unwrap(), gomod);
+    }
+
+    #[test]
+    fn test_patch_source_single_lines_and_fallback() {
+        let src = "module m\ngo 1.21\nrequire a.com/x v1.0.0\nretract [v0.1.0, v0.2.0]";
+        let mut gomod = GoMod::parse_str(src).unwrap();
+        gomod.set(&["retracts", "1"], "v0.3.0").unwrap();
+        gomod
+            .set(&["retracts", "1", "rationale"], "bad build\nsee #12")
+            .unwrap();
+        gomod.requires.push(GoDependency {
+            name: "a.com/y".into(),
+            version: "v1.0.0".into(),
+            indirect: false,
+        });
+        let patched = gomod.patch_source(src);
+        assert_eq!(
+            patched,
+            "module m\ngo 1.21\nrequire a.com/x v1.0.0\nrequire a.com/y v1.0.0\nretract [v0.1.0, v0.2.0]\n// bad build\n// see #12\nretract v0.3.0\n"
+        );
+        assert_eq!(GoMod::parse_str(&patched).unwrap(), gomod);
+
+        assert_eq!(gomod.patch_source("not a go.mod"), gomod.to_string());
+    }
+
+    #[test]
+    fn test_patch_edits() {
+        let src = "module m\ngo 1.21\n\nrequire a.com/x v1.0.0\n\nrequire a.com/y v1.0.0\n";
+        let mut gomod = GoMod::parse_str(src).unwrap();
+        gomod.requires[0] = GoDependency {
+            name: "a.com/z".into(),
+            version: "v0.1.0".into(),
+            indirect: false,
+        };
+        gomod.set(&["requires", "1", "version"], "v1.1.0").unwrap();
+        assert_eq!(
+            gomod.patch_edits(src),
+            [
+                SourceEdit::new(18..18, "require a.com/z v0.1.0\n"),
+                SourceEdit::new(18..42, ""),
+                SourceEdit::new(50..64, "a.com/y v1.1.0"),
+            ]
+        );
+        assert_eq!(
+            gomod.patch_source(src),
+            "module m\ngo 1.21\n\nrequire a.com/z v0.1.0\nrequire a.com/y v1.1.0\n"
+        );
+        assert_eq!(
+            gomod.patch_edits("module"),
+            [SourceEdit::new(0..6, gomod.to_string())]
+        );
+    }
+}
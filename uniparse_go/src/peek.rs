@@ -0,0 +1,108 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use crate::model::{GoMod, ParseError, unquote};
+
+impl GoMod {
+    /// Reads only as much of a `go.mod` file as needed to find its `module`
+    /// directive.
+    ///
+    /// Meant for scanners that need the module identity of many files; the
+    /// rest of the file is neither read nor validated.
+    ///
+    /// # Errors
+    /// Returns [`ParseError::Io`] if the file can't be read and
+    /// [`ParseError::MissingField`] if it has no `module` directive.
+    pub fn peek_module(path: impl AsRef<Path>) -> Result<String, ParseError> {
+        let reader = BufReader::new(File::open(path)?);
+        peek_directive(reader, "module")?.ok_or(ParseError::MissingField("module"))
+    }
+
+    /// Like [`GoMod::peek_module`], but for the `go` directive.
+    ///
+    /// # Errors
+    /// Returns [`ParseError::Io`] if the file can't be read and
+    /// [`ParseError::MissingField`] if it has no `go` directive.
+    pub fn peek_go_version(path: impl AsRef<Path>) -> Result<String, ParseError> {
+        let reader = BufReader::new(File::open(path)?);
+        peek_directive(reader, "go")?.ok_or(ParseError::MissingField("go version"))
+    }
+}
+
+/// Returns the argument of the first top-level `directive` line, without
+/// its comment and quotes, stopping as soon as it is found.
+fn peek_directive(reader: impl BufRead, directive: &str) -> Result<Option<String>, ParseError> {
+    let mut in_block = false;
+
+    for line in reader.lines() {
+        let line = line?;
+        let trimmed = line.split("//").next().unwrap_or_default().trim();
+
+        if in_block {
+            in_block = trimmed != ")";
+            continue;
+        }
+
+        match trimmed.split_once(char::is_whitespace) {
+            Some((_, rest)) if rest.trim() == "(" => in_block = true,
+            Some((found, rest)) if found == directive => {
+                return Ok(Some(unquote(rest.trim()).to_string()));
+            }
+            _ => {}
+        }
+    }
+    Ok(None)
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_peek_stops_at_directive() {
+        // the invalid UTF-8 after the `go` line would fail a full read
+        let mut content = b"// header\nmodule example.com/m\ngo 1.21\n".to_vec();
+        content.extend_from_slice(&[0xff, 0xfe, b'\n']);
+
+        let module = peek_directive(Cursor::new(&content), "module").unwrap();
+        assert_eq!(module.as_deref(), Some("example.com/m"));
+        let go = peek_directive(Cursor::new(&content), "go").unwrap();
+        assert_eq!(go.as_deref(), Some("1.21"));
+    }
+
+    #[test]
+    fn test_peek_strips_comments_and_quotes() {
+        let content = "module \"example.com/m\" // deprecated\ngo 1.21 // x\nrequire ( // deps\n\tgo v1.0.0\n)\n";
+        let module = peek_directive(Cursor::new(content), "module").unwrap();
+        assert_eq!(module.as_deref(), Some("example.com/m"));
+        let go = peek_directive(Cursor::new(content), "go").unwrap();
+        assert_eq!(go.as_deref(), Some("1.21"));
+        assert_eq!(GoMod::parse_str(content).unwrap().go_version, go.unwrap());
+    }
+
+    #[test]
+    fn test_peek_file() {
+        let path = std::env::temp_dir().join("uniparse_go_peek.mod");
+        std::fs::write(
+            &path,
+            "require (\n\tgo v1.0.0\n)\ngo 1.22\nmodule example.com/peek\n",
+        )
+        .unwrap();
+
+        assert_eq!(GoMod::peek_module(&path).unwrap(), "example.com/peek");
+        assert_eq!(GoMod::peek_go_version(&path).unwrap(), "1.22");
+
+        std::fs::write(&path, "go 1.22\n").unwrap();
+        assert!(matches!(
+            GoMod::peek_module(&path),
+            Err(ParseError::MissingField("module"))
+        ));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
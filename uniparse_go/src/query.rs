@@ -0,0 +1,204 @@
+use uniparse_core::DiagnosticCode;
+
+use crate::version::{GoVersion, parse_version};
+
+/// Errors returned by [`parse_version`] and [`parse_module_query`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum QueryError {
+    /// Not a complete canonical version such as `v1.2.3`.
+    #[error("Invalid module version: {0}")]
+    InvalidVersion(String),
+
+    /// A module query without `@version`.
+    #[error("Missing `@version` in module query: {0}")]
+    MissingVersion(String),
+
+    /// A module query with an empty module path or query.
+    #[error("Invalid module query: {0}")]
+    InvalidQuery(String),
+}
+
+impl DiagnosticCode for QueryError {
+    fn code(&self) -> &'static str {
+        match self {
+            QueryError::InvalidVersion(_) => "GOMOD009",
+            QueryError::MissingVersion(_) => "GOMOD010",
+            QueryError::InvalidQuery(_) => "GOMOD011",
+        }
+    }
+}
+
+/// A `path@query` argument, as given to `go get` or `go install`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleQuery {
+    pub path: String,
+    pub query: VersionQuery,
+}
+
+/// What follows the `@` of a [`ModuleQuery`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VersionQuery {
+    /// A complete version, e.g. `@v1.2.3`.
+    Version(GoVersion),
+    /// `@latest`
+    Latest,
+    /// `@upgrade`
+    Upgrade,
+    /// `@patch`
+    Patch,
+    /// `@none`, which removes the module.
+    None,
+    /// A version prefix, comparison, branch or commit, e.g. `@v1.2`,
+    /// `@>=v1.5.0`, `@main` or `@abcdef12`, as written.
+    Other(String),
+}
+
+/// Splits a `path@query` module query.
+///
+/// ```rust
+/// use uniparse_go::{VersionQuery, parse_module_query};
+///
+/// let query = parse_module_query("golang.org/x/tools/gopls@latest").unwrap();
+/// assert_eq!(query.path, "golang.org/x/tools/gopls");
+/// assert_eq!(query.query, VersionQuery::Latest);
+/// ```
+pub fn parse_module_query(query: &str) -> Result<ModuleQuery, QueryError> {
+    let (path, version) = query
+        .split_once('@')
+        .ok_or_else(|| QueryError::MissingVersion(query.to_string()))?;
+    if path.is_empty() || version.is_empty() {
+        return Err(QueryError::InvalidQuery(query.to_string()));
+    }
+    let query = match version {
+        "latest" => VersionQuery::Latest,
+        "upgrade" => VersionQuery::Upgrade,
+        "patch" => VersionQuery::Patch,
+        "none" => VersionQuery::None,
+        other => parse_version(other)
+            .map(VersionQuery::Version)
+            .unwrap_or_else(|_| VersionQuery::Other(other.to_string())),
+    };
+    Ok(ModuleQuery {
+        path: path.to_string(),
+        query,
+    })
+}
+
+/// Splits a module path into its prefix and major version suffix, like
+/// `module.SplitPathVersion` in Go: `github.com/a/b/v2` gives
+/// `("github.com/a/b", "/v2")`, `gopkg.in/yaml.v3` gives
+/// `("gopkg.in/yaml", ".v3")` and a path without a suffix gives the whole
+/// path and `""`.
+///
+/// Returns `None` for suffixes Go rejects, such as `/v1`, `/v0` or `/v2.1`.
+///
+/// ```rust
+/// use uniparse_go::split_path_version;
+///
+/// assert_eq!(split_path_version("github.com/a/b/v2"), Some(("github.com/a/b", "/v2")));
+/// assert_eq!(split_path_version("github.com/a/b"), Some(("github.com/a/b", "")));
+/// assert_eq!(split_path_version("github.com/a/b/v1"), None);
+/// ```
+pub fn split_path_version(path: &str) -> Option<(&str, &str)> {
+    if path.starts_with("gopkg.in/") {
+        return split_gopkg_in(path);
+    }
+
+    let bytes = path.as_bytes();
+    let mut i = bytes.len();
+    let mut dot = false;
+    while i > 0 && (bytes[i - 1].is_ascii_digit() || bytes[i - 1] == b'.') {
+        dot |= bytes[i - 1] == b'.';
+        i -= 1;
+    }
+    if i <= 1 || i == bytes.len() || bytes[i - 1] != b'v' || bytes[i - 2] != b'/' {
+        return Some((path, ""));
+    }
+
+    let (prefix, major) = path.split_at(i - 2);
+    if dot || major.as_bytes()[2] == b'0' || major == "/v1" {
+        return None;
+    }
+    Some((prefix, major))
+}
+
+/// `gopkg.in/name.vN`, where the suffix is required and may end in
+/// `-unstable`.
+fn split_gopkg_in(path: &str) -> Option<(&str, &str)> {
+    let stable = path.strip_suffix("-unstable").unwrap_or(path);
+    let digits = stable.len() - stable.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+    let at = stable.len().checked_sub(digits + 2)?;
+    if digits == 0 || !stable[at..].starts_with(".v") {
+        return None;
+    }
+    let major = &path[at..];
+    if major.as_bytes()[2] == b'0' && major != ".v0" && !major.starts_with(".v0-") {
+        return None;
+    }
+    Some((&path[..at], major))
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_module_query() {
+        let query = parse_module_query("github.com/a/b/v2@v2.1.0").unwrap();
+        assert_eq!(query.path, "github.com/a/b/v2");
+        assert!(matches!(query.query, VersionQuery::Version(ref v) if v.to_string() == "v2.1.0"));
+
+        let queries = ["upgrade", "patch", "none", "v1.2", ">=v1.5.0", "main"]
+            .map(|q| parse_module_query(&format!("a.com/m@{}", q)).unwrap().query);
+        assert_eq!(
+            queries,
+            [
+                VersionQuery::Upgrade,
+                VersionQuery::Patch,
+                VersionQuery::None,
+                VersionQuery::Other("v1.2".into()),
+                VersionQuery::Other(">=v1.5.0".into()),
+                VersionQuery::Other("main".into()),
+            ]
+        );
+
+        let err = parse_module_query("a.com/m").unwrap_err();
+        assert_eq!(err.code(), "GOMOD010");
+        assert_eq!(
+            parse_module_query("@v1.0.0").unwrap_err().code(),
+            "GOMOD011"
+        );
+        assert_eq!(
+            parse_module_query("a.com/m@").unwrap_err().code(),
+            "GOMOD011"
+        );
+    }
+
+    #[test]
+    fn test_split_path_version() {
+        let cases = [
+            ("github.com/a/b/v2", Some(("github.com/a/b", "/v2"))),
+            ("github.com/a/b/v10", Some(("github.com/a/b", "/v10"))),
+            ("github.com/a/b", Some(("github.com/a/b", ""))),
+            ("github.com/a/v2b", Some(("github.com/a/v2b", ""))),
+            ("github.com/a/b/v1", None),
+            ("github.com/a/b/v0", None),
+            ("github.com/a/b/v02", None),
+            ("github.com/a/b/v2.1", None),
+            ("gopkg.in/yaml.v3", Some(("gopkg.in/yaml", ".v3"))),
+            ("gopkg.in/check.v1", Some(("gopkg.in/check", ".v1"))),
+            (
+                "gopkg.in/foo.v2-unstable",
+                Some(("gopkg.in/foo", ".v2-unstable")),
+            ),
+            ("gopkg.in/yaml", None),
+        ];
+        for (path, expected) in cases {
+            assert_eq!(split_path_version(path), expected, "{}", path);
+        }
+    }
+}
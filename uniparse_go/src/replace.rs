@@ -0,0 +1,210 @@
+use std::fmt::{Display, Formatter};
+use uniparse_core::DiagnosticCode;
+
+use crate::model::{GoMod, GoReplace};
+
+/// A `replace` directive whose target the `go` command would reject.
+///
+/// `index` is the position of the directive in [`GoMod::replaces`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplaceIssue {
+    /// The target looks like a relative directory but doesn't start with
+    /// `./` or `../`, e.g. `vendor/fork`. [`GoMod::fix_replace_paths`]
+    /// prefixes `./`.
+    RelativePath { index: usize, new_path: String },
+    /// The target is a module path without a version.
+    MissingVersion { index: usize, new_path: String },
+    /// The target is a directory but has a version.
+    VersionOnDirectory {
+        index: usize,
+        new_path: String,
+        new_version: String,
+    },
+}
+
+impl ReplaceIssue {
+    /// Position of the directive in [`GoMod::replaces`].
+    pub fn index(&self) -> usize {
+        match self {
+            ReplaceIssue::RelativePath { index, .. }
+            | ReplaceIssue::MissingVersion { index, .. }
+            | ReplaceIssue::VersionOnDirectory { index, .. } => *index,
+        }
+    }
+
+    /// The corrected target, when the intent is obvious.
+    pub fn suggestion(&self) -> Option<String> {
+        match self {
+            ReplaceIssue::RelativePath { new_path, .. } => Some(format!("./{}", new_path)),
+            _ => None,
+        }
+    }
+}
+
+impl DiagnosticCode for ReplaceIssue {
+    fn code(&self) -> &'static str {
+        match self {
+            ReplaceIssue::RelativePath { .. } => "GOMOD004",
+            ReplaceIssue::MissingVersion { .. } => "GOMOD005",
+            ReplaceIssue::VersionOnDirectory { .. } => "GOMOD006",
+        }
+    }
+}
+
+impl Display for ReplaceIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReplaceIssue::RelativePath { new_path, .. } => write!(
+                f,
+                "replacement directory `{}` must start with ./ or ../",
+                new_path
+            ),
+            ReplaceIssue::MissingVersion { new_path, .. } => write!(
+                f,
+                "replacement module `{}` needs a version, or a path starting with ./ or ../",
+                new_path
+            ),
+            ReplaceIssue::VersionOnDirectory {
+                new_path,
+                new_version,
+                ..
+            } => write!(
+                f,
+                "replacement directory `{}` must not have a version (`{}`)",
+                new_path, new_version
+            ),
+        }
+    }
+}
+
+impl GoReplace {
+    /// Whether `new_path` is a directory in the `go` command's sense: rooted,
+    /// or starting with `./` or `../` (backslashes count on Windows paths).
+    pub fn is_directory_target(&self) -> bool {
+        is_directory_path(&self.new_path)
+    }
+}
+
+impl GoMod {
+    /// Checks every `replace` target the way `go mod` does, in the order
+    /// the directives are listed.
+    ///
+    /// ```rust
+    /// use uniparse_go::{GoMod, ReplaceIssue};
+    ///
+    /// let m = GoMod::parse_str("module m\ngo 1.22\nreplace a.com/x => forks/x\n").unwrap();
+    /// let issues = m.validate_replaces();
+    /// assert_eq!(issues[0].suggestion().as_deref(), Some("./forks/x"));
+    /// ```
+    pub fn validate_replaces(&self) -> Vec<ReplaceIssue> {
+        self.replaces
+            .iter()
+            .enumerate()
+            .filter_map(|(index, replace)| {
+                let new_path = replace.new_path.clone();
+                match (&replace.new_version, replace.is_directory_target()) {
+                    (Some(version), true) => Some(ReplaceIssue::VersionOnDirectory {
+                        index,
+                        new_path,
+                        new_version: version.clone(),
+                    }),
+                    (None, false) if looks_relative(&new_path) => {
+                        Some(ReplaceIssue::RelativePath { index, new_path })
+                    }
+                    (None, false) => Some(ReplaceIssue::MissingVersion { index, new_path }),
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    /// Applies the fix of every [`ReplaceIssue::RelativePath`], prefixing
+    /// `./` to the target. Returns the indices of the directives changed.
+    pub fn fix_replace_paths(&mut self) -> Vec<usize> {
+        let mut fixed = Vec::new();
+        for issue in self.validate_replaces() {
+            if let Some(path) = issue.suggestion() {
+                self.replaces[issue.index()].new_path = path;
+                fixed.push(issue.index());
+            }
+        }
+        fixed
+    }
+}
+
+fn is_directory_path(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    let windows_drive = bytes.len() >= 3
+        && bytes[0].is_ascii_alphabetic()
+        && bytes[1] == b':'
+        && matches!(bytes[2], b'/' | b'\\');
+    path == "."
+        || path == ".."
+        || ["./", "../", ".\\", "..\\", "/", "\\"]
+            .iter()
+            .any(|prefix| path.starts_with(prefix))
+        || windows_drive
+}
+
+/// A module path's first element is a domain name with a dot in it, so a
+/// target whose first element has none can only be a directory.
+fn looks_relative(path: &str) -> bool {
+    let first = path.split(['/', '\\']).next().unwrap_or_default();
+    !first.contains('.')
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_and_fix_replace_targets() {
+        let mut m = GoMod::parse_str(
+            "module m\ngo 1.22\nreplace (\n\
+             \ta.com/ok => ../ok\n\
+             \tb.com/ok => c.com/fork v1.0.0\n\
+             \td.com/x => vendor/x\n\
+             \te.com/x => f.com/fork\n\
+             \tg.com/x => ./local v1.2.3\n\
+             \th.com/x => C:\\src\\x\n\
+             )\n",
+        )
+        .unwrap();
+
+        let issues = m.validate_replaces();
+        assert_eq!(
+            issues,
+            [
+                ReplaceIssue::RelativePath {
+                    index: 2,
+                    new_path: "vendor/x".into()
+                },
+                ReplaceIssue::MissingVersion {
+                    index: 3,
+                    new_path: "f.com/fork".into()
+                },
+                ReplaceIssue::VersionOnDirectory {
+                    index: 4,
+                    new_path: "./local".into(),
+                    new_version: "v1.2.3".into()
+                },
+            ]
+        );
+        assert_eq!(
+            issues.iter().map(|i| i.code()).collect::<Vec<_>>(),
+            ["GOMOD004", "GOMOD005", "GOMOD006"]
+        );
+        assert_eq!(
+            issues[0].to_string(),
+            "replacement directory `vendor/x` must start with ./ or ../"
+        );
+
+        assert_eq!(m.fix_replace_paths(), [2]);
+        assert_eq!(m.replaces[2].new_path, "./vendor/x");
+        assert_eq!(m.validate_replaces().len(), 2);
+    }
+}
@@ -0,0 +1,163 @@
+use std::cmp::Ordering;
+
+use crate::model::GoMod;
+use crate::version::compare_versions;
+
+/// One `require` line with the comment lines directly above it.
+struct Entry<'a> {
+    lines: Vec<&'a str>,
+    path: &'a str,
+    version: &'a str,
+}
+
+impl GoMod {
+    /// Sorts the entries of every `require ( ... )` block of a `go.mod`
+    /// source by module path, then version, the order `go mod tidy` writes.
+    ///
+    /// Comment lines directly above an entry and comments at the end of its
+    /// line move with it. Blank lines split a block into groups that are
+    /// sorted separately, so a tidy-style second block of `// indirect`
+    /// requirements stays apart. Everything outside `require` blocks,
+    /// including single-line `require` directives, is left untouched.
+    ///
+    /// ```rust
+    /// use uniparse_go::GoMod;
+    ///
+    /// let src = "module m\n\nrequire (\n\tb.com/y v1.0.0\n\ta.com/x v1.0.0 // pinned\n)\n";
+    /// assert_eq!(
+    ///     GoMod::sort_requires(src),
+    ///     "module m\n\nrequire (\n\ta.com/x v1.0.0 // pinned\n\tb.com/y v1.0.0\n)\n"
+    /// );
+    /// ```
+    pub fn sort_requires(source: &str) -> String {
+        let mut out = String::with_capacity(source.len());
+        let mut group: Vec<&str> = Vec::new();
+        let mut in_block = false;
+
+        for line in source.split_inclusive('\n') {
+            let trimmed = line.trim();
+            if !in_block {
+                in_block = is_require_block_start(trimmed);
+                out.push_str(line);
+                continue;
+            }
+
+            if trimmed == ")" || trimmed.is_empty() {
+                write_sorted(&mut out, &group);
+                group.clear();
+                in_block = trimmed != ")";
+                out.push_str(line);
+            } else {
+                group.push(line);
+            }
+        }
+        // unterminated block: keep what we have, sorted
+        write_sorted(&mut out, &group);
+        out
+    }
+}
+
+fn is_require_block_start(trimmed: &str) -> bool {
+    trimmed
+        .strip_prefix("require")
+        .is_some_and(|rest| rest.trim() == "(")
+}
+
+/// Writes a run of block lines with its entries sorted. Comment lines after
+/// the last entry stay at the end.
+fn write_sorted(out: &mut String, lines: &[&str]) {
+    let mut entries = Vec::new();
+    let mut pending = Vec::new();
+
+    for line in lines {
+        pending.push(*line);
+        let code = line.split("//").next().unwrap_or_default();
+        let mut words = code.split_whitespace();
+        if let Some(path) = words.next() {
+            entries.push(Entry {
+                lines: std::mem::take(&mut pending),
+                path,
+                version: words.next().unwrap_or_default(),
+            });
+        }
+    }
+
+    entries.sort_by(|a, b| match a.path.cmp(b.path) {
+        Ordering::Equal => compare_versions(a.version, b.version),
+        other => other,
+    });
+    for line in entries.iter().flat_map(|e| &e.lines).chain(&pending) {
+        out.push_str(line);
+    }
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uniparse_core::SemanticEq;
+
+    #[test]
+    fn test_sort_keeps_comments_with_their_entries() {
+        let src = "module example.com/m
+
+go 1.21
+
+require github.com/zz/single v1.0.0
+
+require (
+\t// needed for the CLI
+\tgithub.com/spf13/cobra v1.8.0
+\tgithub.com/b/lib v1.10.0 // keep in sync with c
+\tgithub.com/b/lib v1.9.0
+\t// trailing note
+)
+
+require (
+\tgolang.org/x/text v0.14.0 // indirect
+\tgolang.org/x/sys v0.15.0 // indirect
+)
+";
+        let sorted = GoMod::sort_requires(src);
+        assert_eq!(
+            sorted,
+            "module example.com/m
+
+go 1.21
+
+require github.com/zz/single v1.0.0
+
+require (
+\tgithub.com/b/lib v1.9.0
+\tgithub.com/b/lib v1.10.0 // keep in sync with c
+\t// needed for the CLI
+\tgithub.com/spf13/cobra v1.8.0
+\t// trailing note
+)
+
+require (
+\tgolang.org/x/sys v0.15.0 // indirect
+\tgolang.org/x/text v0.14.0 // indirect
+)
+"
+        );
+        assert_eq!(GoMod::sort_requires(&sorted), sorted);
+        assert!(
+            GoMod::parse_str(&sorted)
+                .unwrap()
+                .semantic_eq(&GoMod::parse_str(src).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_blank_lines_separate_groups() {
+        let src = "require (\n\tb.com/y v1.0.0\n\ta.com/x v1.0.0\n\n\td.com/w v1.0.0\n\tc.com/z v1.0.0\n)";
+        assert_eq!(
+            GoMod::sort_requires(src),
+            "require (\n\ta.com/x v1.0.0\n\tb.com/y v1.0.0\n\n\tc.com/z v1.0.0\n\td.com/w v1.0.0\n)"
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+use crate::model::{GoMod, ParseError};
+
+/// A location in `go.mod` source text.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Span {
+    /// Line number (starting at 1).
+    pub line: usize,
+    /// Column number in characters (starting at 1).
+    pub column: usize,
+    /// Byte offsets into the source.
+    pub byte_range: Range<usize>,
+}
+
+impl Span {
+    pub(crate) fn from_range(source: &str, byte_range: Range<usize>) -> Self {
+        let before = &source[..byte_range.start];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let column = source[line_start..byte_range.start].chars().count() + 1;
+        Span {
+            line,
+            column,
+            byte_range,
+        }
+    }
+
+    /// Line `line` (starting at 1) of `source` without its indentation and
+    /// comment; the end of `source` if it has fewer lines.
+    pub(crate) fn of_line(source: &str, line: usize) -> Self {
+        let mut offset = 0;
+        for raw in source.split_inclusive('\n').take(line - 1) {
+            offset += raw.len();
+        }
+        let rest = &source[offset..];
+        let text = rest.lines().next().unwrap_or_default();
+        let code = text.split("//").next().unwrap_or_default();
+        let start = offset + code.len() - code.trim_start().len();
+        Span::from_range(source, start..start + code.trim().len())
+    }
+}
+
+/// Where the directives of a parsed [`GoMod`] are in its source, from
+/// [`GoMod::parse_with_spans`]. Each span covers the directive or entry
+/// without its comment, e.g. `module example.com/m` or, inside a `require`
+/// block, `github.com/a/b v1.0.0`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GoModSpans {
+    /// The `module` directive.
+    pub module: Option<Span>,
+    /// The `go` directive.
+    pub go_version: Option<Span>,
+    /// Each entry of [`GoMod::requires`], at the same index.
+    pub requires: Vec<Span>,
+}
+
+impl GoMod {
+    /// Parses like [`GoMod::parse_str`], also returning where the `module`
+    /// and `go` directives and each requirement are, for linters and
+    /// editors to point at.
+    ///
+    /// ```rust
+    /// use uniparse_go::GoMod;
+    ///
+    /// let src = "module example.com/m\n\ngo 1.22\n\nrequire (\n\tgithub.com/a/b v1.0.0 // indirect\n)\n";
+    /// let (gomod, spans) = GoMod::parse_with_spans(src).unwrap();
+    /// let span = &spans.requires[0];
+    /// assert_eq!((span.line, span.column), (6, 2));
+    /// assert_eq!(&src[span.byte_range.clone()], "github.com/a/b v1.0.0");
+    /// assert_eq!(gomod.requires[0].name, "github.com/a/b");
+    /// ```
+    ///
+    /// # Errors
+    /// The same as [`GoMod::parse_str`].
+    pub fn parse_with_spans(content: &str) -> Result<(GoMod, GoModSpans), ParseError> {
+        let mut spans = GoModSpans::default();
+        let mut errors = Vec::new();
+        let gomod = GoMod::parse_spanned(content, &mut spans, &mut errors);
+        match errors.into_iter().next() {
+            Some(err) => Err(err),
+            None => Ok((gomod, spans)),
+        }
+    }
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spans_of_directives_and_requires() {
+        let src = "// the shop API\r\nmodule \"example.com/shop\" // deprecated\r\n\r\n  go 1.22\r\n\r\nrequire github.com/a/x v1.0.0\r\nrequire (\r\n\tgithub.com/a/y v2.0.0+incompatible // indirect\r\n\t\"github.com/é/z\" v0.1.0\r\n)\r\n";
+        let (gomod, spans) = GoMod::parse_with_spans(src).unwrap();
+        assert_eq!(gomod, GoMod::parse_str(src).unwrap());
+        let text = |span: &Span| &src[span.byte_range.clone()];
+
+        let module = spans.module.as_ref().unwrap();
+        assert_eq!((module.line, module.column), (2, 1));
+        assert_eq!(text(module), "module \"example.com/shop\"");
+        let go = spans.go_version.as_ref().unwrap();
+        assert_eq!((go.line, go.column), (4, 3));
+        assert_eq!(text(go), "go 1.22");
+
+        let requires: Vec<_> = spans
+            .requires
+            .iter()
+            .map(|s| (s.line, s.column, text(s)))
+            .collect();
+        assert_eq!(
+            requires,
+            [
+                (6, 1, "require github.com/a/x v1.0.0"),
+                (8, 2, "github.com/a/y v2.0.0+incompatible"),
+                (9, 2, "\"github.com/é/z\" v0.1.0"),
+            ]
+        );
+        assert_eq!(spans.requires.len(), gomod.requires.len());
+    }
+}
@@ -0,0 +1,393 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use uniparse_core::DiagnosticCode;
+
+use crate::model::ParseError;
+use crate::verified::ModuleSum;
+
+/// A parsed `go.sum` file: one entry per line, in file order.
+///
+/// ```rust
+/// use uniparse_go::{GoSum, HashKind};
+///
+/// let sum = GoSum::parse_str(
+///     "github.com/a/x v1.0.0 h1:abc=\ngithub.com/a/x v1.0.0/go.mod h1:def=\n",
+/// )
+/// .unwrap();
+/// assert_eq!(sum.hash("github.com/a/x", "v1.0.0", HashKind::GoMod), Some("h1:def="));
+/// assert!(sum.verify("github.com/a/x", "v1.0.0", HashKind::Zip, "h1:abc=").is_ok());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GoSum {
+    pub entries: Vec<GoSumEntry>,
+}
+
+/// One line of `go.sum`: `<module> <version>[/go.mod] <hash>`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct GoSumEntry {
+    pub module: String,
+    /// The version without the `/go.mod` suffix.
+    pub version: String,
+    pub kind: HashKind,
+    /// The hash with its algorithm prefix, e.g. `h1:abc...=`.
+    pub hash: String,
+}
+
+/// What a `go.sum` hash covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum HashKind {
+    /// The module's whole file tree.
+    Zip,
+    /// The module's `go.mod` alone, written with a `/go.mod` version suffix.
+    GoMod,
+}
+
+impl GoSumEntry {
+    /// The hash algorithm, e.g. `h1` (SHA-256 of a file listing).
+    pub fn algorithm(&self) -> Option<&str> {
+        self.hash.split_once(':').map(|(algorithm, _)| algorithm)
+    }
+}
+
+impl GoSum {
+    /// Parses `go.sum` contents. Blank lines are skipped.
+    ///
+    /// # Errors
+    /// Returns [`ParseError::SumEntry`] for a line that isn't
+    /// `<module> <version>[/go.mod] <hash>`.
+    pub fn parse_str(src: &str) -> Result<GoSum, ParseError> {
+        let mut entries = Vec::new();
+        for (i, line) in src.lines().enumerate() {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let [module, version, hash] = parts[..] else {
+                if parts.is_empty() {
+                    continue;
+                }
+                return Err(ParseError::SumEntry {
+                    line: i + 1,
+                    entry: line.to_string(),
+                });
+            };
+
+            let (version, kind) = match version.strip_suffix("/go.mod") {
+                Some(version) => (version, HashKind::GoMod),
+                None => (version, HashKind::Zip),
+            };
+            entries.push(GoSumEntry {
+                module: module.to_string(),
+                version: version.to_string(),
+                kind,
+                hash: hash.to_string(),
+            });
+        }
+        Ok(GoSum { entries })
+    }
+
+    /// The hash recorded for `module` at `version`. With duplicate lines the
+    /// first one wins, as for the `go` command.
+    pub fn hash(&self, module: &str, version: &str, kind: HashKind) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|e| e.module == module && e.version == version && e.kind == kind)
+            .map(|e| e.hash.as_str())
+    }
+
+    /// Both hashes recorded for `module` at `version`, or `None` if it has
+    /// neither.
+    pub fn get(&self, module: &str, version: &str) -> Option<ModuleSum> {
+        let sum = ModuleSum {
+            zip: self
+                .hash(module, version, HashKind::Zip)
+                .map(str::to_string),
+            go_mod: self
+                .hash(module, version, HashKind::GoMod)
+                .map(str::to_string),
+        };
+        (sum != ModuleSum::default()).then_some(sum)
+    }
+
+    /// Every version of `module` listed, in file order and without repeats.
+    pub fn versions(&self, module: &str) -> Vec<&str> {
+        let mut versions: Vec<&str> = Vec::new();
+        for entry in self.entries.iter().filter(|e| e.module == module) {
+            if !versions.contains(&entry.version.as_str()) {
+                versions.push(&entry.version);
+            }
+        }
+        versions
+    }
+
+    /// Checks a hash computed for a download against the recorded one, like
+    /// the `go` command's checksum verification.
+    ///
+    /// # Errors
+    /// [`SumIssue::Missing`] if nothing is recorded, and
+    /// [`SumIssue::Mismatch`] if the hashes differ.
+    pub fn verify(
+        &self,
+        module: &str,
+        version: &str,
+        kind: HashKind,
+        hash: &str,
+    ) -> Result<(), SumIssue> {
+        match self.hash(module, version, kind) {
+            None => Err(SumIssue::Missing {
+                module: module.to_string(),
+                version: version.to_string(),
+                kind,
+            }),
+            Some(recorded) if recorded != hash => Err(SumIssue::Mismatch {
+                module: module.to_string(),
+                version: version.to_string(),
+                kind,
+                recorded: recorded.to_string(),
+                found: hash.to_string(),
+            }),
+            Some(_) => Ok(()),
+        }
+    }
+
+    /// Lines that record a different hash for a module version already
+    /// listed, e.g. after a bad merge. The `go` command refuses such files.
+    pub fn conflicts(&self) -> Vec<SumIssue> {
+        let mut first: HashMap<(&str, &str, HashKind), &str> = HashMap::new();
+        let mut issues = Vec::new();
+        for entry in &self.entries {
+            let key = (entry.module.as_str(), entry.version.as_str(), entry.kind);
+            match first.get(&key) {
+                Some(&recorded) if recorded != entry.hash => issues.push(SumIssue::Conflict {
+                    module: entry.module.clone(),
+                    version: entry.version.clone(),
+                    kind: entry.kind,
+                    recorded: recorded.to_string(),
+                    found: entry.hash.clone(),
+                }),
+                Some(_) => {}
+                None => {
+                    first.insert(key, &entry.hash);
+                }
+            }
+        }
+        issues
+    }
+
+    /// The hashes of every module version, keyed by module path and
+    /// version.
+    pub(crate) fn module_sums(&self) -> HashMap<(String, String), ModuleSum> {
+        let mut sums: HashMap<(String, String), ModuleSum> = HashMap::new();
+        for entry in &self.entries {
+            let sum = sums
+                .entry((entry.module.clone(), entry.version.clone()))
+                .or_default();
+            let slot = match entry.kind {
+                HashKind::Zip => &mut sum.zip,
+                HashKind::GoMod => &mut sum.go_mod,
+            };
+            slot.get_or_insert_with(|| entry.hash.clone());
+        }
+        sums
+    }
+}
+
+impl Display for GoSumEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {}{} {}",
+            self.module,
+            self.version,
+            suffix(self.kind),
+            self.hash
+        )
+    }
+}
+
+impl Display for GoSum {
+    /// One entry per line, as `go mod tidy` writes them.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for entry in &self.entries {
+            writeln!(f, "{}", entry)?;
+        }
+        Ok(())
+    }
+}
+
+/// A hash that doesn't check out against `go.sum`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SumIssue {
+    /// `go.sum` has no hash for the module version.
+    Missing {
+        module: String,
+        version: String,
+        kind: HashKind,
+    },
+    /// The hash differs from the one `go.sum` records.
+    Mismatch {
+        module: String,
+        version: String,
+        kind: HashKind,
+        recorded: String,
+        found: String,
+    },
+    /// `go.sum` records two different hashes for the module version.
+    Conflict {
+        module: String,
+        version: String,
+        kind: HashKind,
+        recorded: String,
+        found: String,
+    },
+}
+
+impl DiagnosticCode for SumIssue {
+    fn code(&self) -> &'static str {
+        match self {
+            SumIssue::Missing { .. } => "GOMOD013",
+            SumIssue::Mismatch { .. } => "GOMOD014",
+            SumIssue::Conflict { .. } => "GOMOD015",
+        }
+    }
+}
+
+impl Display for SumIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SumIssue::Missing {
+                module,
+                version,
+                kind,
+            } => write!(
+                f,
+                "missing go.sum entry for {} {}{}",
+                module,
+                version,
+                suffix(*kind)
+            ),
+            SumIssue::Mismatch {
+                module,
+                version,
+                kind,
+                recorded,
+                found,
+            } => write!(
+                f,
+                "checksum mismatch for {} {}{}: go.sum has {}, downloaded {}",
+                module,
+                version,
+                suffix(*kind),
+                recorded,
+                found
+            ),
+            SumIssue::Conflict {
+                module,
+                version,
+                kind,
+                recorded,
+                found,
+            } => write!(
+                f,
+                "go.sum lists {} {}{} with two hashes: {} and {}",
+                module,
+                version,
+                suffix(*kind),
+                recorded,
+                found
+            ),
+        }
+    }
+}
+
+fn suffix(kind: HashKind) -> &'static str {
+    match kind {
+        HashKind::Zip => "",
+        HashKind::GoMod => "/go.mod",
+    }
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GO_SUM: &str = "github.com/one/lib v1.0.0 h1:one=
+github.com/one/lib v1.0.0/go.mod h1:onemod=
+
+github.com/one/lib v1.1.0/go.mod h1:onemod11=
+github.com/two/lib v2.0.0/go.mod h1:twomod=
+";
+
+    #[test]
+    fn test_parse_and_lookup() {
+        let sum = GoSum::parse_str(GO_SUM).unwrap();
+        assert_eq!(sum.entries.len(), 4);
+        assert_eq!(
+            sum.entries[1],
+            GoSumEntry {
+                module: "github.com/one/lib".into(),
+                version: "v1.0.0".into(),
+                kind: HashKind::GoMod,
+                hash: "h1:onemod=".into(),
+            }
+        );
+        assert_eq!(sum.entries[0].algorithm(), Some("h1"));
+
+        assert_eq!(
+            sum.get("github.com/one/lib", "v1.0.0"),
+            Some(ModuleSum {
+                zip: Some("h1:one=".into()),
+                go_mod: Some("h1:onemod=".into()),
+            })
+        );
+        assert_eq!(sum.get("github.com/one/lib", "v9.0.0"), None);
+        assert_eq!(
+            sum.hash("github.com/two/lib", "v2.0.0", HashKind::Zip),
+            None
+        );
+        assert_eq!(sum.versions("github.com/one/lib"), ["v1.0.0", "v1.1.0"]);
+        assert_eq!(sum.to_string(), GO_SUM.replace("\n\n", "\n"));
+
+        let err = GoSum::parse_str("github.com/one/lib v1.0.0\n").unwrap_err();
+        assert!(matches!(err, ParseError::SumEntry { line: 1, .. }));
+        assert_eq!(err.code(), "GOMOD016");
+    }
+
+    #[test]
+    fn test_verify_and_conflicts() {
+        let sum = GoSum::parse_str(GO_SUM).unwrap();
+        assert!(
+            sum.verify("github.com/one/lib", "v1.0.0", HashKind::Zip, "h1:one=")
+                .is_ok()
+        );
+
+        let mismatch = sum
+            .verify("github.com/one/lib", "v1.0.0", HashKind::GoMod, "h1:other=")
+            .unwrap_err();
+        assert_eq!(mismatch.code(), "GOMOD014");
+        assert_eq!(
+            mismatch.to_string(),
+            "checksum mismatch for github.com/one/lib v1.0.0/go.mod: go.sum has h1:onemod=, downloaded h1:other="
+        );
+        let missing = sum
+            .verify("github.com/two/lib", "v2.0.0", HashKind::Zip, "h1:two=")
+            .unwrap_err();
+        assert_eq!(missing.code(), "GOMOD013");
+
+        assert!(sum.conflicts().is_empty());
+        let merged = GoSum::parse_str(&format!(
+            "{}github.com/one/lib v1.0.0 h1:one=\ngithub.com/one/lib v1.0.0 h1:theirs=\n",
+            GO_SUM
+        ))
+        .unwrap();
+        let conflicts = merged.conflicts();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].code(), "GOMOD015");
+        assert_eq!(
+            merged.hash("github.com/one/lib", "v1.0.0", HashKind::Zip),
+            Some("h1:one=")
+        );
+    }
+}
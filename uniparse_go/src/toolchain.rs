@@ -0,0 +1,261 @@
+use std::fmt::{Display, Formatter};
+use uniparse_core::DiagnosticCode;
+
+use crate::model::{GoMod, ParseError};
+
+/// A `go` or `toolchain` directive that would break builds, found by
+/// [`GoMod::check_go_versions`].
+///
+/// `line` is the line of the offending directive, starting at 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VersionIssue {
+    /// `toolchain` names an older release than the `go` line requires,
+    /// e.g. `go 1.22.0` with `toolchain go1.21.5`.
+    ToolchainOlderThanGo {
+        line: usize,
+        go_version: String,
+        toolchain: String,
+    },
+    /// The `go` line requires a newer release than the caller supports.
+    GoNewerThanSupported {
+        line: usize,
+        go_version: String,
+        max_supported: String,
+    },
+}
+
+impl VersionIssue {
+    /// Line of the directive the issue is about.
+    pub fn line(&self) -> usize {
+        match self {
+            VersionIssue::ToolchainOlderThanGo { line, .. }
+            | VersionIssue::GoNewerThanSupported { line, .. } => *line,
+        }
+    }
+}
+
+impl DiagnosticCode for VersionIssue {
+    fn code(&self) -> &'static str {
+        match self {
+            VersionIssue::ToolchainOlderThanGo { .. } => "GOMOD007",
+            VersionIssue::GoNewerThanSupported { .. } => "GOMOD008",
+        }
+    }
+}
+
+impl Display for VersionIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VersionIssue::ToolchainOlderThanGo {
+                go_version,
+                toolchain,
+                ..
+            } => write!(
+                f,
+                "toolchain `{}` is older than the required go version `{}`",
+                toolchain, go_version
+            ),
+            VersionIssue::GoNewerThanSupported {
+                go_version,
+                max_supported,
+                ..
+            } => write!(
+                f,
+                "go version `{}` is newer than the newest supported version `{}`",
+                go_version, max_supported
+            ),
+        }
+    }
+}
+
+impl GoMod {
+    /// Checks the `go` and `toolchain` directives of `source`: the
+    /// toolchain must not be older than the `go` version, and the `go`
+    /// version must not be newer than `max_supported` when given, e.g.
+    /// the Go release installed on CI.
+    ///
+    /// Versions are compared the way the `go` command does, so `1.21` (the
+    /// language version) is older than `1.21rc1`, which is older than
+    /// `1.21.0`. `toolchain default` and versions that can't be read are
+    /// not checked.
+    ///
+    /// ```rust
+    /// use uniparse_go::{GoMod, VersionIssue};
+    ///
+    /// let src = "module m\ngo 1.22.0\ntoolchain go1.21.5\n";
+    /// let issues = GoMod::check_go_versions(src, Some("1.23")).unwrap();
+    /// assert!(matches!(issues[..], [VersionIssue::ToolchainOlderThanGo { line: 3, .. }]));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns a [`ParseError`] if `source` isn't a valid `go.mod` file.
+    pub fn check_go_versions(
+        source: &str,
+        max_supported: Option<&str>,
+    ) -> Result<Vec<VersionIssue>, ParseError> {
+        let parsed = GoMod::parse_str(source)?;
+        let go = GoRelease::parse(&parsed.go_version);
+        let mut issues = Vec::new();
+
+        if let Some(toolchain) = parsed.toolchain
+            && let Some((line, _)) = directive_line(source, "toolchain")
+            && let Some(go) = &go
+            && let Some(release) = toolchain
+                .strip_prefix("go")
+                .and_then(|v| GoRelease::parse(v.split(['-', '+']).next().unwrap_or(v)))
+            && release < *go
+        {
+            issues.push(VersionIssue::ToolchainOlderThanGo {
+                line,
+                go_version: parsed.go_version.clone(),
+                toolchain,
+            });
+        }
+
+        if let Some((line, _)) = directive_line(source, "go")
+            && let Some(go) = &go
+            && let Some(max) = max_supported
+            && let Some(max_release) = GoRelease::parse(max)
+            && *go > max_release
+        {
+            issues.push(VersionIssue::GoNewerThanSupported {
+                line,
+                go_version: parsed.go_version,
+                max_supported: max.to_string(),
+            });
+        }
+        Ok(issues)
+    }
+}
+
+/// Line number and argument of the first top-level `directive` line.
+fn directive_line(source: &str, directive: &str) -> Option<(usize, String)> {
+    let mut in_block = false;
+    for (i, line) in source.lines().enumerate() {
+        let trimmed = line.split("//").next().unwrap_or_default().trim();
+        if in_block {
+            in_block = trimmed != ")";
+            continue;
+        }
+        match trimmed.split_once(char::is_whitespace) {
+            Some((_, rest)) if rest.trim() == "(" => in_block = true,
+            Some((found, rest)) if found == directive => {
+                return Some((i + 1, rest.trim().to_string()));
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// A Go release such as `1.21`, `1.21rc1` or `1.21.3`, ordered like the
+/// `go` command orders them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct GoRelease {
+    major: u32,
+    minor: u32,
+    /// 0 for a bare language version, then alpha, beta, rc, and 4 for a
+    /// release with a patch number.
+    stage: u8,
+    /// The patch number, or the pre-release number.
+    number: u32,
+}
+
+impl GoRelease {
+    pub(crate) fn parse(version: &str) -> Option<Self> {
+        let (major, rest) = split_number(version)?;
+        let Some(rest) = rest.strip_prefix('.') else {
+            return rest.is_empty().then_some(GoRelease {
+                major,
+                minor: 0,
+                stage: 0,
+                number: 0,
+            });
+        };
+        let (minor, rest) = split_number(rest)?;
+        let (stage, number) = if rest.is_empty() {
+            (0, 0)
+        } else if let Some(patch) = rest.strip_prefix('.') {
+            (4, patch.parse().ok()?)
+        } else {
+            let (stage, pre) = [("alpha", 1), ("beta", 2), ("rc", 3)]
+                .into_iter()
+                .find_map(|(name, stage)| rest.strip_prefix(name).map(|pre| (stage, pre)))?;
+            (stage, pre.parse().ok()?)
+        };
+        Some(GoRelease {
+            major,
+            minor,
+            stage,
+            number,
+        })
+    }
+}
+
+/// The leading decimal number of `s` and what follows it.
+fn split_number(s: &str) -> Option<(u32, &str)> {
+    let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    Some((s[..end].parse().ok()?, &s[end..]))
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_release_order() {
+        let versions = [
+            "1.20", "1.20.1", "1.21", "1.21rc1", "1.21rc2", "1.21.0", "1.21.3", "1.22",
+        ];
+        let parsed: Vec<_> = versions
+            .iter()
+            .map(|v| GoRelease::parse(v).unwrap())
+            .collect();
+        assert!(parsed.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(GoRelease::parse("1.21beta1").unwrap().stage, 2);
+        assert_eq!(GoRelease::parse("1.x"), None);
+    }
+
+    #[test]
+    fn test_check_go_versions() {
+        let src = "module m\n\ngo 1.22.0\n\ntoolchain go1.21.5 // pinned by CI\n";
+        let issues = GoMod::check_go_versions(src, Some("1.21.9")).unwrap();
+        assert_eq!(
+            issues,
+            [
+                VersionIssue::ToolchainOlderThanGo {
+                    line: 5,
+                    go_version: "1.22.0".into(),
+                    toolchain: "go1.21.5".into(),
+                },
+                VersionIssue::GoNewerThanSupported {
+                    line: 3,
+                    go_version: "1.22.0".into(),
+                    max_supported: "1.21.9".into(),
+                },
+            ]
+        );
+        assert_eq!(
+            issues.iter().map(|i| i.code()).collect::<Vec<_>>(),
+            ["GOMOD007", "GOMOD008"]
+        );
+        assert_eq!(
+            issues[0].to_string(),
+            "toolchain `go1.21.5` is older than the required go version `1.22.0`"
+        );
+
+        let ok = "module m\ngo 1.21\ntoolchain go1.21.0\n";
+        assert!(
+            GoMod::check_go_versions(ok, Some("1.21.0"))
+                .unwrap()
+                .is_empty()
+        );
+        let default = "module m\ngo 1.21\ntoolchain default\n";
+        assert!(GoMod::check_go_versions(default, None).unwrap().is_empty());
+        assert!(GoMod::check_go_versions("go 1.21\n", None).is_err());
+    }
+}
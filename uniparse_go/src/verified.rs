@@ -0,0 +1,164 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::model::{GoDependency, GoMod, ParseError};
+use crate::sum::GoSum;
+
+/// A `go.mod` together with the `go.sum` next to it.
+///
+/// Built by [`VerifiedModuleSet::from_parts`] (or `load`, with the `fs`
+/// feature), which checks that every required module has the checksum of
+/// its file tree; requires without one are listed in
+/// [`missing`](Self::missing) rather than failing the load, so tools can
+/// report all of them at once.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerifiedModuleSet {
+    pub go_mod: GoMod,
+    /// Checksums from `go.sum`, keyed by module path and version; the
+    /// first line wins for a repeated one.
+    pub sums: HashMap<(String, String), ModuleSum>,
+    /// Requires with no `h1:` line for the module's file tree in `go.sum`
+    /// (after replacements); a `/go.mod` line alone doesn't count.
+    pub missing: Vec<GoDependency>,
+}
+
+/// The two checksums `go.sum` may record for a module version.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModuleSum {
+    /// Hash of the module's file tree, e.g. `h1:abc...=`.
+    pub zip: Option<String>,
+    /// Hash of the module's `go.mod` alone (the `/go.mod` line).
+    pub go_mod: Option<String>,
+}
+
+impl VerifiedModuleSet {
+    /// Cross-checks an already parsed `go.mod` against `go.sum` contents.
+    ///
+    /// # Errors
+    /// Returns [`ParseError::SumEntry`] for a malformed `go.sum` line.
+    pub fn from_parts(go_mod: GoMod, go_sum: &str) -> Result<Self, ParseError> {
+        let mut set = VerifiedModuleSet {
+            go_mod,
+            sums: GoSum::parse_str(go_sum)?.module_sums(),
+            missing: Vec::new(),
+        };
+
+        set.missing = set
+            .go_mod
+            .requires
+            .iter()
+            .filter(|dep| {
+                set.effective(dep)
+                    .is_some_and(|key| set.sums.get(&key).is_none_or(|sum| sum.zip.is_none()))
+            })
+            .cloned()
+            .collect();
+
+        #[cfg(feature = "tracing")]
+        for dep in &set.missing {
+            tracing::debug!(module = %dep.name, version = %dep.version, "missing go.sum entry");
+        }
+        Ok(set)
+    }
+
+    /// Whether every required module has the `go.sum` hash of its file
+    /// tree.
+    pub fn is_verified(&self) -> bool {
+        self.missing.is_empty()
+    }
+
+    /// Checksums for the version of `module` this build uses.
+    ///
+    /// Follows `replace` directives, so a module replaced by another module
+    /// version returns that version's checksums. Modules replaced by a local
+    /// directory have none.
+    pub fn sum(&self, module: &str) -> Option<&ModuleSum> {
+        let dep = self.go_mod.requires.iter().find(|d| d.name == module)?;
+        self.sums.get(&self.effective(dep)?)
+    }
+
+    /// The file-tree hash (`h1:...`) of the version of `module` this build
+    /// uses.
+    pub fn hash(&self, module: &str) -> Option<&str> {
+        self.sum(module)?.zip.as_deref()
+    }
+
+    /// The module path and version that is actually downloaded for `dep`, or
+    /// `None` when it is replaced by a local directory. A replacement of
+    /// `dep`'s exact version wins over one of every version, as in `go`.
+    fn effective(&self, dep: &GoDependency) -> Option<(String, String)> {
+        let replaces = || {
+            self.go_mod
+                .replaces
+                .iter()
+                .filter(|r| r.old_path == dep.name)
+        };
+        let replace = replaces()
+            .find(|r| r.old_version.as_ref() == Some(&dep.version))
+            .or_else(|| replaces().find(|r| r.old_version.is_none()));
+
+        match replace {
+            Some(r) if r.is_local() => None,
+            Some(r) => Some((r.new_path.clone(), r.new_version.clone()?)),
+            None => Some((dep.name.clone(), dep.version.clone())),
+        }
+    }
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GO_MOD: &str = "module example.com/app
+go 1.21
+require (
+    github.com/one/lib v1.0.0
+    github.com/two/lib v2.0.0
+    github.com/local/lib v0.1.0
+    github.com/forked/lib v1.5.0
+)
+replace github.com/local/lib => ../lib
+replace github.com/forked/lib => github.com/me/lib v1.5.1
+";
+
+    const GO_SUM: &str = "github.com/one/lib v1.0.0 h1:one=
+github.com/one/lib v1.0.0/go.mod h1:onemod=
+github.com/me/lib v1.5.1/go.mod h1:memod=
+";
+
+    #[test]
+    fn test_cross_checks_requires_against_sums() {
+        let set = VerifiedModuleSet::from_parts(GoMod::parse_str(GO_MOD).unwrap(), GO_SUM).unwrap();
+
+        assert!(!set.is_verified());
+        // the fork only has its `/go.mod` hash
+        let missing: Vec<_> = set.missing.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(missing, ["github.com/two/lib", "github.com/forked/lib"]);
+
+        assert_eq!(set.hash("github.com/one/lib"), Some("h1:one="));
+        let forked = set.sum("github.com/forked/lib").unwrap();
+        assert_eq!(forked.zip, None);
+        assert_eq!(forked.go_mod.as_deref(), Some("h1:memod="));
+        assert!(set.sum("github.com/local/lib").is_none());
+    }
+
+    #[test]
+    fn test_version_specific_replace_wins() {
+        let go_mod = "module example.com/app
+go 1.21
+require github.com/forked/lib v1.5.0
+replace github.com/forked/lib => ../lib
+replace github.com/forked/lib v1.5.0 => github.com/me/lib v1.5.1
+";
+        let go_sum = "github.com/me/lib v1.5.1 h1:me=
+github.com/me/lib v1.5.1/go.mod h1:memod=
+";
+        let set = VerifiedModuleSet::from_parts(GoMod::parse_str(go_mod).unwrap(), go_sum).unwrap();
+        assert!(set.is_verified());
+        assert_eq!(set.hash("github.com/forked/lib"), Some("h1:me="));
+    }
+}
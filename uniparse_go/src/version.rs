@@ -0,0 +1,498 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use uniparse_core::{Predicate, compare_prerelease};
+
+use crate::model::GoDependency;
+use crate::query::QueryError;
+
+/// A Go pseudo-version such as `v0.0.0-20230101120000-abcdef123456`,
+/// which pins a module to a commit that has no release tag.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PseudoVersion {
+    /// The tagged version the commit builds on, e.g. `v1.2.3` for
+    /// `v1.2.4-0.20230101120000-abcdef123456`. `None` when no tag precedes
+    /// the commit (`vX.0.0-...` form).
+    pub base: Option<String>,
+    /// Commit time (UTC).
+    pub timestamp: Timestamp,
+    /// Abbreviated commit hash (12 hex digits).
+    pub revision: String,
+}
+
+/// UTC commit time encoded in a pseudo-version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Timestamp {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+}
+
+impl PseudoVersion {
+    /// Passes pseudo-versions, for a [`Schema`](uniparse_core::Schema) that
+    /// singles out untagged commits.
+    pub const PREDICATE: Predicate = Predicate::Test {
+        name: "pseudo-version",
+        test: |version| PseudoVersion::parse(version).is_some(),
+    };
+
+    /// Decodes a pseudo-version, returning `None` for regular versions.
+    ///
+    /// All three forms produced by the Go toolchain are recognised:
+    /// `vX.0.0-TIMESTAMP-REV`, `vX.Y.Z-pre.0.TIMESTAMP-REV` and
+    /// `vX.Y.(Z+1)-0.TIMESTAMP-REV`. A `+incompatible` suffix is ignored.
+    pub fn parse(version: &str) -> Option<Self> {
+        let version = version.split('+').next()?;
+        let (rest, revision) = version.rsplit_once('-')?;
+        if revision.len() != 12 || !revision.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        let split = rest.len().checked_sub(14)?;
+        let (prefix, timestamp) = (rest.get(..split)?, rest.get(split..)?);
+        let timestamp = Timestamp::parse(timestamp)?;
+
+        let base = if let Some(core) = prefix.strip_suffix("-0.") {
+            // vX.Y.(Z+1)-0.TIMESTAMP: the base is the previous patch release
+            let [major, minor, patch] = semver_core(core)?;
+            Some(format!("v{}.{}.{}", major, minor, patch.checked_sub(1)?))
+        } else if let Some(pre) = prefix.strip_suffix(".0.") {
+            // vX.Y.Z-pre.0.TIMESTAMP: the base is the pre-release itself
+            let (core, _) = pre.split_once('-')?;
+            semver_core(core)?;
+            Some(pre.to_string())
+        } else {
+            let [_, minor, patch] = semver_core(prefix.strip_suffix('-')?)?;
+            if minor != 0 || patch != 0 {
+                return None;
+            }
+            None
+        };
+
+        Some(PseudoVersion {
+            base,
+            timestamp,
+            revision: revision.to_string(),
+        })
+    }
+
+    /// [`base`](Self::base) as a typed version.
+    pub fn base_version(&self) -> Option<GoVersion> {
+        parse_version(self.base.as_deref()?).ok()
+    }
+}
+
+impl Timestamp {
+    /// Parses the `yyyymmddhhmmss` form used in pseudo-versions.
+    fn parse(s: &str) -> Option<Self> {
+        if s.len() != 14 || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let field = |range: std::ops::Range<usize>| s[range].parse::<u8>().ok();
+
+        let timestamp = Timestamp {
+            year: s[..4].parse().ok()?,
+            month: field(4..6)?,
+            day: field(6..8)?,
+            hour: field(8..10)?,
+            minute: field(10..12)?,
+            second: field(12..14)?,
+        };
+        let valid = (1..=12).contains(&timestamp.month)
+            && (1..=31).contains(&timestamp.day)
+            && timestamp.hour < 24
+            && timestamp.minute < 60
+            && timestamp.second < 60;
+        valid.then_some(timestamp)
+    }
+}
+
+impl Display for Timestamp {
+    /// RFC 3339, e.g. `2023-01-01T12:00:00Z`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )
+    }
+}
+
+/// A module version in Go's canonical semver form, e.g. `v1.2.3`,
+/// `v2.0.0-rc.1` or `v4.1.2+incompatible`, read by [`parse_version`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct GoVersion {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    /// Pre-release identifiers after `-`, e.g. `rc.1`. Pseudo-versions
+    /// keep their timestamp and revision here.
+    pub prerelease: Option<String>,
+    /// Build metadata after `+`, e.g. `incompatible`.
+    pub build: Option<String>,
+}
+
+impl Display for GoVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "v{}.{}.{}", self.major, self.minor, self.patch)?;
+        if let Some(pre) = &self.prerelease {
+            write!(f, "-{}", pre)?;
+        }
+        if let Some(build) = &self.build {
+            write!(f, "+{}", build)?;
+        }
+        Ok(())
+    }
+}
+
+impl GoVersion {
+    /// Same as [`parse_version`].
+    ///
+    /// # Errors
+    /// Returns [`QueryError::InvalidVersion`] if `version` isn't a complete
+    /// canonical version.
+    pub fn parse(version: &str) -> Result<Self, QueryError> {
+        parse_version(version)
+    }
+
+    /// Whether this is a pseudo-version, i.e. an untagged commit.
+    pub fn is_pseudo(&self) -> bool {
+        self.as_pseudo().is_some()
+    }
+
+    /// The base version, commit time and revision of a pseudo-version, or
+    /// `None` for a tagged release.
+    ///
+    /// ```rust
+    /// use uniparse_go::GoVersion;
+    ///
+    /// let version = GoVersion::parse("v1.2.4-0.20230101120000-abcdef123456").unwrap();
+    /// let pseudo = version.as_pseudo().unwrap();
+    /// assert_eq!(pseudo.base.as_deref(), Some("v1.2.3"));
+    /// assert_eq!(pseudo.revision, "abcdef123456");
+    /// assert_eq!(pseudo.timestamp.to_string(), "2023-01-01T12:00:00Z");
+    /// assert!(GoVersion::parse("v1.2.3").unwrap().as_pseudo().is_none());
+    /// ```
+    pub fn as_pseudo(&self) -> Option<PseudoVersion> {
+        // only the pre-release part can make a pseudo-version
+        self.prerelease.as_ref()?;
+        PseudoVersion::parse(&self.to_string())
+    }
+
+    pub fn is_prerelease(&self) -> bool {
+        self.prerelease.is_some()
+    }
+}
+
+impl FromStr for GoVersion {
+    type Err = QueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_version(s)
+    }
+}
+
+impl Ord for GoVersion {
+    /// Semver precedence, as the `go` command picks versions: a
+    /// pre-release or pseudo-version sorts below its release, and
+    /// pseudo-versions by commit time. Build metadata such as
+    /// `+incompatible` only breaks ties, so that `Ord` agrees with `Eq`.
+    ///
+    /// ```rust
+    /// use uniparse_go::GoVersion;
+    ///
+    /// let versions = ["v1.10.0", "v1.9.0", "v1.10.0-rc.1", "v0.0.0-20230101000000-abcdef123456"];
+    /// let newest = versions.iter().filter_map(|v| GoVersion::parse(v).ok()).max().unwrap();
+    /// assert_eq!(newest.to_string(), "v1.10.0");
+    /// ```
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| {
+                compare_prerelease(self.prerelease.as_deref(), other.prerelease.as_deref())
+            })
+            .then_with(|| self.build.cmp(&other.build))
+    }
+}
+
+impl PartialOrd for GoVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Parses a full module version such as `v1.2.3` or
+/// `v0.0.0-20230101120000-abcdef123456`.
+///
+/// Only complete versions are accepted: queries and prefixes such as
+/// `latest` or `v1.2` are errors, as is a missing `v`.
+///
+/// ```rust
+/// use uniparse_go::parse_version;
+///
+/// let version = parse_version("v2.0.0-rc.1").unwrap();
+/// assert_eq!((version.major, version.prerelease.as_deref()), (2, Some("rc.1")));
+/// assert!(parse_version("1.2.3").is_err());
+/// ```
+pub fn parse_version(version: &str) -> Result<GoVersion, QueryError> {
+    let invalid = || QueryError::InvalidVersion(version.to_string());
+    let (rest, build) = match version.split_once('+') {
+        Some((rest, build)) => (rest, Some(build)),
+        None => (version, None),
+    };
+    let (core, prerelease) = match rest.split_once('-') {
+        Some((core, pre)) => (core, Some(pre)),
+        None => (rest, None),
+    };
+    let [major, minor, patch] = semver_core(core).ok_or_else(invalid)?;
+    let leading_zero = core[1..]
+        .split('.')
+        .any(|n| n.len() > 1 && n.starts_with('0'));
+    if leading_zero
+        || !prerelease.is_none_or(|pre| identifiers_valid(pre, true))
+        || !build.is_none_or(|build| identifiers_valid(build, false))
+    {
+        return Err(invalid());
+    }
+    Ok(GoVersion {
+        major,
+        minor,
+        patch,
+        prerelease: prerelease.map(str::to_string),
+        build: build.map(str::to_string),
+    })
+}
+
+/// Whether `s` is dot-separated, non-empty `[0-9A-Za-z-]` identifiers;
+/// pre-release numbers may not have leading zeros.
+fn identifiers_valid(s: &str, prerelease: bool) -> bool {
+    s.split('.').all(|id| {
+        let numeric = id.bytes().all(|b| b.is_ascii_digit());
+        !id.is_empty()
+            && id.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-')
+            && !(prerelease && numeric && id.len() > 1 && id.starts_with('0'))
+    })
+}
+
+impl GoDependency {
+    /// Whether the version ends in `+incompatible`: a v2+ tag of a module
+    /// that has no `go.mod` or doesn't use a major version suffix.
+    pub fn is_incompatible(&self) -> bool {
+        self.version.ends_with("+incompatible")
+    }
+
+    /// Whether the required version is a pseudo-version, i.e. an untagged
+    /// commit.
+    pub fn is_pseudo_version(&self) -> bool {
+        self.pseudo_version().is_some()
+    }
+
+    /// The commit the requirement pins, if its version is a
+    /// pseudo-version.
+    pub fn pseudo_version(&self) -> Option<PseudoVersion> {
+        PseudoVersion::parse(&self.version)
+    }
+}
+
+/// Orders module versions by semver precedence, as `go mod tidy` sorts
+/// requirements: the order of [`GoVersion`]. Strings that aren't versions
+/// sort before any that are, then lexically.
+pub(crate) fn compare_versions(a: &str, b: &str) -> Ordering {
+    match (parse_version(a), parse_version(b)) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        (Err(_), Ok(_)) => Ordering::Less,
+        (Ok(_), Err(_)) => Ordering::Greater,
+        (Err(_), Err(_)) => a.cmp(b),
+    }
+}
+
+/// Parses `vMAJOR.MINOR.PATCH` without pre-release or build suffixes.
+fn semver_core(s: &str) -> Option<[u64; 3]> {
+    let mut parts = s.strip_prefix('v')?.split('.');
+    let mut core = [0; 3];
+    for slot in &mut core {
+        let part = parts.next()?;
+        if part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        *slot = part.parse().ok()?;
+    }
+    parts.next().is_none().then_some(core)
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_untagged_pseudo_version() {
+        let pseudo = PseudoVersion::parse("v0.0.0-20230101120000-abcdef123456").unwrap();
+        assert_eq!(pseudo.base, None);
+        assert_eq!(pseudo.timestamp.to_string(), "2023-01-01T12:00:00Z");
+        assert_eq!(pseudo.revision, "abcdef123456");
+    }
+
+    #[test]
+    fn test_parse_pseudo_version_bases() {
+        let after_release = PseudoVersion::parse("v1.2.4-0.20230101120000-abcdef123456").unwrap();
+        assert_eq!(after_release.base.as_deref(), Some("v1.2.3"));
+
+        let after_pre = PseudoVersion::parse("v1.3.0-rc.1.0.20230101120000-abcdef123456").unwrap();
+        assert_eq!(after_pre.base.as_deref(), Some("v1.3.0-rc.1"));
+
+        let incompatible =
+            PseudoVersion::parse("v2.0.0-20190101000000-0123456789ab+incompatible").unwrap();
+        assert_eq!(incompatible.revision, "0123456789ab");
+    }
+
+    #[test]
+    fn test_go_version_as_pseudo() {
+        let version = GoVersion::parse("v1.3.0-rc.1.0.20230101120000-abcdef123456").unwrap();
+        let pseudo = version.as_pseudo().unwrap();
+        assert_eq!(
+            pseudo.base_version(),
+            Some(GoVersion {
+                major: 1,
+                minor: 3,
+                patch: 0,
+                prerelease: Some("rc.1".into()),
+                build: None,
+            })
+        );
+        assert!(pseudo.base_version().unwrap() < version);
+
+        let untagged = GoVersion::parse("v0.0.0-20190101000000-0123456789ab").unwrap();
+        assert_eq!(untagged.as_pseudo().unwrap().base_version(), None);
+        assert!(
+            GoVersion::parse("v1.0.0-rc.1")
+                .unwrap()
+                .as_pseudo()
+                .is_none()
+        );
+
+        let dep = GoDependency {
+            name: "github.com/a/x".into(),
+            version: untagged.to_string(),
+            indirect: false,
+        };
+        assert_eq!(dep.pseudo_version().unwrap().timestamp.year, 2019);
+    }
+
+    #[test]
+    fn test_compare_versions_uses_semver_precedence() {
+        let mut versions = [
+            "v1.10.0",
+            "v1.2.0",
+            "v1.2.0-rc.1",
+            "v1.2.0-beta.11",
+            "v1.2.0-beta.2",
+            "v2.0.0+incompatible",
+            "master",
+        ];
+        versions.sort_by(|a, b| compare_versions(a, b));
+        assert_eq!(
+            versions,
+            [
+                "master",
+                "v1.2.0-beta.2",
+                "v1.2.0-beta.11",
+                "v1.2.0-rc.1",
+                "v1.2.0",
+                "v1.10.0",
+                "v2.0.0+incompatible",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_version() {
+        let version = parse_version("v4.1.2+incompatible").unwrap();
+        assert_eq!((version.major, version.minor, version.patch), (4, 1, 2));
+        assert_eq!(version.build.as_deref(), Some("incompatible"));
+        assert_eq!(version.to_string(), "v4.1.2+incompatible");
+
+        let pseudo = "v0.0.0-20230101120000-abcdef123456";
+        assert_eq!(parse_version(pseudo).unwrap().to_string(), pseudo);
+
+        for bad in [
+            "1.2.3",
+            "v1.2",
+            "v01.2.3",
+            "v1.2.3-",
+            "v1.2.3-01",
+            "v1.2.3+a..b",
+            "latest",
+        ] {
+            let err = parse_version(bad).unwrap_err();
+            assert_eq!(err, QueryError::InvalidVersion(bad.into()), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_go_version_ordering() {
+        let mut versions: Vec<GoVersion> = [
+            "v1.10.0",
+            "v1.2.3",
+            "v1.10.0-rc.1",
+            "v1.10.0-beta.2",
+            "v1.10.0-beta.11",
+            "v2.0.0+incompatible",
+            "v1.2.4-0.20230101000000-abcdef123456",
+            "v0.0.0-20230201000000-abcdef123456",
+            "v0.0.0-20230101000000-abcdef123456",
+        ]
+        .iter()
+        .map(|v| v.parse().unwrap())
+        .collect();
+        versions.sort();
+        let sorted: Vec<String> = versions.iter().map(|v| v.to_string()).collect();
+        assert_eq!(
+            sorted,
+            [
+                "v0.0.0-20230101000000-abcdef123456",
+                "v0.0.0-20230201000000-abcdef123456",
+                "v1.2.3",
+                "v1.2.4-0.20230101000000-abcdef123456",
+                "v1.10.0-beta.2",
+                "v1.10.0-beta.11",
+                "v1.10.0-rc.1",
+                "v1.10.0",
+                "v2.0.0+incompatible",
+            ]
+        );
+        assert!(versions[3].is_pseudo() && versions[3].is_prerelease());
+        assert!(!versions[4].is_pseudo());
+
+        let plain = GoVersion::parse("v2.0.0").unwrap();
+        assert!(plain < versions[8]);
+        assert_ne!(plain, versions[8]);
+    }
+
+    #[test]
+    fn test_regular_versions_are_not_pseudo() {
+        for version in [
+            "v1.2.3",
+            "v1.2.3-rc.1",
+            "v0.0.0-20231301120000-abcdef123456",
+            "v1.2.0-20230101120000-abcdef123456",
+            "v1.2.3-0.20230101120000-xyz",
+        ] {
+            assert!(PseudoVersion::parse(version).is_none(), "{version}");
+        }
+
+        let dep = GoDependency {
+            name: "golang.org/x/tools".into(),
+            version: "v0.0.0-20230101120000-abcdef123456".into(),
+            indirect: false,
+        };
+        assert!(dep.is_pseudo_version());
+    }
+}
@@ -0,0 +1,265 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+use crate::format::write_replaces;
+use crate::model::{GoReplace, ParseError, misspelled_directive, parse_replace, unquote};
+
+/// Directives of `go.work`, including those the model skips.
+const GO_WORK_DIRECTIVES: &[&str] = &["go", "toolchain", "godebug", "use", "replace"];
+
+/// A parsed `go.work` file, the root of a multi-module workspace.
+///
+/// ```rust
+/// use uniparse_go::GoWork;
+///
+/// let work = GoWork::parse_str("go 1.22\n\nuse (\n\t./api\n\t./web\n)\n").unwrap();
+/// assert_eq!(work.uses, ["./api", "./web"]);
+/// assert_eq!(work.go_mod_paths(), ["./api/go.mod", "./web/go.mod"]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GoWork {
+    /// Go version the workspace is built with (from the `go` directive).
+    pub go_version: String,
+    /// Toolchain suggested for the workspace, e.g. `go1.22.3`.
+    #[serde(default)]
+    pub toolchain: Option<String>,
+    /// Module directories from the `use` directives, relative to the
+    /// `go.work` file, in file order.
+    pub uses: Vec<String>,
+    /// Replacements applied to every module of the workspace; they take
+    /// precedence over those of the modules' own `go.mod` files.
+    pub replaces: Vec<GoReplace>,
+}
+
+impl GoWork {
+    /// Parses the contents of a `go.work` file. Comments and unknown
+    /// directives such as `godebug` are skipped.
+    ///
+    /// # Errors
+    /// Returns a [`ParseError`] if the `go` directive is missing, the syntax
+    /// is invalid, or the file has a `module` directive as a `go.mod` does.
+    pub fn parse_str(content: &str) -> Result<GoWork, ParseError> {
+        let mut go_version = None;
+        let mut toolchain = None;
+        let mut uses = Vec::new();
+        let mut replaces = Vec::new();
+        // directive of the open `(` block and the line it was opened on
+        let mut block: Option<(&str, usize)> = None;
+
+        for (i, line) in content.lines().enumerate() {
+            let trimmed = line.split("//").next().unwrap_or_default().trim();
+            let syntax = |msg: String, suggestion: &str| ParseError::Syntax {
+                line: i + 1,
+                msg,
+                suggestion: Some(suggestion.to_string()),
+            };
+            if trimmed.is_empty() {
+                continue;
+            }
+
+            let (directive, rest) = match block {
+                Some(_) if trimmed == ")" => {
+                    block = None;
+                    continue;
+                }
+                Some((directive, _)) => (directive, trimmed),
+                None if trimmed == ")" => {
+                    return Err(syntax(
+                        format!("block closed but never opened at line {}", i + 1),
+                        "remove the `)`",
+                    ));
+                }
+                None => match trimmed.strip_suffix('(').map(str::trim_end) {
+                    Some(directive)
+                        if !directive.is_empty() && !directive.contains(char::is_whitespace) =>
+                    {
+                        block = Some((directive, i + 1));
+                        continue;
+                    }
+                    _ => match trimmed.split_once(char::is_whitespace) {
+                        Some((directive, rest)) => (directive, rest.trim()),
+                        None => (trimmed, ""),
+                    },
+                },
+            };
+
+            match directive {
+                "go" => go_version = Some(rest.to_string()),
+                "toolchain" => toolchain = Some(rest.to_string()),
+                "use" => match rest.split_whitespace().collect::<Vec<_>>()[..] {
+                    [dir] => uses.push(unquote(dir).to_string()),
+                    _ => {
+                        return Err(syntax(
+                            format!("Invalid use entry: `{}`", line),
+                            "write it as `use <directory>`, one directory per line",
+                        ));
+                    }
+                },
+                "replace" => {
+                    let replace = rest
+                        .split_once("=>")
+                        .and_then(|(old, new)| parse_replace(old, new))
+                        .ok_or_else(|| {
+                            syntax(
+                                format!("Invalid replace entry: `{}`", line),
+                                "write it as `<old path> [version] => <new path> [version]`",
+                            )
+                        })?;
+                    replaces.push(replace);
+                }
+                "module" => {
+                    return Err(syntax(
+                        "`module` isn't allowed in go.work".to_string(),
+                        "this looks like a go.mod file; parse it with `GoMod::parse_str`",
+                    ));
+                }
+                _ => {
+                    if let Some(err) = misspelled_directive(directive, GO_WORK_DIRECTIVES, i + 1) {
+                        return Err(err);
+                    }
+                    #[cfg(feature = "tracing")]
+                    tracing::trace!(line = i + 1, directive, "skipping directive");
+                }
+            }
+        }
+
+        if let Some((directive, opened)) = block {
+            return Err(ParseError::Syntax {
+                line: opened,
+                msg: format!(
+                    "`{}` block opened at line {} is never closed",
+                    directive, opened
+                ),
+                suggestion: Some("add a closing `)`".to_string()),
+            });
+        }
+
+        Ok(GoWork {
+            go_version: go_version.ok_or(ParseError::MissingField("go version"))?,
+            toolchain,
+            uses,
+            replaces,
+        })
+    }
+
+    /// The `go.mod` path of every used module, relative to the `go.work`
+    /// file.
+    pub fn go_mod_paths(&self) -> Vec<String> {
+        self.uses
+            .iter()
+            .map(|dir| format!("{}/go.mod", dir.trim_end_matches('/')))
+            .collect()
+    }
+}
+
+impl Display for GoWork {
+    /// Canonical go.work syntax, laid out like [`GoMod`](crate::GoMod)'s.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "go {}", self.go_version)?;
+        if let Some(toolchain) = &self.toolchain {
+            writeln!(f, "\ntoolchain {}", toolchain)?;
+        }
+        match self.uses.as_slice() {
+            [] => {}
+            [dir] => writeln!(f, "\nuse {}", dir)?,
+            dirs => {
+                writeln!(f, "\nuse (")?;
+                for dir in dirs {
+                    writeln!(f, "\t{}", dir)?;
+                }
+                writeln!(f, ")")?;
+            }
+        }
+        write_replaces(f, &self.replaces, &Default::default())
+    }
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GoMod;
+    use uniparse_core::DiagnosticCode;
+
+    const GO_WORK: &str = "// workspace for the shop services
+go 1.22.1
+
+toolchain go1.22.3
+
+use ./common
+use (
+\t./payments // card and invoice flows
+\t\"./web/\"
+)
+
+godebug default=go1.21
+
+replace (
+\texample.com/shop/legacy => ../legacy
+\tgithub.com/a/x v1.0.0 => github.com/fork/x v1.0.1
+)
+";
+
+    #[test]
+    fn test_parse_go_work() {
+        let work = GoWork::parse_str(GO_WORK).unwrap();
+        assert_eq!(work.go_version, "1.22.1");
+        assert_eq!(work.toolchain.as_deref(), Some("go1.22.3"));
+        assert_eq!(work.uses, ["./common", "./payments", "./web/"]);
+        assert_eq!(
+            work.go_mod_paths(),
+            ["./common/go.mod", "./payments/go.mod", "./web/go.mod"]
+        );
+        assert_eq!(work.replaces.len(), 2);
+        assert_eq!(work.replaces[1].new_version.as_deref(), Some("v1.0.1"));
+
+        assert_eq!(
+            work.to_string(),
+            "go 1.22.1
+
+toolchain go1.22.3
+
+use (
+\t./common
+\t./payments
+\t./web/
+)
+
+replace (
+\texample.com/shop/legacy => ../legacy
+\tgithub.com/a/x v1.0.0 => github.com/fork/x v1.0.1
+)
+"
+        );
+        assert_eq!(GoWork::parse_str(&work.to_string()).unwrap(), work);
+    }
+
+    #[test]
+    fn test_go_work_errors() {
+        let err = GoWork::parse_str("use ./a\n").unwrap_err();
+        assert_eq!(err.code(), "GOMOD003");
+
+        let err = GoWork::parse_str("module example.com/m\ngo 1.22\n").unwrap_err();
+        assert!(matches!(err, ParseError::Syntax { line: 1, .. }));
+
+        let err = GoWork::parse_str("go 1.22\nuse (\n\t./a\n").unwrap_err();
+        assert!(matches!(err, ParseError::Syntax { line: 2, .. }));
+
+        let err = GoWork::parse_str("go 1.22\nreplce a.com/x => ../x\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unexpected token on line 2: unknown directive `replce`"
+        );
+
+        // and the other way around
+        let err = GoMod::parse_str(GO_WORK).unwrap_err();
+        assert_eq!(err.code(), "GOMOD012");
+        let ParseError::WrongFileType { suggestion, .. } = err else {
+            unreachable!()
+        };
+        assert!(suggestion.contains("GoWork::parse_str"));
+    }
+}
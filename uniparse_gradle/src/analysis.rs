@@ -0,0 +1,670 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::iter::Peekable;
+use std::ops::Range;
+use std::str::CharIndices;
+use uniparse_core::{DiagnosticCode, Severity};
+
+/// Location of a finding within the analysed source.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Span {
+    /// Line number (starting at 1).
+    pub line: usize,
+    /// Column number in characters (starting at 1).
+    pub column: usize,
+    /// Byte offsets into the source.
+    pub byte_range: Range<usize>,
+}
+
+impl Span {
+    pub(crate) fn from_range(source: &str, byte_range: Range<usize>) -> Self {
+        let before = &source[..byte_range.start];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let column = source[line_start..byte_range.start].chars().count() + 1;
+        Span {
+            line,
+            column,
+            byte_range,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DiagnosticKind {
+    /// `System.getenv` read while configuring the build.
+    ConfigCacheEnvRead,
+    /// `System.getProperty` read while configuring the build.
+    ConfigCacheSystemPropertyRead,
+    /// External process started while configuring the build.
+    ConfigCacheExternalProcess,
+    /// `project` accessed from a task action at execution time.
+    ConfigCacheProjectAtExecution,
+    /// Build listener registered on `gradle`.
+    ConfigCacheBuildListener,
+    /// Property assigned more than once in the same block.
+    DuplicateAssignment,
+    /// Top-level statement left out by [`DSLBlock::parse_lenient`].
+    ///
+    /// [`DSLBlock::parse_lenient`]: crate::DSLBlock::parse_lenient
+    SkippedStatement,
+    /// Dependency declared on a configuration removed in Gradle 7, such as
+    /// `compile`. See [`GradleBuild::deprecated_configurations`].
+    ///
+    /// [`GradleBuild::deprecated_configurations`]: crate::GradleBuild::deprecated_configurations
+    DeprecatedConfiguration,
+    /// Statement that ended early, or a block that is never closed; reported
+    /// by [`DSLBlock::parse_recovering`].
+    ///
+    /// [`DSLBlock::parse_recovering`]: crate::DSLBlock::parse_recovering
+    UnexpectedEnd,
+    /// Statement with a token the grammar doesn't allow; reported by
+    /// [`DSLBlock::parse_recovering`].
+    ///
+    /// [`DSLBlock::parse_recovering`]: crate::DSLBlock::parse_recovering
+    UnexpectedToken,
+    /// Statement with a character the tokenizer doesn't support; reported
+    /// by [`DSLBlock::parse_recovering`].
+    ///
+    /// [`DSLBlock::parse_recovering`]: crate::DSLBlock::parse_recovering
+    UnexpectedCharacter,
+}
+
+impl DiagnosticCode for DiagnosticKind {
+    fn code(&self) -> &'static str {
+        match self {
+            DiagnosticKind::ConfigCacheEnvRead => "GRADLE003",
+            DiagnosticKind::ConfigCacheSystemPropertyRead => "GRADLE004",
+            DiagnosticKind::ConfigCacheExternalProcess => "GRADLE005",
+            DiagnosticKind::ConfigCacheProjectAtExecution => "GRADLE006",
+            DiagnosticKind::ConfigCacheBuildListener => "GRADLE007",
+            DiagnosticKind::DuplicateAssignment => "GRADLE011",
+            DiagnosticKind::SkippedStatement => "GRADLE012",
+            DiagnosticKind::DeprecatedConfiguration => "GRADLE013",
+            DiagnosticKind::UnexpectedEnd => "GRADLE001",
+            DiagnosticKind::UnexpectedToken => "GRADLE002",
+            DiagnosticKind::UnexpectedCharacter => "GRADLE008",
+        }
+    }
+
+    /// [`Severity::Error`] for syntax errors, [`Severity::Warning`] for the
+    /// rest: the build still runs.
+    fn severity(&self) -> Severity {
+        match self {
+            DiagnosticKind::UnexpectedEnd
+            | DiagnosticKind::UnexpectedToken
+            | DiagnosticKind::UnexpectedCharacter => Severity::Error,
+            _ => Severity::Warning,
+        }
+    }
+}
+
+/// Where an [`ExternalInput`] is read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InputSource {
+    /// `System.getenv`, `providers.environmentVariable`
+    EnvironmentVariable,
+    /// `System.getProperty`, `providers.systemProperty`
+    SystemProperty,
+    /// `findProperty`, `property`, `hasProperty`,
+    /// `providers.gradleProperty`, e.g. from `gradle.properties` or `-P`
+    GradleProperty,
+}
+
+/// A read of a value from outside the build, found by [`external_inputs`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExternalInput {
+    pub source: InputSource,
+    /// Name of the variable or property, when it is a string literal.
+    pub name: Option<String>,
+    /// The call, from its receiver to the closing parenthesis.
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub kind: DiagnosticKind,
+    pub message: String,
+    pub span: Span,
+    /// Other locations involved, e.g. earlier assignments of the same
+    /// property.
+    pub related: Vec<Span>,
+}
+
+impl DiagnosticCode for Diagnostic {
+    fn code(&self) -> &'static str {
+        self.kind.code()
+    }
+
+    fn severity(&self) -> Severity {
+        self.kind.severity()
+    }
+}
+
+/// Flags patterns that are syntactically visible and known to be incompatible
+/// with Gradle's configuration cache.
+///
+/// The analysis works on the raw source so it also covers scripts that
+/// [`DSLBlock`](crate::DSLBlock) cannot model. Comments and string literals are
+/// ignored.
+pub fn config_cache_issues(source: &str) -> Vec<Diagnostic> {
+    use DiagnosticKind::*;
+
+    let masked = mask_source(source);
+    let mut diagnostics = Vec::new();
+
+    for_each_word(&masked, |start, after_dot, blocks| {
+        let rest = &masked[start..];
+        let word = word_at(rest);
+        let in_action = blocks.iter().any(|b| TASK_ACTIONS.contains(&b.as_str()));
+
+        let found = if after_dot {
+            // `"git describe".execute()` on any receiver
+            (word == "execute" && rest[word.len()..].starts_with("()") && !in_action).then_some((
+                ConfigCacheExternalProcess,
+                "execute()".len(),
+                "external process started at configuration time; use providers.exec() or move it into a task action",
+            ))
+        } else if in_action && (word == "project" || word.starts_with("project.")) {
+            Some((
+                ConfigCacheProjectAtExecution,
+                "project".len(),
+                "`project` accessed from a task action; capture the value during configuration instead",
+            ))
+        } else if in_action {
+            None
+        } else if word.starts_with("System.getenv") {
+            Some((
+                ConfigCacheEnvRead,
+                "System.getenv".len(),
+                "environment variable read at configuration time; use providers.environmentVariable()",
+            ))
+        } else if word.starts_with("System.getProperty") {
+            Some((
+                ConfigCacheSystemPropertyRead,
+                "System.getProperty".len(),
+                "system property read at configuration time; use providers.systemProperty()",
+            ))
+        } else if is_external_process_call(rest) {
+            Some((
+                ConfigCacheExternalProcess,
+                word.len(),
+                "external process started at configuration time; use providers.exec() or move it into a task action",
+            ))
+        } else {
+            None
+        };
+
+        let found = found.or_else(|| {
+            (!after_dot && BUILD_LISTENERS.contains(&word)).then_some((
+                ConfigCacheBuildListener,
+                word.len(),
+                "build listeners are not supported; use a build service or flow action",
+            ))
+        });
+
+        if let Some((kind, len, message)) = found {
+            diagnostics.push(Diagnostic {
+                kind,
+                message: message.to_string(),
+                span: Span::from_range(source, start..start + len),
+                related: Vec::new(),
+            });
+        }
+    });
+
+    diagnostics
+}
+
+/// Flags properties assigned more than once in the same block, such as
+/// `mainClassName` set twice in `application { }`. Only the last assignment
+/// takes effect, so the earlier ones are usually leftovers from a merge.
+///
+/// Blocks are told apart by their header: two `android { }` blocks configure
+/// the same extension and are treated as one, while
+/// `tasks.register("a") { }` and `tasks.register("b") { }` are not.
+pub fn duplicate_assignments(source: &str) -> Vec<Diagnostic> {
+    let masked = mask_source(source);
+    let mut diagnostics = Vec::new();
+    // (enclosing block headers, property) -> earlier assignments
+    let mut seen: HashMap<(Vec<String>, String), Vec<Span>> = HashMap::new();
+    let mut blocks: Vec<(String, usize)> = Vec::new();
+    let mut paren_depth = 0usize;
+    let mut statement_start: Option<usize> = None;
+    let mut at_statement_start = true;
+
+    for (idx, ch) in masked.char_indices() {
+        if at_statement_start && !ch.is_whitespace() {
+            at_statement_start = false;
+            statement_start = Some(idx);
+
+            let word = word_at(&masked[idx..]);
+            let after = masked[idx + word.len()..].trim_start_matches([' ', '\t']);
+            let is_assignment = !word.is_empty()
+                && !word.starts_with(|c: char| c.is_ascii_digit())
+                && paren_depth == 0
+                && after.starts_with('=')
+                && !after.starts_with("==");
+
+            if is_assignment {
+                let span = Span::from_range(source, idx..idx + word.len());
+                let path = blocks.iter().map(|(header, _)| header.clone()).collect();
+                let earlier = seen.entry((path, word.to_string())).or_default();
+                if let Some(last) = earlier.last() {
+                    diagnostics.push(Diagnostic {
+                        kind: DiagnosticKind::DuplicateAssignment,
+                        message: format!(
+                            "`{}` is assigned again; the value from line {} is overwritten",
+                            word, last.line
+                        ),
+                        span: span.clone(),
+                        related: earlier.clone(),
+                    });
+                }
+                earlier.push(span);
+            }
+        }
+
+        match ch {
+            '(' => paren_depth += 1,
+            ')' => paren_depth = paren_depth.saturating_sub(1),
+            '{' => {
+                let header = statement_start.map_or("", |start| source[start..idx].trim());
+                blocks.push((header.to_string(), paren_depth));
+                paren_depth = 0;
+                at_statement_start = true;
+            }
+            '}' => {
+                paren_depth = blocks.pop().map_or(0, |(_, depth)| depth);
+                at_statement_start = true;
+            }
+            '\n' | ';' => at_statement_start = true,
+            _ => {}
+        }
+    }
+
+    diagnostics
+}
+
+/// Lists every environment variable, system property and Gradle property
+/// the script reads, for auditing what a build depends on besides its
+/// sources.
+///
+/// Like [`config_cache_issues`] this scans the raw source, so it covers
+/// scripts [`DSLBlock`](crate::DSLBlock) cannot model, and reads in task
+/// actions are listed too. Comments and string literals are ignored, apart
+/// from the `${…}` expressions interpolated into strings.
+pub fn external_inputs(source: &str) -> Vec<ExternalInput> {
+    let masked = mask(source, true);
+    let mut inputs = Vec::new();
+
+    for_each_word(&masked, |start, after_dot, _| {
+        if after_dot {
+            return;
+        }
+        let word = word_at(&masked[start..]);
+        let Some(&(_, input_source)) = INPUT_READS.iter().find(|(call, _)| {
+            word == *call
+                || word.strip_suffix(call).is_some_and(|receiver| {
+                    receiver == "project."
+                        || (call.starts_with("providers.") && receiver.ends_with('.'))
+                })
+        }) else {
+            return;
+        };
+
+        let after = start + word.len();
+        let rest = &masked[after..];
+        let Some(open) = rest
+            .find(|c: char| !c.is_whitespace())
+            .filter(|&i| rest[i..].starts_with('('))
+        else {
+            return;
+        };
+        let open = after + open;
+        let mut depth = 0usize;
+        let close = masked[open..]
+            .char_indices()
+            .find(|&(_, c)| {
+                match c {
+                    '(' => depth += 1,
+                    ')' => depth -= 1,
+                    _ => {}
+                }
+                depth == 0
+            })
+            .map_or(masked.len(), |(i, c)| open + i + c.len_utf8());
+
+        inputs.push(ExternalInput {
+            source: input_source,
+            name: literal_arg(source, &masked, open + 1),
+            span: Span::from_range(source, start..close),
+        });
+    });
+
+    inputs
+}
+
+/// The value of a plain string literal that is the first argument of a call
+/// whose arguments start at `from`. Interpolated strings have no fixed
+/// value and give `None`.
+fn literal_arg(source: &str, masked: &str, from: usize) -> Option<String> {
+    let rest = &masked[from..];
+    let start = from + rest.len() - rest.trim_start().len();
+    let quote = masked[start..]
+        .chars()
+        .next()
+        .filter(|&c| c == '"' || c == '\'')?;
+    let end = start + 1 + masked[start + 1..].find(quote)?;
+    let literal = &source[start + 1..end];
+
+    let followed_by = masked[end + 1..].trim_start().chars().next();
+    let interpolated = quote == '"' && literal.contains('$');
+    (matches!(followed_by, Some(')' | ',')) && !interpolated).then(|| literal.to_string())
+}
+
+const INPUT_READS: &[(&str, InputSource)] = &[
+    ("System.getenv", InputSource::EnvironmentVariable),
+    (
+        "providers.environmentVariable",
+        InputSource::EnvironmentVariable,
+    ),
+    ("System.getProperty", InputSource::SystemProperty),
+    ("providers.systemProperty", InputSource::SystemProperty),
+    ("providers.gradleProperty", InputSource::GradleProperty),
+    ("findProperty", InputSource::GradleProperty),
+    ("hasProperty", InputSource::GradleProperty),
+    ("property", InputSource::GradleProperty),
+];
+
+const TASK_ACTIONS: &[&str] = &["doFirst", "doLast"];
+
+const BUILD_LISTENERS: &[&str] = &[
+    "gradle.buildFinished",
+    "gradle.addBuildListener",
+    "gradle.addListener",
+];
+
+fn is_external_process_call(rest: &str) -> bool {
+    let word = word_at(rest);
+    ["project.exec", "project.javaexec", "exec", "javaexec"].contains(&word)
+        && next_non_space(&rest[word.len()..]).is_some_and(|c| c == '{' || c == '(')
+}
+
+/// Dotted identifier starting at the beginning of `rest`.
+pub(crate) fn word_at(rest: &str) -> &str {
+    let end = rest
+        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+        .unwrap_or(rest.len());
+    &rest[..end]
+}
+
+fn next_non_space(rest: &str) -> Option<char> {
+    rest.chars().find(|c| !c.is_whitespace())
+}
+
+/// Calls `f` at the start of every identifier in `masked`, passing whether it
+/// directly follows a `.` and the names of the enclosing blocks (innermost last).
+pub(crate) fn for_each_word(masked: &str, mut f: impl FnMut(usize, bool, &[String])) {
+    let mut blocks: Vec<String> = Vec::new();
+    let mut last_word = String::new();
+    let mut paren_depth = 0usize;
+    let mut prev: Option<char> = None;
+
+    for (idx, ch) in masked.char_indices() {
+        let is_word_char = ch.is_alphanumeric() || ch == '_';
+        let starts_word = is_word_char && !prev.is_some_and(|p| p.is_alphanumeric() || p == '_');
+
+        if starts_word {
+            let after_dot = prev == Some('.');
+            f(idx, after_dot, &blocks);
+            if paren_depth == 0 && !after_dot {
+                last_word = word_at(&masked[idx..]).to_string();
+            }
+        }
+
+        match ch {
+            '(' => paren_depth += 1,
+            ')' => paren_depth = paren_depth.saturating_sub(1),
+            '{' => {
+                blocks.push(std::mem::take(&mut last_word));
+                paren_depth = 0;
+            }
+            '}' => {
+                blocks.pop();
+                last_word.clear();
+            }
+            c if !c.is_whitespace() && !is_word_char && c != '.' && paren_depth == 0 => {
+                last_word.clear()
+            }
+            _ => {}
+        }
+        prev = Some(ch);
+    }
+}
+
+/// Replaces comments and string literal contents with spaces, keeping byte
+/// offsets and newlines intact.
+pub(crate) fn mask_source(source: &str) -> String {
+    mask(source, false)
+}
+
+/// [`mask_source`], but `interpolations` keeps the code of the `${…}`
+/// expressions in double-quoted strings, with only the `${` and `}` around
+/// it blanked.
+fn mask(source: &str, interpolations: bool) -> String {
+    let mut out = String::with_capacity(source.len());
+    let mut chars = source.char_indices().peekable();
+    mask_code(source, &mut chars, &mut out, interpolations, false);
+    out
+}
+
+/// Masks code from `chars` into `out`. `in_interpolation` stops at the `}`
+/// closing a `${` and blanks it.
+fn mask_code(
+    source: &str,
+    chars: &mut Peekable<CharIndices>,
+    out: &mut String,
+    interpolations: bool,
+    in_interpolation: bool,
+) {
+    let blank = |c: char| if c == '\n' { '\n' } else { ' ' };
+    let pad = |out: &mut String, c: char| {
+        for _ in 0..c.len_utf8() {
+            out.push(blank(c));
+        }
+    };
+    let mut depth = 0usize;
+
+    while let Some((i, ch)) = chars.next() {
+        match ch {
+            '/' if source[i..].starts_with("//") => {
+                while let Some(&(_, c)) = chars.peek() {
+                    if c == '\n' {
+                        break;
+                    }
+                    chars.next();
+                }
+                out.push_str(&" ".repeat(chars.peek().map_or(source.len(), |&(j, _)| j) - i));
+            }
+            '/' if source[i..].starts_with("/*") => {
+                pad(out, ch);
+                let mut prev = ' ';
+                for (_, c) in chars.by_ref() {
+                    pad(out, c);
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+            }
+            '"' | '\'' => {
+                out.push(ch);
+                let mut escaped = false;
+                while let Some((j, c)) = chars.next() {
+                    if c == ch && !escaped {
+                        out.push(c);
+                        break;
+                    }
+                    if interpolations && ch == '"' && !escaped && source[j..].starts_with("${") {
+                        chars.next();
+                        out.push_str("  ");
+                        mask_code(source, chars, out, interpolations, true);
+                        continue;
+                    }
+                    escaped = c == '\\' && !escaped;
+                    pad(out, c);
+                }
+            }
+            '{' if in_interpolation => {
+                depth += 1;
+                out.push(ch);
+            }
+            '}' if in_interpolation && depth == 0 => {
+                out.push(' ');
+                return;
+            }
+            '}' if in_interpolation => {
+                depth -= 1;
+                out.push(ch);
+            }
+            _ => out.push(ch),
+        }
+    }
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lists_external_inputs() {
+        let src = r#"// System.getenv("COMMENTED")
+def token = System.getenv("TOKEN") ?: ""
+val home = providers.environmentVariable("HOME").orNull
+version = project.findProperty('releaseVersion') ?: "dev"
+if (hasProperty("ci")) {
+    println "${System.getProperty("user.name")}"
+}
+def key = System.getenv(name)
+def all = System.getenv()
+def url = project.providers.gradleProperty("repoUrl")
+val p = property("p$suffix")
+"#;
+        let inputs = external_inputs(src);
+        let found: Vec<_> = inputs
+            .iter()
+            .map(|i| (i.source, i.name.as_deref(), i.span.line))
+            .collect();
+
+        use InputSource::*;
+        assert_eq!(
+            found,
+            [
+                (EnvironmentVariable, Some("TOKEN"), 2),
+                (EnvironmentVariable, Some("HOME"), 3),
+                (GradleProperty, Some("releaseVersion"), 4),
+                (GradleProperty, Some("ci"), 5),
+                (SystemProperty, Some("user.name"), 6),
+                (EnvironmentVariable, None, 8),
+                (EnvironmentVariable, None, 9),
+                (GradleProperty, Some("repoUrl"), 10),
+                (GradleProperty, None, 11),
+            ]
+        );
+        assert_eq!(
+            &src[inputs[2].span.byte_range.clone()],
+            "project.findProperty('releaseVersion')"
+        );
+    }
+
+    #[test]
+    fn test_flags_configuration_time_reads() {
+        let src = "def token = System.getenv(\"TOKEN\")\nversion = System.getProperty('v')\n";
+        let diags = config_cache_issues(src);
+
+        assert_eq!(diags.len(), 2);
+        assert_eq!(diags[0].kind, DiagnosticKind::ConfigCacheEnvRead);
+        assert_eq!(diags[0].span.line, 1);
+        assert_eq!(diags[0].span.column, 13);
+        assert_eq!(&src[diags[0].span.byte_range.clone()], "System.getenv");
+        assert_eq!(diags[1].kind, DiagnosticKind::ConfigCacheSystemPropertyRead);
+        assert_eq!(diags[1].span.line, 2);
+    }
+
+    #[test]
+    fn test_exec_only_flagged_outside_task_actions() {
+        let src = r#"
+        project.exec { commandLine "git", "rev-parse" }
+        tasks.register("hello") {
+            doLast {
+                exec { commandLine "echo" }
+            }
+        }
+        def sha = "git rev-parse HEAD".execute().text
+        "#;
+        let diags = config_cache_issues(src);
+
+        let kinds: Vec<_> = diags.iter().map(|d| (d.kind, d.span.line)).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (DiagnosticKind::ConfigCacheExternalProcess, 2),
+                (DiagnosticKind::ConfigCacheExternalProcess, 8),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_project_access_in_task_action() {
+        let src = r#"
+        tasks.register("printVersion") {
+            doLast { println project.version }
+        }
+        gradle.buildFinished { println "done" }
+        "#;
+        let diags = config_cache_issues(src);
+
+        assert_eq!(diags.len(), 2);
+        assert_eq!(diags[0].kind, DiagnosticKind::ConfigCacheProjectAtExecution);
+        assert_eq!(diags[0].span.line, 3);
+        assert_eq!(diags[1].kind, DiagnosticKind::ConfigCacheBuildListener);
+    }
+
+    #[test]
+    fn test_comments_and_strings_are_ignored() {
+        let src = r#"
+        // System.getenv("A")
+        /* project.exec { } */
+        description = "uses System.getenv at runtime"
+        "#;
+        assert!(config_cache_issues(src).is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_assignments_list_both_locations() {
+        let src = r#"
+        application {
+            mainClassName = "a.Main"
+        }
+        tasks.register("one") { group = "build" }
+        tasks.register("two") { group = "build" }
+        application {
+            mainClassName = "b.Main"
+        }
+        if (x == y) { }
+        "#;
+        let diags = duplicate_assignments(src);
+
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].code(), "GRADLE011");
+        assert_eq!(diags[0].span.line, 8);
+        assert_eq!(&src[diags[0].span.byte_range.clone()], "mainClassName");
+        assert_eq!(diags[0].related.len(), 1);
+        assert_eq!(diags[0].related[0].line, 3);
+    }
+}
@@ -0,0 +1,569 @@
+//! Syntax tree of a Gradle script.
+//!
+//! [`DSLBlock`](crate::DSLBlock) keeps only what most tools need and drops
+//! the rest: which arguments were parenthesised, trailing closures on calls,
+//! chained calls such as `id "x" version "1.0"`, comments and source
+//! positions. [`Script`] keeps all of these, with a [`Span`] on every node,
+//! for tools such as linters that need to point back into the file. Both are
+//! built from the same token stream as [`tokenize_spanned`].
+//!
+//! [`tokenize_spanned`]: crate::tokenize_spanned
+
+use crate::analysis::Span;
+use crate::lexer::{SpannedToken, Token, lex};
+use crate::model::{Operator, ParseError};
+
+/// A whole script.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Script {
+    pub statements: Vec<Statement>,
+    /// Spans of the `//` and `/* */` comments, in source order.
+    pub comments: Vec<Span>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Statement {
+    pub kind: StatementKind,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum StatementKind {
+    /// `target = value`
+    Assignment { target: Name, value: Expr },
+    /// A method call with or without parentheses: `implementation "g:a:1"`,
+    /// `android { ... }`, `tasks.named("test") { ... }`.
+    ///
+    /// `if (c) { ... } else { ... }` is read as a call to `if` with a
+    /// [`ExprKind::Condition`] argument and a closure, with `else` (and any
+    /// `else if`) chained on.
+    Call(Call),
+}
+
+/// A possibly dotted name such as `version` or `tasks.named`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Name {
+    pub text: String,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Call {
+    pub name: Name,
+    pub args: Vec<Argument>,
+    /// Whether the arguments are written in parentheses.
+    pub parenthesized: bool,
+    /// A trailing `{ ... }` closure.
+    pub closure: Option<Block>,
+    /// The call chained on without a dot, e.g. `version "1.0"` in
+    /// `id "x" version "1.0"`.
+    pub chained: Option<Box<Call>>,
+    pub span: Span,
+}
+
+/// The statements between a pair of braces.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Block {
+    pub statements: Vec<Statement>,
+    /// Includes the braces.
+    pub span: Span,
+}
+
+/// A call argument; `name` is set for named arguments such as `group: "g"`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Argument {
+    pub name: Option<Name>,
+    pub value: Expr,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expr {
+    pub kind: ExprKind,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum ExprKind {
+    /// A string literal, without its quotes.
+    String(String),
+    Bool(bool),
+    /// A number literal as written.
+    Number(String),
+    /// A variable, property or constant, e.g. `libs.retrofit`.
+    Name(String),
+    /// A call in parentheses, e.g. `findProperty("v")`.
+    Call(Call),
+    /// The condition of an `if`, as written between its parentheses.
+    Condition(String),
+    /// `lhs + rhs` or `lhs ?: rhs`, grouped from the left.
+    Binary {
+        lhs: Box<Expr>,
+        op: Operator,
+        rhs: Box<Expr>,
+    },
+}
+
+impl Script {
+    /// Parses `source` into a syntax tree.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(len = source.len()), err)
+    )]
+    pub fn parse(source: &str) -> Result<Self, ParseError> {
+        let (tokens, comments) = lex(source)?;
+        let mut parser = Parser {
+            tokens: &tokens,
+            i: 0,
+        };
+        let statements = parser.statements()?;
+        if let Some(t) = parser.peek() {
+            return Err(parser.unexpected(t));
+        }
+        Ok(Script {
+            statements,
+            comments,
+        })
+    }
+
+    /// Every statement, including those in closures, depth first in source
+    /// order.
+    pub fn walk(&self) -> impl Iterator<Item = &Statement> {
+        let mut stack: Vec<&Statement> = self.statements.iter().rev().collect();
+        std::iter::from_fn(move || {
+            let statement = stack.pop()?;
+            if let StatementKind::Call(call) = &statement.kind {
+                let mut nested = Vec::new();
+                let mut next = Some(call);
+                while let Some(call) = next {
+                    if let Some(block) = &call.closure {
+                        nested.extend(&block.statements);
+                    }
+                    next = call.chained.as_deref();
+                }
+                stack.extend(nested.into_iter().rev());
+            }
+            Some(statement)
+        })
+    }
+}
+
+struct Parser<'a> {
+    tokens: &'a [SpannedToken],
+    i: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&'a SpannedToken> {
+        self.tokens.get(self.i)
+    }
+
+    fn peek_token(&self) -> Option<&'a Token> {
+        self.peek().map(|t| &t.token)
+    }
+
+    fn next(&mut self) -> Option<&'a SpannedToken> {
+        let token = self.peek()?;
+        self.i += 1;
+        Some(token)
+    }
+
+    fn skip_newlines(&mut self) {
+        while let Some(Token::Newline) = self.peek_token() {
+            self.i += 1;
+        }
+    }
+
+    /// Span of the last token consumed.
+    fn last_span(&self) -> &'a Span {
+        &self.tokens[self.i - 1].span
+    }
+
+    fn unexpected(&self, token: &SpannedToken) -> ParseError {
+        ParseError::UnexpectedToken(format!(
+            "{:?} at line {}, column {}",
+            token.token, token.span.line, token.span.column
+        ))
+    }
+
+    fn expect(&mut self, expected: Token, after: &str) -> Result<&'a SpannedToken, ParseError> {
+        match self.next() {
+            Some(t) if t.token == expected => Ok(t),
+            Some(t) => Err(self.unexpected(t)),
+            None => Err(ParseError::UnexpectedEnd(after.to_string())),
+        }
+    }
+
+    /// Statements up to a `}` or the end of the input, neither consumed.
+    fn statements(&mut self) -> Result<Vec<Statement>, ParseError> {
+        let mut statements = Vec::new();
+        loop {
+            self.skip_newlines();
+            match self.peek() {
+                None
+                | Some(SpannedToken {
+                    token: Token::CloseBrace,
+                    ..
+                }) => return Ok(statements),
+                Some(_) => statements.push(self.statement()?),
+            }
+        }
+    }
+
+    fn statement(&mut self) -> Result<Statement, ParseError> {
+        let name = self.name()?;
+
+        let statement = if let Some(Token::Equals) = self.peek_token() {
+            self.i += 1;
+            self.skip_newlines();
+            let value = self.expr(&name.text)?;
+            Statement {
+                span: join(&name.span, &value.span),
+                kind: StatementKind::Assignment {
+                    target: name,
+                    value,
+                },
+            }
+        } else {
+            let call = self.call(name)?;
+            Statement {
+                span: call.span.clone(),
+                kind: StatementKind::Call(call),
+            }
+        };
+
+        match self.peek() {
+            None
+            | Some(SpannedToken {
+                token: Token::Newline | Token::CloseBrace,
+                ..
+            }) => Ok(statement),
+            Some(t) => Err(self.unexpected(t)),
+        }
+    }
+
+    fn name(&mut self) -> Result<Name, ParseError> {
+        match self.next() {
+            Some(SpannedToken {
+                token: Token::Identifier(text),
+                span,
+            }) => Ok(Name {
+                text: text.clone(),
+                span: span.clone(),
+            }),
+            Some(t) => Err(self.unexpected(t)),
+            None => Err(ParseError::UnexpectedEnd("statement".into())),
+        }
+    }
+
+    /// The arguments, closure and chained calls following `name` in a
+    /// statement.
+    fn call(&mut self, name: Name) -> Result<Call, ParseError> {
+        let mut call = self.paren_call(name)?;
+
+        // `else if`: the `if` is chained on, not an argument
+        if !call.parenthesized
+            && call.name.text != "else"
+            && let Some(Token::String(_) | Token::Bool(_) | Token::Number(_) | Token::Identifier(_)) =
+                self.peek_token()
+        {
+            call.args = self.args(&call.name.text, false)?;
+        }
+
+        // a block's brace may sit on the next line
+        if call.args.is_empty() || call.parenthesized {
+            let at = self.i;
+            self.skip_newlines();
+            if !matches!(self.peek_token(), Some(Token::OpenBrace)) {
+                self.i = at;
+            }
+        }
+        if let Some(Token::OpenBrace) = self.peek_token() {
+            call.closure = Some(self.block()?);
+        }
+
+        if let Some(Token::Identifier(_)) = self.peek_token() {
+            let name = self.name()?;
+            call.chained = Some(Box::new(self.call(name)?));
+        }
+
+        call.span = join(&call.name.span, self.last_span());
+        Ok(call)
+    }
+
+    /// `name` with its arguments if they're in parentheses.
+    fn paren_call(&mut self, name: Name) -> Result<Call, ParseError> {
+        if let Some(SpannedToken {
+            token: Token::Condition(text),
+            span,
+        }) = self.peek()
+        {
+            self.i += 1;
+            let condition = Expr {
+                kind: ExprKind::Condition(text.clone()),
+                span: span.clone(),
+            };
+            return Ok(Call {
+                span: join(&name.span, span),
+                name,
+                args: vec![Argument {
+                    name: None,
+                    value: condition,
+                }],
+                parenthesized: true,
+                closure: None,
+                chained: None,
+            });
+        }
+
+        let parenthesized = matches!(self.peek_token(), Some(Token::OpenParen));
+        let args = if parenthesized {
+            self.i += 1;
+            self.args(&name.text, true)?
+        } else {
+            Vec::new()
+        };
+        Ok(Call {
+            span: join(&name.span, self.last_span()),
+            name,
+            args,
+            parenthesized,
+            closure: None,
+            chained: None,
+        })
+    }
+
+    /// A comma separated argument list. With `parenthesized` the list ends
+    /// with a `)`, which is consumed, and may span lines.
+    fn args(&mut self, callee: &str, parenthesized: bool) -> Result<Vec<Argument>, ParseError> {
+        let mut args = Vec::new();
+        if parenthesized {
+            self.skip_newlines();
+            if let Some(Token::CloseParen) = self.peek_token() {
+                self.i += 1;
+                return Ok(args);
+            }
+        }
+
+        loop {
+            let name = match (self.peek_token(), self.tokens.get(self.i + 1)) {
+                (
+                    Some(Token::Identifier(_)),
+                    Some(SpannedToken {
+                        token: Token::Colon,
+                        ..
+                    }),
+                ) => {
+                    let name = self.name()?;
+                    self.i += 1;
+                    self.skip_newlines();
+                    Some(name)
+                }
+                _ => None,
+            };
+            let value = self.expr(callee)?;
+            args.push(Argument { name, value });
+
+            if parenthesized {
+                self.skip_newlines();
+            }
+            match self.peek_token() {
+                Some(Token::Comma) => {
+                    self.i += 1;
+                    self.skip_newlines();
+                }
+                Some(Token::CloseParen) if parenthesized => {
+                    self.i += 1;
+                    return Ok(args);
+                }
+                _ if !parenthesized => return Ok(args),
+                Some(_) => return Err(self.unexpected(self.peek().unwrap())),
+                None => return Err(ParseError::UnexpectedEnd(callee.to_string())),
+            }
+        }
+    }
+
+    fn block(&mut self) -> Result<Block, ParseError> {
+        let open = self.expect(Token::OpenBrace, "block")?;
+        let statements = self.statements()?;
+        let close = self.expect(Token::CloseBrace, "block")?;
+        Ok(Block {
+            statements,
+            span: join(&open.span, &close.span),
+        })
+    }
+
+    /// `operand ((+|?:) operand)*`
+    fn expr(&mut self, after: &str) -> Result<Expr, ParseError> {
+        let mut expr = self.operand(after)?;
+        loop {
+            let op = match self.peek_token() {
+                Some(Token::Plus) => Operator::Plus,
+                Some(Token::Elvis) => Operator::Elvis,
+                _ => return Ok(expr),
+            };
+            self.i += 1;
+            self.skip_newlines();
+            let rhs = self.operand(after)?;
+            expr = Expr {
+                span: join(&expr.span, &rhs.span),
+                kind: ExprKind::Binary {
+                    lhs: Box::new(expr),
+                    op,
+                    rhs: Box::new(rhs),
+                },
+            };
+        }
+    }
+
+    fn operand(&mut self, after: &str) -> Result<Expr, ParseError> {
+        let Some(token) = self.next() else {
+            return Err(ParseError::UnexpectedEnd(after.to_string()));
+        };
+        let kind = match &token.token {
+            Token::String(s) => ExprKind::String(s.clone()),
+            Token::Bool(b) => ExprKind::Bool(*b),
+            Token::Number(n) => ExprKind::Number(n.clone()),
+            Token::Identifier(text) => {
+                let name = Name {
+                    text: text.clone(),
+                    span: token.span.clone(),
+                };
+                if let Some(Token::OpenParen) = self.peek_token() {
+                    let call = self.paren_call(name)?;
+                    return Ok(Expr {
+                        span: call.span.clone(),
+                        kind: ExprKind::Call(call),
+                    });
+                }
+                ExprKind::Name(name.text)
+            }
+            _ => return Err(self.unexpected(token)),
+        };
+        Ok(Expr {
+            kind,
+            span: token.span.clone(),
+        })
+    }
+}
+
+/// The span from the start of `first` to the end of `last`.
+fn join(first: &Span, last: &Span) -> Span {
+    Span {
+        line: first.line,
+        column: first.column,
+        byte_range: first.byte_range.start..last.byte_range.end,
+    }
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(statement: &Statement) -> &Call {
+        match &statement.kind {
+            StatementKind::Call(call) => call,
+            other => panic!("Expected a call, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_keeps_call_shapes_and_spans() {
+        let source = r#"plugins {
+    id("org.jetbrains.kotlin.jvm") version "1.9.0" // kotlin
+}
+
+tasks.named("test") {
+    useJUnitPlatform()
+}
+
+dependencies {
+    implementation group: "g", name: "a"
+}
+version = "1." + patch
+"#;
+        let script = Script::parse(source).unwrap();
+        assert_eq!(script.statements.len(), 4);
+        assert_eq!(script.comments.len(), 1);
+
+        let plugins = call(&script.statements[0]);
+        let id = call(&plugins.closure.as_ref().unwrap().statements[0]);
+        assert!(id.parenthesized);
+        assert_eq!(id.chained.as_ref().unwrap().name.text, "version");
+        assert_eq!(
+            &source[id.span.byte_range.clone()],
+            r#"id("org.jetbrains.kotlin.jvm") version "1.9.0""#
+        );
+
+        let named = call(&script.statements[1]);
+        assert_eq!(named.name.text, "tasks.named");
+        assert_eq!(named.closure.as_ref().unwrap().span.line, 5);
+
+        let dependencies = call(&script.statements[2]);
+        let implementation = call(&dependencies.closure.as_ref().unwrap().statements[0]);
+        let names: Vec<_> = implementation
+            .args
+            .iter()
+            .map(|a| a.name.as_ref().unwrap().text.as_str())
+            .collect();
+        assert_eq!(names, ["group", "name"]);
+
+        let StatementKind::Assignment { target, value } = &script.statements[3].kind else {
+            panic!("Expected an assignment");
+        };
+        assert_eq!(target.text, "version");
+        assert!(matches!(
+            value.kind,
+            ExprKind::Binary {
+                op: Operator::Plus,
+                ..
+            }
+        ));
+        assert_eq!(script.statements[3].span.line, 12);
+    }
+
+    #[test]
+    fn test_walk_visits_nested_statements() {
+        let script = Script::parse("a {\n  b {\n    c 'x'\n  }\n}\nd()").unwrap();
+        let names: Vec<_> = script.walk().map(|s| call(s).name.text.as_str()).collect();
+        assert_eq!(names, ["a", "b", "c", "d"]);
+    }
+
+    #[test]
+    fn test_if_else_chains() {
+        let source = "if (ci && !local) {\n  a 'x'\n} else if (b) {\n  c 'y'\n} else {\n  d 'z'\n}";
+        let script = Script::parse(source).unwrap();
+        assert_eq!(script.statements.len(), 1);
+
+        let branch = call(&script.statements[0]);
+        assert_eq!(branch.name.text, "if");
+        assert_eq!(
+            branch.args[0].value.kind,
+            ExprKind::Condition("ci && !local".into())
+        );
+        let otherwise = branch.chained.as_ref().unwrap();
+        assert_eq!(otherwise.name.text, "else");
+        assert_eq!(otherwise.chained.as_ref().unwrap().name.text, "if");
+
+        let names: Vec<_> = script.walk().map(|s| call(s).name.text.as_str()).collect();
+        assert_eq!(names, ["if", "a", "c", "d"]);
+    }
+
+    #[test]
+    fn test_errors_point_at_token() {
+        let err = Script::parse("a {\n  b 'x' )\n}").unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::UnexpectedToken("CloseParen at line 2, column 9".into())
+        );
+        assert_eq!(
+            Script::parse("a {"),
+            Err(ParseError::UnexpectedEnd("block".into()))
+        );
+    }
+}
@@ -0,0 +1,377 @@
+use crate::dependency::Coordinates;
+use crate::model::ParseError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A Gradle version catalog, read from `gradle/libs.versions.toml` or
+/// declared in the settings script.
+///
+/// Aliases are stored normalised the way Gradle exposes them to build
+/// scripts: `-` and `_` become `.`, so `retrofit-core` is looked up as
+/// `libs.retrofit.core`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VersionCatalog {
+    /// Accessor name used in build scripts, e.g. `libs`.
+    pub name: String,
+    pub versions: HashMap<String, String>,
+    pub libraries: HashMap<String, Coordinates>,
+    pub plugins: HashMap<String, CatalogPlugin>,
+    /// Bundle alias to the (normalised) library aliases it groups.
+    pub bundles: HashMap<String, Vec<String>>,
+}
+
+/// A plugin declared in a catalog.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CatalogPlugin {
+    pub id: String,
+    pub version: Option<String>,
+}
+
+/// What a catalog accessor such as `libs.plugins.kotlin.jvm` points at.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CatalogEntry<'a> {
+    Library(&'a Coordinates),
+    Plugin(&'a CatalogPlugin),
+    Version(&'a str),
+    Bundle(&'a [String]),
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Section {
+    None,
+    Versions,
+    Libraries,
+    Plugins,
+    Bundles,
+}
+
+impl VersionCatalog {
+    /// Parses the TOML catalog format.
+    ///
+    /// Only the subset Gradle catalogs use is understood: the four
+    /// top-level tables, string values, inline tables and string arrays.
+    /// `version.ref` entries are resolved against `[versions]`, and rich
+    /// versions (`{ strictly = "..." }`) are reduced to their first constraint.
+    pub fn parse_toml(name: &str, source: &str) -> Result<Self, ParseError> {
+        let mut catalog = VersionCatalog::new(name);
+        let mut section = Section::None;
+        // libraries and plugins may reference versions declared further down
+        let mut pending: Vec<(usize, Section, String, HashMap<String, String>)> = Vec::new();
+
+        let mut lines = source.lines().enumerate().map(|(i, l)| (i + 1, l));
+        while let Some((line_no, raw)) = lines.next() {
+            let mut line = strip_toml_comment(raw).trim().to_string();
+            if line.is_empty() {
+                continue;
+            }
+            let err = |message: String| ParseError::Catalog {
+                line: line_no,
+                message,
+            };
+
+            if line.starts_with('[') {
+                section = match line.trim_matches(['[', ']']).trim() {
+                    "versions" => Section::Versions,
+                    "libraries" => Section::Libraries,
+                    "plugins" => Section::Plugins,
+                    "bundles" => Section::Bundles,
+                    other => return Err(err(format!("unknown table '{}'", other))),
+                };
+                continue;
+            }
+
+            // arrays may be split over several lines
+            if line.contains('[') {
+                while !line.contains(']') {
+                    let Some((_, next)) = lines.next() else {
+                        return Err(err("unterminated array".into()));
+                    };
+                    line.push(' ');
+                    line.push_str(strip_toml_comment(next).trim());
+                }
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| err(format!("expected `key = value`, got '{}'", line)))?;
+            let alias = normalize_alias(key.trim().trim_matches('"'));
+            let value = value.trim();
+
+            match section {
+                Section::None => return Err(err(format!("'{}' outside of a table", alias))),
+                Section::Versions => {
+                    let version = match inline_table(value) {
+                        Some(fields) => ["strictly", "require", "prefer"]
+                            .iter()
+                            .find_map(|k| fields.get(*k).cloned()),
+                        None => toml_string(value),
+                    };
+                    let version =
+                        version.ok_or_else(|| err(format!("invalid version '{}'", alias)))?;
+                    catalog.versions.insert(alias, version);
+                }
+                Section::Libraries | Section::Plugins => {
+                    let fields = match inline_table(value) {
+                        Some(fields) => fields,
+                        None => {
+                            let notation = toml_string(value)
+                                .ok_or_else(|| err(format!("invalid entry '{}'", alias)))?;
+                            let mut fields = HashMap::new();
+                            fields.insert("notation".to_string(), notation);
+                            fields
+                        }
+                    };
+                    pending.push((line_no, section, alias, fields));
+                }
+                Section::Bundles => {
+                    let items = value
+                        .trim_start_matches('[')
+                        .trim_end_matches(']')
+                        .split(',')
+                        .filter_map(|item| toml_string(item.trim()))
+                        .map(|item| normalize_alias(&item))
+                        .collect();
+                    catalog.bundles.insert(alias, items);
+                }
+            }
+        }
+
+        for (line, section, alias, fields) in pending {
+            let version = match (fields.get("version"), fields.get("version.ref")) {
+                (Some(v), _) => Some(v.clone()),
+                (None, Some(r)) => Some(
+                    catalog
+                        .versions
+                        .get(&normalize_alias(r))
+                        .cloned()
+                        .ok_or_else(|| ParseError::Catalog {
+                            line,
+                            message: format!("unknown version reference '{}'", r),
+                        })?,
+                ),
+                (None, None) => None,
+            };
+            let invalid = || ParseError::Catalog {
+                line,
+                message: format!("incomplete entry '{}'", alias),
+            };
+
+            if section == Section::Plugins {
+                let (id, version) = match fields.get("notation") {
+                    Some(n) => match n.split_once(':') {
+                        Some((id, v)) => (id.to_string(), Some(v.to_string())),
+                        None => (n.clone(), version),
+                    },
+                    None => (fields.get("id").cloned().ok_or_else(invalid)?, version),
+                };
+                catalog.plugins.insert(alias, CatalogPlugin { id, version });
+                continue;
+            }
+
+            let mut coords = match (fields.get("notation"), fields.get("module")) {
+                (Some(n), _) | (None, Some(n)) => Coordinates::parse(n).ok_or_else(invalid)?,
+                (None, None) => Coordinates {
+                    group: fields.get("group").cloned().ok_or_else(invalid)?,
+                    name: fields.get("name").cloned().ok_or_else(invalid)?,
+                    version: None,
+                },
+            };
+            if version.is_some() {
+                coords.version = version;
+            }
+            catalog.libraries.insert(alias, coords);
+        }
+
+        Ok(catalog)
+    }
+
+    /// An empty catalog exposed to build scripts as `name`.
+    pub fn new(name: impl Into<String>) -> Self {
+        VersionCatalog {
+            name: name.into(),
+            ..VersionCatalog::default()
+        }
+    }
+
+    pub fn add_version(&mut self, alias: &str, version: impl Into<String>) {
+        self.versions.insert(normalize_alias(alias), version.into());
+    }
+
+    pub fn add_library(&mut self, alias: &str, coordinates: Coordinates) {
+        self.libraries.insert(normalize_alias(alias), coordinates);
+    }
+
+    pub fn add_plugin(&mut self, alias: &str, plugin: CatalogPlugin) {
+        self.plugins.insert(normalize_alias(alias), plugin);
+    }
+
+    pub fn add_bundle<S: AsRef<str>>(&mut self, alias: &str, libraries: &[S]) {
+        let libraries = libraries
+            .iter()
+            .map(|l| normalize_alias(l.as_ref()))
+            .collect();
+        self.bundles.insert(normalize_alias(alias), libraries);
+    }
+
+    /// Looks up a version by alias.
+    pub fn version(&self, alias: &str) -> Option<&str> {
+        self.versions
+            .get(&normalize_alias(alias))
+            .map(String::as_str)
+    }
+
+    /// Looks up a library by alias, e.g. `retrofit-core` or `retrofit.core`.
+    pub fn library(&self, alias: &str) -> Option<&Coordinates> {
+        self.libraries.get(&normalize_alias(alias))
+    }
+
+    /// Looks up a plugin by alias.
+    pub fn plugin(&self, alias: &str) -> Option<&CatalogPlugin> {
+        self.plugins.get(&normalize_alias(alias))
+    }
+
+    /// Resolves a build script accessor such as `libs.retrofit`,
+    /// `libs.versions.kotlin` or `libs.bundles.net`.
+    ///
+    /// Returns `None` when the accessor belongs to a different catalog or the
+    /// alias is unknown.
+    pub fn lookup(&self, accessor: &str) -> Option<CatalogEntry<'_>> {
+        let path = accessor
+            .strip_prefix(self.name.as_str())?
+            .strip_prefix('.')?;
+
+        if let Some(alias) = path.strip_prefix("versions.") {
+            return self
+                .versions
+                .get(alias)
+                .map(|v| CatalogEntry::Version(v.as_str()));
+        }
+        if let Some(alias) = path.strip_prefix("plugins.") {
+            return self.plugin(alias).map(CatalogEntry::Plugin);
+        }
+        if let Some(alias) = path.strip_prefix("bundles.") {
+            return self
+                .bundles
+                .get(alias)
+                .map(|b| CatalogEntry::Bundle(b.as_slice()));
+        }
+        self.library(path).map(CatalogEntry::Library)
+    }
+}
+
+/// Maps an alias to the dotted form used by generated accessors.
+fn normalize_alias(alias: &str) -> String {
+    alias.replace(['-', '_'], ".")
+}
+
+fn strip_toml_comment(line: &str) -> &str {
+    let mut in_string = false;
+    for (idx, c) in line.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..idx],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn toml_string(value: &str) -> Option<String> {
+    let value = value.trim();
+    ['"', '\'']
+        .iter()
+        .find_map(|q| value.strip_prefix(*q)?.strip_suffix(*q))
+        .map(str::to_string)
+}
+
+/// Reads `{ key = "value", other.key = "value" }` into a flat map.
+fn inline_table(value: &str) -> Option<HashMap<String, String>> {
+    let body = value.strip_prefix('{')?.strip_suffix('}')?;
+    let mut fields = HashMap::new();
+    for field in body.split(',').filter(|f| !f.trim().is_empty()) {
+        let (key, value) = field.split_once('=')?;
+        let key = key.trim().replace(' ', "");
+        // `version = { ref = "x" }` is not worth a nested parser
+        fields.insert(key, toml_string(value)?);
+    }
+    Some(fields)
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CATALOG: &str = r#"
+    [versions]
+    retrofit = "2.9.0"
+    kotlin = { strictly = "1.9.22" }
+
+    [libraries]
+    retrofit-core = { module = "com.squareup.retrofit2:retrofit", version.ref = "retrofit" }
+    okhttp = { group = "com.squareup.okhttp3", name = "okhttp", version = "4.12.0" }
+    junit = "junit:junit:4.13.2" # test only
+
+    [plugins]
+    kotlin-jvm = { id = "org.jetbrains.kotlin.jvm", version.ref = "kotlin" }
+
+    [bundles]
+    network = [
+        "retrofit-core",
+        "okhttp",
+    ]
+    "#;
+
+    #[test]
+    fn test_parse_catalog() {
+        let catalog = VersionCatalog::parse_toml("libs", CATALOG).unwrap();
+
+        assert_eq!(
+            catalog.library("retrofit-core").unwrap().to_string(),
+            "com.squareup.retrofit2:retrofit:2.9.0"
+        );
+        assert_eq!(
+            catalog.library("okhttp").unwrap().version.as_deref(),
+            Some("4.12.0")
+        );
+        assert_eq!(catalog.library("junit").unwrap().name, "junit");
+        assert_eq!(
+            catalog.plugin("kotlin.jvm"),
+            Some(&CatalogPlugin {
+                id: "org.jetbrains.kotlin.jvm".into(),
+                version: Some("1.9.22".into()),
+            })
+        );
+        assert_eq!(catalog.bundles["network"], vec!["retrofit.core", "okhttp"]);
+    }
+
+    #[test]
+    fn test_lookup_accessors() {
+        let catalog = VersionCatalog::parse_toml("libs", CATALOG).unwrap();
+
+        assert!(matches!(
+            catalog.lookup("libs.retrofit.core"),
+            Some(CatalogEntry::Library(c)) if c.name == "retrofit"
+        ));
+        assert_eq!(
+            catalog.lookup("libs.versions.kotlin"),
+            Some(CatalogEntry::Version("1.9.22"))
+        );
+        assert!(matches!(
+            catalog.lookup("libs.bundles.network"),
+            Some(CatalogEntry::Bundle(b)) if b.len() == 2
+        ));
+        assert!(catalog.lookup("deps.okhttp").is_none());
+        assert!(catalog.lookup("libs.missing").is_none());
+    }
+
+    #[test]
+    fn test_unknown_version_reference() {
+        let src = "[libraries]\nfoo = { module = \"g:a\", version.ref = \"nope\" }\n";
+        let err = VersionCatalog::parse_toml("libs", src).unwrap_err();
+        assert!(matches!(err, ParseError::Catalog { line: 2, .. }));
+    }
+}
@@ -0,0 +1,54 @@
+//! The signatures this crate had before structured errors and ordered
+//! entries, kept as deprecated wrappers so callers can migrate one call
+//! site at a time. See [`uniparse_core::compat`] for the policy.
+
+use std::collections::HashMap;
+
+use uniparse_core::compat::IntoLegacy;
+
+use crate::lexer::Token;
+use crate::model::{DSLBlock, DSLValue, Entries};
+
+/// [`DSLBlock::parse_str`] with the error as its message.
+///
+/// ```rust
+/// #![allow(deprecated)]
+/// use uniparse_gradle::compat;
+///
+/// let block = compat::parse_str("group = 'com.example'\n").unwrap();
+/// assert_eq!(block.get(&["group"]).and_then(|v| v.as_str()), Some("com.example"));
+/// assert!(compat::parse_str("x = ").is_err());
+/// ```
+#[deprecated(since = "0.1.0", note = "use `DSLBlock::parse_str` instead")]
+pub fn parse_str(source: &str) -> Result<DSLBlock, String> {
+    DSLBlock::parse_str(source).into_legacy()
+}
+
+/// Parses the block starting at `tokens[start]`, returning it with the
+/// index just past it.
+#[deprecated(
+    since = "0.1.0",
+    note = "parse the source with `DSLBlock::parse_str` instead"
+)]
+pub fn parse_tokens(tokens: &[Token], start: usize) -> Result<(DSLBlock, usize), String> {
+    crate::parser::parse_tokens(tokens, start).into_legacy()
+}
+
+/// The entries of a block as the map they used to be: one value per key,
+/// the last statement winning where a key repeats.
+///
+/// ```rust
+/// #![allow(deprecated)]
+/// use uniparse_gradle::{DSLBlock, compat};
+///
+/// let block = DSLBlock::parse_str("version = '1'\nversion = '2'\n").unwrap();
+/// let map = compat::entries_map(&block.entries);
+/// assert_eq!(map["version"].as_str(), Some("2"));
+/// ```
+#[deprecated(since = "0.1.0", note = "use the ordered `Entries` directly")]
+pub fn entries_map(entries: &Entries) -> HashMap<String, DSLValue> {
+    entries
+        .iter()
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect()
+}
@@ -0,0 +1,238 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::dependency::Dependency;
+use crate::model::{DSLBlock, DSLValue};
+
+/// `(configuration, configurations it extends)` as set up by the `java`
+/// and `java-library` plugins.
+const JAVA_LIBRARY_DEFAULTS: &[(&str, &[&str])] = &[
+    ("implementation", &["api"]),
+    ("compileOnly", &["compileOnlyApi"]),
+    ("compileClasspath", &["compileOnly", "implementation"]),
+    ("runtimeClasspath", &["runtimeOnly", "implementation"]),
+    ("testImplementation", &["implementation"]),
+    ("testRuntimeOnly", &["runtimeOnly"]),
+    (
+        "testCompileClasspath",
+        &["testCompileOnly", "testImplementation"],
+    ),
+    (
+        "testRuntimeClasspath",
+        &["testRuntimeOnly", "testImplementation"],
+    ),
+    // the variants consumers of the project resolve
+    ("apiElements", &["api", "compileOnlyApi"]),
+    ("runtimeElements", &["implementation", "runtimeOnly"]),
+];
+
+/// The configuration consumers compile against.
+const API_ELEMENTS: &str = "apiElements";
+
+/// Which configurations extend which: a dependency declared in a
+/// configuration is also in every configuration that extends it.
+///
+/// Starts from the `java-library` plugin's defaults, e.g. `implementation`
+/// extends `api` and the published `apiElements` variant extends `api` and
+/// `compileOnlyApi`.
+///
+/// ```rust
+/// use uniparse_gradle::ConfigurationGraph;
+///
+/// let graph = ConfigurationGraph::default();
+/// assert!(graph.is_exported("api"));
+/// assert!(!graph.is_exported("implementation"));
+/// assert!(graph.inherited_by("api").contains("testRuntimeClasspath"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigurationGraph {
+    /// Each configuration to the ones it extends directly.
+    pub extends: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl Default for ConfigurationGraph {
+    fn default() -> Self {
+        let extends = JAVA_LIBRARY_DEFAULTS
+            .iter()
+            .map(|(child, parents)| {
+                let parents = parents.iter().map(|p| p.to_string()).collect();
+                (child.to_string(), parents)
+            })
+            .collect();
+        ConfigurationGraph { extends }
+    }
+}
+
+impl ConfigurationGraph {
+    /// Records that `child` extends `parent`, like
+    /// `configurations.child.extendsFrom(configurations.parent)`.
+    pub fn add_extends(&mut self, child: &str, parent: &str) {
+        self.extends
+            .entry(child.to_string())
+            .or_default()
+            .insert(parent.to_string());
+    }
+
+    /// Every configuration `configuration` extends, directly or through
+    /// others.
+    pub fn ancestors(&self, configuration: &str) -> BTreeSet<&str> {
+        let mut found = BTreeSet::new();
+        let mut pending = vec![configuration];
+        while let Some(name) = pending.pop() {
+            for parent in self.extends.get(name).into_iter().flatten() {
+                if found.insert(parent.as_str()) {
+                    pending.push(parent);
+                }
+            }
+        }
+        found
+    }
+
+    /// Every configuration that extends `configuration`, directly or
+    /// through others, i.e. where its dependencies end up.
+    pub fn inherited_by(&self, configuration: &str) -> BTreeSet<&str> {
+        self.extends
+            .keys()
+            .filter(|child| self.ancestors(child).contains(configuration))
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Whether dependencies declared in `configuration` are part of the
+    /// project's API: they reach `apiElements`, so consumers compile
+    /// against them too.
+    pub fn is_exported(&self, configuration: &str) -> bool {
+        configuration == API_ELEMENTS || self.ancestors(API_ELEMENTS).contains(configuration)
+    }
+}
+
+impl DSLBlock {
+    /// The configuration graph of the script: the `java-library` defaults
+    /// plus the `extendsFrom` calls of its `configurations { }` block,
+    /// written as `name { extendsFrom other }` or
+    /// `name.extendsFrom(other)`.
+    ///
+    /// ```rust
+    /// use uniparse_gradle::DSLBlock;
+    ///
+    /// let build = DSLBlock::parse_str(
+    ///     "configurations {\n    api { extendsFrom shaded }\n}\n",
+    /// )
+    /// .unwrap();
+    /// assert!(build.configuration_graph().is_exported("shaded"));
+    /// ```
+    pub fn configuration_graph(&self) -> ConfigurationGraph {
+        let mut graph = ConfigurationGraph::default();
+        for (key, value) in self.entries.iter() {
+            match (key.as_str(), value) {
+                ("configurations", DSLValue::Block(block)) => {
+                    for (key, value) in block.entries.iter() {
+                        add_extends(&mut graph, key, value);
+                    }
+                }
+                (key, value) => {
+                    if let Some(key) = key.strip_prefix("configurations.") {
+                        add_extends(&mut graph, key, value);
+                    }
+                }
+            }
+        }
+        graph
+    }
+
+    /// The dependencies consumers of the project compile against: those
+    /// declared in a configuration the
+    /// [`configuration_graph`](Self::configuration_graph) exports.
+    pub fn api_dependencies(&self) -> Vec<Dependency> {
+        let graph = self.configuration_graph();
+        let mut deps = self.dependencies();
+        deps.retain(|d| graph.is_exported(&d.configuration));
+        deps
+    }
+}
+
+/// Adds the edges of one statement of a `configurations { }` block.
+fn add_extends(graph: &mut ConfigurationGraph, key: &str, value: &DSLValue) {
+    let (child, parents) = match (key.strip_suffix(".extendsFrom"), value) {
+        (Some(child), parents) => (child, parents),
+        (None, DSLValue::Block(block)) => match block.entries.get("extendsFrom") {
+            Some(parents) => (key, parents),
+            None => return,
+        },
+        _ => return,
+    };
+    let parents = match parents {
+        DSLValue::FunctionCall(args) => args.iter().collect(),
+        single => vec![single],
+    };
+    for parent in parents {
+        if let DSLValue::Reference(name) | DSLValue::Identifier(name) = parent {
+            let name = name.strip_prefix("configurations.").unwrap_or(name);
+            graph.add_extends(child, name);
+        }
+    }
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_graph() {
+        let graph = ConfigurationGraph::default();
+        assert_eq!(
+            graph.ancestors("testCompileClasspath"),
+            BTreeSet::from([
+                "api",
+                "implementation",
+                "testCompileOnly",
+                "testImplementation"
+            ])
+        );
+        assert!(graph.is_exported("compileOnlyApi"));
+        assert!(!graph.is_exported("runtimeOnly"));
+        assert!(!graph.is_exported("testImplementation"));
+        assert!(
+            graph
+                .inherited_by("runtimeOnly")
+                .contains("runtimeElements")
+        );
+    }
+
+    #[test]
+    fn test_script_edges_and_api_dependencies() {
+        let build = DSLBlock::parse_str(
+            r#"
+            configurations {
+                bundled { extendsFrom(configurations.internal) }
+                api.extendsFrom(bundled)
+            }
+            configurations.compileOnly.extendsFrom(provided)
+            dependencies {
+                api 'com.google.guava:guava:33.0-jre'
+                implementation 'org.slf4j:slf4j-api:2.0.12'
+                internal 'com.example:shaded-util:1.0'
+                provided 'jakarta.servlet:jakarta.servlet-api:6.0.0'
+                testImplementation 'junit:junit:4.13.2'
+            }
+            "#,
+        )
+        .unwrap();
+
+        let graph = build.configuration_graph();
+        assert_eq!(graph.ancestors("bundled"), BTreeSet::from(["internal"]));
+        assert!(graph.ancestors("compileClasspath").contains("provided"));
+        assert!(graph.is_exported("internal"));
+        assert!(!graph.is_exported("provided"));
+
+        let api: Vec<_> = build
+            .api_dependencies()
+            .into_iter()
+            .map(|d| d.configuration)
+            .collect();
+        assert_eq!(api, ["api", "internal"]);
+    }
+}
@@ -0,0 +1,171 @@
+use crate::ast::{Expr, ExprKind, Script, Statement, StatementKind};
+use crate::cst::GradleBuild;
+use crate::model::ParseError;
+
+/// The coordinates a project publishes under, collected by
+/// [`GradleBuild::project_coordinates`].
+///
+/// Each field holds the string a literal assigns, or the expression as
+/// written when it isn't a literal, e.g. `"app-" + flavor`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProjectCoordinates {
+    pub group: Option<String>,
+    pub version: Option<String>,
+    /// `archivesBaseName`, or `archivesName` of the `base` extension that
+    /// replaces it since Gradle 7.1.
+    pub archives_name: Option<String>,
+}
+
+impl GradleBuild {
+    /// Collects the `group`, `version` and archive name the script sets,
+    /// whichever syntax it uses: `group = 'g'`, the call form `group 'g'`,
+    /// `project.version = '1.0'`, `archivesBaseName = 'app'`,
+    /// `base { archivesName = 'app' }` or `base.archivesName.set("app")`.
+    ///
+    /// Assignments in `allprojects { }` count too, since they apply to this
+    /// project as well. When a coordinate is set more than once the last
+    /// assignment wins, as it does when Gradle runs the script.
+    ///
+    /// ```rust
+    /// use uniparse_gradle::GradleBuild;
+    ///
+    /// let build = GradleBuild::parse("allprojects {\n    group = 'com.example'\n}\nversion = '1.2.0'\n").unwrap();
+    /// let coordinates = build.project_coordinates().unwrap();
+    /// assert_eq!(coordinates.group.as_deref(), Some("com.example"));
+    /// assert_eq!(coordinates.version.as_deref(), Some("1.2.0"));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns a [`ParseError`] if the script, edits included, no longer
+    /// parses into statements.
+    pub fn project_coordinates(&self) -> Result<ProjectCoordinates, ParseError> {
+        let source = self.to_string();
+        let script = Script::parse(&source)?;
+        let mut out = ProjectCoordinates::default();
+        collect(&script.statements, Scope::Project, &source, &mut out);
+        Ok(out)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Scope {
+    Project,
+    /// Inside `base { }`.
+    Base,
+}
+
+fn collect(list: &[Statement], scope: Scope, source: &str, out: &mut ProjectCoordinates) {
+    for statement in list {
+        match &statement.kind {
+            StatementKind::Assignment { target, value } => {
+                assign(out, scope, &target.text, value, source);
+            }
+            StatementKind::Call(call) => match (&call.closure, call.name.text.as_str()) {
+                (Some(block), "allprojects") if scope == Scope::Project => {
+                    collect(&block.statements, Scope::Project, source, out);
+                }
+                (Some(block), "base") if scope == Scope::Project => {
+                    collect(&block.statements, Scope::Base, source, out);
+                }
+                // `base.archivesName.set("app")`, or the call form of an
+                // assignment, `group 'com.example'`
+                (None, name) if call.args.len() == 1 && call.args[0].name.is_none() => {
+                    let property = name.strip_suffix(".set").unwrap_or(name);
+                    assign(out, scope, property, &call.args[0].value, source);
+                }
+                _ => {}
+            },
+        }
+    }
+}
+
+fn assign(out: &mut ProjectCoordinates, scope: Scope, target: &str, value: &Expr, source: &str) {
+    let target = target.strip_prefix("project.").unwrap_or(target);
+    let field = match (scope, target) {
+        (Scope::Project, "group") => &mut out.group,
+        (Scope::Project, "version") => &mut out.version,
+        (Scope::Project, "archivesBaseName" | "base.archivesName")
+        | (Scope::Base, "archivesName") => &mut out.archives_name,
+        _ => return,
+    };
+    *field = Some(match &value.kind {
+        ExprKind::String(s) => s.clone(),
+        _ => source[value.span.byte_range.clone()].to_string(),
+    });
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_coordinates() {
+        let groovy = GradleBuild::parse(
+            r#"
+allprojects {
+    group = 'com.example'
+    version = '0.1.0'
+}
+
+project.version = '1.0.0-' + buildNumber
+archivesBaseName = "app"
+
+subprojects {
+    group = 'com.example.libs'
+}
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            groovy.project_coordinates().unwrap(),
+            ProjectCoordinates {
+                group: Some("com.example".into()),
+                version: Some("'1.0.0-' + buildNumber".into()),
+                archives_name: Some("app".into()),
+            }
+        );
+
+        let kotlin = GradleBuild::parse(
+            "group = \"com.example\"\nbase {\n    archivesName.set(\"app-kts\")\n}\n",
+        )
+        .unwrap();
+        assert_eq!(
+            kotlin
+                .project_coordinates()
+                .unwrap()
+                .archives_name
+                .as_deref(),
+            Some("app-kts")
+        );
+
+        let dotted =
+            GradleBuild::parse("base.archivesName = 'a'\nbase.archivesName.set('b')\n").unwrap();
+        assert_eq!(
+            dotted.project_coordinates().unwrap(),
+            ProjectCoordinates {
+                archives_name: Some("b".into()),
+                ..ProjectCoordinates::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_call_form() {
+        let build = GradleBuild::parse(
+            "group 'com.example'\nproject.version '2.0.0'\nbase {\n    archivesName 'app'\n}\n",
+        )
+        .unwrap();
+        assert_eq!(
+            build.project_coordinates().unwrap(),
+            ProjectCoordinates {
+                group: Some("com.example".into()),
+                version: Some("2.0.0".into()),
+                archives_name: Some("app".into()),
+            }
+        );
+    }
+}
@@ -0,0 +1,616 @@
+//! Lossless syntax tree of a Gradle script, for edits that must leave the
+//! rest of the file exactly as it was.
+//!
+//! The tree is a [`uniparse_core`] CST built from the same tokens as
+//! [`Script`], with the whitespace and comments in between kept as trivia.
+//! Statements become [`SyntaxKind::Statement`] nodes that include the
+//! comments on the lines directly above them and a comment at the end of
+//! their last line; closures become [`SyntaxKind::Block`] nodes.
+
+use std::fmt::{self, Display, Formatter};
+use std::ops::Range;
+
+use uniparse_core::{
+    CstKind, GreenElement, GreenNode, GreenNodeBuilder, GreenToken, SyntaxElement, SyntaxNode,
+    comments_above,
+};
+
+use crate::ast::{Block, Script, Statement, StatementKind};
+use crate::lexer::{Token, lex, quote};
+use crate::model::ParseError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SyntaxKind {
+    /// The whole file.
+    Script,
+    /// A statement with the comments attached to it.
+    Statement,
+    /// A `{ ... }` closure, braces included.
+    Block,
+    Identifier,
+    /// A string literal, quotes included.
+    String,
+    Bool,
+    Number,
+    Equals,
+    OpenBrace,
+    CloseBrace,
+    OpenParen,
+    CloseParen,
+    Comma,
+    Plus,
+    Elvis,
+    Colon,
+    Semicolon,
+    /// The condition of an `if`, parentheses included.
+    Condition,
+    Newline,
+    Whitespace,
+    Comment,
+}
+
+impl CstKind for SyntaxKind {
+    fn is_trivia(self) -> bool {
+        matches!(
+            self,
+            SyntaxKind::Newline | SyntaxKind::Whitespace | SyntaxKind::Comment
+        )
+    }
+
+    fn is_comment(self) -> bool {
+        self == SyntaxKind::Comment
+    }
+
+    fn is_newline(self) -> bool {
+        self == SyntaxKind::Newline
+    }
+}
+
+/// A build script held as a lossless syntax tree, for tools that rewrite
+/// build files.
+///
+/// ```rust
+/// use uniparse_gradle::GradleBuild;
+///
+/// let mut build = GradleBuild::parse("dependencies {\n    api 'g:a:1'\n}\n").unwrap();
+/// build.add_dependency("implementation", "g:b:2");
+/// assert_eq!(
+///     build.to_string(),
+///     "dependencies {\n    api 'g:a:1'\n    implementation 'g:b:2'\n}\n"
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct GradleBuild {
+    pub(crate) root: SyntaxNode<SyntaxKind>,
+}
+
+impl GradleBuild {
+    /// Parses `source`. Printing the result gives back `source` unchanged.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(len = source.len()), err)
+    )]
+    pub fn parse(source: &str) -> Result<Self, ParseError> {
+        let script = Script::parse(source)?;
+        let mut tree = TreeBuilder {
+            source,
+            leaves: leaves(source)?,
+            i: 0,
+            builder: GreenNodeBuilder::new(),
+        };
+        tree.builder.start_node(SyntaxKind::Script);
+        tree.statements(&script.statements);
+        tree.leaves_until(source.len());
+        tree.builder.finish_node();
+        Ok(GradleBuild {
+            root: SyntaxNode::new_root(tree.builder.finish()),
+        })
+    }
+
+    pub fn syntax(&self) -> &SyntaxNode<SyntaxKind> {
+        &self.root
+    }
+
+    /// Adds `scope "coordinate"` to the top-level `dependencies` block,
+    /// after the last entry with the same scope or else at the end of the
+    /// block. A `dependencies` block is appended to the file if there is
+    /// none.
+    ///
+    /// The new line copies the indentation of its neighbours, and the call
+    /// style (`scope "x"` or `scope("x")`) and quotes of the existing
+    /// entries. Nothing else in the file changes.
+    pub fn add_dependency(&mut self, scope: &str, coordinate: &str) {
+        let blocks: Vec<_> = self
+            .root
+            .children()
+            .filter(|s| call_name(s).as_deref() == Some("dependencies"))
+            .filter_map(|s| s.children().find(|n| n.kind() == SyntaxKind::Block))
+            .collect();
+        let has_scope = |block: &SyntaxNode<SyntaxKind>| {
+            block
+                .children()
+                .any(|e| call_name(&e).as_deref() == Some(scope))
+        };
+        let Some(block) = blocks.iter().find(|b| has_scope(b)).or(blocks.last()) else {
+            self.append_dependencies_block(scope, coordinate);
+            return;
+        };
+
+        let entries: Vec<_> = block.children().collect();
+        let anchor = entries
+            .iter()
+            .rev()
+            .find(|e| call_name(e).as_deref() == Some(scope))
+            .or(entries.last());
+        let style = Style::detect(anchor.into_iter().cloned().chain(self.root.descendants()));
+        let entry = style.entry(scope, coordinate);
+
+        let outer = block
+            .parent()
+            .and_then(|s| indent_before(&s))
+            .unwrap_or_default();
+        let indent = anchor
+            .and_then(indent_before)
+            .unwrap_or_else(|| outer.clone() + &self.indent_unit());
+
+        let green = match anchor {
+            Some(anchor) => {
+                let at = anchor.index() + 1;
+                block.splice_children(at..at, [newline(), whitespace(&indent), entry])
+            }
+            None => {
+                let children = block.children_with_tokens();
+                let multiline = children.iter().any(|c| c.kind() == SyntaxKind::Newline);
+                if multiline {
+                    block.splice_children(1..1, [newline(), whitespace(&indent), entry])
+                } else {
+                    // `{}` or `{ }`: put the closing brace on its own line
+                    block.splice_children(
+                        1..children.len() - 1,
+                        [
+                            newline(),
+                            whitespace(&indent),
+                            entry,
+                            newline(),
+                            whitespace(&outer),
+                        ],
+                    )
+                }
+            }
+        };
+        self.root = SyntaxNode::new_root(green);
+    }
+
+    fn append_dependencies_block(&mut self, scope: &str, coordinate: &str) {
+        let text = self.root.to_string();
+        let mut elements = Vec::new();
+        if !text.is_empty() && !text.ends_with("\n\n") {
+            if !text.ends_with('\n') {
+                elements.push(newline());
+            }
+            elements.push(newline());
+        }
+
+        let style = Style::detect(self.root.descendants());
+        let block = GreenNode::new(
+            SyntaxKind::Block,
+            vec![
+                token(SyntaxKind::OpenBrace, "{"),
+                newline(),
+                whitespace(&self.indent_unit()),
+                style.entry(scope, coordinate),
+                newline(),
+                token(SyntaxKind::CloseBrace, "}"),
+            ],
+        );
+        elements.push(
+            GreenNode::new(
+                SyntaxKind::Statement,
+                vec![
+                    token(SyntaxKind::Identifier, "dependencies"),
+                    whitespace(" "),
+                    block.into(),
+                ],
+            )
+            .into(),
+        );
+        elements.push(newline());
+
+        let end = self.root.green().children().len();
+        self.root = SyntaxNode::new_root(self.root.splice_children(end..end, elements));
+    }
+
+    /// One level of indentation as used in the file: a tab, or the
+    /// narrowest indentation of any line. Four spaces if nothing is
+    /// indented.
+    fn indent_unit(&self) -> String {
+        let mut tokens = self.root.tokens().peekable();
+        let mut unit: Option<String> = None;
+        while let Some(t) = tokens.next() {
+            if t.kind() != SyntaxKind::Newline {
+                continue;
+            }
+            if let Some(next) = tokens.peek()
+                && next.kind() == SyntaxKind::Whitespace
+                && unit.as_ref().is_none_or(|u| next.text().len() < u.len())
+            {
+                if next.text().starts_with('\t') {
+                    return "\t".into();
+                }
+                unit = Some(next.text().into());
+            }
+        }
+        unit.unwrap_or_else(|| "    ".into())
+    }
+}
+
+impl Display for GradleBuild {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.root.fmt(f)
+    }
+}
+
+/// How dependency declarations are written in a file.
+struct Style {
+    parenthesized: bool,
+    quote: char,
+}
+
+impl Style {
+    /// The style of the first statement in `samples` shaped like
+    /// `name "x"` or `name("x")`, or Groovy with double quotes.
+    fn detect(samples: impl Iterator<Item = SyntaxNode<SyntaxKind>>) -> Self {
+        for node in samples.filter(|n| n.kind() == SyntaxKind::Statement) {
+            let tokens: Vec<_> = node.tokens().filter(|t| !t.kind().is_trivia()).collect();
+            let (parenthesized, string) = match tokens.as_slice() {
+                [name, string, ..]
+                    if name.kind() == SyntaxKind::Identifier
+                        && string.kind() == SyntaxKind::String =>
+                {
+                    (false, string)
+                }
+                [name, open, string, ..]
+                    if name.kind() == SyntaxKind::Identifier
+                        && open.kind() == SyntaxKind::OpenParen
+                        && string.kind() == SyntaxKind::String =>
+                {
+                    (true, string)
+                }
+                _ => continue,
+            };
+            return Style {
+                parenthesized,
+                quote: string.text().chars().next().unwrap_or('"'),
+            };
+        }
+        Style {
+            parenthesized: false,
+            quote: '"',
+        }
+    }
+
+    fn entry(&self, scope: &str, coordinate: &str) -> GreenElement<SyntaxKind> {
+        let string = token(SyntaxKind::String, &quote(coordinate, self.quote));
+        let mut children = vec![token(SyntaxKind::Identifier, scope)];
+        if self.parenthesized {
+            children.extend([
+                token(SyntaxKind::OpenParen, "("),
+                string,
+                token(SyntaxKind::CloseParen, ")"),
+            ]);
+        } else {
+            children.extend([whitespace(" "), string]);
+        }
+        GreenNode::new(SyntaxKind::Statement, children).into()
+    }
+}
+
+/// The name a statement starts with, e.g. `implementation` or
+/// `dependencies`.
+fn call_name(statement: &SyntaxNode<SyntaxKind>) -> Option<String> {
+    if statement.kind() != SyntaxKind::Statement {
+        return None;
+    }
+    statement
+        .tokens()
+        .find(|t| !t.kind().is_trivia())
+        .filter(|t| t.kind() == SyntaxKind::Identifier)
+        .map(|t| t.text().to_string())
+}
+
+/// The indentation of the line `node` starts, if it starts the line.
+fn indent_before(node: &SyntaxNode<SyntaxKind>) -> Option<String> {
+    let Some(parent) = node.parent() else {
+        return Some(String::new());
+    };
+    let siblings = parent.children_with_tokens();
+    let index = node.index();
+    let starts_line = |i: usize| i == 0 || siblings[i - 1].kind() == SyntaxKind::Newline;
+    match index.checked_sub(1).map(|i| &siblings[i]) {
+        None => Some(String::new()),
+        Some(SyntaxElement::Token(t)) if t.kind() == SyntaxKind::Newline => Some(String::new()),
+        Some(SyntaxElement::Token(t))
+            if t.kind() == SyntaxKind::Whitespace && starts_line(index - 1) =>
+        {
+            Some(t.text().to_string())
+        }
+        _ => None,
+    }
+}
+
+fn token(kind: SyntaxKind, text: &str) -> GreenElement<SyntaxKind> {
+    GreenToken::new(kind, text).into()
+}
+
+fn newline() -> GreenElement<SyntaxKind> {
+    token(SyntaxKind::Newline, "\n")
+}
+
+fn whitespace(text: &str) -> GreenElement<SyntaxKind> {
+    token(SyntaxKind::Whitespace, text)
+}
+
+type Leaf = (SyntaxKind, Range<usize>);
+
+/// Every token of `source` in order, trivia included.
+fn leaves(source: &str) -> Result<Vec<Leaf>, ParseError> {
+    let (tokens, _) = lex(source)?;
+    let mut leaves = Vec::new();
+    let mut pos = 0;
+    // separators are re-read from the gaps, where each line break is kept
+    for t in tokens.iter().filter(|t| t.token != Token::Newline) {
+        let range = t.span.byte_range.clone();
+        trivia(source, pos..range.start, &mut leaves);
+        let kind = match t.token {
+            Token::Identifier(_) => SyntaxKind::Identifier,
+            Token::String(_) => SyntaxKind::String,
+            Token::Bool(_) => SyntaxKind::Bool,
+            Token::Number(_) => SyntaxKind::Number,
+            Token::Equals => SyntaxKind::Equals,
+            Token::OpenBrace => SyntaxKind::OpenBrace,
+            Token::CloseBrace => SyntaxKind::CloseBrace,
+            Token::OpenParen => SyntaxKind::OpenParen,
+            Token::CloseParen => SyntaxKind::CloseParen,
+            Token::Comma => SyntaxKind::Comma,
+            Token::Plus => SyntaxKind::Plus,
+            Token::Elvis => SyntaxKind::Elvis,
+            Token::Colon => SyntaxKind::Colon,
+            Token::Condition(_) => SyntaxKind::Condition,
+            Token::Newline => unreachable!("separators are filtered out"),
+        };
+        pos = range.end;
+        leaves.push((kind, range));
+    }
+    trivia(source, pos..source.len(), &mut leaves);
+    Ok(leaves)
+}
+
+/// Splits a gap between tokens into whitespace, line breaks, `;` and
+/// comments.
+fn trivia(source: &str, range: Range<usize>, leaves: &mut Vec<Leaf>) {
+    let mut pos = range.start;
+    while pos < range.end {
+        let rest = &source[pos..range.end];
+        let (kind, len) = if rest.starts_with("//") {
+            (SyntaxKind::Comment, rest.find('\n').unwrap_or(rest.len()))
+        } else if let Some(body) = rest.strip_prefix("/*") {
+            let len = body.find("*/").map_or(rest.len(), |i| i + 4);
+            (SyntaxKind::Comment, len)
+        } else if rest.starts_with('\n') {
+            (SyntaxKind::Newline, 1)
+        } else if rest.starts_with(';') {
+            (SyntaxKind::Semicolon, 1)
+        } else {
+            let len = rest
+                .find(|c: char| c == '\n' || !c.is_whitespace())
+                .unwrap_or(rest.len());
+            let first = rest.chars().next().map_or(1, char::len_utf8);
+            (SyntaxKind::Whitespace, len.max(first))
+        };
+        leaves.push((kind, pos..pos + len));
+        pos += len;
+    }
+}
+
+struct TreeBuilder<'a> {
+    source: &'a str,
+    leaves: Vec<Leaf>,
+    /// The next leaf to add.
+    i: usize,
+    builder: GreenNodeBuilder<SyntaxKind>,
+}
+
+impl TreeBuilder<'_> {
+    fn leaf(&mut self) {
+        let (kind, range) = &self.leaves[self.i];
+        self.builder.token(*kind, &self.source[range.clone()]);
+        self.i += 1;
+    }
+
+    fn kind(&self, i: usize) -> Option<SyntaxKind> {
+        self.leaves.get(i).map(|(kind, _)| *kind)
+    }
+
+    /// Adds the leaves starting before `offset`.
+    fn leaves_until(&mut self, offset: usize) {
+        while self
+            .leaves
+            .get(self.i)
+            .is_some_and(|(_, r)| r.start < offset)
+        {
+            self.leaf();
+        }
+    }
+
+    fn statements(&mut self, statements: &[Statement]) {
+        for statement in statements {
+            let range = &statement.span.byte_range;
+            let first = self.i
+                + self.leaves[self.i..]
+                    .iter()
+                    .position(|(_, r)| r.start >= range.start)
+                    .unwrap_or(self.leaves.len() - self.i);
+            let start = comments_above(|j| self.kind(j), self.i, first);
+            while self.i < start {
+                self.leaf();
+            }
+
+            self.builder.start_node(SyntaxKind::Statement);
+            for block in closures(statement) {
+                self.leaves_until(block.span.byte_range.start);
+                self.builder.start_node(SyntaxKind::Block);
+                self.leaf();
+                self.statements(&block.statements);
+                self.leaves_until(block.span.byte_range.end - 1);
+                self.leaf();
+                self.builder.finish_node();
+            }
+            self.leaves_until(range.end);
+
+            // a comment at the end of the line
+            let comment = match self.kind(self.i) {
+                Some(SyntaxKind::Whitespace) => self.i + 1,
+                _ => self.i,
+            };
+            if self.kind(comment) == Some(SyntaxKind::Comment) {
+                while self.i <= comment {
+                    self.leaf();
+                }
+            }
+            self.builder.finish_node();
+        }
+    }
+}
+
+/// The closures of a statement's call and of the calls chained on it.
+fn closures(statement: &Statement) -> Vec<&Block> {
+    let mut blocks = Vec::new();
+    if let StatementKind::Call(call) = &statement.kind {
+        let mut next = Some(call);
+        while let Some(call) = next {
+            blocks.extend(&call.closure);
+            next = call.chained.as_deref();
+        }
+    }
+    blocks
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::DSLBlock;
+
+    const BUILD: &str = r#"plugins {
+    id 'java'
+}
+
+// libraries
+dependencies {
+    implementation 'com.google.guava:guava:33.0.0-jre' // pinned
+
+    // tests
+    testImplementation 'junit:junit:4.13.2'
+}
+"#;
+
+    #[test]
+    fn test_round_trip_and_comment_attachment() {
+        let build = GradleBuild::parse(BUILD).unwrap();
+        assert_eq!(build.to_string(), BUILD);
+
+        let statements: Vec<_> = build.syntax().children().collect();
+        assert_eq!(statements.len(), 2);
+        assert!(
+            statements[1]
+                .to_string()
+                .starts_with("// libraries\ndependencies {")
+        );
+
+        let block = statements[1].children().next().unwrap();
+        let entries: Vec<_> = block.children().map(|e| e.to_string()).collect();
+        assert_eq!(
+            entries,
+            [
+                "implementation 'com.google.guava:guava:33.0.0-jre' // pinned",
+                "// tests\n    testImplementation 'junit:junit:4.13.2'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_add_dependency_after_same_scope() {
+        let mut build = GradleBuild::parse(BUILD).unwrap();
+        build.add_dependency("implementation", "com.squareup.okhttp3:okhttp:4.12.0");
+        build.add_dependency("testImplementation", "org.mockito:mockito-core:5.11.0");
+        assert_eq!(
+            build.to_string(),
+            BUILD
+                .replace(
+                    "// pinned\n",
+                    "// pinned\n    implementation 'com.squareup.okhttp3:okhttp:4.12.0'\n"
+                )
+                .replace(
+                    "4.13.2'\n",
+                    "4.13.2'\n    testImplementation 'org.mockito:mockito-core:5.11.0'\n"
+                )
+        );
+
+        // unknown scopes go last
+        build.add_dependency("runtimeOnly", "org.postgresql:postgresql:42.7.3");
+        assert!(
+            build
+                .to_string()
+                .ends_with("5.11.0'\n    runtimeOnly 'org.postgresql:postgresql:42.7.3'\n}\n")
+        );
+    }
+
+    #[test]
+    fn test_add_dependency_kotlin_style_and_empty_blocks() {
+        let mut build =
+            GradleBuild::parse("plugins {\n\tkotlin(\"jvm\")\n}\n\ndependencies {}\n").unwrap();
+        build.add_dependency("implementation", "g:a:1");
+        assert_eq!(
+            build.to_string(),
+            "plugins {\n\tkotlin(\"jvm\")\n}\n\ndependencies {\n\timplementation(\"g:a:1\")\n}\n"
+        );
+
+        // nested `dependencies` blocks are left alone
+        let mut build = GradleBuild::parse("buildscript {\n  dependencies {\n  }\n}").unwrap();
+        build.add_dependency("api", "g:a:1");
+        assert_eq!(
+            build.to_string(),
+            "buildscript {\n  dependencies {\n  }\n}\n\ndependencies {\n  api \"g:a:1\"\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_add_dependency_escapes_quotes() {
+        let mut build =
+            GradleBuild::parse("dependencies {\n    implementation 'g:a:1'\n}\n").unwrap();
+        build.add_dependency("implementation", "g:it's:1");
+        build.add_dependency("implementation", "g:back\\slash:1");
+        let text = build.to_string();
+        assert!(
+            text.contains(
+                "    implementation 'g:it\\'s:1'\n    implementation 'g:back\\\\slash:1'\n"
+            )
+        );
+
+        let block = DSLBlock::parse_str(&text).unwrap();
+        let dependencies = block
+            .get(&["dependencies"])
+            .and_then(|d| d.as_block())
+            .unwrap();
+        let values: Vec<_> = dependencies
+            .entries
+            .get_all("implementation")
+            .filter_map(|v| v.as_str())
+            .collect();
+        assert_eq!(values, ["g:a:1", "g:it's:1", "g:back\\slash:1"]);
+    }
+}
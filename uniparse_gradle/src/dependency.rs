@@ -0,0 +1,430 @@
+use crate::catalog::{CatalogEntry, VersionCatalog};
+use crate::format::DependencyStyle;
+use crate::model::{DSLBlock, DSLValue};
+use crate::properties::interpolate;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use uniparse_core::normalize_dependency_id;
+
+/// Maven coordinates in `group:name:version` form.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Coordinates {
+    pub group: String,
+    pub name: String,
+    pub version: Option<String>,
+}
+
+impl Coordinates {
+    /// Parses string notation such as `org.example:lib:1.2.3` or
+    /// `org.example:lib`. Classifiers and `@ext` suffixes are dropped.
+    pub fn parse(notation: &str) -> Option<Self> {
+        let notation = notation.split('@').next()?;
+        let mut parts = notation.split(':');
+        let group = parts.next()?.trim();
+        let name = parts.next()?.trim();
+        if group.is_empty() || name.is_empty() {
+            return None;
+        }
+
+        Some(Coordinates {
+            group: group.to_string(),
+            name: name.to_string(),
+            version: parts
+                .next()
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(str::to_string),
+        })
+    }
+
+    /// `group:name` in canonical form, without the version, for matching
+    /// against dependencies of other manifests.
+    pub fn dependency_id(&self) -> String {
+        normalize_dependency_id(&format!("{}:{}", self.group, self.name))
+    }
+}
+
+impl Display for Coordinates {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.group, self.name)?;
+        if let Some(version) = &self.version {
+            write!(f, ":{}", version)?;
+        }
+        Ok(())
+    }
+}
+
+/// How a dependency was written in the build script.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum DependencyNotation {
+    /// A string literal, e.g. `"org.example:lib:1.2.3"`.
+    Literal(String),
+    /// A catalog accessor or variable, e.g. `libs.retrofit` or `deps.okhttp`.
+    Reference(String),
+}
+
+/// A single entry of a `dependencies { }` block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Dependency {
+    /// Configuration name, e.g. `implementation`.
+    pub configuration: String,
+    pub notation: DependencyNotation,
+    /// For a declaration inside `if (...) { }`, the condition as written;
+    /// see [`DSLValue::Conditional`].
+    pub condition: Option<String>,
+}
+
+impl Dependency {
+    /// Resolves the dependency to concrete coordinates; see
+    /// [`Resolver::resolve`].
+    pub fn resolve(&self, resolver: &Resolver) -> Option<Coordinates> {
+        resolver.resolve(&self.notation)
+    }
+}
+
+/// What references in dependency declarations can be resolved against.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Resolver {
+    /// Version catalogs, matched by their accessor name.
+    pub catalogs: Vec<VersionCatalog>,
+    /// Extra properties keyed by the reference as written, e.g.
+    /// `deps.okhttp` → `com.squareup.okhttp3:okhttp:4.12.0`.
+    pub ext: HashMap<String, String>,
+    /// Plugin versions pinned in settings' `pluginManagement { plugins { } }`,
+    /// keyed by plugin id.
+    pub plugin_versions: HashMap<String, String>,
+    /// Project properties, e.g. from [`GradleProperties`](crate::GradleProperties),
+    /// substituted for `$name` / `${name}` in literals.
+    pub properties: HashMap<String, String>,
+}
+
+impl Resolver {
+    /// Turns a notation into coordinates.
+    ///
+    /// Literals are parsed once `properties` are substituted into them.
+    /// References are looked up in the catalogs first, then in `ext`.
+    /// Bundles, plugin and version accessors don't name a single library
+    /// and resolve to `None`.
+    pub fn resolve(&self, notation: &DependencyNotation) -> Option<Coordinates> {
+        match notation {
+            DependencyNotation::Literal(s) => Coordinates::parse(&interpolate(s, &self.properties)),
+            DependencyNotation::Reference(name) => {
+                let from_catalog = self.catalogs.iter().find_map(|c| c.lookup(name));
+                match from_catalog {
+                    Some(CatalogEntry::Library(coords)) => Some(coords.clone()),
+                    Some(_) => None,
+                    None => self.ext.get(name).and_then(|s| Coordinates::parse(s)),
+                }
+            }
+        }
+    }
+}
+
+impl DSLBlock {
+    /// Entries of the top-level `dependencies { }` block, sorted by
+    /// configuration.
+    ///
+    /// Build-time dependencies declared under `buildscript { }` are not
+    /// included; see [`DSLBlock::buildscript_classpath`]. Map notation
+    /// (`group: 'g', name: 'a'`) is read as the equivalent string literal;
+    /// other values that are neither literals nor references (e.g. nested
+    /// closures) are skipped.
+    pub fn dependencies(&self) -> Vec<Dependency> {
+        self.dependencies_at(&["dependencies"])
+    }
+
+    /// The `classpath` entries of `buildscript { dependencies { } }`, i.e.
+    /// the plugins and libraries the build script itself runs with.
+    pub fn buildscript_classpath(&self) -> Vec<Dependency> {
+        let mut deps = self.dependencies_at(&["buildscript", "dependencies"]);
+        deps.retain(|d| d.configuration == "classpath");
+        deps
+    }
+
+    /// Dependencies of every block at `path`; a script may open the same
+    /// `dependencies { }` block more than once. Declarations under `if`
+    /// branches, inside the block or around it, are included with their
+    /// condition.
+    fn dependencies_at(&self, path: &[&str]) -> Vec<Dependency> {
+        let mut blocks = Vec::new();
+        blocks_at(self, path, None, &mut blocks);
+        let mut entries = Vec::new();
+        for (block, condition) in blocks {
+            conditional_entries(block, condition, &mut entries);
+        }
+
+        let mut deps: Vec<Dependency> = entries
+            .into_iter()
+            .filter_map(|(configuration, value, condition)| {
+                // `implementation("g:a:v")` is the same as `implementation "g:a:v"`
+                let value = match value {
+                    DSLValue::FunctionCall(args) if args.len() == 1 => &args[0],
+                    other => other,
+                };
+                let notation = match value {
+                    DSLValue::String(s) => DependencyNotation::Literal(s.clone()),
+                    DSLValue::Reference(r) => DependencyNotation::Reference(r.clone()),
+                    // `group: 'g', name: 'a', version: 'v'`
+                    DSLValue::MultiArgs(_) => match DependencyStyle::String.apply(value)? {
+                        DSLValue::String(s) => DependencyNotation::Literal(s),
+                        _ => return None,
+                    },
+                    _ => return None,
+                };
+                Some(Dependency {
+                    configuration: configuration.clone(),
+                    notation,
+                    condition,
+                })
+            })
+            .collect();
+        // stable, so declarations keep their order within a configuration
+        deps.sort_by(|a, b| a.configuration.cmp(&b.configuration));
+        deps
+    }
+}
+
+/// Both conditions must hold.
+fn and(outer: Option<&str>, inner: &str) -> String {
+    match outer {
+        Some(outer) => format!("({}) && ({})", outer, inner),
+        None => inner.to_string(),
+    }
+}
+
+/// The blocks at `path` below `block`, looking through `if` branches, each
+/// with the condition it is under.
+fn blocks_at<'a>(
+    block: &'a DSLBlock,
+    path: &[&str],
+    condition: Option<&str>,
+    out: &mut Vec<(&'a DSLBlock, Option<String>)>,
+) {
+    let Some((first, rest)) = path.split_first() else {
+        out.push((block, condition.map(str::to_string)));
+        return;
+    };
+    for (key, value) in &block.entries {
+        match value {
+            DSLValue::Block(nested) if key == first => blocks_at(nested, rest, condition, out),
+            DSLValue::Conditional {
+                condition: inner,
+                block: nested,
+            } => blocks_at(nested, path, Some(&and(condition, inner)), out),
+            _ => {}
+        }
+    }
+}
+
+/// The entries of `block`, with those of its `if` branches flattened in
+/// place, each with the condition it is under.
+fn conditional_entries<'a>(
+    block: &'a DSLBlock,
+    condition: Option<String>,
+    out: &mut Vec<(&'a String, &'a DSLValue, Option<String>)>,
+) {
+    for (key, value) in &block.entries {
+        match value {
+            DSLValue::Conditional {
+                condition: inner,
+                block: nested,
+            } => conditional_entries(nested, Some(and(condition.as_deref(), inner)), out),
+            _ => out.push((key, value, condition.clone())),
+        }
+    }
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_references_are_typed_and_resolvable() {
+        let build = DSLBlock::parse_str(
+            r#"
+            dependencies {
+                implementation libs.retrofit
+                api deps.okhttp
+                testImplementation "junit:junit:4.13"
+                compileOnly unknown.thing
+                runtimeOnly("org.slf4j:slf4j-simple:2.0.9")
+            }
+            "#,
+        )
+        .unwrap();
+
+        let deps = build.dependencies();
+        assert_eq!(deps.len(), 5);
+        assert_eq!(
+            deps[0],
+            Dependency {
+                configuration: "api".into(),
+                notation: DependencyNotation::Reference("deps.okhttp".into()),
+                condition: None,
+            }
+        );
+
+        let catalog = VersionCatalog::parse_toml(
+            "libs",
+            "[libraries]\nretrofit = \"com.squareup.retrofit2:retrofit:2.9.0\"\n",
+        )
+        .unwrap();
+        let mut resolver = Resolver {
+            catalogs: vec![catalog],
+            ..Resolver::default()
+        };
+        resolver.ext.insert(
+            "deps.okhttp".into(),
+            "com.squareup.okhttp3:okhttp:4.12.0".into(),
+        );
+
+        let resolved: Vec<_> = deps
+            .iter()
+            .map(|d| d.resolve(&resolver).map(|c| c.to_string()))
+            .collect();
+        assert_eq!(
+            resolved,
+            vec![
+                Some("com.squareup.okhttp3:okhttp:4.12.0".to_string()),
+                None,
+                Some("com.squareup.retrofit2:retrofit:2.9.0".to_string()),
+                Some("org.slf4j:slf4j-simple:2.0.9".to_string()),
+                Some("junit:junit:4.13".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_buildscript_classpath_is_kept_apart() {
+        let build = DSLBlock::parse_str(
+            r#"
+            buildscript {
+                repositories {
+                    mavenCentral()
+                }
+                dependencies {
+                    classpath "com.android.tools.build:gradle:8.2.0"
+                }
+            }
+            dependencies {
+                implementation "org.example:lib:1.0"
+            }
+            "#,
+        )
+        .unwrap();
+
+        let classpath = build.buildscript_classpath();
+        assert_eq!(classpath.len(), 1);
+        assert_eq!(
+            classpath[0].resolve(&Resolver::default()),
+            Coordinates::parse("com.android.tools.build:gradle:8.2.0")
+        );
+
+        let deps = build.dependencies();
+        assert_eq!(deps.len(), 1);
+        assert_eq!(deps[0].configuration, "implementation");
+        assert!(
+            DSLBlock::parse_str("")
+                .unwrap()
+                .buildscript_classpath()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_literals_use_properties() {
+        let build = DSLBlock::parse_str(
+            "dependencies {\n    implementation \"com.squareup.okhttp3:okhttp:$okhttpVersion\"\n}",
+        )
+        .unwrap();
+        let resolver = Resolver {
+            properties: HashMap::from([("okhttpVersion".into(), "4.12.0".into())]),
+            ..Resolver::default()
+        };
+
+        assert_eq!(
+            build.dependencies()[0]
+                .resolve(&resolver)
+                .unwrap()
+                .to_string(),
+            "com.squareup.okhttp3:okhttp:4.12.0"
+        );
+    }
+
+    #[test]
+    fn test_coordinates_parse() {
+        let c = Coordinates::parse("org.example:lib:1.2.3:sources@jar").unwrap();
+        assert_eq!(c.to_string(), "org.example:lib:1.2.3");
+        assert_eq!(Coordinates::parse("g:a").unwrap().version, None);
+        assert!(Coordinates::parse(":simple-jar").is_none());
+        assert_eq!(c.dependency_id(), "org.example:lib");
+    }
+
+    #[test]
+    fn test_if_guarded_dependencies_keep_their_condition() {
+        let build = DSLBlock::parse_str(
+            r#"
+            dependencies {
+                implementation "org.example:core:1.0"
+                if (System.getenv("CI") != null) {
+                    testImplementation "org.example:ci-reporter:1.0"
+                } else if (isRelease) {
+                    implementation "org.example:release:1.0"
+                } else {
+                    implementation "org.example:dev:1.0"
+                }
+            }
+            if (enableMetrics) {
+                dependencies {
+                    runtimeOnly "io.micrometer:micrometer-core:1.12.0"
+                }
+            }
+            "#,
+        )
+        .unwrap();
+
+        let deps: Vec<_> = build
+            .dependencies()
+            .into_iter()
+            .map(|d| match d.notation {
+                DependencyNotation::Literal(s) => (s, d.condition),
+                other => panic!("Expected a literal, got {:?}", other),
+            })
+            .collect();
+        let ci = r#"System.getenv("CI") != null"#;
+        assert_eq!(
+            deps,
+            [
+                ("org.example:core:1.0".to_string(), None),
+                (
+                    "org.example:release:1.0".to_string(),
+                    Some(format!("!({}) && (isRelease)", ci))
+                ),
+                (
+                    "org.example:dev:1.0".to_string(),
+                    Some(format!("!({}) && !(isRelease)", ci))
+                ),
+                (
+                    "io.micrometer:micrometer-core:1.12.0".to_string(),
+                    Some("enableMetrics".to_string())
+                ),
+                (
+                    "org.example:ci-reporter:1.0".to_string(),
+                    Some(ci.to_string())
+                ),
+            ]
+        );
+
+        let printed = build.to_string();
+        assert!(printed.contains("if (enableMetrics) {\n    dependencies {"));
+        assert_eq!(
+            DSLBlock::parse_str(&printed).unwrap().dependencies().len(),
+            5
+        );
+    }
+}
@@ -0,0 +1,176 @@
+use std::fmt::{self, Display, Formatter};
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::analysis::mask_source;
+
+/// The language a Gradle script is written in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize)]
+pub enum GradleDialect {
+    /// `build.gradle`
+    #[default]
+    Groovy,
+    /// `build.gradle.kts`
+    Kotlin,
+}
+
+impl GradleDialect {
+    /// The dialect a file name implies: `.gradle.kts` for Kotlin, `.gradle`
+    /// for Groovy, and `None` for anything else.
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        let name = path.as_ref().file_name()?.to_str()?;
+        if name.ends_with(".gradle.kts") {
+            Some(GradleDialect::Kotlin)
+        } else if name.ends_with(".gradle") {
+            Some(GradleDialect::Groovy)
+        } else {
+            None
+        }
+    }
+}
+
+impl Display for GradleDialect {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            GradleDialect::Groovy => write!(f, "Groovy DSL"),
+            GradleDialect::Kotlin => write!(f, "Kotlin DSL"),
+        }
+    }
+}
+
+/// Guesses the dialect of a script from its syntax, for sources without a
+/// file name, such as stdin or blobs fetched from an API. Prefer
+/// [`GradleDialect::from_path`] when the name is known.
+///
+/// Each line is scored. Groovy is suggested by single-quoted strings,
+/// `def`, calls without parentheses (`id 'java'`) and named arguments
+/// (`apply plugin: 'java'`). Kotlin is suggested by `val`/`var`,
+/// backquoted plugin ids, `::`, type arguments (`register<Copy>(...)`),
+/// `id("...")` style calls and `=` assignments. String templates count a
+/// little towards Kotlin, since Groovy has them too. Comments are ignored,
+/// and a tie, e.g. an empty script, is Groovy.
+///
+/// ```rust
+/// use uniparse_gradle::{GradleDialect, detect_dialect};
+///
+/// assert_eq!(detect_dialect("plugins {\n    id 'java'\n}\n"), GradleDialect::Groovy);
+/// assert_eq!(detect_dialect("plugins {\n    id(\"java\")\n}\nval v = \"1.0\"\n"), GradleDialect::Kotlin);
+/// ```
+pub fn detect_dialect(src: &str) -> GradleDialect {
+    let masked = mask_source(src);
+    let (mut groovy, mut kotlin) = (0, 0);
+
+    for (line, code) in src.lines().zip(masked.lines()) {
+        let code = code.trim();
+        let word_len = code
+            .find(|c: char| !c.is_alphanumeric() && c != '_' && c != '.')
+            .unwrap_or(code.len());
+        let (word, rest) = (&code[..word_len], code[word_len..].trim_start());
+
+        match word {
+            "val" | "var" => kotlin += 3,
+            "def" => groovy += 3,
+            _ => {}
+        }
+        if !word.is_empty() && !matches!(word, "val" | "var" | "def" | "return") {
+            if rest.starts_with(['\'', '"']) {
+                groovy += 2;
+            } else if rest.starts_with('=') && !rest.starts_with("==") {
+                kotlin += 1;
+            }
+        }
+        // `apply plugin: 'java'`, `group: 'g', name: 'a'`
+        if rest.contains(": '") || rest.contains(": \"") {
+            groovy += 2;
+        }
+        if code.contains('`') || code.contains("::") || has_type_arguments(code) {
+            kotlin += 3;
+        }
+        if code.contains("(\"") {
+            kotlin += 1;
+        }
+        // Kotlin only has one-character `'c'` literals
+        groovy += 2 * code
+            .split('\'')
+            .skip(1)
+            .step_by(2)
+            .filter(|s| s.len() > 1)
+            .count();
+        if line.contains("\"$") || line.contains("${") {
+            kotlin += 1;
+        }
+    }
+
+    if kotlin > groovy {
+        GradleDialect::Kotlin
+    } else {
+        GradleDialect::Groovy
+    }
+}
+
+/// Whether `code` has a call with type arguments, like `register<Copy>(`
+/// or `withType<Test> {`.
+fn has_type_arguments(code: &str) -> bool {
+    code.match_indices('<').any(|(i, _)| {
+        let before = code[..i].chars().next_back();
+        let inner = &code[i + 1..];
+        let Some(end) = inner.find('>') else {
+            return false;
+        };
+        let after = inner[end + 1..].trim_start();
+        before.is_some_and(char::is_alphanumeric)
+            && inner[..end]
+                .chars()
+                .all(|c| c.is_alphanumeric() || "_.<, ".contains(c))
+            && after.starts_with(['(', '{'])
+    })
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_dialect() {
+        let groovy = [
+            "apply plugin: 'java'\n",
+            "dependencies {\n    implementation \"com.google.guava:guava:33.0-jre\"\n}\n",
+            "def ver = \"1.0\"\nversion = ver\n",
+            "// val x = \"kotlin in a comment\"\nplugins {\n    id 'application'\n}\n",
+            "",
+        ];
+        for src in groovy {
+            assert_eq!(detect_dialect(src), GradleDialect::Groovy, "{src}");
+        }
+
+        let kotlin = [
+            "plugins {\n    `java-library`\n}\n",
+            "val kotlinVersion = \"1.9.0\"\n",
+            "tasks.register<Copy>(\"copyDocs\") {\n    from(\"docs\")\n}\n",
+            "dependencies {\n    implementation(\"com.squareup.okhttp3:okhttp:4.12.0\")\n}\nversion = \"1.0\"\n",
+            "tasks.withType<Test> {\n    useJUnitPlatform()\n}\n",
+        ];
+        for src in kotlin {
+            assert_eq!(detect_dialect(src), GradleDialect::Kotlin, "{src}");
+        }
+    }
+
+    #[test]
+    fn test_dialect_from_path() {
+        assert_eq!(
+            GradleDialect::from_path("app/build.gradle.kts"),
+            Some(GradleDialect::Kotlin)
+        );
+        assert_eq!(
+            GradleDialect::from_path("settings.gradle"),
+            Some(GradleDialect::Groovy)
+        );
+        assert_eq!(GradleDialect::from_path("go.mod"), None);
+        assert_eq!(GradleDialect::Kotlin.to_string(), "Kotlin DSL");
+    }
+}
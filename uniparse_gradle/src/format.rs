@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+
+use crate::model::{DSLBlock, DSLValue};
+use crate::parser::write_block;
+
+/// Options for [`DSLBlock::to_string_with`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// How entries of `dependencies { }` blocks are written.
+    pub dependency_style: DependencyStyle,
+}
+
+/// Notation used for dependencies on output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DependencyStyle {
+    /// Write each dependency the way it was parsed.
+    #[default]
+    Preserve,
+    /// `implementation "g:a:v"`
+    String,
+    /// `implementation group: "g", name: "a", version: "v"`
+    Map,
+}
+
+impl DependencyStyle {
+    /// The value rewritten in this style, or `None` if it is already in it
+    /// or can't be converted without losing information (classifiers,
+    /// `@ext` suffixes, extra named arguments, non-literal parts).
+    pub(crate) fn apply(self, value: &DSLValue) -> Option<DSLValue> {
+        match (self, value) {
+            (DependencyStyle::Preserve, _) => None,
+            (_, DSLValue::FunctionCall(args)) if args.len() == 1 => {
+                let converted = self.apply(&args[0])?;
+                Some(match converted {
+                    DSLValue::String(_) => DSLValue::FunctionCall(vec![converted]),
+                    // named arguments print the same with or without parens
+                    other => other,
+                })
+            }
+            (DependencyStyle::String, DSLValue::MultiArgs(args)) => {
+                let part = |key: &str| args.get(key).and_then(DSLValue::as_str);
+                let known = ["group", "name", "version"];
+                if args.keys().any(|k| !known.contains(&k.as_str())) {
+                    return None;
+                }
+
+                let mut notation = format!("{}:{}", part("group")?, part("name")?);
+                if let Some(version) = args.get("version") {
+                    notation = format!("{}:{}", notation, version.as_str()?);
+                }
+                Some(DSLValue::String(notation))
+            }
+            (DependencyStyle::Map, DSLValue::String(notation)) => {
+                if notation.contains('@') {
+                    return None;
+                }
+                let parts: Vec<&str> = notation.split(':').collect();
+                if !(2..=3).contains(&parts.len()) || parts.iter().any(|p| p.is_empty()) {
+                    return None;
+                }
+
+                let keys = ["group", "name", "version"];
+                let args: HashMap<String, DSLValue> = keys
+                    .iter()
+                    .zip(parts)
+                    .map(|(k, v)| (k.to_string(), DSLValue::String(v.to_string())))
+                    .collect();
+                Some(DSLValue::MultiArgs(args))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl DSLBlock {
+    /// Renders the script like [`Display`](std::fmt::Display), applying
+    /// `options`.
+    ///
+    /// ```
+    /// use uniparse_gradle::{DSLBlock, DependencyStyle, FormatOptions};
+    ///
+    /// let build = DSLBlock::parse_str("dependencies {\n  implementation 'g:a:1.0'\n}").unwrap();
+    /// let options = FormatOptions {
+    ///     dependency_style: DependencyStyle::Map,
+    /// };
+    /// assert!(build
+    ///     .to_string_with(&options)
+    ///     .contains(r#"implementation group: "g", name: "a", version: "1.0""#));
+    /// ```
+    pub fn to_string_with(&self, options: &FormatOptions) -> String {
+        let mut out = String::new();
+        write_block(&mut out, self, 0, options, false).expect("writing to a String cannot fail");
+        out
+    }
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BUILD: &str = r#"
+    dependencies {
+        implementation "org.example:lib:1.0"
+        api group: 'org.example', name: 'core', version: '2.0'
+        runtimeOnly("org.example:rt:3.0")
+        testImplementation "org.example:fixtures:1.0:tests@jar"
+    }
+    description "org.example:not-a-dep:1.0"
+    "#;
+
+    fn format(style: DependencyStyle) -> String {
+        DSLBlock::parse_str(BUILD)
+            .unwrap()
+            .to_string_with(&FormatOptions {
+                dependency_style: style,
+            })
+    }
+
+    #[test]
+    fn test_string_style() {
+        let out = format(DependencyStyle::String);
+        assert!(out.contains(r#"api "org.example:core:2.0""#));
+        assert!(out.contains(r#"implementation "org.example:lib:1.0""#));
+    }
+
+    #[test]
+    fn test_map_style_keeps_unconvertible_entries() {
+        let out = format(DependencyStyle::Map);
+        assert!(
+            out.contains(r#"implementation group: "org.example", name: "lib", version: "1.0""#)
+        );
+        assert!(out.contains(r#"runtimeOnly group: "org.example", name: "rt", version: "3.0""#));
+        assert!(out.contains(r#"testImplementation "org.example:fixtures:1.0:tests@jar""#));
+        assert!(out.contains(r#"description "org.example:not-a-dep:1.0""#));
+
+        // the output parses back to the same dependencies
+        let reparsed = DSLBlock::parse_str(&out).unwrap();
+        assert_eq!(
+            reparsed.dependencies().len(),
+            DSLBlock::parse_str(BUILD).unwrap().dependencies().len()
+        );
+    }
+}
@@ -0,0 +1,410 @@
+use std::ops::Range;
+
+use crate::analysis::{Diagnostic, DiagnosticKind, Span, mask_source};
+use crate::model::{DSLBlock, DSLValue, Entries, ParseError};
+
+impl DSLBlock {
+    /// Parses a script, skipping top-level statements the model can't
+    /// represent instead of failing.
+    ///
+    /// `import` lines, `def` declarations, annotations and any other
+    /// top-level statement that doesn't parse are left out of the returned
+    /// block and reported as [`DiagnosticKind::SkippedStatement`]; the
+    /// diagnostic's span covers the whole statement, so its raw text is
+    /// `&source[d.span.byte_range.clone()]`. The rest of the script is
+    /// parsed as usual, so a partial model is always available.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(len = source.len()))
+    )]
+    pub fn parse_lenient(source: &str) -> (DSLBlock, Vec<Diagnostic>) {
+        let masked = mask_source(source);
+        let mut diagnostics = Vec::new();
+        let entries = recover_statements(
+            source,
+            &masked,
+            0..source.len(),
+            Recovery::Skip,
+            &mut diagnostics,
+        );
+        let block = DSLBlock {
+            name: "".to_string(),
+            entries,
+        };
+        (block, diagnostics)
+    }
+}
+
+impl DSLBlock {
+    /// Parses a script, reporting every syntax error instead of stopping at
+    /// the first one, for editors that show all problems at once.
+    ///
+    /// Statements are the recovery points: one that doesn't parse is left
+    /// out and reported with the error it raised, and the next statement is
+    /// parsed as usual. A failing `name { ... }` block is not dropped as a
+    /// whole; its statements are recovered one by one the same way, so a
+    /// single bad line inside `dependencies { }` only loses that line. An
+    /// unclosed block keeps what it holds up to the end of the script.
+    /// Statements skipped by [`DSLBlock::parse_lenient`] are reported as
+    /// [`DiagnosticKind::SkippedStatement`] as there.
+    ///
+    /// ```rust
+    /// use uniparse_gradle::DSLBlock;
+    ///
+    /// let (build, diagnostics) = DSLBlock::parse_recovering(
+    ///     "dependencies {\n    implementation 'g:a:1' 'g:b:1'\n    api 'g:c:1'\n}\nversion = '1.0'\n",
+    /// );
+    /// assert_eq!(build.dependencies().len(), 1);
+    /// assert!(build.get(&["version"]).is_some());
+    /// assert_eq!(diagnostics.len(), 1);
+    /// ```
+    pub fn parse_recovering(source: &str) -> (DSLBlock, Vec<Diagnostic>) {
+        let masked = mask_source(source);
+        let mut diagnostics = Vec::new();
+        let entries = recover_statements(
+            source,
+            &masked,
+            0..source.len(),
+            Recovery::Nested,
+            &mut diagnostics,
+        );
+        let block = DSLBlock {
+            name: "".to_string(),
+            entries,
+        };
+        (block, diagnostics)
+    }
+}
+
+/// What [`recover_statements`] does with a statement that doesn't parse.
+#[derive(Clone, Copy)]
+enum Recovery {
+    /// Skip it, as [`DSLBlock::parse_lenient`] does.
+    Skip,
+    /// Recover the statements of a failing block one by one and report the
+    /// rest as syntax errors, as [`DSLBlock::parse_recovering`] does.
+    Nested,
+}
+
+/// Parses the statements in `range` one at a time, adding a diagnostic for
+/// each one that is skipped or fails.
+fn recover_statements(
+    source: &str,
+    masked: &str,
+    range: Range<usize>,
+    recovery: Recovery,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Entries {
+    let mut entries = Entries::new();
+    for range in statements(masked, range) {
+        let text = &source[range.clone()];
+        let first_line = text.lines().next().unwrap_or_default().trim();
+
+        let skip = |reason: &str, diagnostics: &mut Vec<Diagnostic>| {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(statement = first_line, %reason, "skipping statement");
+            diagnostics.push(Diagnostic {
+                kind: DiagnosticKind::SkippedStatement,
+                message: format!("skipped {}: `{}`", reason, first_line),
+                span: Span::from_range(source, range.clone()),
+                related: Vec::new(),
+            });
+        };
+
+        if let Some(reason) = skip_reason(first_line) {
+            skip(reason, diagnostics);
+            continue;
+        }
+        let err = match DSLBlock::parse_str(text) {
+            Ok(block) => {
+                for (key, value) in block.entries.iter() {
+                    entries.push(key.clone(), value.clone());
+                }
+                continue;
+            }
+            Err(err) => err,
+        };
+        if let Recovery::Skip = recovery {
+            skip(&format!("unsupported statement ({})", err), diagnostics);
+            continue;
+        }
+
+        match block_parts(masked, range.clone()) {
+            Some((name, body, closed)) => {
+                let name = source[name].to_string();
+                if !closed {
+                    diagnostics.push(Diagnostic {
+                        kind: DiagnosticKind::UnexpectedEnd,
+                        message: format!("`{}` block is never closed", name),
+                        span: Span::from_range(source, range),
+                        related: Vec::new(),
+                    });
+                }
+                let nested = recover_statements(source, masked, body, recovery, diagnostics);
+                entries.push(
+                    name.clone(),
+                    DSLValue::Block(DSLBlock {
+                        name,
+                        entries: nested,
+                    }),
+                );
+            }
+            None => {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(statement = first_line, %err, "dropping statement");
+                diagnostics.push(Diagnostic {
+                    kind: syntax_kind(&err),
+                    message: err.to_string(),
+                    span: Span::from_range(source, range),
+                    related: Vec::new(),
+                });
+            }
+        }
+    }
+    entries
+}
+
+/// Splits a `name { body }` statement into the ranges of its name and
+/// body, and whether the block is closed. `None` for anything else, such
+/// as a call with arguments before the brace.
+fn block_parts(masked: &str, range: Range<usize>) -> Option<(Range<usize>, Range<usize>, bool)> {
+    let text = &masked[range.clone()];
+    let brace = text.find('{')?;
+    let name = text[..brace].trim();
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '.')
+    {
+        return None;
+    }
+    let name_start = range.start + text.find(name)?;
+    let body_start = range.start + brace + 1;
+
+    let mut depth = 0usize;
+    for (i, c) in masked[body_start..range.end].char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' if depth == 0 => {
+                let close = body_start + i;
+                // `} else { ... }` and the like aren't a plain block
+                if !masked[close + 1..range.end].trim().is_empty() {
+                    return None;
+                }
+                return Some((name_start..name_start + name.len(), body_start..close, true));
+            }
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    Some((
+        name_start..name_start + name.len(),
+        body_start..range.end,
+        false,
+    ))
+}
+
+fn syntax_kind(err: &ParseError) -> DiagnosticKind {
+    match err {
+        ParseError::UnexpectedEnd(_) => DiagnosticKind::UnexpectedEnd,
+        ParseError::UnexpectedCharacter(_) => DiagnosticKind::UnexpectedCharacter,
+        _ => DiagnosticKind::UnexpectedToken,
+    }
+}
+
+/// Statements that are never part of the model, recognised by how they
+/// start.
+fn skip_reason(first_line: &str) -> Option<&'static str> {
+    let keyword = first_line.split_whitespace().next()?;
+    match keyword {
+        "import" => Some("import"),
+        "package" => Some("package declaration"),
+        "def" | "val" | "var" => Some("local declaration"),
+        _ if keyword.starts_with('@') => Some("annotation"),
+        _ => None,
+    }
+}
+
+/// Byte ranges of the statements within `range` of the masked source, one
+/// per line unless a brace or parenthesis keeps the statement open across
+/// lines. Comments and blank lines are not part of any statement.
+fn statements(masked: &str, range: Range<usize>) -> Vec<Range<usize>> {
+    let mut statements = Vec::new();
+    let mut depth = 0usize;
+    let mut start: Option<usize> = None;
+    let mut end = 0;
+
+    let mut offset = range.start;
+    for line in masked[range].split_inclusive('\n') {
+        let content = line.trim_end();
+        if start.is_none() && !content.trim().is_empty() {
+            start = Some(offset + (content.len() - content.trim_start().len()));
+        }
+        if !content.trim().is_empty() {
+            end = offset + content.len();
+        }
+
+        for c in content.chars() {
+            match c {
+                '{' | '(' | '[' => depth += 1,
+                '}' | ')' | ']' => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+
+        // `}` or `{` on the next line still belongs to this statement
+        let continues = depth > 0;
+        if !continues && let Some(begin) = start.take() {
+            statements.push(begin..end);
+        }
+        offset += line.len();
+    }
+    if let Some(begin) = start {
+        statements.push(begin..end);
+    }
+
+    merge_detached_braces(masked, statements)
+}
+
+/// Joins `android` and a `{` on the following line back into one statement.
+fn merge_detached_braces(masked: &str, statements: Vec<Range<usize>>) -> Vec<Range<usize>> {
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(statements.len());
+    for range in statements {
+        match merged.last_mut() {
+            Some(prev) if masked[range.clone()].starts_with('{') => prev.end = range.end,
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::DSLValue;
+    use uniparse_core::{DiagnosticCode, Severity};
+
+    #[test]
+    fn test_skips_and_reports_unsupported_statements() {
+        let src = r#"import java.time.*
+// comment
+@Suppress("UnstableApiUsage")
+plugins {
+    id "java"
+}
+def stamp = java.time.Instant.now()
+version = "1.0"
+tasks.withType(JavaCompile).configureEach { options.encoding = 'UTF-8' }
+dependencies
+{
+    implementation "g:a:1"
+}
+"#;
+        let (block, diagnostics) = DSLBlock::parse_lenient(src);
+
+        let keys: Vec<_> = block.entries.keys().map(String::as_str).collect();
+        assert_eq!(keys, ["plugins", "version", "dependencies"]);
+        assert_eq!(block.dependencies().len(), 1);
+        assert_eq!(
+            block.get(&["version"]).and_then(DSLValue::as_str),
+            Some("1.0")
+        );
+
+        let skipped: Vec<_> = diagnostics
+            .iter()
+            .map(|d| (d.span.line, &src[d.span.byte_range.clone()]))
+            .collect();
+        assert_eq!(
+            skipped,
+            [
+                (1, "import java.time.*"),
+                (3, "@Suppress(\"UnstableApiUsage\")"),
+                (7, "def stamp = java.time.Instant.now()"),
+                (
+                    9,
+                    "tasks.withType(JavaCompile).configureEach { options.encoding = 'UTF-8' }"
+                ),
+            ]
+        );
+        assert!(diagnostics.iter().all(|d| d.code() == "GRADLE012"));
+        assert!(diagnostics[0].message.contains("import"));
+    }
+
+    #[test]
+    fn test_valid_script_has_no_diagnostics() {
+        let src = "plugins {\n    id 'java'\n}\ngroup = 'org.example'\n";
+        let (block, diagnostics) = DSLBlock::parse_lenient(src);
+        assert!(diagnostics.is_empty());
+        assert_eq!(block, DSLBlock::parse_str(src).unwrap());
+
+        let (block, diagnostics) = DSLBlock::parse_recovering(src);
+        assert!(diagnostics.is_empty());
+        assert_eq!(block, DSLBlock::parse_str(src).unwrap());
+    }
+
+    #[test]
+    fn test_recovering_reports_every_error() {
+        let src = r#"import java.time.*
+plugins {
+    id 'java'
+}
+group = 
+android {
+    compileSdk 34
+    defaultConfig {
+        minSdk 24 ~
+        targetSdk 34
+    }
+}
+dependencies {
+    implementation 'com.squareup.okhttp3:okhttp:4.12.0'
+    testImplementation 'junit:junit:4.13.2' 'extra'
+    api 'com.google.guava:guava:33.0-jre'
+version = '1.0'
+"#;
+        let (block, diagnostics) = DSLBlock::parse_recovering(src);
+
+        let found: Vec<_> = diagnostics
+            .iter()
+            .map(|d| (d.code(), d.severity(), d.span.line))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("GRADLE012", Severity::Warning, 1),
+                ("GRADLE001", Severity::Error, 5),
+                ("GRADLE008", Severity::Error, 9),
+                ("GRADLE001", Severity::Error, 13),
+                ("GRADLE002", Severity::Error, 15),
+            ]
+        );
+        assert_eq!(&src[diagnostics[2].span.byte_range.clone()], "minSdk 24 ~");
+        assert!(diagnostics[3].message.contains("`dependencies`"));
+
+        // everything around the errors is kept
+        let keys: Vec<_> = block.entries.keys().map(String::as_str).collect();
+        assert_eq!(keys, ["plugins", "android", "dependencies"]);
+        assert!(
+            block
+                .get(&["android", "defaultConfig", "targetSdk"])
+                .is_some()
+        );
+        assert!(block.get(&["android", "compileSdk"]).is_some());
+        let deps: Vec<_> = block
+            .dependencies()
+            .into_iter()
+            .map(|d| d.configuration)
+            .collect();
+        assert_eq!(deps, ["api", "implementation"]);
+        // the unclosed block runs to the end of the script
+        assert!(block.get(&["dependencies", "version"]).is_some());
+
+        // parse_lenient drops whole top-level statements instead
+        let (lenient, _) = DSLBlock::parse_lenient(src);
+        assert!(lenient.get(&["android"]).is_none());
+    }
+}
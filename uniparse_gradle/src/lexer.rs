@@ -0,0 +1,380 @@
+use crate::analysis::Span;
+use crate::model::ParseError;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    Identifier(String),
+    String(String),
+    Bool(bool),
+    /// A number literal as written, e.g. `34`, `1.8` or `0x1F`.
+    Number(String),
+    Equals,
+    OpenBrace,
+    CloseBrace,
+    OpenParen,
+    CloseParen,
+    Comma,
+    /// `+`
+    Plus,
+    /// `?:`
+    Elvis,
+    /// `:` in named arguments such as `group: 'g'`
+    Colon,
+    /// End of a statement: a line break or `;`.
+    Newline,
+    /// The parenthesised condition after `if`, kept as raw text without the
+    /// parentheses, since conditions use operators the DSL doesn't model.
+    Condition(String),
+}
+
+/// A [`Token`] with the place it was read from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpannedToken {
+    pub token: Token,
+    /// For a [`Token::Newline`] standing for several line breaks, the first
+    /// of them.
+    pub span: Span,
+}
+
+/// Tokenizes a Gradle script.
+///
+/// # Panics
+/// Panics on characters the tokenizer does not understand; use
+/// [`try_tokenize`] to get a [`ParseError`] instead.
+pub fn tokenize(input: &str) -> Vec<Token> {
+    try_tokenize(input).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Tokenizes a Gradle script, returning an error on unsupported input.
+pub fn try_tokenize(input: &str) -> Result<Vec<Token>, ParseError> {
+    Ok(tokenize_spanned(input)?
+        .into_iter()
+        .map(|t| t.token)
+        .collect())
+}
+
+/// Tokenizes a Gradle script, keeping the span of every token. `//` and
+/// `/* */` comments are skipped.
+pub fn tokenize_spanned(input: &str) -> Result<Vec<SpannedToken>, ParseError> {
+    lex(input).map(|(tokens, _)| tokens)
+}
+
+/// Splits `input` into tokens and the spans of its comments.
+pub(crate) fn lex(input: &str) -> Result<(Vec<SpannedToken>, Vec<Span>), ParseError> {
+    let mut cursor = Cursor {
+        input,
+        pos: 0,
+        line: 1,
+        column: 1,
+    };
+    let mut tokens: Vec<SpannedToken> = Vec::new();
+    let mut comments = Vec::new();
+
+    while let Some(ch) = cursor.peek() {
+        let start = cursor.mark();
+        let token = match ch {
+            // Statement separators, collapsed so blank lines produce one token
+            '\n' | ';' => {
+                cursor.bump();
+                if matches!(
+                    tokens.last(),
+                    None | Some(SpannedToken {
+                        token: Token::Newline,
+                        ..
+                    })
+                ) {
+                    continue;
+                }
+                Token::Newline
+            }
+
+            // Skip whitespace
+            c if c.is_whitespace() => {
+                cursor.bump();
+                continue;
+            }
+
+            // Comments
+            '/' if cursor.rest().starts_with("//") => {
+                while cursor.peek().is_some_and(|c| c != '\n') {
+                    cursor.bump();
+                }
+                comments.push(cursor.span_from(start));
+                continue;
+            }
+            '/' if cursor.rest().starts_with("/*") => {
+                let len = cursor.rest()[2..]
+                    .find("*/")
+                    .map_or(cursor.rest().len(), |i| i + 4);
+                let end = cursor.pos + len;
+                let multiline = cursor.rest()[..len].contains('\n');
+                while cursor.pos < end {
+                    cursor.bump();
+                }
+                comments.push(cursor.span_from(start));
+                // a comment spanning lines still ends the statement before it
+                if !multiline
+                    || matches!(
+                        tokens.last(),
+                        None | Some(SpannedToken {
+                            token: Token::Newline,
+                            ..
+                        })
+                    )
+                {
+                    continue;
+                }
+                Token::Newline
+            }
+
+            // `if (...)`: everything up to the matching parenthesis
+            '(' if matches!(
+                tokens.last(),
+                Some(SpannedToken { token: Token::Identifier(name), .. }) if name == "if"
+            ) =>
+            {
+                let len = condition_len(cursor.rest())
+                    .ok_or_else(|| ParseError::UnexpectedEnd("if".into()))?;
+                let end = cursor.pos + len;
+                let text = cursor.rest()[1..len - 1].trim().to_string();
+                while cursor.pos < end {
+                    cursor.bump();
+                }
+                Token::Condition(text)
+            }
+
+            // Symbols
+            '{' | '}' | '(' | ')' | '=' | ',' | '+' | ':' => {
+                cursor.bump();
+                match ch {
+                    '{' => Token::OpenBrace,
+                    '}' => Token::CloseBrace,
+                    '(' => Token::OpenParen,
+                    ')' => Token::CloseParen,
+                    '=' => Token::Equals,
+                    ',' => Token::Comma,
+                    '+' => Token::Plus,
+                    _ => Token::Colon,
+                }
+            }
+            '?' => {
+                cursor.bump();
+                if cursor.peek() != Some(':') {
+                    return Err(ParseError::UnexpectedCharacter('?'));
+                }
+                cursor.bump();
+                Token::Elvis
+            }
+
+            // Strings
+            '"' | '\'' => {
+                cursor.bump();
+                let mut value = String::new();
+                while let Some(c) = cursor.bump() {
+                    if c == ch {
+                        break;
+                    }
+                    // `\'`, `\"` and `\\`; other escapes such as `\$` are kept
+                    // as written
+                    if c == '\\'
+                        && let Some(escaped @ ('\\' | '\'' | '"')) = cursor.peek()
+                    {
+                        cursor.bump();
+                        value.push(escaped);
+                        continue;
+                    }
+                    value.push(c);
+                }
+                Token::String(value)
+            }
+
+            // Numbers, suffixes such as `L` and `f` included
+            _ if ch.is_ascii_digit() => {
+                let mut number = String::new();
+                while let Some(c) = cursor.peek() {
+                    if c.is_ascii_alphanumeric() || c == '_' || c == '.' {
+                        number.push(c);
+                        cursor.bump();
+                    } else {
+                        break;
+                    }
+                }
+                Token::Number(number)
+            }
+
+            // Identifiers or booleans
+            _ if ch.is_alphabetic() || ch == '_' => {
+                let mut ident = String::new();
+                while let Some(c) = cursor.peek() {
+                    if c.is_alphanumeric() || c == '_' || c == '.' || c == '-' {
+                        ident.push(c);
+                        cursor.bump();
+                    } else {
+                        break;
+                    }
+                }
+
+                match ident.as_str() {
+                    "true" => Token::Bool(true),
+                    "false" => Token::Bool(false),
+                    _ => Token::Identifier(ident),
+                }
+            }
+
+            _ => {
+                return Err(ParseError::UnexpectedCharacter(ch));
+            }
+        };
+        tokens.push(SpannedToken {
+            token,
+            span: cursor.span_from(start),
+        });
+    }
+
+    Ok((tokens, comments))
+}
+
+/// Length of the parenthesised text at the start of `rest`, through the
+/// matching `)`; parentheses inside string literals don't count.
+fn condition_len(rest: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut quote = None;
+    for (i, c) in rest.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// `text` as a string literal in `quote`s, escaping the quote and
+/// backslashes the way the lexer reads them back.
+pub(crate) fn quote(text: &str, quote: char) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push(quote);
+    for c in text.chars() {
+        if c == quote || c == '\\' {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out.push(quote);
+    out
+}
+
+/// Position in the input, tracking line and column as it advances so spans
+/// don't need to rescan the source.
+struct Cursor<'a> {
+    input: &'a str,
+    pos: usize,
+    line: usize,
+    column: usize,
+}
+
+impl Cursor<'_> {
+    fn rest(&self) -> &str {
+        &self.input[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn mark(&self) -> (usize, usize, usize) {
+        (self.pos, self.line, self.column)
+    }
+
+    fn span_from(&self, (start, line, column): (usize, usize, usize)) -> Span {
+        Span {
+            line,
+            column,
+            byte_range: start..self.pos,
+        }
+    }
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spans_point_into_source() {
+        let source = "plugins {\n  id 'java' // build\n}\nversion = \"1.0\"";
+        let tokens = tokenize_spanned(source).unwrap();
+
+        let java = tokens
+            .iter()
+            .find(|t| t.token == Token::String("java".into()))
+            .unwrap();
+        assert_eq!((java.span.line, java.span.column), (2, 6));
+        assert_eq!(&source[java.span.byte_range.clone()], "'java'");
+
+        let version = tokens
+            .iter()
+            .find(|t| t.token == Token::Identifier("version".into()))
+            .unwrap();
+        assert_eq!((version.span.line, version.span.column), (4, 1));
+        assert_eq!(
+            tokens.iter().map(|t| &t.token).collect::<Vec<_>>(),
+            try_tokenize(source).unwrap().iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_comments_are_skipped() {
+        let source = "a 'x' /* one */ // two\n/* three\n */ b 'y'";
+        let (tokens, comments) = lex(source).unwrap();
+
+        assert_eq!(
+            tokens.into_iter().map(|t| t.token).collect::<Vec<_>>(),
+            vec![
+                Token::Identifier("a".into()),
+                Token::String("x".into()),
+                Token::Newline,
+                Token::Identifier("b".into()),
+                Token::String("y".into()),
+            ]
+        );
+        assert_eq!(
+            comments
+                .iter()
+                .map(|s| &source[s.byte_range.clone()])
+                .collect::<Vec<_>>(),
+            vec!["/* one */", "// two", "/* three\n */"]
+        );
+        assert_eq!(
+            try_tokenize("if (a != \"(\") {}").unwrap()[1],
+            Token::Condition("a != \"(\"".into())
+        );
+        assert_eq!(
+            try_tokenize("a / b"),
+            Err(ParseError::UnexpectedCharacter('/'))
+        );
+    }
+}
@@ -1,5 +1,58 @@
+mod analysis;
+mod ast;
+mod catalog;
+pub mod compat;
+mod configurations;
+mod coordinates;
+mod cst;
+mod dependency;
+mod dialect;
+mod format;
+mod lenient;
+mod lexer;
+mod lockfile;
+#[cfg(feature = "macros")]
+mod macros;
+mod migrate;
 mod model;
 mod parser;
+mod patch;
+mod plugins;
+mod project;
+mod properties;
+mod schema;
+mod settings;
+mod strings;
+mod test_config;
 
-pub use model::{DSLBlock, DSLValue};
-pub use parser::Token;
+pub use analysis::{
+    Diagnostic, DiagnosticKind, ExternalInput, InputSource, Span, config_cache_issues,
+    duplicate_assignments, external_inputs,
+};
+pub use ast::{Argument, Block, Call, Expr, ExprKind, Name, Script, Statement, StatementKind};
+pub use catalog::{CatalogEntry, CatalogPlugin, VersionCatalog};
+pub use configurations::ConfigurationGraph;
+pub use coordinates::ProjectCoordinates;
+pub use cst::{GradleBuild, SyntaxKind};
+pub use dependency::{Coordinates, Dependency, DependencyNotation, Resolver};
+pub use dialect::{GradleDialect, detect_dialect};
+pub use format::{DependencyStyle, FormatOptions};
+pub use lexer::{SpannedToken, Token, tokenize, tokenize_spanned, try_tokenize};
+pub use lockfile::Lockfile;
+#[cfg(feature = "macros")]
+#[doc(hidden)]
+pub use macros::support as __macro_support;
+pub use model::{DSLBlock, DSLValue, Entries, Expression, Operand, Operator, ParseError};
+pub use plugins::{PluginId, PluginRequest};
+pub use project::{
+    BuildSrc, ConventionPlugin, GradleProject, IncludedBuild, LoadProgress, ProjectNode,
+};
+pub use properties::GradleProperties;
+pub use schema::{SpannedViolation, validate_schema};
+pub use settings::{GradleSettings, IncludedBuildDecl};
+pub use strings::StringLiteral;
+pub use test_config::{Parallelism, TestConfig, TestFramework};
+pub use uniparse_core::{
+    AsValueRef, CancellationToken, Capabilities, DiagnosticCode, Document, ParsedFile, Schema,
+    SchemaViolation, SemanticEq, Severity, SourceEdit, ValueKind, ValueRef, ViolationKind,
+};
@@ -0,0 +1,248 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter};
+
+use crate::configurations::ConfigurationGraph;
+use crate::dependency::{Dependency, Resolver};
+use crate::model::DSLBlock;
+
+/// Classpaths of the `java` plugin's main and test source sets, which
+/// Gradle writes to the lockfile even when nothing resolves into them.
+const STANDARD_CLASSPATHS: &[&str] = &[
+    "annotationProcessor",
+    "compileClasspath",
+    "runtimeClasspath",
+    "testAnnotationProcessor",
+    "testCompileClasspath",
+    "testRuntimeClasspath",
+];
+
+/// The contents of a `gradle.lockfile`, built by [`DSLBlock::to_lockfile`]
+/// and rendered by its `Display` impl.
+///
+/// Only declared dependencies are listed: working out their transitive
+/// dependencies needs the repositories, which a parser can't reach. Gradle
+/// will add those when it next writes the file with `--write-locks`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Lockfile {
+    /// `group:name:version` to the classpaths it is locked for.
+    pub entries: BTreeMap<String, BTreeSet<String>>,
+    /// Declared dependencies left out: their version couldn't be resolved
+    /// or is dynamic, such as `1.+` or `latest.release`, their
+    /// configuration doesn't feed a known classpath, or they are declared
+    /// under an `if`, which may not hold when Gradle writes the locks.
+    pub unlocked: Vec<Dependency>,
+}
+
+impl DSLBlock {
+    /// Locks the dependencies of the top-level `dependencies { }` block,
+    /// resolving references with `resolver`.
+    ///
+    /// Each configuration is mapped to the classpaths that extend it in the
+    /// [`configuration_graph`](Self::configuration_graph): `implementation`
+    /// to the main and test compile and runtime classpaths,
+    /// `testRuntimeOnly` to `testRuntimeClasspath`, and so on. Other source
+    /// sets get the `java` plugin's pattern, so `debugImplementation` feeds
+    /// `debugCompileClasspath` and `debugRuntimeClasspath`.
+    ///
+    /// ```rust
+    /// use uniparse_gradle::{DSLBlock, Resolver};
+    ///
+    /// let build = DSLBlock::parse_str("dependencies {\n    testImplementation 'junit:junit:4.13.2'\n}\n").unwrap();
+    /// let lockfile = build.to_lockfile(&Resolver::default());
+    /// assert!(lockfile.to_string().contains("junit:junit:4.13.2=testCompileClasspath,testRuntimeClasspath\n"));
+    /// ```
+    pub fn to_lockfile(&self, resolver: &Resolver) -> Lockfile {
+        let mut lockfile = Lockfile::default();
+        let dependencies = self.dependencies();
+        let mut graph = self.configuration_graph();
+        for dependency in &dependencies {
+            add_source_set(&mut graph, &dependency.configuration);
+        }
+        for dependency in dependencies {
+            let version = dependency
+                .resolve(resolver)
+                .filter(|c| c.version.as_deref().is_some_and(|v| !is_dynamic(v)));
+            let classpaths = classpaths(&graph, &dependency.configuration);
+            match version {
+                Some(coords) if !classpaths.is_empty() && dependency.condition.is_none() => {
+                    lockfile
+                        .entries
+                        .entry(coords.to_string())
+                        .or_default()
+                        .extend(classpaths);
+                }
+                _ => lockfile.unlocked.push(dependency),
+            }
+        }
+        lockfile
+    }
+}
+
+impl Display for Lockfile {
+    /// The header, one sorted line per entry and the `empty=` line, as
+    /// Gradle writes them.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "# This is a Gradle generated file for dependency locking."
+        )?;
+        writeln!(f, "# Manual edits can break the build and are not advised.")?;
+        writeln!(f, "# This file is expected to be part of source control.")?;
+        for (coords, classpaths) in &self.entries {
+            let classpaths: Vec<_> = classpaths.iter().map(String::as_str).collect();
+            writeln!(f, "{}={}", coords, classpaths.join(","))?;
+        }
+        let empty: Vec<_> = STANDARD_CLASSPATHS
+            .iter()
+            .filter(|c| !self.entries.values().any(|set| set.contains(**c)))
+            .copied()
+            .collect();
+        writeln!(f, "empty={}", empty.join(","))
+    }
+}
+
+/// Versions Gradle resolves to something else at build time.
+fn is_dynamic(version: &str) -> bool {
+    version.ends_with('+') || version.starts_with("latest.") || version.starts_with(['[', ']', '('])
+}
+
+/// The classpaths that dependencies declared in `configuration` end up
+/// on.
+fn classpaths(graph: &ConfigurationGraph, configuration: &str) -> Vec<String> {
+    let mut found = graph.inherited_by(configuration);
+    found.insert(configuration);
+    found
+        .into_iter()
+        .filter(|name| {
+            name.ends_with("Classpath")
+                || name.ends_with("AnnotationProcessor")
+                || *name == "annotationProcessor"
+        })
+        .map(str::to_string)
+        .collect()
+}
+
+/// Adds the edges the `java` plugin sets up for a source set other than
+/// `main` and `test`, whose configurations the default graph doesn't know,
+/// if `configuration` belongs to one, e.g. `debugImplementation`.
+fn add_source_set(graph: &mut ConfigurationGraph, configuration: &str) {
+    const KINDS: &[&str] = &[
+        "Implementation",
+        "Api",
+        "CompileOnly",
+        "RuntimeOnly",
+        "AnnotationProcessor",
+    ];
+    let Some(source_set) = KINDS
+        .iter()
+        .find_map(|kind| configuration.strip_suffix(kind))
+        .filter(|name| !name.is_empty() && *name != "test")
+    else {
+        return;
+    };
+    for (classpath, only) in [
+        ("CompileClasspath", "CompileOnly"),
+        ("RuntimeClasspath", "RuntimeOnly"),
+    ] {
+        let classpath = format!("{}{}", source_set, classpath);
+        graph.add_extends(&classpath, &format!("{}{}", source_set, only));
+        graph.add_extends(&classpath, &format!("{}Implementation", source_set));
+    }
+    graph.add_extends(
+        &format!("{}Implementation", source_set),
+        &format!("{}Api", source_set),
+    );
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VersionCatalog;
+
+    #[test]
+    fn test_lockfile_from_dependencies() {
+        let build = DSLBlock::parse_str(
+            r#"
+dependencies {
+    implementation 'com.google.code.gson:gson:2.10.1'
+    api libs.okhttp
+    compileOnly 'org.projectlombok:lombok:1.18.30'
+    annotationProcessor 'org.projectlombok:lombok:1.18.30'
+    runtimeOnly 'org.postgresql:postgresql:42.+'
+    testImplementation 'org.junit.jupiter:junit-jupiter:5.10.0'
+    debugImplementation 'com.squareup.leakcanary:leakcanary-android:2.12'
+    implementation 'org.slf4j:slf4j-api'
+    detekt 'io.gitlab.arturbosch.detekt:detekt-cli:1.23.1'
+}
+"#,
+        )
+        .unwrap();
+        let catalog = VersionCatalog::parse_toml(
+            "libs",
+            "[libraries]\nokhttp = \"com.squareup.okhttp3:okhttp:4.12.0\"\n",
+        )
+        .unwrap();
+        let resolver = Resolver {
+            catalogs: vec![catalog],
+            ..Resolver::default()
+        };
+
+        let lockfile = build.to_lockfile(&resolver);
+        assert_eq!(
+            lockfile.to_string(),
+            "\
+# This is a Gradle generated file for dependency locking.
+# Manual edits can break the build and are not advised.
+# This file is expected to be part of source control.
+com.google.code.gson:gson:2.10.1=compileClasspath,runtimeClasspath,testCompileClasspath,testRuntimeClasspath
+com.squareup.leakcanary:leakcanary-android:2.12=debugCompileClasspath,debugRuntimeClasspath
+com.squareup.okhttp3:okhttp:4.12.0=compileClasspath,runtimeClasspath,testCompileClasspath,testRuntimeClasspath
+org.junit.jupiter:junit-jupiter:5.10.0=testCompileClasspath,testRuntimeClasspath
+org.projectlombok:lombok:1.18.30=annotationProcessor,compileClasspath
+empty=testAnnotationProcessor
+"
+        );
+
+        let mut unlocked: Vec<_> = lockfile
+            .unlocked
+            .iter()
+            .map(|d| d.configuration.as_str())
+            .collect();
+        unlocked.sort();
+        assert_eq!(unlocked, ["detekt", "implementation", "runtimeOnly"]);
+    }
+
+    #[test]
+    fn test_lockfile_follows_extends_from_and_skips_conditions() {
+        let build = DSLBlock::parse_str(
+            r#"
+configurations {
+    integrationTestImplementation { extendsFrom implementation }
+    integrationTestCompileClasspath { extendsFrom integrationTestImplementation }
+}
+dependencies {
+    implementation 'com.google.code.gson:gson:2.10.1'
+    if (project.hasProperty('withMetrics')) {
+        implementation 'io.micrometer:micrometer-core:1.12.0'
+    }
+}
+"#,
+        )
+        .unwrap();
+
+        let lockfile = build.to_lockfile(&Resolver::default());
+        assert!(lockfile.to_string().contains(
+            "com.google.code.gson:gson:2.10.1=compileClasspath,integrationTestCompileClasspath,runtimeClasspath,testCompileClasspath,testRuntimeClasspath\n"
+        ));
+        assert!(!lockfile.to_string().contains("micrometer"));
+        assert_eq!(lockfile.unlocked.len(), 1);
+        assert_eq!(
+            lockfile.unlocked[0].condition.as_deref(),
+            Some("project.hasProperty('withMetrics')")
+        );
+    }
+}
@@ -0,0 +1,340 @@
+/// Builds a [`DSLBlock`](crate::DSLBlock) from Gradle-like syntax, giving
+/// the same value [`DSLBlock::parse_str`](crate::DSLBlock::parse_str) would
+/// for the script. Needs the `macros` feature.
+///
+/// Statements are written as in a Groovy script, one after the other:
+///
+/// - `name { ... }` is a block.
+/// - `name "x"`, `name 34`, `name true` and `name libs.retrofit` are calls
+///   with one argument; `name("x", project(":core"))` is a call with
+///   parentheses.
+/// - `name = "x"` is an assignment.
+/// - `id "x" .version "1.0"` is `id "x" version "1.0"`; the dot keeps it
+///   apart from a new statement, as Rust macros don't see line breaks.
+/// - `(expr)` after `=` or as an argument inserts a Rust value converted
+///   with `DSLValue::from`.
+///
+/// A `;` between statements is allowed but not needed.
+///
+/// ```rust
+/// use uniparse_gradle::{DSLBlock, dsl};
+///
+/// let built = dsl! {
+///     plugins {
+///         id "java"
+///         id "org.jetbrains.kotlin.jvm" .version "1.9.0"
+///     }
+///     version = "1.0"
+///     dependencies {
+///         implementation "com.google.guava:guava:33.0-jre"
+///         api(project(":core"))
+///     }
+/// };
+///
+/// let parsed = DSLBlock::parse_str(
+///     r#"
+///     plugins {
+///         id "java"
+///         id "org.jetbrains.kotlin.jvm" version "1.9.0"
+///     }
+///     version = "1.0"
+///     dependencies {
+///         implementation "com.google.guava:guava:33.0-jre"
+///         api(project(":core"))
+///     }
+///     "#,
+/// )
+/// .unwrap();
+/// assert_eq!(built, parsed);
+/// ```
+#[macro_export]
+macro_rules! dsl {
+    // statements, with the `(key, value),` pairs done so far
+    (@entries [$($done:tt)*]) => {
+        <$crate::Entries as ::std::iter::FromIterator<_>>::from_iter([$($done)*])
+    };
+    (@entries [$($done:tt)*] ; $($rest:tt)*) => {
+        $crate::dsl!(@entries [$($done)*] $($rest)*)
+    };
+    (@entries [$($done:tt)*] $key:ident { $($body:tt)* } $($rest:tt)*) => {
+        $crate::dsl!(@entries [$($done)* (
+            ::std::string::String::from(stringify!($key)),
+            $crate::DSLValue::Block($crate::DSLBlock {
+                name: ::std::string::String::from(stringify!($key)),
+                entries: $crate::dsl!(@entries [] $($body)*),
+            }),
+        ),] $($rest)*)
+    };
+    (@entries [$($done:tt)*] $key:ident ( $($args:tt)* ) $($rest:tt)*) => {
+        $crate::dsl!(@entries [$($done)* (
+            ::std::string::String::from(stringify!($key)),
+            $crate::DSLValue::FunctionCall($crate::dsl!(@args [] () $($args)*)),
+        ),] $($rest)*)
+    };
+    (@entries [$($done:tt)*] $key:ident = $($rest:tt)*) => {
+        $crate::dsl!(@value [$($done)*] $key assign $($rest)*)
+    };
+    (@entries [$($done:tt)*] $key:ident $($rest:tt)+) => {
+        $crate::dsl!(@value [$($done)*] $key call $($rest)*)
+    };
+
+    // the value of `$key`, as a call argument or assigned
+    (@value [$($done:tt)*] $key:ident $mode:ident ( $value:expr ) $($rest:tt)*) => {
+        $crate::dsl!(@entries [$($done)* (
+            ::std::string::String::from(stringify!($key)),
+            $crate::__macro_support::$mode($crate::DSLValue::from($value)),
+        ),] $($rest)*)
+    };
+    (@value [$($done:tt)*] $key:ident $mode:ident - $number:literal $($rest:tt)*) => {
+        $crate::dsl!(@entries [$($done)* (
+            ::std::string::String::from(stringify!($key)),
+            $crate::DSLValue::Number(::std::string::String::from(concat!("-", stringify!($number)))),
+        ),] $($rest)*)
+    };
+    (@value [$($done:tt)*] $key:ident call $value:literal . $name:ident $($rest:tt)*) => {
+        $crate::dsl!(@multi [$($done)*] $key [(
+            ::std::string::String::from("value"),
+            $crate::__macro_support::literal($value, stringify!($value)),
+        ),] . $name $($rest)*)
+    };
+    (@value [$($done:tt)*] $key:ident $mode:ident $value:literal $($rest:tt)*) => {
+        $crate::dsl!(@entries [$($done)* (
+            ::std::string::String::from(stringify!($key)),
+            $crate::__macro_support::$mode(
+                $crate::__macro_support::literal($value, stringify!($value)),
+            ),
+        ),] $($rest)*)
+    };
+    (@value [$($done:tt)*] $key:ident $mode:ident $first:ident $($rest:tt)*) => {
+        $crate::dsl!(@path [$($done)*] $key (stringify!($first)) $($rest)*)
+    };
+
+    // `a.b.C`
+    (@path [$($done:tt)*] $key:ident ($($name:expr),+) . $next:ident $($rest:tt)*) => {
+        $crate::dsl!(@path [$($done)*] $key ($($name),+, ".", stringify!($next)) $($rest)*)
+    };
+    (@path [$($done:tt)*] $key:ident ($($name:expr),+) $($rest:tt)*) => {
+        $crate::dsl!(@entries [$($done)* (
+            ::std::string::String::from(stringify!($key)),
+            $crate::__macro_support::name(concat!($($name),+)),
+        ),] $($rest)*)
+    };
+
+    // `id "x" .version "1.0"`
+    (@multi [$($done:tt)*] $key:ident [$($args:tt)*] . $name:ident $value:literal $($rest:tt)*) => {
+        $crate::dsl!(@multi [$($done)*] $key [$($args)* (
+            ::std::string::String::from(stringify!($name)),
+            $crate::__macro_support::literal($value, stringify!($value)),
+        ),] $($rest)*)
+    };
+    (@multi [$($done:tt)*] $key:ident [$($args:tt)*] $($rest:tt)*) => {
+        $crate::dsl!(@entries [$($done)* (
+            ::std::string::String::from(stringify!($key)),
+            $crate::DSLValue::MultiArgs(::std::collections::HashMap::from([$($args)*])),
+        ),] $($rest)*)
+    };
+
+    // call arguments: the tokens of one, up to the next top-level comma
+    (@args [$($done:expr,)*] ()) => {
+        ::std::vec![$($done,)*]
+    };
+    (@args [$($done:expr,)*] ($($arg:tt)+)) => {
+        ::std::vec![$($done,)* $crate::dsl!(@arg $($arg)+)]
+    };
+    (@args [$($done:expr,)*] ($($arg:tt)+) , $($rest:tt)*) => {
+        $crate::dsl!(@args [$($done,)* $crate::dsl!(@arg $($arg)+),] () $($rest)*)
+    };
+    (@args [$($done:expr,)*] ($($arg:tt)*) $next:tt $($rest:tt)*) => {
+        $crate::dsl!(@args [$($done,)*] ($($arg)* $next) $($rest)*)
+    };
+    (@arg ( $value:expr )) => {
+        $crate::DSLValue::from($value)
+    };
+    (@arg $name:ident ( $($args:tt)* )) => {
+        $crate::__macro_support::call_expression(stringify!($name), $crate::dsl!(@args [] () $($args)*))
+    };
+    (@arg - $number:literal) => {
+        $crate::DSLValue::Number(::std::string::String::from(concat!("-", stringify!($number))))
+    };
+    (@arg $value:literal) => {
+        $crate::__macro_support::literal($value, stringify!($value))
+    };
+    (@arg $first:ident $(. $next:ident)*) => {
+        $crate::__macro_support::name(concat!(stringify!($first) $(, ".", stringify!($next))*))
+    };
+
+    ($($statements:tt)*) => {
+        $crate::DSLBlock {
+            name: ::std::string::String::new(),
+            entries: $crate::dsl!(@entries [] $($statements)*),
+        }
+    };
+}
+
+/// What the expansion of [`dsl!`] calls; not a public API.
+pub mod support {
+    use crate::model::{DSLValue, Expression, Operand};
+    use crate::parser::name_value;
+
+    /// A literal token of the macro input.
+    pub trait Literal {
+        /// The value of the literal, whose source text is `text`.
+        fn value(self, text: &str) -> DSLValue;
+    }
+
+    impl Literal for &str {
+        fn value(self, _: &str) -> DSLValue {
+            DSLValue::String(self.to_string())
+        }
+    }
+
+    impl Literal for bool {
+        fn value(self, _: &str) -> DSLValue {
+            DSLValue::Bool(self)
+        }
+    }
+
+    macro_rules! number_literal {
+        ($($ty:ty),*) => {
+            $(impl Literal for $ty {
+                fn value(self, text: &str) -> DSLValue {
+                    DSLValue::Number(text.to_string())
+                }
+            })*
+        };
+    }
+
+    number_literal!(i32, i64, u32, u64, f32, f64);
+
+    pub fn literal(value: impl Literal, text: &str) -> DSLValue {
+        value.value(text)
+    }
+
+    /// `key "x"`: the value as is.
+    pub fn call(value: DSLValue) -> DSLValue {
+        value
+    }
+
+    /// `key = "x"`: a string is an assignment.
+    pub fn assign(value: DSLValue) -> DSLValue {
+        match value {
+            DSLValue::String(s) => DSLValue::Assignment(s),
+            other => other,
+        }
+    }
+
+    pub fn name(name: &str) -> DSLValue {
+        name_value(name.to_string())
+    }
+
+    /// A call in argument position, e.g. `project(":core")`.
+    pub fn call_expression(name: &str, args: Vec<DSLValue>) -> DSLValue {
+        DSLValue::Expression(Expression {
+            first: Operand::Call {
+                name: name.to_string(),
+                args: args.into_iter().map(expression).collect(),
+            },
+            rest: Vec::new(),
+        })
+    }
+
+    fn expression(value: DSLValue) -> Expression {
+        let first = match value {
+            DSLValue::Expression(expression) => return expression,
+            DSLValue::String(s) | DSLValue::Assignment(s) => Operand::String(s),
+            DSLValue::Bool(b) => Operand::Bool(b),
+            DSLValue::Number(n) => Operand::Number(n),
+            DSLValue::Reference(name) | DSLValue::Identifier(name) => Operand::Reference(name),
+            other => panic!("{:?} can't be a call argument", other),
+        };
+        Expression {
+            first,
+            rest: Vec::new(),
+        }
+    }
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use crate::{DSLBlock, DSLValue, Entries};
+
+    #[test]
+    fn test_dsl_matches_parsed_script() {
+        let jvm = 17;
+        let built = dsl! {
+            plugins {
+                id "com.android.application"
+                id "org.jetbrains.kotlin.android" .version "1.9.0"
+            }
+            android {
+                compileSdk 34
+                defaultConfig {
+                    minSdk = 21
+                    versionName = "1.0"
+                    multiDexEnabled true
+                }
+                compileOptions { sourceCompatibility = JavaVersion.VERSION_17 }
+            }
+            dependencies {
+                implementation libs.androidx.core;
+                implementation("com.squareup.okhttp3:okhttp:4.12.0")
+                testImplementation(project(":testing"), "junit:junit:4.13.2")
+            }
+            jvmTarget = (jvm.to_string())
+        };
+
+        let parsed = DSLBlock::parse_str(
+            r#"
+            plugins {
+                id 'com.android.application'
+                id 'org.jetbrains.kotlin.android' version '1.9.0'
+            }
+            android {
+                compileSdk 34
+                defaultConfig {
+                    minSdk = 21
+                    versionName = "1.0"
+                    multiDexEnabled true
+                }
+                compileOptions { sourceCompatibility = JavaVersion.VERSION_17 }
+            }
+            dependencies {
+                implementation libs.androidx.core
+                implementation("com.squareup.okhttp3:okhttp:4.12.0")
+                testImplementation(project(":testing"), "junit:junit:4.13.2")
+            }
+            jvmTarget = "17"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(built, parsed);
+    }
+
+    #[test]
+    fn test_dsl_values() {
+        assert_eq!(
+            dsl! {},
+            DSLBlock {
+                name: String::new(),
+                entries: Entries::new(),
+            }
+        );
+        let block = dsl! { offset -3; ratio = 1.50; names() };
+        assert_eq!(
+            block.entries.get("offset"),
+            Some(&DSLValue::Number("-3".into()))
+        );
+        assert_eq!(
+            block.entries.get("ratio"),
+            Some(&DSLValue::Number("1.50".into()))
+        );
+        assert_eq!(
+            block.entries.get("names"),
+            Some(&DSLValue::FunctionCall(Vec::new()))
+        );
+    }
+}
@@ -0,0 +1,148 @@
+use uniparse_core::{CstKind, GreenToken, SyntaxNode, SyntaxToken};
+
+use crate::analysis::{Diagnostic, DiagnosticKind, Span};
+use crate::cst::{GradleBuild, SyntaxKind};
+
+/// Configurations removed in Gradle 7, with the ones that replace them.
+const DEPRECATED_CONFIGURATIONS: &[(&str, &str)] = &[
+    ("compile", "implementation"),
+    ("runtime", "runtimeOnly"),
+    ("testCompile", "testImplementation"),
+    ("testRuntime", "testRuntimeOnly"),
+];
+
+/// The configuration that replaces `name`, if `name` is deprecated.
+fn replacement(name: &str) -> Option<&'static str> {
+    DEPRECATED_CONFIGURATIONS
+        .iter()
+        .find(|(old, _)| *old == name)
+        .map(|(_, new)| *new)
+}
+
+impl GradleBuild {
+    /// Flags dependencies declared on a deprecated configuration such as
+    /// `compile` or `testCompile`, anywhere inside a `dependencies` block.
+    pub fn deprecated_configurations(&self) -> Vec<Diagnostic> {
+        let source = self.to_string();
+        deprecated_names(self.syntax())
+            .into_iter()
+            .map(|(name, new)| Diagnostic {
+                kind: DiagnosticKind::DeprecatedConfiguration,
+                message: format!(
+                    "configuration `{}` is deprecated; use `{}` instead",
+                    name.text(),
+                    new
+                ),
+                span: Span::from_range(&source, name.text_range()),
+                related: Vec::new(),
+            })
+            .collect()
+    }
+
+    /// Renames every configuration flagged by
+    /// [`deprecated_configurations`](Self::deprecated_configurations) to its
+    /// modern equivalent, e.g. `testCompile` to `testImplementation`.
+    /// Returns the number of declarations changed; nothing else in the
+    /// file is touched.
+    ///
+    /// ```rust
+    /// use uniparse_gradle::GradleBuild;
+    ///
+    /// let mut build = GradleBuild::parse("dependencies {\n    compile 'g:a:1'\n}\n").unwrap();
+    /// assert_eq!(build.migrate_deprecated_configurations(), 1);
+    /// assert_eq!(build.to_string(), "dependencies {\n    implementation 'g:a:1'\n}\n");
+    /// ```
+    pub fn migrate_deprecated_configurations(&mut self) -> usize {
+        let mut count = 0;
+        // each edit builds a new tree, so look the next name up again in it
+        while let Some((name, new)) = deprecated_names(self.syntax()).into_iter().next() {
+            let green = name.replace_with(GreenToken::new(SyntaxKind::Identifier, new));
+            self.root = SyntaxNode::new_root(green);
+            count += 1;
+        }
+        count
+    }
+}
+
+/// The names of statements in `dependencies` blocks that use a deprecated
+/// configuration, with their replacements.
+fn deprecated_names(root: &SyntaxNode<SyntaxKind>) -> Vec<(SyntaxToken<SyntaxKind>, &'static str)> {
+    root.descendants()
+        .filter(|s| s.kind() == SyntaxKind::Statement)
+        .filter(|s| {
+            s.ancestors()
+                .skip(1)
+                .any(|a| first_identifier(&a).is_some_and(|t| t.text() == "dependencies"))
+        })
+        .filter_map(|s| {
+            let name = first_identifier(&s)?;
+            let new = replacement(name.text())?;
+            Some((name, new))
+        })
+        .collect()
+}
+
+fn first_identifier(statement: &SyntaxNode<SyntaxKind>) -> Option<SyntaxToken<SyntaxKind>> {
+    if statement.kind() != SyntaxKind::Statement {
+        return None;
+    }
+    statement
+        .tokens()
+        .find(|t| !t.kind().is_trivia())
+        .filter(|t| t.kind() == SyntaxKind::Identifier)
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uniparse_core::DiagnosticCode;
+
+    #[test]
+    fn test_flag_and_migrate_deprecated_configurations() {
+        let src = "\
+configurations {
+    compile.exclude module: 'commons-logging'
+}
+
+dependencies {
+    // kept for the legacy client
+    compile 'com.google.guava:guava:20.0'
+    runtime('org.postgresql:postgresql:42.2.5')
+    if (isCi) {
+        testCompile 'junit:junit:4.12' // pinned
+    }
+    testRuntime group: 'org.junit.vintage', name: 'junit-vintage-engine', version: '5.4.0'
+    implementation 'org.slf4j:slf4j-api:1.7.25'
+}
+";
+        let mut build = GradleBuild::parse(src).unwrap();
+        let issues = build.deprecated_configurations();
+        assert_eq!(
+            issues
+                .iter()
+                .map(|d| (d.span.line, d.span.column))
+                .collect::<Vec<_>>(),
+            [(7, 5), (8, 5), (10, 9), (12, 5)]
+        );
+        assert!(issues.iter().all(|d| d.code() == "GRADLE013"));
+        assert_eq!(
+            issues[0].message,
+            "configuration `compile` is deprecated; use `implementation` instead"
+        );
+
+        assert_eq!(build.migrate_deprecated_configurations(), 4);
+        assert_eq!(
+            build.to_string(),
+            src.replace("    compile '", "    implementation '")
+                .replace("runtime(", "runtimeOnly(")
+                .replace("testCompile", "testImplementation")
+                .replace("testRuntime ", "testRuntimeOnly ")
+        );
+        assert!(build.to_string().contains("compile.exclude"));
+        assert!(build.deprecated_configurations().is_empty());
+    }
+}
@@ -1,18 +1,405 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use uniparse_core::{AsValueRef, DiagnosticCode, SemanticEq, ValueRef};
+
+use crate::lexer::quote;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum DSLValue {
     String(String),
     Bool(bool),
+    /// A number literal as written, e.g. `compileSdk 34` or `minSdk = 21`.
+    Number(String),
     Block(DSLBlock),
     Assignment(String),                   // ✅ for key = "value"
     FunctionCall(Vec<DSLValue>),          // ✅ for key(), key("arg")
     MultiArgs(HashMap<String, DSLValue>), // ✅ for id "a" version "b"
+    Reference(String),                    // ✅ for libs.retrofit, deps.okhttp
+    Identifier(String),                   // ✅ for JavaVersion.VERSION_17
+    Expression(Expression),               // ✅ for "a" + b, x ?: "1.0"
+    /// The body of `if (condition) { ... }`, stored under the key `if`.
+    /// `condition` is the raw text between the parentheses; `else`
+    /// branches become entries of their own with the negated condition,
+    /// e.g. `!(isCi)`.
+    Conditional {
+        condition: String,
+        block: DSLBlock,
+    },
+}
+
+/// An unevaluated `+` / `?:` expression, kept as a flat sequence of operands
+/// in source order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Expression {
+    pub first: Operand,
+    pub rest: Vec<(Operator, Operand)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Operator {
+    /// `+`
+    Plus,
+    /// `?:`
+    Elvis,
+}
+
+/// A single operand of an [`Expression`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Operand {
+    String(String),
+    Bool(bool),
+    Number(String),
+    /// A variable or property, e.g. `suffix` or `project.version`.
+    Reference(String),
+    /// A method call, e.g. `project.findProperty('v')`.
+    Call {
+        name: String,
+        args: Vec<Expression>,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DSLBlock {
     pub name: String,
-    pub entries: HashMap<String, DSLValue>,
+    pub entries: Entries,
+}
+
+/// The statements of a block in source order.
+///
+/// Keys may repeat: a `dependencies { }` block usually has several
+/// `implementation` lines, and a script may open the same block twice.
+/// Map-style lookups see the last statement for a key, the same one Gradle
+/// would end up using for a property.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Entries(Vec<(String, DSLValue)>);
+
+/// Errors returned while parsing a Gradle script.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ParseError {
+    /// The input ended while a statement was still incomplete.
+    #[error("Expected token after identifier '{0}', but reached end")]
+    UnexpectedEnd(String),
+
+    /// A token appeared where the grammar does not allow it.
+    #[error("Unexpected token: {0}")]
+    UnexpectedToken(String),
+
+    /// The tokenizer met a character it does not support.
+    #[error("Unexpected character in input: {0}")]
+    UnexpectedCharacter(char),
+
+    /// The script could not be read.
+    #[error("IO error: {0}")]
+    Io(String),
+
+    /// A `libs.versions.toml` version catalog is malformed.
+    #[error("Invalid version catalog at line {line}: {message}")]
+    Catalog { line: usize, message: String },
+}
+
+impl DiagnosticCode for ParseError {
+    fn code(&self) -> &'static str {
+        match self {
+            ParseError::UnexpectedEnd(_) => "GRADLE001",
+            ParseError::UnexpectedToken(_) => "GRADLE002",
+            ParseError::UnexpectedCharacter(_) => "GRADLE008",
+            ParseError::Io(_) => "GRADLE009",
+            ParseError::Catalog { .. } => "GRADLE010",
+        }
+    }
+}
+
+impl SemanticEq for DSLBlock {
+    /// Statement order inside a block is ignored; values are compared with
+    /// [`DSLValue::semantic_eq`].
+    fn semantic_eq(&self, other: &Self) -> bool {
+        self.name == other.name && entries_eq(&self.entries, &other.entries)
+    }
+}
+
+impl SemanticEq for DSLValue {
+    /// `key "v"`, `key("v")` and `key = "v"` all set the same value, so a
+    /// single-argument call and an assignment compare equal to the bare
+    /// value.
+    fn semantic_eq(&self, other: &Self) -> bool {
+        match (self.unwrap_call(), other.unwrap_call()) {
+            (
+                DSLValue::String(a) | DSLValue::Assignment(a),
+                DSLValue::String(b) | DSLValue::Assignment(b),
+            ) => a == b,
+            (DSLValue::Block(a), DSLValue::Block(b)) => a.semantic_eq(b),
+            (
+                DSLValue::Conditional {
+                    condition: c1,
+                    block: a,
+                },
+                DSLValue::Conditional {
+                    condition: c2,
+                    block: b,
+                },
+            ) => c1 == c2 && a.semantic_eq(b),
+            (DSLValue::FunctionCall(a), DSLValue::FunctionCall(b)) => a[..].semantic_eq(&b[..]),
+            (DSLValue::MultiArgs(a), DSLValue::MultiArgs(b)) => map_eq(a, b),
+            (a, b) => a == b,
+        }
+    }
+}
+
+impl AsValueRef for DSLValue {
+    /// Single-argument calls read as their argument, like
+    /// [`SemanticEq`]; other calls as the sequence of their arguments.
+    /// Named arguments are a map with sorted keys. Expressions are opaque.
+    fn as_value_ref(&self) -> ValueRef<'_> {
+        match self.unwrap_call() {
+            DSLValue::String(s) | DSLValue::Assignment(s) => ValueRef::Str(s),
+            DSLValue::Bool(b) => ValueRef::Bool(*b),
+            DSLValue::Number(n) => ValueRef::Number(n),
+            DSLValue::Reference(name) | DSLValue::Identifier(name) => ValueRef::Ident(name),
+            DSLValue::Block(block) | DSLValue::Conditional { block, .. } => block.as_value_ref(),
+            DSLValue::FunctionCall(args) => {
+                ValueRef::Seq(args.iter().map(|v| v.as_value_ref()).collect())
+            }
+            DSLValue::MultiArgs(args) => {
+                let mut entries: Vec<_> = args
+                    .iter()
+                    .map(|(k, v)| (k.as_str(), v.as_value_ref()))
+                    .collect();
+                entries.sort_by(|a, b| a.0.cmp(b.0));
+                ValueRef::Map(entries)
+            }
+            DSLValue::Expression(_) => ValueRef::Opaque,
+        }
+    }
+}
+
+impl AsValueRef for DSLBlock {
+    /// The entries in source order, repeated keys included.
+    fn as_value_ref(&self) -> ValueRef<'_> {
+        ValueRef::Map(
+            self.entries
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_value_ref()))
+                .collect(),
+        )
+    }
+}
+
+impl DSLValue {
+    fn unwrap_call(&self) -> &DSLValue {
+        match self {
+            DSLValue::FunctionCall(args) if args.len() == 1 => args[0].unwrap_call(),
+            value => value,
+        }
+    }
+}
+
+fn map_eq(a: &HashMap<String, DSLValue>, b: &HashMap<String, DSLValue>) -> bool {
+    a.len() == b.len()
+        && a.iter()
+            .all(|(key, value)| b.get(key).is_some_and(|other| value.semantic_eq(other)))
+}
+
+/// Compares statements as a multiset, so reordering never matters but
+/// repeating a statement does.
+fn entries_eq(a: &Entries, b: &Entries) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let mut used = vec![false; b.len()];
+    a.iter().all(|(key, value)| {
+        let found = (0..b.len()).find(|&i| {
+            let (k, v) = &b.0[i];
+            !used[i] && k == key && value.semantic_eq(v)
+        });
+        found.map(|i| used[i] = true).is_some()
+    })
+}
+
+impl Entries {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The last statement for `key`.
+    pub fn get(&self, key: &str) -> Option<&DSLValue> {
+        self.0.iter().rev().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    /// The last statement for `key`, mutably.
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut DSLValue> {
+        self.0
+            .iter_mut()
+            .rev()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v)
+    }
+
+    /// Every statement for `key`, in source order.
+    pub fn get_all<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a DSLValue> + 'a {
+        self.0.iter().filter(move |(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.0.iter().any(|(k, _)| k == key)
+    }
+
+    /// Replaces the last statement for `key`, or appends one if there is
+    /// none, returning the previous value.
+    pub fn insert(&mut self, key: String, value: DSLValue) -> Option<DSLValue> {
+        match self.get_mut(&key) {
+            Some(slot) => Some(std::mem::replace(slot, value)),
+            None => {
+                self.0.push((key, value));
+                None
+            }
+        }
+    }
+
+    /// Appends a statement, keeping any earlier ones for the same key.
+    pub fn push(&mut self, key: String, value: DSLValue) {
+        self.0.push((key, value));
+    }
+
+    /// Removes every statement for `key`, returning the last one.
+    pub fn remove(&mut self, key: &str) -> Option<DSLValue> {
+        let mut removed = None;
+        let mut i = 0;
+        while i < self.0.len() {
+            if self.0[i].0 == key {
+                removed = Some(self.0.remove(i).1);
+            } else {
+                i += 1;
+            }
+        }
+        removed
+    }
+
+    /// Statements in source order.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &DSLValue)> {
+        self.0.iter().map(|(k, v)| (k, v))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.0.iter().map(|(k, _)| k)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &DSLValue> {
+        self.0.iter().map(|(_, v)| v)
+    }
+}
+
+impl From<&str> for DSLValue {
+    fn from(value: &str) -> Self {
+        DSLValue::String(value.to_string())
+    }
+}
+
+impl From<String> for DSLValue {
+    fn from(value: String) -> Self {
+        DSLValue::String(value)
+    }
+}
+
+impl From<bool> for DSLValue {
+    fn from(value: bool) -> Self {
+        DSLValue::Bool(value)
+    }
+}
+
+impl From<DSLBlock> for DSLValue {
+    fn from(block: DSLBlock) -> Self {
+        DSLValue::Block(block)
+    }
+}
+
+impl<'a> IntoIterator for &'a Entries {
+    type Item = (&'a String, &'a DSLValue);
+    type IntoIter = std::iter::Map<
+        std::slice::Iter<'a, (String, DSLValue)>,
+        fn(&'a (String, DSLValue)) -> (&'a String, &'a DSLValue),
+    >;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter().map(|(k, v)| (k, v))
+    }
+}
+
+impl FromIterator<(String, DSLValue)> for Entries {
+    fn from_iter<I: IntoIterator<Item = (String, DSLValue)>>(iter: I) -> Self {
+        Entries(iter.into_iter().collect())
+    }
+}
+
+impl Expression {
+    /// All operands in source order.
+    pub fn operands(&self) -> impl Iterator<Item = &Operand> {
+        std::iter::once(&self.first).chain(self.rest.iter().map(|(_, o)| o))
+    }
+
+    /// String literals among the top-level operands.
+    pub fn literals(&self) -> impl Iterator<Item = &str> {
+        self.operands().filter_map(|o| match o {
+            Operand::String(s) => Some(s.as_str()),
+            _ => None,
+        })
+    }
+
+    /// The literal after the last `?:`, i.e. the value used when everything
+    /// before it is null.
+    pub fn fallback(&self) -> Option<&str> {
+        match self.rest.last() {
+            Some((Operator::Elvis, Operand::String(s))) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Expression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.first)?;
+        for (op, operand) in &self.rest {
+            write!(f, " {} {}", op, operand)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Operator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Operator::Plus => write!(f, "+"),
+            Operator::Elvis => write!(f, "?:"),
+        }
+    }
+}
+
+impl Display for Operand {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Operand::String(s) => f.write_str(&quote(s, '"')),
+            Operand::Bool(b) => write!(f, "{}", b),
+            Operand::Number(n) => write!(f, "{}", n),
+            Operand::Reference(name) => write!(f, "{}", name),
+            Operand::Call { name, args } => {
+                write!(f, "{}(", name)?;
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", arg)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
 }
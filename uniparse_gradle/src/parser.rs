@@ -1,130 +1,38 @@
-use crate::model::{DSLBlock, DSLValue};
+use crate::format::FormatOptions;
+use crate::lexer::{Token, quote, try_tokenize};
+use crate::model::{DSLBlock, DSLValue, Entries, Expression, Operand, Operator, ParseError};
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 use std::str::FromStr;
+use uniparse_core::{Capabilities, ParsedFile};
 
-#[derive(Debug, Clone)]
-pub enum Token {
-    Identifier(String),
-    String(String),
-    Bool(bool),
-    Equals,
-    OpenBrace,
-    CloseBrace,
-    OpenParen,
-    CloseParen,
-}
-
-pub fn tokenize(input: &str) -> Vec<Token> {
-    let mut tokens = Vec::new();
-    let mut chars = input.chars().peekable();
-
-    while let Some(&ch) = chars.peek() {
-        match ch {
-            // Skip whitespace
-            c if c.is_whitespace() => {
-                chars.next();
-            }
-
-            // Symbols
-            '{' => {
-                tokens.push(Token::OpenBrace);
-                chars.next();
-            }
-            '}' => {
-                tokens.push(Token::CloseBrace);
-                chars.next();
-            }
-            '(' => {
-                tokens.push(Token::OpenParen);
-                chars.next();
-            }
-            ')' => {
-                tokens.push(Token::CloseParen);
-                chars.next();
-            }
-            '=' => {
-                tokens.push(Token::Equals);
-                chars.next();
-            }
-
-            // Strings
-            '"' | '\'' => {
-                let quote = chars.next().unwrap();
-                let mut value = String::new();
-                while let Some(&c) = chars.peek() {
-                    if c == quote {
-                        chars.next();
-                        break;
-                    }
-                    value.push(c);
-                    chars.next();
-                }
-                tokens.push(Token::String(value));
-            }
-
-            // Identifiers or booleans
-            _ if ch.is_alphabetic() || ch == '_' => {
-                let mut ident = String::new();
-                while let Some(&c) = chars.peek() {
-                    if c.is_alphanumeric() || c == '_' || c == '.' || c == '-' {
-                        ident.push(c);
-                        chars.next();
-                    } else {
-                        break;
-                    }
-                }
-
-                // Look ahead for ()
-                if chars.peek() == Some(&'(') {
-                    chars.next(); // consume (
-                    if chars.peek() == Some(&')') {
-                        chars.next(); // consume )
-                        tokens.push(Token::Identifier(ident));
-                        tokens.push(Token::OpenParen);
-                        tokens.push(Token::CloseParen);
-                        continue;
-                    } else {
-                        panic!("Unexpected char after '(': expected ')'");
-                    }
-                }
-
-                match ident.as_str() {
-                    "true" => tokens.push(Token::Bool(true)),
-                    "false" => tokens.push(Token::Bool(false)),
-                    _ => tokens.push(Token::Identifier(ident)),
-                }
-            }
-
-            _ => {
-                panic!("Unexpected character in input: {}", ch);
-            }
-        }
-    }
-
-    tokens
-}
-
-pub fn parse_tokens(tokens: &[Token], start: usize) -> Result<(DSLBlock, usize), String> {
-    let mut entries = HashMap::new();
+pub fn parse_tokens(tokens: &[Token], start: usize) -> Result<(DSLBlock, usize), ParseError> {
+    let mut entries = Entries::new();
     let mut i = start;
 
     while i < tokens.len() {
         match &tokens[i] {
+            Token::Newline => i += 1,
             Token::Identifier(key) => {
                 let key = key.clone();
                 i += 1;
 
-                // Handle block call
+                if key == "if" && matches!(tokens.get(i), Some(Token::Condition(_))) {
+                    i = parse_if(tokens, i, &[], &mut entries)?;
+                    continue;
+                }
+
+                // Handle block call, allowing the brace on the next line
                 if i >= tokens.len() {
-                    return Err(format!(
-                        "Expected token after identifier '{}', but reached end",
-                        key
-                    ));
+                    return Err(ParseError::UnexpectedEnd(key));
+                }
+                let brace = skip_newlines(tokens, i);
+                if let Some(Token::OpenBrace) = tokens.get(brace) {
+                    i = brace;
                 }
                 if let Token::OpenBrace = &tokens[i] {
                     let (nested_block, consumed) = parse_tokens(tokens, i + 1)?;
-                    entries.insert(
+                    entries.push(
                         key.clone(),
                         DSLValue::Block(DSLBlock {
                             name: key.clone(),
@@ -136,50 +44,98 @@ pub fn parse_tokens(tokens: &[Token], start: usize) -> Result<(DSLBlock, usize),
                 }
 
                 if matches!(tokens[i], Token::Equals) {
-                    i += 1;
-                    if let Token::String(s) = &tokens[i] {
-                        entries.insert(key, DSLValue::Assignment(s.clone()));
+                    i = skip_newlines(tokens, i + 1);
+                    if let Some((expr, next)) = parse_expression(tokens, i)?
+                        && !expr.rest.is_empty()
+                    {
+                        entries.push(key, DSLValue::Expression(expr));
+                        i = next;
+                        continue;
+                    }
+                    if let Some(Token::String(s)) = tokens.get(i) {
+                        entries.push(key, DSLValue::Assignment(s.clone()));
                         i += 1;
                         continue;
                     }
                 }
 
-                if let Token::String(val1) = &tokens[i] {
-                    if i + 2 < tokens.len() {
-                        if let Token::Identifier(subkey) = &tokens[i + 1] {
-                            if let Token::String(val2) = &tokens[i + 2] {
-                                let mut args = HashMap::new();
-                                args.insert("value".to_string(), DSLValue::String(val1.clone()));
-                                args.insert(subkey.clone(), DSLValue::String(val2.clone()));
-                                entries.insert(key, DSLValue::MultiArgs(args));
-                                i += 3;
-                                continue;
-                            }
-                        }
-                    }
+                // `implementation "g:a:" + v` is a call with one argument
+                if matches!(tokens.get(i + 1), Some(Token::Plus | Token::Elvis))
+                    && let Some((expr, next)) = parse_expression(tokens, i)?
+                {
+                    entries.push(key, DSLValue::FunctionCall(vec![expression_value(expr)]));
+                    i = next;
+                    continue;
+                }
+
+                // `implementation group: 'g', name: 'a'` and the same inside
+                // parentheses
+                if is_named_arg(tokens, i) {
+                    let (args, next) = parse_named_args(tokens, i, &key, false)?;
+                    entries.push(key, DSLValue::MultiArgs(args));
+                    i = next;
+                    continue;
+                }
+                if matches!(tokens.get(i), Some(Token::OpenParen)) && is_named_arg(tokens, i + 1) {
+                    let (args, next) = parse_named_args(tokens, i + 1, &key, true)?;
+                    entries.push(key, DSLValue::MultiArgs(args));
+                    i = next;
+                    continue;
+                }
+
+                // `id "a" version "b"`: only within one statement, so two
+                // adjacent single-argument lines never merge
+                if let Some(Token::String(val1)) = tokens.get(i)
+                    && i + 2 < tokens.len()
+                    && let Token::Identifier(subkey) = &tokens[i + 1]
+                    && let Token::String(val2) = &tokens[i + 2]
+                {
+                    let mut args = HashMap::new();
+                    args.insert("value".to_string(), DSLValue::String(val1.clone()));
+                    args.insert(subkey.clone(), DSLValue::String(val2.clone()));
+                    entries.push(key, DSLValue::MultiArgs(args));
+                    i += 3;
+                    continue;
                 }
 
-                if matches!(
-                    (&tokens[i], &tokens[i + 1]),
-                    (Token::OpenParen, Token::CloseParen)
-                ) {
-                    entries.insert(key, DSLValue::FunctionCall(vec![]));
-                    i += 2;
+                if let Some(Token::OpenParen) = tokens.get(i) {
+                    let (args, next) = parse_call_args(tokens, i, &key)?;
+                    let args = args.into_iter().map(expression_value).collect();
+                    entries.push(key, DSLValue::FunctionCall(args));
+                    i = next;
                     continue;
                 }
 
-                match &tokens[i] {
+                match tokens
+                    .get(i)
+                    .ok_or_else(|| ParseError::UnexpectedEnd(key.clone()))?
+                {
                     Token::String(s) => {
-                        entries.insert(key, DSLValue::String(s.clone()));
+                        entries.push(key, DSLValue::String(s.clone()));
                         i += 1;
                     }
                     Token::Bool(b) => {
-                        entries.insert(key, DSLValue::Bool(*b));
+                        entries.push(key, DSLValue::Bool(*b));
+                        i += 1;
+                    }
+                    Token::Number(n) => {
+                        entries.push(key, DSLValue::Number(n.clone()));
+                        i += 1;
+                    }
+                    // `implementation libs.retrofit`: a catalog or variable
+                    // reference, only when it ends the statement
+                    Token::Identifier(name)
+                        if matches!(
+                            tokens.get(i + 1),
+                            None | Some(Token::Newline | Token::CloseBrace)
+                        ) =>
+                    {
+                        entries.push(key, name_value(name.clone()));
                         i += 1;
                     }
                     Token::OpenBrace => {
                         let (nested, consumed) = parse_tokens(tokens, i + 1)?;
-                        entries.insert(
+                        entries.push(
                             key.clone(),
                             DSLValue::Block(DSLBlock {
                                 name: key.clone(),
@@ -188,7 +144,12 @@ pub fn parse_tokens(tokens: &[Token], start: usize) -> Result<(DSLBlock, usize),
                         );
                         i = consumed;
                     }
-                    _ => panic!("Unexpected token after identifier: {:?}", tokens[i]),
+                    other => {
+                        return Err(ParseError::UnexpectedToken(format!(
+                            "{:?} after identifier '{}'",
+                            other, key
+                        )));
+                    }
                 }
             }
             Token::CloseBrace => {
@@ -200,7 +161,7 @@ pub fn parse_tokens(tokens: &[Token], start: usize) -> Result<(DSLBlock, usize),
                     i + 1,
                 ));
             }
-            _ => panic!("Unexpected token: {:?}", tokens[i]),
+            other => return Err(ParseError::UnexpectedToken(format!("{:?}", other))),
         }
     }
 
@@ -213,11 +174,253 @@ pub fn parse_tokens(tokens: &[Token], start: usize) -> Result<(DSLBlock, usize),
     ))
 }
 
+/// Parses `operand (+|?: operand)*` starting at `i`, or returns `None` if
+/// no operand starts there.
+fn parse_expression(tokens: &[Token], i: usize) -> Result<Option<(Expression, usize)>, ParseError> {
+    let Some((first, mut i)) = parse_operand(tokens, i)? else {
+        return Ok(None);
+    };
+
+    let mut rest = Vec::new();
+    loop {
+        let op = match tokens.get(i) {
+            Some(Token::Plus) => Operator::Plus,
+            Some(Token::Elvis) => Operator::Elvis,
+            _ => break,
+        };
+        let (operand, next) =
+            parse_operand(tokens, skip_newlines(tokens, i + 1))?.ok_or_else(|| {
+                ParseError::UnexpectedToken(format!("expected operand after '{}'", op))
+            })?;
+        rest.push((op, operand));
+        i = next;
+    }
+
+    Ok(Some((Expression { first, rest }, i)))
+}
+
+fn parse_operand(tokens: &[Token], i: usize) -> Result<Option<(Operand, usize)>, ParseError> {
+    let operand = match tokens.get(i) {
+        Some(Token::String(s)) => (Operand::String(s.clone()), i + 1),
+        Some(Token::Bool(b)) => (Operand::Bool(*b), i + 1),
+        Some(Token::Number(n)) => (Operand::Number(n.clone()), i + 1),
+        Some(Token::Identifier(name)) => match tokens.get(i + 1) {
+            Some(Token::OpenParen) => {
+                let (args, next) = parse_call_args(tokens, i + 1, name)?;
+                let name = name.clone();
+                (Operand::Call { name, args }, next)
+            }
+            _ => (Operand::Reference(name.clone()), i + 1),
+        },
+        _ => return Ok(None),
+    };
+    Ok(Some(operand))
+}
+
+/// Parses a parenthesised, comma separated argument list; `i` points at the
+/// opening parenthesis.
+fn parse_call_args(
+    tokens: &[Token],
+    i: usize,
+    name: &str,
+) -> Result<(Vec<Expression>, usize), ParseError> {
+    let mut args = Vec::new();
+    let mut i = skip_newlines(tokens, i + 1);
+    if let Some(Token::CloseParen) = tokens.get(i) {
+        return Ok((args, i + 1));
+    }
+
+    loop {
+        let (arg, next) = parse_expression(tokens, i)?.ok_or_else(|| match tokens.get(i) {
+            Some(other) => {
+                ParseError::UnexpectedToken(format!("{:?} in arguments of '{}'", other, name))
+            }
+            None => ParseError::UnexpectedEnd(name.to_string()),
+        })?;
+        args.push(arg);
+
+        i = skip_newlines(tokens, next);
+        match tokens.get(i) {
+            Some(Token::Comma) => i = skip_newlines(tokens, i + 1),
+            Some(Token::CloseParen) => return Ok((args, i + 1)),
+            Some(other) => {
+                return Err(ParseError::UnexpectedToken(format!(
+                    "{:?} in arguments of '{}'",
+                    other, name
+                )));
+            }
+            None => return Err(ParseError::UnexpectedEnd(name.to_string())),
+        }
+    }
+}
+
+fn is_named_arg(tokens: &[Token], i: usize) -> bool {
+    matches!(
+        (tokens.get(i), tokens.get(i + 1)),
+        (Some(Token::Identifier(_)), Some(Token::Colon))
+    )
+}
+
+/// Parses `name: value, name: value`, starting at the first name. With
+/// `parenthesised` the list must end with `)`, which is consumed; otherwise
+/// it ends with the statement.
+fn parse_named_args(
+    tokens: &[Token],
+    mut i: usize,
+    key: &str,
+    parenthesised: bool,
+) -> Result<(HashMap<String, DSLValue>, usize), ParseError> {
+    let mut args = HashMap::new();
+
+    loop {
+        let Some(Token::Identifier(name)) = tokens.get(i) else {
+            return Err(match tokens.get(i) {
+                Some(other) => {
+                    ParseError::UnexpectedToken(format!("{:?} in arguments of '{}'", other, key))
+                }
+                None => ParseError::UnexpectedEnd(key.to_string()),
+            });
+        };
+        let value_at = skip_newlines(tokens, i + 2);
+        let (value, next) = parse_expression(tokens, value_at)?
+            .ok_or_else(|| ParseError::UnexpectedEnd(name.clone()))?;
+        args.insert(name.clone(), expression_value(value));
+
+        i = if parenthesised {
+            skip_newlines(tokens, next)
+        } else {
+            next
+        };
+        match tokens.get(i) {
+            Some(Token::Comma) => i = skip_newlines(tokens, i + 1),
+            Some(Token::CloseParen) if parenthesised => return Ok((args, i + 1)),
+            _ if !parenthesised => return Ok((args, i)),
+            Some(other) => {
+                return Err(ParseError::UnexpectedToken(format!(
+                    "{:?} in arguments of '{}'",
+                    other, key
+                )));
+            }
+            None => return Err(ParseError::UnexpectedEnd(key.to_string())),
+        }
+    }
+}
+
+/// Plain literals and references become their own [`DSLValue`]s; anything
+/// else stays an expression.
+fn expression_value(expr: Expression) -> DSLValue {
+    if !expr.rest.is_empty() {
+        return DSLValue::Expression(expr);
+    }
+    match expr.first {
+        Operand::String(s) => DSLValue::String(s),
+        Operand::Bool(b) => DSLValue::Bool(b),
+        Operand::Number(n) => DSLValue::Number(n),
+        Operand::Reference(name) => name_value(name),
+        call @ Operand::Call { .. } => DSLValue::Expression(Expression {
+            first: call,
+            rest: Vec::new(),
+        }),
+    }
+}
+
+/// A bare name in value position: a constant of a class such as
+/// `JavaVersion.VERSION_17` (an upper-case name qualified by a class name)
+/// becomes an [`DSLValue::Identifier`], anything else a
+/// [`DSLValue::Reference`], including upper-case properties of objects
+/// such as `deps.OKHTTP`.
+pub(crate) fn name_value(name: String) -> DSLValue {
+    let mut segments = name.rsplit('.');
+    let last = segments.next().unwrap_or_default();
+    let owner = segments.next().unwrap_or_default();
+    let is_constant = last.chars().any(|c| c.is_ascii_uppercase())
+        && last
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+    // `JavaVersion`, not `deps` or `LIBS`
+    let is_class = owner.starts_with(|c: char| c.is_ascii_uppercase())
+        && owner.chars().any(|c| c.is_ascii_lowercase());
+    if is_constant && is_class {
+        DSLValue::Identifier(name)
+    } else {
+        DSLValue::Reference(name)
+    }
+}
+
+/// Parses `(condition) { ... }` at `i`, which follows an `if`, and any
+/// `else if` / `else` branches after it. Each branch is pushed as a
+/// [`DSLValue::Conditional`] whose condition includes the negations of the
+/// branches before it (`negated`). Returns the index after the last branch.
+fn parse_if(
+    tokens: &[Token],
+    i: usize,
+    negated: &[String],
+    entries: &mut Entries,
+) -> Result<usize, ParseError> {
+    let Some(Token::Condition(condition)) = tokens.get(i) else {
+        return Err(ParseError::UnexpectedEnd("if".into()));
+    };
+    let mut conditions = negated.to_vec();
+    conditions.push(if negated.is_empty() {
+        condition.clone()
+    } else {
+        format!("({})", condition)
+    });
+    let next = parse_branch(tokens, i + 1, conditions.join(" && "), entries)?;
+
+    let after = skip_newlines(tokens, next);
+    if !matches!(tokens.get(after), Some(Token::Identifier(k)) if k == "else") {
+        return Ok(next);
+    }
+    let mut negated = negated.to_vec();
+    negated.push(format!("!({})", condition));
+    match (tokens.get(after + 1), tokens.get(after + 2)) {
+        (Some(Token::Identifier(k)), Some(Token::Condition(_))) if k == "if" => {
+            parse_if(tokens, after + 2, &negated, entries)
+        }
+        _ => parse_branch(tokens, after + 1, negated.join(" && "), entries),
+    }
+}
+
+/// Parses the `{ ... }` of an `if` or `else` branch at `i`.
+fn parse_branch(
+    tokens: &[Token],
+    i: usize,
+    condition: String,
+    entries: &mut Entries,
+) -> Result<usize, ParseError> {
+    let brace = skip_newlines(tokens, i);
+    match tokens.get(brace) {
+        Some(Token::OpenBrace) => {}
+        Some(other) => return Err(ParseError::UnexpectedToken(format!("{:?} after if", other))),
+        None => return Err(ParseError::UnexpectedEnd("if".into())),
+    }
+    let (block, next) = parse_tokens(tokens, brace + 1)?;
+    entries.push(
+        "if".to_string(),
+        DSLValue::Conditional {
+            condition,
+            block: DSLBlock {
+                name: "if".to_string(),
+                entries: block.entries,
+            },
+        },
+    );
+    Ok(next)
+}
+
+fn skip_newlines(tokens: &[Token], mut i: usize) -> usize {
+    while let Some(Token::Newline) = tokens.get(i) {
+        i += 1;
+    }
+    i
+}
+
 pub fn strip_comments(input: &str) -> String {
     input
         .lines()
         .map(|line| {
-            match line.find("//") {
+            match comment_start(line) {
                 Some(idx) => &line[..idx], // Cut at comment start
                 None => line,
             }
@@ -228,13 +431,35 @@ pub fn strip_comments(input: &str) -> String {
         .join("\n")
 }
 
+/// Byte offset of a `//` comment on `line`, ignoring `//` inside string
+/// literals such as URLs.
+fn comment_start(line: &str) -> Option<usize> {
+    let mut quote = None;
+    let mut prev = ' ';
+    for (idx, c) in line.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if c == q && prev != '\\' => quote = None,
+            (None, '/') if prev == '/' => return Some(idx - 1),
+            _ => {}
+        }
+        prev = c;
+    }
+    None
+}
+
 impl FromStr for DSLBlock {
-    type Err = String;
+    type Err = ParseError;
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(len = s.len()), err)
+    )]
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let clean = strip_comments(s);
-        let tokens = tokenize(&clean);
-        println!("TOKENS: {:#?}", tokens);
+        let tokens = try_tokenize(&clean)?;
+        #[cfg(feature = "tracing")]
+        tracing::trace!(count = tokens.len(), ?tokens, "tokenized gradle script");
         let (parsed, _) = parse_tokens(&tokens, 0)?;
         Ok(parsed)
     }
@@ -242,52 +467,92 @@ impl FromStr for DSLBlock {
 
 impl Display for DSLBlock {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        fn write_block(f: &mut Formatter<'_>, block: &DSLBlock, indent: usize) -> std::fmt::Result {
-            let pad = "    ".repeat(indent);
-            for (key, val) in &block.entries {
-                match val {
-                    DSLValue::String(s) => writeln!(f, "{}{} \"{}\"", pad, key, s)?,
-                    DSLValue::Bool(b) => writeln!(f, "{}{} {}", pad, key, b)?,
-                    DSLValue::Block(b) => {
-                        writeln!(f, "{}{} {{", pad, key)?;
-                        write_block(f, b, indent + 1)?;
-                        writeln!(f, "{}}}", pad)?;
-                    }
-                    DSLValue::Assignment(val) => writeln!(f, "{}{} = \"{}\"", pad, key, val)?,
-                    DSLValue::FunctionCall(args) => {
-                        if args.is_empty() {
-                            writeln!(f, "{}{}()", pad, key)?;
-                        } else {
-                            let arg_str = args
-                                .iter()
-                                .map(|v| match v {
-                                    DSLValue::String(s) => format!("\"{}\"", s),
-                                    DSLValue::Bool(b) => b.to_string(),
-                                    _ => "?".into(),
-                                })
-                                .collect::<Vec<_>>()
-                                .join(", ");
-                            write!(f, "{}{}({})", pad, key, arg_str)?;
-                        }
-                    }
-                    DSLValue::MultiArgs(map) => {
-                        for (subkey, subval) in map {
-                            if let DSLValue::String(s) = subval {
-                                writeln!(f, "{}{} {} \"{}\"", pad, key, subkey, s)?;
-                            }
-                        }
-                    }
+        write_block(f, self, 0, &FormatOptions::default(), false)
+    }
+}
+
+pub(crate) fn write_block(
+    f: &mut impl std::fmt::Write,
+    block: &DSLBlock,
+    indent: usize,
+    options: &FormatOptions,
+    in_dependencies: bool,
+) -> std::fmt::Result {
+    let pad = "    ".repeat(indent);
+    for (key, val) in &block.entries {
+        let normalized = in_dependencies
+            .then(|| options.dependency_style.apply(val))
+            .flatten();
+        match normalized.as_ref().unwrap_or(val) {
+            DSLValue::String(s) => writeln!(f, "{}{} {}", pad, key, quote(s, '"'))?,
+            DSLValue::Bool(b) => writeln!(f, "{}{} {}", pad, key, b)?,
+            DSLValue::Number(n) => writeln!(f, "{}{} {}", pad, key, n)?,
+            DSLValue::Block(b) => {
+                writeln!(f, "{}{} {{", pad, key)?;
+                write_block(f, b, indent + 1, options, key == "dependencies")?;
+                writeln!(f, "{}}}", pad)?;
+            }
+            DSLValue::Conditional { condition, block } => {
+                writeln!(f, "{}if ({}) {{", pad, condition)?;
+                write_block(f, block, indent + 1, options, in_dependencies)?;
+                writeln!(f, "{}}}", pad)?;
+            }
+            DSLValue::Assignment(val) => writeln!(f, "{}{} = {}", pad, key, quote(val, '"'))?,
+            DSLValue::Reference(name) => writeln!(f, "{}{} {}", pad, key, name)?,
+            // constants are assigned, as in `sourceCompatibility = JavaVersion.VERSION_17`
+            DSLValue::Identifier(name) => writeln!(f, "{}{} = {}", pad, key, name)?,
+            DSLValue::FunctionCall(args) => {
+                let arg_str = args.iter().map(inline).collect::<Vec<_>>().join(", ");
+                writeln!(f, "{}{}({})", pad, key, arg_str)?;
+            }
+            DSLValue::Expression(expr) => writeln!(f, "{}{} = {}", pad, key, expr)?,
+            // `id "a" version "b"`
+            DSLValue::MultiArgs(map) if map.contains_key("value") => {
+                write!(f, "{}{} {}", pad, key, inline(&map["value"]))?;
+                for (subkey, subval) in sorted_args(map).filter(|(k, _)| *k != "value") {
+                    write!(f, " {} {}", subkey, inline(subval))?;
                 }
+                writeln!(f)?;
+            }
+            // `implementation group: "g", name: "a"`
+            DSLValue::MultiArgs(map) => {
+                let args = sorted_args(map)
+                    .map(|(k, v)| format!("{}: {}", k, inline(v)))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                writeln!(f, "{}{} {}", pad, key, args)?;
             }
-            Ok(())
         }
+    }
+    Ok(())
+}
 
-        write_block(f, self, 0)
+/// A value as it appears in an argument list.
+fn inline(value: &DSLValue) -> String {
+    match value {
+        DSLValue::String(s) => quote(s, '"'),
+        DSLValue::Bool(b) => b.to_string(),
+        DSLValue::Number(n) | DSLValue::Reference(n) | DSLValue::Identifier(n) => n.clone(),
+        DSLValue::Expression(expr) => expr.to_string(),
+        _ => "?".into(),
     }
 }
 
+/// Named arguments with the coordinate parts first, in their usual order.
+fn sorted_args(map: &HashMap<String, DSLValue>) -> impl Iterator<Item = (&str, &DSLValue)> {
+    let rank = |k: &str| {
+        ["group", "name", "version"]
+            .iter()
+            .position(|c| *c == k)
+            .unwrap_or(3)
+    };
+    let mut args: Vec<_> = map.iter().map(|(k, v)| (k.as_str(), v)).collect();
+    args.sort_by(|(a, _), (b, _)| rank(a).cmp(&rank(b)).then(a.cmp(b)));
+    args.into_iter()
+}
+
 impl DSLBlock {
-    pub fn parse_str(source: &str) -> Result<Self, String> {
+    pub fn parse_str(source: &str) -> Result<Self, ParseError> {
         DSLBlock::from_str(source)
     }
 
@@ -295,12 +560,16 @@ impl DSLBlock {
         self.to_string()
     }
 
+    /// The value at `path`, the keys of the enclosing blocks followed by
+    /// the entry's own. Blocks are told apart by where they are, so
+    /// `["buildscript", "dependencies"]` and `["dependencies"]` are separate
+    /// blocks. Where a key repeats, the last statement is used.
     pub fn get(&self, path: &[&str]) -> Option<&DSLValue> {
-        let mut current = self.entries.get(path[0])?;
+        let mut current = self.entries.get(path.first()?)?;
 
         for key in &path[1..] {
             current = match current {
-                DSLValue::Block(block) => block.entries.get(*key)?,
+                DSLValue::Block(block) => block.entries.get(key)?,
                 _ => return None,
             }
         }
@@ -329,7 +598,7 @@ impl DSLBlock {
                     key_string.clone(),
                     DSLValue::Block(DSLBlock {
                         name: key_string.clone(),
-                        entries: HashMap::new(),
+                        entries: Entries::new(),
                     }),
                 );
             }
@@ -353,15 +622,80 @@ impl DSLBlock {
         let mut current = &mut self.entries;
 
         for key in &path[..path.len() - 1] {
-            current = match current.get_mut(*key) {
+            current = match current.get_mut(key) {
                 Some(DSLValue::Block(block)) => &mut block.entries,
                 _ => return Err(format!("Path segment '{}' is not a block", key)),
             }
         }
 
-        current.remove(*path.last().unwrap());
+        current.remove(path.last().unwrap());
         Ok(())
     }
+
+    /// Every entry at any depth, in source order, with the path of keys
+    /// leading to it.
+    ///
+    /// A block is yielded before its contents, and repeated keys are all
+    /// yielded, so `walk` sees each statement of the script exactly once.
+    /// The branches of an `if` are walked like blocks named `if`, so their
+    /// statements have `"if"` in their path.
+    pub fn walk(&self) -> impl Iterator<Item = (Vec<&str>, &DSLValue)> {
+        fn visit<'a>(
+            block: &'a DSLBlock,
+            prefix: &[&'a str],
+            out: &mut Vec<(Vec<&'a str>, &'a DSLValue)>,
+        ) {
+            for (key, value) in &block.entries {
+                let mut path = prefix.to_vec();
+                path.push(key.as_str());
+                match value {
+                    DSLValue::Block(nested) | DSLValue::Conditional { block: nested, .. } => {
+                        out.push((path.clone(), value));
+                        visit(nested, &path, out);
+                    }
+                    _ => out.push((path, value)),
+                }
+            }
+        }
+
+        let mut out = Vec::new();
+        visit(self, &[], &mut out);
+        out.into_iter()
+    }
+}
+
+impl ParsedFile for DSLBlock {
+    type Error = ParseError;
+    type Value = DSLValue;
+    type OwnedValue = DSLValue;
+
+    /// Through [`GradleBuild`](crate::GradleBuild) and
+    /// [`Script`](crate::Script) rather than this model.
+    const CAPABILITIES: Capabilities = Capabilities {
+        lossless_editing: true,
+        spans: true,
+        comments: true,
+    };
+
+    fn parse_str(src: &str) -> Result<Self, ParseError> {
+        DSLBlock::parse_str(src)
+    }
+
+    fn to_string_pretty(&self) -> String {
+        DSLBlock::to_string_pretty(self)
+    }
+
+    fn get(&self, path: &[&str]) -> Option<&DSLValue> {
+        DSLBlock::get(self, path)
+    }
+
+    fn set(&mut self, path: &[&str], value: DSLValue) -> Result<(), String> {
+        DSLBlock::set(self, path, value)
+    }
+
+    fn remove(&mut self, path: &[&str]) -> Result<(), String> {
+        DSLBlock::remove(self, path)
+    }
 }
 
 impl DSLValue {
@@ -379,6 +713,22 @@ impl DSLValue {
         }
     }
 
+    /// The referenced name for values like `libs.retrofit`.
+    pub fn as_reference(&self) -> Option<&str> {
+        match self {
+            DSLValue::Reference(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// The constant for enum-like values such as `JavaVersion.VERSION_17`.
+    pub fn as_identifier(&self) -> Option<&str> {
+        match self {
+            DSLValue::Identifier(name) => Some(name),
+            _ => None,
+        }
+    }
+
     pub fn as_block(&self) -> Option<&DSLBlock> {
         match self {
             DSLValue::Block(b) => Some(b),
@@ -397,6 +747,8 @@ impl DSLValue {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::lexer::tokenize;
+    use uniparse_core::DiagnosticCode;
 
     fn sample_input() -> &'static str {
         r#"
@@ -485,6 +837,39 @@ mod tests {
         assert!(block.get(&["application", "mainClassName"]).is_none());
     }
 
+    #[test]
+    fn test_buildscript_and_project_dependencies_stay_apart() {
+        let src = "dependencies {\n    implementation 'a:b:1'\n}\nbuildscript {\n    dependencies {\n        classpath 'c:d:2'\n    }\n}\n";
+        let mut block = DSLBlock::from_str(src).unwrap();
+        let classpath = |block: &DSLBlock| {
+            block
+                .get(&["buildscript", "dependencies", "classpath"])
+                .and_then(DSLValue::as_str)
+                .map(str::to_string)
+        };
+        assert_eq!(classpath(&block).as_deref(), Some("c:d:2"));
+        assert_eq!(
+            block
+                .get(&["dependencies", "implementation"])
+                .and_then(DSLValue::as_str),
+            Some("a:b:1")
+        );
+
+        block
+            .set(
+                &["buildscript", "dependencies", "classpath"],
+                DSLValue::String("c:d:3".into()),
+            )
+            .unwrap();
+        block.remove(&["dependencies", "implementation"]).unwrap();
+        assert_eq!(classpath(&block).as_deref(), Some("c:d:3"));
+        assert!(block.get(&["dependencies", "implementation"]).is_none());
+
+        let reparsed = DSLBlock::from_str(&block.to_string()).unwrap();
+        assert_eq!(reparsed, block);
+        assert_eq!(classpath(&reparsed).as_deref(), Some("c:d:3"));
+    }
+
     #[test]
     fn test_parse_assignment_and_function_call() {
         let block = DSLBlock::from_str(sample_input()).unwrap();
@@ -513,6 +898,122 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_multi_args_do_not_span_statements() {
+        let input = r#"
+        plugins {
+            id "org.jetbrains.kotlin.jvm" version "1.9.0"
+        }
+        repositories {
+            maven { url "https://repo.example.com" }
+            name "internal"
+        }
+        dependencies {
+            implementation "org.example:lib:1.2.3"
+            testImplementation "junit:junit:4.13"
+        }
+        "#;
+        let block = DSLBlock::from_str(input).unwrap();
+
+        match block.get(&["plugins", "id"]) {
+            Some(DSLValue::MultiArgs(args)) => {
+                assert_eq!(
+                    args.get("version").and_then(DSLValue::as_str),
+                    Some("1.9.0")
+                )
+            }
+            other => panic!("Expected MultiArgs, got {:?}", other),
+        }
+        assert_eq!(
+            block
+                .get(&["dependencies", "implementation"])
+                .and_then(DSLValue::as_str),
+            Some("org.example:lib:1.2.3")
+        );
+        assert_eq!(
+            block
+                .get(&["dependencies", "testImplementation"])
+                .and_then(DSLValue::as_str),
+            Some("junit:junit:4.13")
+        );
+        assert_eq!(
+            block
+                .get(&["repositories", "name"])
+                .and_then(DSLValue::as_str),
+            Some("internal")
+        );
+    }
+
+    #[test]
+    fn test_semicolons_separate_statements() {
+        let tokens = tokenize(r#"group "a"; version "b""#);
+        let (block, _) = parse_tokens(&tokens, 0).unwrap();
+
+        assert_eq!(
+            block.entries.get("group").and_then(DSLValue::as_str),
+            Some("a")
+        );
+        assert_eq!(
+            block.entries.get("version").and_then(DSLValue::as_str),
+            Some("b")
+        );
+    }
+
+    #[test]
+    fn test_expression_values() {
+        let input = r#"
+        version = project.findProperty('v') ?: '1.0'
+        archivesBaseName = "app-" + flavor
+        dependencies {
+            implementation "org.example:lib:" + libVersion
+            api("org.example:api:1.0")
+        }
+        "#;
+        let block = DSLBlock::from_str(input).unwrap();
+
+        let Some(DSLValue::Expression(version)) = block.get(&["version"]) else {
+            panic!("Expected expression, got {:?}", block.get(&["version"]));
+        };
+        assert_eq!(version.fallback(), Some("1.0"));
+        match &version.first {
+            Operand::Call { name, args } => {
+                assert_eq!(name, "project.findProperty");
+                assert_eq!(args[0].first, Operand::String("v".into()));
+            }
+            other => panic!("Expected call, got {:?}", other),
+        }
+
+        let Some(DSLValue::Expression(name)) = block.get(&["archivesBaseName"]) else {
+            panic!("Expected expression");
+        };
+        assert_eq!(name.literals().collect::<Vec<_>>(), vec!["app-"]);
+        assert_eq!(
+            name.rest,
+            vec![(Operator::Plus, Operand::Reference("flavor".into()))]
+        );
+
+        assert!(matches!(
+            block.get(&["dependencies", "implementation"]),
+            Some(DSLValue::FunctionCall(args)) if matches!(args[..], [DSLValue::Expression(_)])
+        ));
+        assert_eq!(
+            block.get(&["dependencies", "api"]),
+            Some(&DSLValue::FunctionCall(vec![DSLValue::String(
+                "org.example:api:1.0".into()
+            )]))
+        );
+
+        let output = block.to_string();
+        assert!(output.contains("version = project.findProperty(\"v\") ?: \"1.0\""));
+        assert!(output.contains("implementation(\"org.example:lib:\" + libVersion)"));
+    }
+
+    #[test]
+    fn test_unterminated_call_arguments() {
+        let err = DSLBlock::from_str("implementation(\"a:b:1\"").unwrap_err();
+        assert_eq!(err, ParseError::UnexpectedEnd("implementation".into()));
+    }
+
     #[test]
     fn test_empty_function_call() {
         let input = r#"deploy()"#;
@@ -544,6 +1045,19 @@ mod tests {
         let _ = tokenize("invalid$char");
     }
 
+    #[test]
+    fn test_parse_error_codes() {
+        let err = DSLBlock::parse_str("plugins").unwrap_err();
+        assert_eq!(err, ParseError::UnexpectedEnd("plugins".into()));
+        assert_eq!(err.code(), "GRADLE001");
+
+        let err = DSLBlock::parse_str("= \"x\"").unwrap_err();
+        assert_eq!(err.code(), "GRADLE002");
+
+        let diag = &crate::config_cache_issues("def a = System.getenv('A')")[0];
+        assert_eq!(diag.code(), "GRADLE003");
+    }
+
     #[test]
     fn test_display_output() {
         let block = DSLBlock::from_str(sample_input()).unwrap();
@@ -551,4 +1065,190 @@ mod tests {
         assert!(output.contains("mainClassName"));
         assert!(output.contains("buildDir"));
     }
+
+    #[test]
+    fn test_number_literals() {
+        let src = "android {\n    compileSdk 34\n    defaultConfig { minSdk = 21 }\n    versionCode(0x1F)\n}\n";
+        let block = DSLBlock::parse_str(src).unwrap();
+        assert_eq!(
+            block.get(&["android", "compileSdk"]),
+            Some(&DSLValue::Number("34".into()))
+        );
+        assert_eq!(
+            block.get(&["android", "defaultConfig", "minSdk"]),
+            Some(&DSLValue::Number("21".into()))
+        );
+        assert_eq!(
+            block.get_ref(&["android", "versionCode"]),
+            Some(uniparse_core::ValueRef::Number("0x1F"))
+        );
+        assert_eq!(crate::GradleBuild::parse(src).unwrap().to_string(), src);
+    }
+
+    #[test]
+    fn test_enum_constants_are_identifiers() {
+        let src = r#"
+        java {
+            sourceCompatibility = JavaVersion.VERSION_17
+            targetCompatibility(org.gradle.api.JavaVersion.VERSION_17)
+        }
+        tasks.jar { duplicatesStrategy DuplicatesStrategy.EXCLUDE }
+        dependencies {
+            implementation libs.retrofit
+            api deps.OKHTTP
+        }
+        "#;
+        let block = DSLBlock::parse_str(src).unwrap();
+
+        assert_eq!(
+            block
+                .get(&["java", "sourceCompatibility"])
+                .and_then(DSLValue::as_identifier),
+            Some("JavaVersion.VERSION_17")
+        );
+        assert_eq!(
+            block.get(&["java", "targetCompatibility"]),
+            Some(&DSLValue::FunctionCall(vec![DSLValue::Identifier(
+                "org.gradle.api.JavaVersion.VERSION_17".into()
+            )]))
+        );
+        assert_eq!(
+            block
+                .get(&["tasks.jar", "duplicatesStrategy"])
+                .and_then(DSLValue::as_identifier),
+            Some("DuplicatesStrategy.EXCLUDE")
+        );
+        assert_eq!(
+            block
+                .get(&["dependencies", "implementation"])
+                .and_then(DSLValue::as_reference),
+            Some("libs.retrofit")
+        );
+        assert_eq!(
+            block
+                .get(&["dependencies", "api"])
+                .and_then(DSLValue::as_reference),
+            Some("deps.OKHTTP")
+        );
+        assert_eq!(block.dependencies().len(), 2);
+
+        let output = block.to_string();
+        assert!(output.contains("sourceCompatibility = JavaVersion.VERSION_17\n"));
+        assert!(output.contains("duplicatesStrategy = DuplicatesStrategy.EXCLUDE\n"));
+        assert!(output.contains("targetCompatibility(org.gradle.api.JavaVersion.VERSION_17)\n"));
+        assert_eq!(DSLBlock::parse_str(&output).unwrap(), block);
+    }
+
+    #[test]
+    fn test_get_ref_reads_any_call_style() {
+        use uniparse_core::ValueRef;
+
+        let block = DSLBlock::parse_str(
+            r#"
+            android {
+                namespace("com.example")
+                compileSdk = "34"
+            }
+            dependencies {
+                implementation libs.retrofit
+                implementation group: "g", name: "a"
+            }
+            "#,
+        )
+        .unwrap();
+
+        let android = ParsedFile::get_ref(&block, &["android"]).unwrap();
+        assert_eq!(
+            android,
+            ValueRef::Map(vec![
+                ("namespace", ValueRef::Str("com.example")),
+                ("compileSdk", ValueRef::Str("34")),
+            ])
+        );
+        let ValueRef::Map(deps) = ParsedFile::get_ref(&block, &["dependencies"]).unwrap() else {
+            panic!("Expected a map");
+        };
+        assert_eq!(deps[0].1, ValueRef::Ident("libs.retrofit"));
+        assert_eq!(deps[1].1.get("name"), Some(&ValueRef::Str("a")));
+    }
+
+    #[test]
+    fn test_semantic_eq_ignores_statement_order_and_call_style() {
+        use uniparse_core::SemanticEq;
+
+        let a = DSLBlock::parse_str(
+            "android { compileSdk = \"34\"\n namespace \"com.example\" }\nversion = \"1.0\"",
+        )
+        .unwrap();
+        let b = DSLBlock::parse_str(
+            "version \"1.0\"\nandroid { namespace(\"com.example\")\n compileSdk \"34\" }",
+        )
+        .unwrap();
+        assert!(a.semantic_eq(&b));
+
+        let c = DSLBlock::parse_str(
+            "version \"1.1\"\nandroid { namespace \"com.example\"\n compileSdk \"34\" }",
+        )
+        .unwrap();
+        assert!(!a.semantic_eq(&c));
+    }
+
+    #[test]
+    fn test_walk_in_source_order_with_duplicates() {
+        let block = DSLBlock::parse_str(
+            r#"
+            version = "1.0"
+            dependencies {
+                implementation "a:a:1"
+                testImplementation "b:b:1"
+                implementation "c:c:1"
+            }
+            version = "2.0"
+            "#,
+        )
+        .unwrap();
+
+        let paths: Vec<String> = block.walk().map(|(path, _)| path.join(".")).collect();
+        assert_eq!(
+            paths,
+            [
+                "version",
+                "dependencies",
+                "dependencies.implementation",
+                "dependencies.testImplementation",
+                "dependencies.implementation",
+                "version",
+            ]
+        );
+
+        let deps = block
+            .get(&["dependencies"])
+            .and_then(DSLValue::as_block)
+            .unwrap();
+        assert_eq!(deps.entries.get_all("implementation").count(), 2);
+        assert_eq!(
+            block.get(&["version"]).and_then(DSLValue::as_str),
+            Some("2.0")
+        );
+        assert_eq!(block.dependencies().len(), 3);
+    }
+
+    #[test]
+    fn test_walk_into_if_branches() {
+        let block = DSLBlock::parse_str(
+            "dependencies {\n    if (ci) {\n        implementation 'a:a:1'\n    } else {\n        implementation 'b:b:1'\n    }\n}\n",
+        )
+        .unwrap();
+        let paths: Vec<String> = block.walk().map(|(path, _)| path.join(".")).collect();
+        assert_eq!(
+            paths,
+            [
+                "dependencies",
+                "dependencies.if",
+                "dependencies.if.implementation",
+                "dependencies.if",
+                "dependencies.if.implementation",
+            ]
+        );
+    }
 }
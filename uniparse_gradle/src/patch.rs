@@ -0,0 +1,540 @@
+use std::ops::Range;
+
+use uniparse_core::{CstKind, SourceEdit, SyntaxNode, whole_lines};
+
+use crate::cst::{GradleBuild, SyntaxKind};
+use crate::format::FormatOptions;
+use crate::model::{DSLBlock, DSLValue, Entries};
+use crate::parser::write_block;
+
+type Node = SyntaxNode<SyntaxKind>;
+type Edit = (Range<usize>, String);
+type Entry<'a> = (&'a String, &'a DSLValue);
+
+impl DSLBlock {
+    /// Writes this model into `original`, the script it was parsed from,
+    /// rewriting only the statements that changed.
+    ///
+    /// Statements of the [`GradleBuild`] tree are matched to entries in
+    /// order, an `if`/`else` chain to the entry of each of its branches. A
+    /// changed entry replaces its statement, or for blocks only the
+    /// statements inside it that changed; removed entries are deleted with
+    /// their lines and comments and new ones are inserted after the
+    /// statement before them, with its indentation. Comments and layout
+    /// everywhere else stay byte for byte as they were.
+    ///
+    /// Where statements can't be matched to entries one to one, only the
+    /// statements between the unchanged ones at the start and the end of
+    /// that block are rewritten. If `original` doesn't parse the result is
+    /// the [`Display`](std::fmt::Display) output.
+    ///
+    /// ```rust
+    /// use uniparse_gradle::{DSLBlock, DSLValue};
+    ///
+    /// let src = "// app module\nandroid {\n  compileSdk 33 // bump yearly\n  namespace = 'com.example'\n}\n";
+    /// let mut build = DSLBlock::parse_str(src).unwrap();
+    /// build.set(&["android", "compileSdk"], DSLValue::Number("34".into())).unwrap();
+    /// assert_eq!(
+    ///     build.patch_source(src),
+    ///     "// app module\nandroid {\n  compileSdk 34 // bump yearly\n  namespace = 'com.example'\n}\n"
+    /// );
+    /// ```
+    pub fn patch_source(&self, original: &str) -> String {
+        SourceEdit::apply(original, &self.patch_edits(original))
+    }
+
+    /// The edits [`DSLBlock::patch_source`] makes, in source order, e.g. to
+    /// send to an editor. If `original` doesn't parse, that is a single edit
+    /// replacing all of it.
+    pub fn patch_edits(&self, original: &str) -> Vec<SourceEdit> {
+        let (Ok(build), Ok(old)) = (GradleBuild::parse(original), DSLBlock::parse_str(original))
+        else {
+            return vec![SourceEdit::new(0..original.len(), self.to_string())];
+        };
+        let mut edits = Vec::new();
+        let parent = Parent {
+            start: 0,
+            pad: String::new(),
+        };
+        patch_statements(
+            original,
+            build.syntax(),
+            &old.entries,
+            &self.entries,
+            &parent,
+            &mut edits,
+        );
+
+        edits.sort_by_key(|(range, _)| (range.start, range.end));
+        edits
+            .into_iter()
+            .map(|(range, text)| SourceEdit::new(range, text))
+            .collect()
+    }
+}
+
+/// Where a list of statements lives: the first line its statements can
+/// start on, and the indentation of statements added to it when it has
+/// none to copy.
+struct Parent {
+    start: usize,
+    pad: String,
+}
+
+/// A statement and the number of entries the model reads from it: one, or
+/// one per branch of an `if`/`else` chain.
+struct Group {
+    node: Node,
+    key: String,
+    len: usize,
+}
+
+impl Group {
+    /// The statements directly inside `container`, a script or a block.
+    fn all(container: &Node) -> Vec<Group> {
+        container
+            .children()
+            .filter(|n| n.kind() == SyntaxKind::Statement)
+            .map(|node| {
+                let (key, chain) = {
+                    let mut tokens = node.tokens().filter(|t| !t.kind().is_trivia());
+                    let key = tokens
+                        .next()
+                        .filter(|t| t.kind() == SyntaxKind::Identifier)
+                        .map(|t| t.text().to_string())
+                        .unwrap_or_default();
+                    let chain = key == "if"
+                        && tokens
+                            .next()
+                            .is_some_and(|t| t.kind() == SyntaxKind::Condition);
+                    (key, chain)
+                };
+                let len = if chain { blocks(&node).len() } else { 1 };
+                Group { node, key, len }
+            })
+            .collect()
+    }
+
+    /// The statement without the comments above it and at its end.
+    fn code_range(&self) -> Range<usize> {
+        let mut tokens = self.node.tokens().filter(|t| !t.kind().is_trivia());
+        let start = tokens
+            .next()
+            .map_or(self.node.text_range().start, |t| t.text_range().start);
+        let end = tokens.last().map_or(start, |t| t.text_range().end);
+        start..end.max(start)
+    }
+
+    /// Whether `entries` are the ones this statement holds.
+    fn holds(&self, entries: &[Entry]) -> bool {
+        entries.len() == self.len && entries.iter().all(|(k, _)| **k == self.key)
+    }
+}
+
+/// The closures of a statement, one per branch of an `if`/`else` chain.
+fn blocks(statement: &Node) -> Vec<Node> {
+    statement
+        .children()
+        .filter(|n| n.kind() == SyntaxKind::Block)
+        .collect()
+}
+
+/// Edits turning the statements of `container`, which hold `old`, into
+/// `new`.
+fn patch_statements(
+    src: &str,
+    container: &Node,
+    old: &Entries,
+    new: &Entries,
+    parent: &Parent,
+    edits: &mut Vec<Edit>,
+) {
+    let groups = Group::all(container);
+    let old: Vec<_> = old.iter().collect();
+    let new: Vec<_> = new.iter().collect();
+    let mut spans = Vec::new();
+    let mut k = 0;
+    for group in &groups {
+        spans.push(k..(k + group.len).min(old.len()));
+        k += group.len;
+    }
+    let matched = k == old.len()
+        && groups
+            .iter()
+            .zip(&spans)
+            .all(|(g, r)| g.holds(&old[r.clone()]));
+    if !matched {
+        patch_between_unchanged(src, &groups, &old, &new, parent, edits);
+        return;
+    }
+
+    let mut anchor = parent.start;
+    let mut pad = parent.pad.clone();
+    let (mut i, mut j) = (0, 0);
+    while i < groups.len() || j < new.len() {
+        let group = groups.get(i);
+        if let Some(group) = group
+            && j < new.len()
+            && *new[j].0 == group.key
+        {
+            let taken = new[j..]
+                .iter()
+                .take(group.len)
+                .take_while(|(k, _)| **k == group.key)
+                .count();
+            pad = indentation(src, group.code_range().start).to_string();
+            patch_group(
+                src,
+                group,
+                &old[spans[i].clone()],
+                &new[j..j + taken],
+                &pad,
+                edits,
+            );
+            anchor = line_end(src, group.node.text_range().end);
+            i += 1;
+            j += taken;
+        } else if let Some(group) = group
+            && !new[j..].iter().any(|(k, _)| **k == group.key)
+        {
+            edits.push((whole_lines(src, group.node.text_range()), String::new()));
+            anchor = line_end(src, group.node.text_range().end);
+            i += 1;
+        } else {
+            edits.push(insertion(src, anchor, &render(&new[j..=j], &pad)));
+            j += 1;
+        }
+    }
+}
+
+/// Edits turning `group`, which holds `old`, into `new`: the statements of
+/// its blocks where the shape allows it, else the statement itself.
+fn patch_group(
+    src: &str,
+    group: &Group,
+    old: &[Entry],
+    new: &[Entry],
+    pad: &str,
+    edits: &mut Vec<Edit>,
+) {
+    if old == new {
+        return;
+    }
+    let blocks = blocks(&group.node);
+    let mut nested = Vec::new();
+    let patched = old.len() == new.len()
+        && blocks.len() == old.len()
+        && old.iter().zip(new).zip(&blocks).all(|((old, new), block)| {
+            let (old, new) = match (old.1, new.1) {
+                (DSLValue::Block(old), DSLValue::Block(new)) => (old, new),
+                (
+                    DSLValue::Conditional {
+                        condition: old_condition,
+                        block: old,
+                    },
+                    DSLValue::Conditional {
+                        condition: new_condition,
+                        block: new,
+                    },
+                ) if old_condition == new_condition => (old, new),
+                _ => return false,
+            };
+            patch_block(src, block, &old.entries, &new.entries, pad, &mut nested)
+        });
+    if patched {
+        edits.extend(nested);
+    } else {
+        edits.push((
+            group.code_range(),
+            render(new, pad)[pad.len()..].to_string(),
+        ));
+    }
+}
+
+/// Edits turning the statements of `block`, which hold `old`, into `new`.
+/// Returns `false` if that needs adding or removing statements in a block
+/// written on one line.
+fn patch_block(
+    src: &str,
+    block: &Node,
+    old: &Entries,
+    new: &Entries,
+    pad: &str,
+    edits: &mut Vec<Edit>,
+) -> bool {
+    let range = block.text_range();
+    let same_keys = old.len() == new.len() && old.keys().eq(new.keys());
+    if !same_keys && !src[range.clone()].contains('\n') {
+        return false;
+    }
+    let parent = Parent {
+        start: line_end(src, range.start),
+        pad: format!("{}    ", pad),
+    };
+    patch_statements(src, block, old, new, &parent, edits);
+    true
+}
+
+/// For statements that can't be matched to entries one to one: rewrites
+/// the statements between the unchanged ones at the start and at the end.
+fn patch_between_unchanged(
+    src: &str,
+    groups: &[Group],
+    old: &[Entry],
+    new: &[Entry],
+    parent: &Parent,
+    edits: &mut Vec<Edit>,
+) {
+    let unchanged = |group: &Group, old: Option<&[Entry]>, new: Option<&[Entry]>| {
+        old.zip(new)
+            .is_some_and(|(old, new)| group.holds(old) && old == new)
+    };
+    // groups and entries kept at the start
+    let (mut first, mut k) = (0, 0);
+    while let Some(group) = groups.get(first)
+        && unchanged(group, old.get(k..k + group.len), new.get(k..k + group.len))
+    {
+        k += group.len;
+        first += 1;
+    }
+    // and at the end, not overlapping those
+    let (mut last, mut from_end) = (groups.len(), 0);
+    while last > first {
+        let group = &groups[last - 1];
+        let n = from_end + group.len;
+        let (Some(o), Some(w)) = (old.len().checked_sub(n), new.len().checked_sub(n)) else {
+            break;
+        };
+        if o < k || w < k || !unchanged(group, old.get(o..o + group.len), new.get(w..w + group.len))
+        {
+            break;
+        }
+        from_end = n;
+        last -= 1;
+    }
+
+    let added = &new[k..new.len() - from_end];
+    match &groups[first..last] {
+        [] if added.is_empty() => {}
+        [] => {
+            let anchor = match first {
+                0 => parent.start,
+                _ => line_end(src, groups[first - 1].node.text_range().end),
+            };
+            let pad = match first {
+                0 => parent.pad.clone(),
+                _ => indentation(src, groups[first - 1].code_range().start).to_string(),
+            };
+            edits.push(insertion(src, anchor, &render(added, &pad)));
+        }
+        [head, .., tail] | [head @ tail] if added.is_empty() => {
+            let range = head.node.text_range().start..tail.node.text_range().end;
+            edits.push((whole_lines(src, range), String::new()));
+        }
+        [head, .., tail] | [head @ tail] => {
+            let start = head.code_range().start;
+            let pad = indentation(src, start);
+            let text = render(added, pad);
+            edits.push((start..tail.code_range().end, text[pad.len()..].to_string()));
+        }
+    }
+}
+
+/// An edit adding `text` as lines of their own at `anchor`, the start of a
+/// line.
+fn insertion(src: &str, anchor: usize, text: &str) -> Edit {
+    let mut text = format!("{}\n", text);
+    if anchor == src.len() && !src.is_empty() && !src.ends_with('\n') {
+        text.insert(0, '\n');
+    }
+    (anchor..anchor, text)
+}
+
+/// The entries as statements indented by `pad`, without a final line
+/// break.
+fn render(entries: &[Entry], pad: &str) -> String {
+    let mut block = DSLBlock {
+        name: String::new(),
+        entries: Entries::new(),
+    };
+    for (key, value) in entries {
+        block.entries.push((*key).clone(), (*value).clone());
+    }
+    let mut text = String::new();
+    // writing to a String can't fail
+    let _ = write_block(&mut text, &block, 0, &FormatOptions::default(), false);
+    text.trim_end()
+        .lines()
+        .map(|line| format!("{}{}", pad, line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The whitespace at the start of the line holding `at`.
+fn indentation(src: &str, at: usize) -> &str {
+    let start = src[..at].rfind('\n').map_or(0, |i| i + 1);
+    let line = &src[start..at];
+    &line[..line.len() - line.trim_start().len()]
+}
+
+/// The offset after the line break ending the line holding `at`.
+fn line_end(src: &str, at: usize) -> usize {
+    src[at..].find('\n').map_or(src.len(), |i| at + i + 1)
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SRC: &str = r#"plugins {
+  id 'java'
+}
+
+// keep in sync with the BOM
+dependencies {
+  implementation 'com.google.guava:guava:31.0-jre' // pinned
+  testImplementation 'junit:junit:4.12'
+}
+
+java {
+  toolchain { languageVersion = 17 }
+}
+version = '1.0.0'"#;
+
+    #[test]
+    fn test_patch_source_unchanged_is_identical() {
+        let build = DSLBlock::parse_str(SRC).unwrap();
+        assert_eq!(build.patch_source(SRC), SRC);
+    }
+
+    #[test]
+    fn test_patch_source_only_touches_changes() {
+        let mut build = DSLBlock::parse_str(SRC).unwrap();
+        build
+            .set(
+                &["dependencies", "implementation"],
+                DSLValue::String("com.google.guava:guava:33.0-jre".into()),
+            )
+            .unwrap();
+        build
+            .remove(&["dependencies", "testImplementation"])
+            .unwrap();
+        build
+            .set(
+                &["dependencies", "testRuntimeOnly"],
+                DSLValue::String("org.junit.platform:junit-platform-launcher".into()),
+            )
+            .unwrap();
+        build
+            .set(&["version"], DSLValue::Assignment("1.1.0".into()))
+            .unwrap();
+        build
+            .set(&["group"], DSLValue::Assignment("com.example".into()))
+            .unwrap();
+
+        let patched = build.patch_source(SRC);
+        assert_eq!(
+            patched,
+            r#"plugins {
+  id 'java'
+}
+
+// keep in sync with the BOM
+dependencies {
+  implementation "com.google.guava:guava:33.0-jre" // pinned
+  testRuntimeOnly "org.junit.platform:junit-platform-launcher"
+}
+
+java {
+  toolchain { languageVersion = 17 }
+}
+version = "1.1.0"
+group = "com.example"
+"#
+        );
+        assert_eq!(DSLBlock::parse_str(&patched).unwrap(), build);
+    }
+
+    #[test]
+    fn test_patch_source_keeps_if_else_chains() {
+        let src = "android {\n    if (ci) {\n        minify true\n    } else {\n        minify false\n    }\n    compileSdk 33\n}\n";
+        let mut build = DSLBlock::parse_str(src).unwrap();
+        build
+            .set(&["android", "compileSdk"], DSLValue::Number("34".into()))
+            .unwrap();
+        let patched = build.patch_source(src);
+        assert_eq!(patched, src.replace("compileSdk 33", "compileSdk 34"));
+        assert_eq!(DSLBlock::parse_str(&patched).unwrap(), build);
+        assert_eq!(build.patch_source("android {"), build.to_string());
+    }
+
+    #[test]
+    fn test_patch_source_keeps_untouched_bytes() {
+        let src = r#"// Copyright Example Corp.
+// SPDX-License-Identifier: Apache-2.0
+
+plugins { id 'java' }
+
+if (isCi) { // CI only
+    version = '1.0.0'
+} else {
+    version = '1.0.0-SNAPSHOT'
+}
+
+dependencies {
+    implementation 'com.google.guava:guava:31.0-jre' // pinned
+}
+"#;
+        let mut build = DSLBlock::parse_str(src).unwrap();
+        build
+            .set(
+                &["dependencies", "testImplementation"],
+                DSLValue::String("junit:junit:4.13.2".into()),
+            )
+            .unwrap();
+        let patched = build.patch_source(src);
+        assert_eq!(
+            patched,
+            src.replace(
+                "// pinned\n",
+                "// pinned\n    testImplementation \"junit:junit:4.13.2\"\n"
+            )
+        );
+        assert_eq!(DSLBlock::parse_str(&patched).unwrap(), build);
+
+        let Some(DSLValue::Conditional { block, .. }) = build.entries.get_mut("if") else {
+            panic!("no else branch");
+        };
+        block.entries.insert(
+            "version".into(),
+            DSLValue::Assignment("2.0.0-SNAPSHOT".into()),
+        );
+        let patched = build.patch_source(src);
+        assert_eq!(
+            patched,
+            src.replace("'1.0.0-SNAPSHOT'", "\"2.0.0-SNAPSHOT\"")
+                .replace(
+                    "// pinned\n",
+                    "// pinned\n    testImplementation \"junit:junit:4.13.2\"\n"
+                )
+        );
+        assert_eq!(DSLBlock::parse_str(&patched).unwrap(), build);
+    }
+
+    #[test]
+    fn test_patch_edits() {
+        let src = "android {\n    compileSdk 33\n}\n";
+        let mut build = DSLBlock::parse_str(src).unwrap();
+        build
+            .set(&["android", "compileSdk"], DSLValue::Number("34".into()))
+            .unwrap();
+        assert_eq!(
+            build.patch_edits(src),
+            [SourceEdit::new(14..27, "compileSdk 34")]
+        );
+    }
+}
@@ -0,0 +1,267 @@
+use crate::catalog::CatalogEntry;
+use crate::dependency::Resolver;
+use crate::model::{DSLBlock, DSLValue};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+/// A single entry of a `plugins { }` block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginRequest {
+    pub id: PluginId,
+    /// Version as written, if any. Core plugins such as `java` have none,
+    /// and community plugins may take theirs from `pluginManagement` or a
+    /// version catalog.
+    pub version: Option<String>,
+    /// `false` for `apply false`: the plugin is only put on the build
+    /// classpath, typically in a root project for its subprojects to apply.
+    pub applied: bool,
+}
+
+/// How a plugin request names its plugin.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PluginId {
+    /// A plugin id, e.g. `org.jetbrains.kotlin.jvm` for `id("...")` or
+    /// `kotlin("jvm")`.
+    Id(String),
+    /// A version catalog accessor, e.g. `libs.plugins.kotlin.jvm` for
+    /// `alias(libs.plugins.kotlin.jvm)`.
+    Alias(String),
+}
+
+impl PluginId {
+    /// The plugin id, unless this is a catalog alias that hasn't been
+    /// resolved; see [`Resolver::resolve_plugin`].
+    pub fn as_id(&self) -> Option<&str> {
+        match self {
+            PluginId::Id(id) => Some(id),
+            PluginId::Alias(_) => None,
+        }
+    }
+}
+
+impl Display for PluginId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PluginId::Id(id) => f.write_str(id),
+            PluginId::Alias(alias) => write!(f, "alias({})", alias),
+        }
+    }
+}
+
+impl PluginRequest {
+    /// A request for plugin `id` without a version, applied.
+    pub fn new(id: impl Into<String>) -> Self {
+        PluginRequest {
+            id: PluginId::Id(id.into()),
+            version: None,
+            applied: true,
+        }
+    }
+
+    /// The request with the id and version Gradle would actually use; see
+    /// [`Resolver::resolve_plugin`].
+    pub fn resolve(&self, resolver: &Resolver) -> PluginRequest {
+        resolver.resolve_plugin(self)
+    }
+}
+
+impl Resolver {
+    /// Looks catalog aliases up in [`catalogs`](Resolver::catalogs), and
+    /// fills in the version of a plugin request that doesn't specify one
+    /// from the catalog entry or [`plugin_versions`](Resolver::plugin_versions).
+    /// A version written in the build script wins, as it does in Gradle.
+    /// Aliases that aren't in any catalog are left as they are.
+    pub fn resolve_plugin(&self, plugin: &PluginRequest) -> PluginRequest {
+        let mut resolved = plugin.clone();
+        if let PluginId::Alias(alias) = &plugin.id
+            && let Some(CatalogEntry::Plugin(entry)) =
+                self.catalogs.iter().find_map(|c| c.lookup(alias))
+        {
+            resolved.id = PluginId::Id(entry.id.clone());
+            resolved.version = resolved.version.or_else(|| entry.version.clone());
+        }
+        if let Some(id) = resolved.id.as_id()
+            && resolved.version.is_none()
+        {
+            resolved.version = self.plugin_versions.get(id).cloned();
+        }
+        resolved
+    }
+}
+
+impl DSLBlock {
+    /// Entries of the top-level `plugins { }` block in declaration order,
+    /// with versions as written in this script.
+    ///
+    /// `id "x"`, `id("x")`, Kotlin's `kotlin("jvm")` shorthand and
+    /// `alias(libs.plugins.x)`, each optionally followed by `version "1.0"`
+    /// and `apply false`, are recognised; other entries are skipped.
+    pub fn plugins(&self) -> Vec<PluginRequest> {
+        let Some(DSLValue::Block(block)) = self.get(&["plugins"]) else {
+            return Vec::new();
+        };
+
+        let mut plugins: Vec<PluginRequest> = Vec::new();
+        for (key, value) in &block.entries {
+            let single = match value {
+                DSLValue::FunctionCall(args) if args.len() == 1 => &args[0],
+                other => other,
+            };
+
+            match (key.as_str(), value) {
+                ("id", DSLValue::MultiArgs(args)) => {
+                    if let Some(id) = args.get("value").and_then(DSLValue::as_str) {
+                        plugins.push(PluginRequest {
+                            version: args
+                                .get("version")
+                                .and_then(DSLValue::as_str)
+                                .map(str::to_string),
+                            ..PluginRequest::new(id)
+                        });
+                    }
+                }
+                ("id", _) => plugins.extend(single.as_str().map(PluginRequest::new)),
+                ("kotlin", _) => {
+                    plugins.extend(single.as_str().map(|module| {
+                        PluginRequest::new(format!("org.jetbrains.kotlin.{}", module))
+                    }))
+                }
+                ("alias", _) => {
+                    if let DSLValue::Reference(alias) = single {
+                        plugins.push(PluginRequest {
+                            id: PluginId::Alias(alias.clone()),
+                            version: None,
+                            applied: true,
+                        });
+                    }
+                }
+                // `id("x") version "1.0" apply false` is read as three
+                // statements
+                ("version", _) => {
+                    if let Some(last) = plugins.last_mut().filter(|p| p.version.is_none()) {
+                        last.version = single.as_str().map(str::to_string);
+                    }
+                }
+                ("apply", _) => {
+                    if let (Some(last), DSLValue::Bool(applied)) = (plugins.last_mut(), single) {
+                        last.applied = *applied;
+                    }
+                }
+                _ => {}
+            }
+        }
+        plugins
+    }
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plugins_in_both_dialects() {
+        let groovy = DSLBlock::parse_str(
+            r#"
+            plugins {
+                id 'java-library'
+                id 'com.diffplug.spotless' version '6.25.0'
+            }
+            "#,
+        )
+        .unwrap();
+        let kotlin = DSLBlock::parse_str(
+            r#"
+            plugins {
+                id("java-library")
+                id("com.diffplug.spotless") version "6.25.0"
+            }
+            "#,
+        )
+        .unwrap();
+
+        let expected = vec![
+            PluginRequest::new("java-library"),
+            PluginRequest {
+                version: Some("6.25.0".into()),
+                ..PluginRequest::new("com.diffplug.spotless")
+            },
+        ];
+        assert_eq!(groovy.plugins(), expected);
+        assert_eq!(kotlin.plugins(), expected);
+        assert!(DSLBlock::parse_str("").unwrap().plugins().is_empty());
+    }
+
+    #[test]
+    fn test_resolver_fills_in_managed_versions() {
+        let build = DSLBlock::parse_str(
+            "plugins {\n    kotlin(\"jvm\")\n    id 'org.x.y' version '2.0'\n}\n",
+        )
+        .unwrap();
+
+        let mut resolver = Resolver::default();
+        resolver
+            .plugin_versions
+            .insert("org.jetbrains.kotlin.jvm".into(), "1.9.22".into());
+        resolver
+            .plugin_versions
+            .insert("org.x.y".into(), "1.0".into());
+
+        let resolved: Vec<_> = build
+            .plugins()
+            .iter()
+            .map(|p| p.resolve(&resolver).version)
+            .collect();
+        assert_eq!(resolved, [Some("1.9.22".into()), Some("2.0".into())]);
+    }
+
+    #[test]
+    fn test_aliases_and_apply_false() {
+        let build = DSLBlock::parse_str(
+            r#"
+            plugins {
+                alias(libs.plugins.kotlin.jvm) apply false
+                id("com.diffplug.spotless") version "6.25.0" apply false
+                id 'org.x.y' version '1.0' apply false
+                alias(libs.plugins.unknown)
+            }
+            "#,
+        )
+        .unwrap();
+        let plugins = build.plugins();
+        assert_eq!(
+            plugins
+                .iter()
+                .map(|p| (p.id.to_string(), p.version.as_deref(), p.applied))
+                .collect::<Vec<_>>(),
+            [
+                ("alias(libs.plugins.kotlin.jvm)".into(), None, false),
+                ("com.diffplug.spotless".into(), Some("6.25.0"), false),
+                ("org.x.y".into(), Some("1.0"), false),
+                ("alias(libs.plugins.unknown)".into(), None, true),
+            ]
+        );
+
+        let catalog = crate::VersionCatalog::parse_toml(
+            "libs",
+            "[plugins]\nkotlin-jvm = { id = \"org.jetbrains.kotlin.jvm\", version = \"1.9.22\" }\n",
+        )
+        .unwrap();
+        let resolver = Resolver {
+            catalogs: vec![catalog],
+            ..Resolver::default()
+        };
+        assert_eq!(
+            plugins[0].resolve(&resolver),
+            PluginRequest {
+                version: Some("1.9.22".into()),
+                applied: false,
+                ..PluginRequest::new("org.jetbrains.kotlin.jvm")
+            }
+        );
+        assert_eq!(plugins[3].resolve(&resolver), plugins[3]);
+    }
+}
@@ -0,0 +1,502 @@
+use crate::catalog::VersionCatalog;
+use crate::dependency::Resolver;
+use crate::model::{DSLBlock, ParseError};
+use crate::plugins::PluginRequest;
+use crate::settings::GradleSettings;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use uniparse_core::CancellationToken;
+
+const SETTINGS_FILES: &[&str] = &["settings.gradle", "settings.gradle.kts"];
+const BUILD_FILES: &[&str] = &["build.gradle", "build.gradle.kts"];
+
+/// A Gradle build loaded from disk: the root project, its included
+/// subprojects, and the other places build logic may live.
+#[derive(Debug, Clone)]
+pub struct GradleProject {
+    pub root_dir: PathBuf,
+    /// Path of the settings script, if the build has one.
+    pub settings_file: Option<PathBuf>,
+    pub settings: GradleSettings,
+    /// The root project (path `:`) followed by every included subproject.
+    pub projects: Vec<ProjectNode>,
+    /// Composite builds pulled in with `includeBuild`.
+    pub included_builds: Vec<IncludedBuild>,
+    /// The `buildSrc` directory, if present.
+    pub build_src: Option<BuildSrc>,
+    /// `gradle/libs.versions.toml` (as `libs`) followed by the catalogs
+    /// declared in settings.
+    pub catalogs: Vec<VersionCatalog>,
+}
+
+/// A single project within a [`GradleProject`] tree.
+#[derive(Debug, Clone)]
+pub struct ProjectNode {
+    /// Gradle project path, e.g. `:` or `:libs:core`.
+    pub path: String,
+    pub dir: PathBuf,
+    pub build_file: Option<PathBuf>,
+    /// The parsed build script, when it exists and could be parsed.
+    pub build: Option<DSLBlock>,
+    /// Why the build script could not be parsed.
+    pub error: Option<ParseError>,
+}
+
+/// How far [`GradleProject::load_tree_with_progress`] has got.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LoadProgress {
+    /// Projects declared in settings, plus the root project.
+    pub discovered: usize,
+    /// Projects loaded so far, including those without a build script.
+    pub loaded: usize,
+    /// Build scripts that parsed.
+    pub parsed: usize,
+    /// Build scripts that couldn't be read or parsed.
+    pub failed: usize,
+}
+
+/// A composite build referenced from settings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IncludedBuild {
+    /// Path as written in the settings script.
+    pub path: String,
+    /// Directory resolved against the root project directory.
+    pub dir: PathBuf,
+    /// Whether it was included from `pluginManagement { }`.
+    pub plugin_build: bool,
+}
+
+/// The `buildSrc` directory and the convention plugins it provides.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuildSrc {
+    pub dir: PathBuf,
+    pub build_file: Option<PathBuf>,
+    /// Precompiled script plugins (`*.gradle` / `*.gradle.kts` under
+    /// `src/main/groovy` or `src/main/kotlin`).
+    pub convention_plugins: Vec<ConventionPlugin>,
+}
+
+/// A precompiled script plugin found in `buildSrc`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConventionPlugin {
+    /// Plugin id derived from the file name, e.g. `java-conventions`.
+    pub id: String,
+    pub file: PathBuf,
+}
+
+impl GradleProject {
+    /// Loads the project tree rooted at `root`.
+    ///
+    /// Build scripts that fail to parse are kept in the tree with their
+    /// [`ProjectNode::error`] set, so a single bad script doesn't hide the rest.
+    ///
+    /// # Errors
+    /// Returns an IO error if the settings script or the default version
+    /// catalog exists but can't be read, or the catalog is malformed.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(root = %root.as_ref().display()))
+    )]
+    pub fn load_tree(root: impl AsRef<Path>) -> io::Result<Self> {
+        Self::load_tree_cancellable(root, &CancellationToken::default())
+    }
+
+    /// Like [`GradleProject::load_tree`], checking `cancel` before each
+    /// build script.
+    ///
+    /// # Errors
+    /// Returns an [`io::ErrorKind::Interrupted`] error once `cancel` is
+    /// cancelled, and the errors of [`GradleProject::load_tree`].
+    pub fn load_tree_cancellable(
+        root: impl AsRef<Path>,
+        cancel: &CancellationToken,
+    ) -> io::Result<Self> {
+        Self::load_tree_with_progress(root, cancel, |_, _| {})
+    }
+
+    /// Like [`GradleProject::load_tree_cancellable`], calling `progress`
+    /// after each project is loaded with the counts so far and the project
+    /// itself, e.g. to draw a progress bar and list broken scripts as they
+    /// are found.
+    ///
+    /// ```rust
+    /// use uniparse_gradle::{CancellationToken, GradleProject};
+    ///
+    /// let dir = std::env::temp_dir();
+    /// let tree = GradleProject::load_tree_with_progress(&dir, &CancellationToken::new(), |p, project| {
+    ///     eprintln!("[{}/{}] {}", p.loaded, p.discovered, project.path);
+    /// });
+    /// ```
+    ///
+    /// # Errors
+    /// The errors of [`GradleProject::load_tree_cancellable`].
+    pub fn load_tree_with_progress(
+        root: impl AsRef<Path>,
+        cancel: &CancellationToken,
+        mut progress: impl FnMut(&LoadProgress, &ProjectNode),
+    ) -> io::Result<Self> {
+        let root_dir = root.as_ref().to_path_buf();
+
+        let settings_file = find_first(&root_dir, SETTINGS_FILES);
+        let settings = match &settings_file {
+            Some(path) => GradleSettings::parse_str(&fs::read_to_string(path)?),
+            None => GradleSettings::default(),
+        };
+
+        let mut discovered = vec![(":".to_string(), root_dir.clone())];
+        for include in &settings.includes {
+            let path = if include.starts_with(':') {
+                include.clone()
+            } else {
+                format!(":{}", include)
+            };
+            let dir = path
+                .split(':')
+                .filter(|s| !s.is_empty())
+                .fold(root_dir.clone(), |dir, segment| dir.join(segment));
+            discovered.push((path, dir));
+        }
+
+        let mut counts = LoadProgress {
+            discovered: discovered.len(),
+            ..LoadProgress::default()
+        };
+        let mut projects = Vec::with_capacity(discovered.len());
+        for (path, dir) in discovered {
+            cancel.check()?;
+            let project = ProjectNode::load(path, dir);
+            counts.loaded += 1;
+            counts.parsed += usize::from(project.build.is_some());
+            counts.failed += usize::from(project.error.is_some());
+            progress(&counts, &project);
+            projects.push(project);
+        }
+
+        let included_builds = settings
+            .included_builds
+            .iter()
+            .map(|decl| IncludedBuild {
+                path: decl.path.clone(),
+                dir: root_dir.join(&decl.path),
+                plugin_build: decl.plugin_build,
+            })
+            .collect();
+
+        let build_src = BuildSrc::detect(&root_dir.join("buildSrc"));
+
+        let mut catalogs = Vec::new();
+        let default_catalog = root_dir.join("gradle/libs.versions.toml");
+        if default_catalog.is_file() {
+            let source = fs::read_to_string(&default_catalog)?;
+            let catalog = VersionCatalog::parse_toml("libs", &source)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            catalogs.push(catalog);
+        }
+        catalogs.extend(settings.version_catalogs.iter().cloned());
+
+        Ok(GradleProject {
+            root_dir,
+            settings_file,
+            settings,
+            projects,
+            included_builds,
+            build_src,
+            catalogs,
+        })
+    }
+
+    /// Looks up a project by its Gradle path, e.g. `:app`.
+    pub fn project(&self, path: &str) -> Option<&ProjectNode> {
+        self.projects.iter().find(|p| p.path == path)
+    }
+
+    /// A [`Resolver`] for catalog references in this build's scripts.
+    pub fn resolver(&self) -> Resolver {
+        let plugin_versions = self
+            .settings
+            .plugin_management
+            .iter()
+            .filter_map(|p| Some((p.id.as_id()?.to_string(), p.version.clone()?)))
+            .collect();
+        Resolver {
+            catalogs: self.catalogs.clone(),
+            plugin_versions,
+            ..Resolver::default()
+        }
+    }
+
+    /// The plugins a project applies, with versions pinned in settings'
+    /// `pluginManagement { plugins { } }` filled in where the build script
+    /// leaves them out. Empty for unknown projects and unparsed scripts.
+    pub fn plugins(&self, path: &str) -> Vec<PluginRequest> {
+        let Some(build) = self.project(path).and_then(|p| p.build.as_ref()) else {
+            return Vec::new();
+        };
+        let resolver = self.resolver();
+        build
+            .plugins()
+            .iter()
+            .map(|p| p.resolve(&resolver))
+            .collect()
+    }
+
+    /// Directories outside the regular project list that may contain build
+    /// logic or dependency declarations: `buildSrc` and included builds.
+    pub fn build_logic_dirs(&self) -> Vec<&Path> {
+        self.build_src
+            .iter()
+            .map(|b| b.dir.as_path())
+            .chain(self.included_builds.iter().map(|b| b.dir.as_path()))
+            .collect()
+    }
+}
+
+impl ProjectNode {
+    fn load(path: String, dir: PathBuf) -> Self {
+        let build_file = find_first(&dir, BUILD_FILES);
+        let parsed = build_file.as_ref().map(|file| {
+            fs::read_to_string(file)
+                .map_err(|e| ParseError::Io(e.to_string()))
+                .and_then(|src| DSLBlock::parse_str(&src))
+        });
+
+        #[cfg(feature = "tracing")]
+        if let Some(Err(e)) = &parsed {
+            tracing::debug!(project = %path, error = %e, "build script failed to parse");
+        }
+
+        let (build, error) = match parsed {
+            Some(Ok(block)) => (Some(block), None),
+            Some(Err(e)) => (None, Some(e)),
+            None => (None, None),
+        };
+
+        ProjectNode {
+            path,
+            dir,
+            build_file,
+            build,
+            error,
+        }
+    }
+}
+
+impl BuildSrc {
+    fn detect(dir: &Path) -> Option<Self> {
+        if !dir.is_dir() {
+            return None;
+        }
+
+        let mut convention_plugins = Vec::new();
+        for lang in ["groovy", "kotlin"] {
+            collect_script_plugins(&dir.join("src/main").join(lang), &mut convention_plugins);
+        }
+        convention_plugins.sort_by(|a, b| a.id.cmp(&b.id));
+
+        Some(BuildSrc {
+            dir: dir.to_path_buf(),
+            build_file: find_first(dir, BUILD_FILES),
+            convention_plugins,
+        })
+    }
+}
+
+fn collect_script_plugins(dir: &Path, out: &mut Vec<ConventionPlugin>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for path in entries.flatten().map(|e| e.path()) {
+        if path.is_dir() {
+            collect_script_plugins(&path, out);
+            continue;
+        }
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let id = name
+            .strip_suffix(".gradle.kts")
+            .or_else(|| name.strip_suffix(".gradle"));
+        if let Some(id) = id {
+            out.push(ConventionPlugin {
+                id: id.to_string(),
+                file: path.clone(),
+            });
+        }
+    }
+}
+
+fn find_first(dir: &Path, names: &[&str]) -> Option<PathBuf> {
+    names.iter().map(|n| dir.join(n)).find(|p| p.is_file())
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(root: &Path, rel: &str, content: &str) {
+        let path = root.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_load_tree_with_composite_builds_and_build_src() {
+        let root = std::env::temp_dir().join("uniparse_gradle_load_tree");
+        let _ = fs::remove_dir_all(&root);
+        write(
+            &root,
+            "settings.gradle",
+            "include ':app', ':libs:core'\nincludeBuild '../platform'\n",
+        );
+        write(&root, "build.gradle", "plugins {\n id \"base\"\n}\n");
+        write(
+            &root,
+            "app/build.gradle",
+            "dependencies {\n implementation libs.retrofit\n}\n",
+        );
+        write(
+            &root,
+            "gradle/libs.versions.toml",
+            "[libraries]\nretrofit = \"com.squareup.retrofit2:retrofit:2.9.0\"\n",
+        );
+        write(&root, "libs/core/build.gradle.kts", "plugins { $ }\n");
+        write(&root, "buildSrc/build.gradle.kts", "");
+        write(
+            &root,
+            "buildSrc/src/main/groovy/java-conventions.gradle",
+            "plugins { id 'java' }\n",
+        );
+
+        let tree = GradleProject::load_tree(&root).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        let paths: Vec<_> = tree.projects.iter().map(|p| p.path.as_str()).collect();
+        assert_eq!(paths, vec![":", ":app", ":libs:core"]);
+        let app = tree.project(":app").unwrap().build.as_ref().unwrap();
+        let resolved = app.dependencies()[0].resolve(&tree.resolver()).unwrap();
+        assert_eq!(
+            resolved.to_string(),
+            "com.squareup.retrofit2:retrofit:2.9.0"
+        );
+
+        let core = tree.project(":libs:core").unwrap();
+        assert!(core.dir.ends_with("libs/core"));
+        assert!(core.build.is_none());
+        assert_eq!(core.error, Some(ParseError::UnexpectedCharacter('$')));
+
+        assert_eq!(tree.included_builds.len(), 1);
+        assert!(tree.included_builds[0].dir.ends_with("../platform"));
+
+        let build_src = tree.build_src.as_ref().unwrap();
+        assert!(build_src.build_file.is_some());
+        assert_eq!(build_src.convention_plugins[0].id, "java-conventions");
+        assert_eq!(tree.build_logic_dirs().len(), 2);
+    }
+
+    #[test]
+    fn test_load_tree_without_settings() {
+        let root = std::env::temp_dir().join("uniparse_gradle_no_settings");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+
+        let tree = GradleProject::load_tree(&root).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert!(tree.settings_file.is_none());
+        assert_eq!(tree.projects.len(), 1);
+        assert!(tree.projects[0].build_file.is_none());
+        assert!(tree.build_src.is_none());
+    }
+
+    #[test]
+    fn test_load_tree_reports_progress() {
+        let root = std::env::temp_dir().join("uniparse_gradle_progress");
+        let _ = fs::remove_dir_all(&root);
+        for dir in ["app", "lib"] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        fs::write(
+            root.join("settings.gradle"),
+            "include ':app', ':lib', ':docs'\n",
+        )
+        .unwrap();
+        fs::write(root.join("build.gradle"), "plugins {\n}\n").unwrap();
+        fs::write(root.join("app/build.gradle"), "version = '1.0'\n").unwrap();
+        fs::write(root.join("lib/build.gradle"), "version = $\n").unwrap();
+
+        let mut seen = Vec::new();
+        let tree = GradleProject::load_tree_with_progress(
+            &root,
+            &CancellationToken::new(),
+            |p, project| seen.push((*p, project.path.clone())),
+        );
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(tree.unwrap().projects.len(), 4);
+        let progress = |loaded, parsed, failed| LoadProgress {
+            discovered: 4,
+            loaded,
+            parsed,
+            failed,
+        };
+        assert_eq!(
+            seen,
+            [
+                (progress(1, 1, 0), ":".to_string()),
+                (progress(2, 2, 0), ":app".to_string()),
+                (progress(3, 2, 1), ":lib".to_string()),
+                (progress(4, 2, 1), ":docs".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_load_tree_cancelled() {
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let err = GradleProject::load_tree_cancellable(std::env::temp_dir(), &cancel).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::Interrupted);
+    }
+
+    #[test]
+    fn test_plugins_use_versions_pinned_in_settings() {
+        let root = std::env::temp_dir().join("uniparse_gradle_plugin_management");
+        let _ = fs::remove_dir_all(&root);
+        write(
+            &root,
+            "settings.gradle.kts",
+            "pluginManagement {\n    plugins {\n        id(\"com.diffplug.spotless\") version \"6.25.0\"\n    }\n}\ninclude(\"app\")\n",
+        );
+        write(
+            &root,
+            "app/build.gradle.kts",
+            "plugins {\n    id(\"java\")\n    id(\"com.diffplug.spotless\")\n}\n",
+        );
+
+        let tree = GradleProject::load_tree(&root).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        let plugins: Vec<_> = tree
+            .plugins(":app")
+            .into_iter()
+            .map(|p| (p.id.to_string(), p.version))
+            .collect();
+        assert_eq!(
+            plugins,
+            [
+                ("java".to_string(), None),
+                (
+                    "com.diffplug.spotless".to_string(),
+                    Some("6.25.0".to_string())
+                ),
+            ]
+        );
+        assert!(tree.plugins(":missing").is_empty());
+    }
+}
@@ -0,0 +1,289 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+use crate::project::GradleProject;
+
+/// The effective project properties of a build, merged from its
+/// `gradle.properties` files.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GradleProperties {
+    pub values: HashMap<String, String>,
+    /// The files that were read, lowest precedence first.
+    pub files: Vec<PathBuf>,
+}
+
+impl GradleProperties {
+    /// Parses a single file in Java `.properties` syntax: `key=value`,
+    /// `key: value` or `key value`, `#` and `!` comments, `\` line
+    /// continuations and escapes. A key set twice keeps its last value.
+    pub fn parse_str(source: &str) -> Self {
+        let mut values = HashMap::new();
+        let mut lines = source.lines();
+
+        while let Some(line) = lines.next() {
+            let mut logical = line.trim_start().to_string();
+            if logical.is_empty() || logical.starts_with(['#', '!']) {
+                continue;
+            }
+            while ends_with_continuation(&logical) {
+                logical.pop();
+                match lines.next() {
+                    Some(next) => logical.push_str(next.trim_start()),
+                    None => break,
+                }
+            }
+
+            let (key, value) = split_entry(&logical);
+            values.insert(unescape(key), unescape(value));
+        }
+
+        GradleProperties {
+            values,
+            files: Vec::new(),
+        }
+    }
+
+    /// Layers the `gradle.properties` files that apply to the project in
+    /// `project_dir`, in Gradle's order of precedence:
+    ///
+    /// 1. `gradle_user_home/gradle.properties` (usually `~/.gradle`),
+    /// 2. `project_dir/gradle.properties`, then those of the directories
+    ///    between it and `root_dir`,
+    /// 3. `root_dir/gradle.properties`.
+    ///
+    /// A property set in several files takes the value from the one listed
+    /// first. Files that don't exist are skipped.
+    ///
+    /// # Errors
+    /// Returns an IO error if one of the files exists but can't be read.
+    pub fn load(
+        project_dir: impl AsRef<Path>,
+        root_dir: impl AsRef<Path>,
+        gradle_user_home: Option<&Path>,
+    ) -> io::Result<Self> {
+        let project_dir = project_dir.as_ref();
+        let root_dir = root_dir.as_ref();
+
+        // root first, so directories closer to the project override it
+        let mut dirs: Vec<&Path> = project_dir
+            .ancestors()
+            .take_while(|dir| dir.starts_with(root_dir))
+            .collect();
+        if dirs.last() != Some(&root_dir) {
+            dirs.push(root_dir);
+        }
+        dirs.reverse();
+        dirs.extend(gradle_user_home);
+
+        let mut merged = GradleProperties::default();
+        for dir in dirs {
+            let path = dir.join("gradle.properties");
+            let source = match fs::read_to_string(&path) {
+                Ok(source) => source,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            };
+            merged.values.extend(Self::parse_str(&source).values);
+            merged.files.push(path);
+        }
+        Ok(merged)
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(String::as_str)
+    }
+
+    /// Replaces `$name` and `${name}` in a Groovy string with the property
+    /// values. Unknown names are left as written.
+    pub fn interpolate(&self, text: &str) -> String {
+        interpolate(text, &self.values)
+    }
+}
+
+impl GradleProject {
+    /// The effective properties of the project at Gradle path `path`; see
+    /// [`GradleProperties::load`]. Unknown projects get the root
+    /// directory's properties.
+    ///
+    /// # Errors
+    /// Returns an IO error if a properties file exists but can't be read.
+    pub fn properties(
+        &self,
+        path: &str,
+        gradle_user_home: Option<&Path>,
+    ) -> io::Result<GradleProperties> {
+        let dir = self.project(path).map_or(&self.root_dir, |p| &p.dir);
+        GradleProperties::load(dir, &self.root_dir, gradle_user_home)
+    }
+}
+
+/// Replaces `$name` and `${name}` with values from `properties`.
+pub(crate) fn interpolate(text: &str, properties: &HashMap<String, String>) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(dollar) = rest.find('$') {
+        out.push_str(&rest[..dollar]);
+        let after = &rest[dollar + 1..];
+        let (name, len) = match after.strip_prefix('{') {
+            Some(braced) => match braced.find('}') {
+                Some(end) => (&braced[..end], end + 2),
+                None => ("", 0),
+            },
+            None => {
+                let end = after
+                    .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+                    .unwrap_or(after.len());
+                let name = after[..end].trim_end_matches('.');
+                (name, name.len())
+            }
+        };
+
+        match properties.get(name) {
+            Some(value) if !name.is_empty() => out.push_str(value),
+            _ => out.push_str(&rest[dollar..dollar + 1 + len]),
+        }
+        rest = &after[len..];
+    }
+
+    out.push_str(rest);
+    out
+}
+
+fn ends_with_continuation(line: &str) -> bool {
+    let backslashes = line.chars().rev().take_while(|&c| c == '\\').count();
+    backslashes % 2 == 1
+}
+
+/// Splits a logical line at the first unescaped `=`, `:` or whitespace.
+fn split_entry(line: &str) -> (&str, &str) {
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match c {
+            '\\' => escaped = true,
+            '=' | ':' => return (line[..i].trim_end(), line[i + 1..].trim_start()),
+            c if c.is_whitespace() => {
+                let value = line[i..].trim_start();
+                let value = value
+                    .strip_prefix(['=', ':'])
+                    .map_or(value, str::trim_start);
+                return (&line[..i], value);
+            }
+            _ => {}
+        }
+    }
+    (line, "")
+}
+
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some('r') => out.push('\r'),
+            Some('f') => out.push('\u{c}'),
+            Some('u') => {
+                let hex: String = chars.by_ref().take(4).collect();
+                match u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                    Some(decoded) => out.push(decoded),
+                    None => {
+                        out.push_str("\\u");
+                        out.push_str(&hex);
+                    }
+                }
+            }
+            Some(other) => out.push(other),
+            None => {}
+        }
+    }
+    out
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_properties_syntax() {
+        let props = GradleProperties::parse_str(
+            "# comment\n\
+             ! also a comment\n\
+             org.gradle.jvmargs=-Xmx2g \\\n    -Dfile.encoding=UTF-8\n\
+             kotlinVersion: 1.9.0\n\
+             group com.example\n\
+             path\\=with\\:colon = a\\tb\n\
+             unicode=caf\\u00e9\n\
+             empty=\n\
+             kotlinVersion=2.0.0\n",
+        );
+
+        assert_eq!(
+            props.get("org.gradle.jvmargs"),
+            Some("-Xmx2g -Dfile.encoding=UTF-8")
+        );
+        assert_eq!(props.get("kotlinVersion"), Some("2.0.0"));
+        assert_eq!(props.get("group"), Some("com.example"));
+        assert_eq!(props.get("path=with:colon"), Some("a\tb"));
+        assert_eq!(props.get("unicode"), Some("café"));
+        assert_eq!(props.get("empty"), Some(""));
+        assert_eq!(props.values.len(), 6);
+    }
+
+    #[test]
+    fn test_load_layers_files_by_precedence() {
+        // one directory per process, so concurrent test runs don't share it
+        let root =
+            std::env::temp_dir().join(format!("uniparse_gradle_properties_{}", std::process::id()));
+        let home = root.join("home");
+        let app = root.join("libs/app");
+        fs::create_dir_all(&app).unwrap();
+        fs::create_dir_all(&home).unwrap();
+        fs::write(
+            root.join("gradle.properties"),
+            "a=root\nb=root\nc=root\nd=root\n",
+        )
+        .unwrap();
+        fs::write(root.join("libs/gradle.properties"), "b=libs\nc=libs\n").unwrap();
+        fs::write(app.join("gradle.properties"), "c=app\n").unwrap();
+        fs::write(home.join("gradle.properties"), "d=home\n").unwrap();
+
+        let props = GradleProperties::load(&app, &root, Some(&home)).unwrap();
+        let _ = fs::remove_dir_all(&root);
+
+        assert_eq!(props.get("a"), Some("root"));
+        assert_eq!(props.get("b"), Some("libs"));
+        assert_eq!(props.get("c"), Some("app"));
+        assert_eq!(props.get("d"), Some("home"));
+        assert_eq!(props.files.len(), 4);
+        assert_eq!(props.files[0], root.join("gradle.properties"));
+    }
+
+    #[test]
+    fn test_interpolate() {
+        let props = GradleProperties::parse_str("okhttpVersion=4.12.0\nv=1\n");
+        assert_eq!(
+            props.interpolate("com.squareup.okhttp3:okhttp:$okhttpVersion"),
+            "com.squareup.okhttp3:okhttp:4.12.0"
+        );
+        assert_eq!(
+            props.interpolate("g:a:${v}.0 $unknown ${v"),
+            "g:a:1.0 $unknown ${v"
+        );
+        assert_eq!(props.interpolate("cost: $"), "cost: $");
+    }
+}
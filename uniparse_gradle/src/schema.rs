@@ -0,0 +1,156 @@
+use uniparse_core::{AsValueRef, DiagnosticCode, Schema, SchemaViolation};
+
+use crate::analysis::Span;
+use crate::ast::{Script, Statement, StatementKind};
+use crate::model::{DSLBlock, ParseError};
+
+/// A [`SchemaViolation`] with the place in the script it is about.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpannedViolation {
+    pub violation: SchemaViolation,
+    /// The statement at the violation's path or, for a missing key, the
+    /// innermost enclosing statement that exists. `None` for a key missing
+    /// at the top level of the script.
+    pub span: Option<Span>,
+}
+
+impl DiagnosticCode for SpannedViolation {
+    fn code(&self) -> &'static str {
+        self.violation.code()
+    }
+}
+
+impl DSLBlock {
+    /// Checks the script against a [`Schema`]. Blocks are maps keyed by
+    /// statement name, so `android.compileSdk` is the path `["android",
+    /// "compileSdk"]`; a repeated statement is checked by its last value.
+    pub fn validate_schema(&self, schema: &Schema) -> Vec<SchemaViolation> {
+        schema.validate(&self.as_value_ref())
+    }
+}
+
+/// Parses `source` and checks it against `schema` like
+/// [`DSLBlock::validate_schema`], locating every violation in the source.
+///
+/// ```rust
+/// use uniparse_gradle::{Schema, ValueKind, validate_schema};
+///
+/// let schema = Schema::default().required(
+///     "android",
+///     Schema::default().required("compileSdk", Schema::of(ValueKind::Number).at_least(34.0)),
+/// );
+/// let issues = validate_schema("android {\n    compileSdk 33\n}\n", &schema).unwrap();
+/// assert_eq!(issues[0].violation.to_string(), "`android.compileSdk`: 33 is less than the minimum 34");
+/// assert_eq!(issues[0].span.as_ref().unwrap().line, 2);
+/// ```
+pub fn validate_schema(source: &str, schema: &Schema) -> Result<Vec<SpannedViolation>, ParseError> {
+    let block = DSLBlock::parse_str(source)?;
+    let script = Script::parse(source)?;
+    Ok(block
+        .validate_schema(schema)
+        .into_iter()
+        .map(|violation| SpannedViolation {
+            span: locate(&script.statements, &violation.path),
+            violation,
+        })
+        .collect())
+}
+
+/// The span of the deepest statement along `path`, taking the last
+/// statement of a name like [`DSLBlock::validate_schema`] does.
+fn locate(statements: &[Statement], path: &[String]) -> Option<Span> {
+    let (first, rest) = path.split_first()?;
+    let statement = statements
+        .iter()
+        .rev()
+        .find(|s| statement_name(s) == Some(first.as_str()))?;
+    let nested = match &statement.kind {
+        StatementKind::Call(call) => call.closure.as_ref(),
+        _ => None,
+    };
+    nested
+        .and_then(|block| locate(&block.statements, rest))
+        .or_else(|| Some(statement.span.clone()))
+}
+
+fn statement_name(statement: &Statement) -> Option<&str> {
+    match &statement.kind {
+        StatementKind::Assignment { target, .. } => Some(&target.text),
+        StatementKind::Call(call) => Some(&call.name.text),
+    }
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uniparse_core::{ValueKind, ViolationKind};
+
+    #[test]
+    fn test_violations_point_at_statements() {
+        let src = "\
+plugins {
+    id 'com.android.application'
+}
+
+android {
+    compileSdk 33
+    defaultConfig {
+        minSdk = 0x15
+        targetSdk 'latest'
+    }
+}
+";
+        let schema = Schema::default()
+            .required(
+                "android",
+                Schema::default()
+                    .required("compileSdk", Schema::of(ValueKind::Number).at_least(34.0))
+                    .required("namespace", Schema::of(ValueKind::Str))
+                    .field(
+                        "defaultConfig",
+                        Schema::default()
+                            .required("minSdk", Schema::of(ValueKind::Number).at_least(24.0))
+                            .field("targetSdk", Schema::of(ValueKind::Number)),
+                    ),
+            )
+            .required("dependencies", Schema::of(ValueKind::Map));
+
+        let issues = validate_schema(src, &schema).unwrap();
+        let found: Vec<_> = issues
+            .iter()
+            .map(|i| {
+                (
+                    i.violation.path.join("."),
+                    i.code(),
+                    i.span.as_ref().map(|s| (s.line, s.column)),
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("dependencies".to_string(), "SCHEMA001", None),
+                ("android.namespace".to_string(), "SCHEMA001", Some((5, 1))),
+                ("android.compileSdk".to_string(), "SCHEMA004", Some((6, 5))),
+                (
+                    "android.defaultConfig.minSdk".to_string(),
+                    "SCHEMA004",
+                    Some((8, 9))
+                ),
+                (
+                    "android.defaultConfig.targetSdk".to_string(),
+                    "SCHEMA002",
+                    Some((9, 9))
+                ),
+            ]
+        );
+        assert!(matches!(
+            &issues[3].violation.kind,
+            ViolationKind::BelowMinimum { found, .. } if found == "0x15"
+        ));
+    }
+}
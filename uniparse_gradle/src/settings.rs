@@ -0,0 +1,391 @@
+use crate::analysis::{for_each_word, mask_source, word_at};
+use crate::catalog::{CatalogPlugin, VersionCatalog};
+use crate::dependency::Coordinates;
+use crate::plugins::PluginRequest;
+use serde::{Deserialize, Serialize};
+
+/// The parts of a `settings.gradle(.kts)` file that shape the project tree.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GradleSettings {
+    /// Value of `rootProject.name`, if set.
+    pub root_project_name: Option<String>,
+    /// Project paths passed to `include`, e.g. `:app` or `:libs:core`.
+    pub includes: Vec<String>,
+    /// Composite builds declared with `includeBuild`.
+    pub included_builds: Vec<IncludedBuildDecl>,
+    /// Catalogs declared in `dependencyResolutionManagement { versionCatalogs { } }`.
+    pub version_catalogs: Vec<VersionCatalog>,
+    /// Plugins declared in `pluginManagement { plugins { } }`, which pin
+    /// versions for every project in the build.
+    pub plugin_management: Vec<PluginRequest>,
+}
+
+/// A single `includeBuild("...")` declaration.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IncludedBuildDecl {
+    /// Path as written, relative to the settings file.
+    pub path: String,
+    /// Whether the build was included inside `pluginManagement { }`, i.e. it
+    /// contributes plugins rather than regular dependencies.
+    pub plugin_build: bool,
+}
+
+impl GradleSettings {
+    /// Extracts project structure declarations from a settings script.
+    ///
+    /// Only literal string arguments are recognised; computed project names
+    /// are skipped.
+    pub fn parse_str(source: &str) -> Self {
+        let masked = mask_source(source);
+        let mut settings = GradleSettings::default();
+        // the library or plugin a chained `.version(..)` applies to
+        let mut last_entry: Option<CatalogTarget> = None;
+        let mut version_refs: Vec<(CatalogTarget, String)> = Vec::new();
+
+        for_each_word(&masked, |start, after_dot, blocks| {
+            let word = word_at(&masked[start..]);
+            let args_start = start + word.len();
+            let args = || string_args(source, &masked, args_start);
+
+            if let Some(catalog) = catalog_index(&mut settings.version_catalogs, blocks) {
+                let entry = &mut settings.version_catalogs[catalog];
+                match (after_dot, word, args().as_slice()) {
+                    (false, "version", [alias, version]) => entry.add_version(alias, version),
+                    (false, "library", [alias, notation]) => {
+                        if let Some(coords) = Coordinates::parse(notation) {
+                            entry.add_library(alias, coords);
+                            last_entry = Some(CatalogTarget::library(catalog, alias));
+                        }
+                    }
+                    (false, "library", [alias, group, name]) => {
+                        let coords = Coordinates {
+                            group: group.clone(),
+                            name: name.clone(),
+                            version: None,
+                        };
+                        entry.add_library(alias, coords);
+                        last_entry = Some(CatalogTarget::library(catalog, alias));
+                    }
+                    (false, "plugin", [alias, id]) => {
+                        let plugin = CatalogPlugin {
+                            id: id.clone(),
+                            version: None,
+                        };
+                        entry.add_plugin(alias, plugin);
+                        last_entry = Some(CatalogTarget::plugin(catalog, alias));
+                    }
+                    (false, "bundle", [alias, libraries @ ..]) => {
+                        entry.add_bundle(alias, libraries)
+                    }
+                    (true, "version", [version]) => {
+                        if let Some(target) = &last_entry {
+                            target.set_version(&mut settings.version_catalogs, version.clone());
+                        }
+                    }
+                    (true, "versionRef", [version_ref]) => {
+                        if let Some(target) = last_entry.clone() {
+                            version_refs.push((target, version_ref.clone()));
+                        }
+                    }
+                    _ => {}
+                }
+                return;
+            }
+
+            if after_dot {
+                return;
+            }
+            if let [.., parent, block] = blocks
+                && parent == "pluginManagement"
+                && block == "plugins"
+            {
+                let plugins = &mut settings.plugin_management;
+                match (word, args().into_iter().next()) {
+                    ("id", Some(id)) => plugins.push(PluginRequest::new(id)),
+                    ("kotlin", Some(module)) => plugins.push(PluginRequest::new(format!(
+                        "org.jetbrains.kotlin.{}",
+                        module
+                    ))),
+                    ("version", Some(version)) => {
+                        if let Some(last) = plugins.last_mut() {
+                            last.version = Some(version);
+                        }
+                    }
+                    _ => {}
+                }
+                return;
+            }
+            match word {
+                "include" => settings.includes.extend(args()),
+                "includeBuild" => {
+                    let plugin_build = blocks.iter().any(|b| b == "pluginManagement");
+                    settings.included_builds.extend(
+                        args()
+                            .into_iter()
+                            .map(|path| IncludedBuildDecl { path, plugin_build }),
+                    );
+                }
+                "rootProject.name" => settings.root_project_name = args().into_iter().next(),
+                // `create("libs") { }` inside `versionCatalogs { }`
+                "create" | "register" if blocks.last().is_some_and(|b| b == "versionCatalogs") => {
+                    if let Some(name) = args().into_iter().next() {
+                        settings.version_catalogs.push(VersionCatalog::new(name));
+                    }
+                }
+                _ => {}
+            }
+        });
+
+        // versions may be declared after the entries referencing them
+        for (target, version_ref) in version_refs {
+            let catalog = &settings.version_catalogs[target.catalog];
+            if let Some(version) = catalog.version(&version_ref).map(str::to_string) {
+                target.set_version(&mut settings.version_catalogs, version);
+            }
+        }
+
+        settings
+    }
+}
+
+/// A library or plugin alias within one of the settings' catalogs.
+#[derive(Clone)]
+struct CatalogTarget {
+    catalog: usize,
+    plugin: bool,
+    alias: String,
+}
+
+impl CatalogTarget {
+    fn library(catalog: usize, alias: &str) -> Self {
+        CatalogTarget {
+            catalog,
+            plugin: false,
+            alias: alias.to_string(),
+        }
+    }
+
+    fn plugin(catalog: usize, alias: &str) -> Self {
+        CatalogTarget {
+            catalog,
+            plugin: true,
+            alias: alias.to_string(),
+        }
+    }
+
+    fn set_version(&self, catalogs: &mut [VersionCatalog], version: String) {
+        let catalog = &mut catalogs[self.catalog];
+        if self.plugin {
+            if let Some(mut plugin) = catalog.plugin(&self.alias).cloned() {
+                plugin.version = Some(version);
+                catalog.add_plugin(&self.alias, plugin);
+            }
+        } else if let Some(mut coords) = catalog.library(&self.alias).cloned() {
+            coords.version = Some(version);
+            catalog.add_library(&self.alias, coords);
+        }
+    }
+}
+
+/// The catalog whose builder block directly encloses the current word:
+/// the last `create(..)`-ed one, or for Groovy's `libs { }` form the catalog
+/// named after the block.
+fn catalog_index(catalogs: &mut Vec<VersionCatalog>, blocks: &[String]) -> Option<usize> {
+    let [.., parent, block] = blocks else {
+        return None;
+    };
+    if parent != "versionCatalogs" {
+        return None;
+    }
+    if block == "create" || block == "register" {
+        return catalogs.len().checked_sub(1);
+    }
+
+    match catalogs.iter().position(|c| c.name == *block) {
+        Some(i) => Some(i),
+        None => {
+            catalogs.push(VersionCatalog::new(block.clone()));
+            Some(catalogs.len() - 1)
+        }
+    }
+}
+
+/// Collects the string literals of the statement starting at `from`: either a
+/// parenthesised argument list or the rest of the line, following trailing
+/// commas onto continuation lines.
+pub(crate) fn string_args(source: &str, masked: &str, from: usize) -> Vec<String> {
+    let rest = &masked[from..];
+    let trimmed = rest.trim_start();
+    let offset = from + rest.len() - trimmed.len();
+
+    let end = if trimmed.starts_with('(') {
+        let mut depth = 0usize;
+        trimmed
+            .char_indices()
+            .find(|&(_, c)| {
+                match c {
+                    '(' => depth += 1,
+                    ')' => depth -= 1,
+                    _ => {}
+                }
+                depth == 0
+            })
+            .map_or(trimmed.len(), |(i, _)| i)
+    } else {
+        let mut end = 0;
+        for line in trimmed.split_inclusive('\n') {
+            end += line.len();
+            if !line.trim_end().ends_with(',') {
+                break;
+            }
+        }
+        end
+    };
+
+    let region = &masked[offset..offset + end];
+    let mut args = Vec::new();
+    let mut open: Option<(usize, char)> = None;
+    for (i, c) in region.char_indices() {
+        match (open, c) {
+            (None, '"' | '\'') => open = Some((i, c)),
+            (Some((start, quote)), c) if c == quote => {
+                args.push(source[offset + start + 1..offset + i].to_string());
+                open = None;
+            }
+            _ => {}
+        }
+    }
+    args
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_groovy_settings() {
+        let src = r#"
+        rootProject.name = 'shop'
+        // include ':disabled'
+        include ':app', ':libs:core',
+            ':libs:ui'
+        includeBuild '../platform'
+        "#;
+        let settings = GradleSettings::parse_str(src);
+
+        assert_eq!(settings.root_project_name.as_deref(), Some("shop"));
+        assert_eq!(settings.includes, vec![":app", ":libs:core", ":libs:ui"]);
+        assert_eq!(
+            settings.included_builds,
+            vec![IncludedBuildDecl {
+                path: "../platform".into(),
+                plugin_build: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_kotlin_settings_with_plugin_builds() {
+        let src = r#"
+        pluginManagement {
+            includeBuild("build-logic")
+        }
+        rootProject.name = "shop"
+        include("app", "lib")
+        includeBuild("../platform")
+        "#;
+        let settings = GradleSettings::parse_str(src);
+
+        assert_eq!(settings.includes, vec!["app", "lib"]);
+        assert_eq!(settings.included_builds.len(), 2);
+        assert!(settings.included_builds[0].plugin_build);
+        assert_eq!(settings.included_builds[0].path, "build-logic");
+        assert!(!settings.included_builds[1].plugin_build);
+    }
+
+    #[test]
+    fn test_plugin_management_versions() {
+        let src = r#"
+        pluginManagement {
+            repositories { gradlePluginPortal() }
+            plugins {
+                id 'com.diffplug.spotless' version '6.25.0'
+                id("org.x.y") version "1.0"
+                kotlin("jvm") version "1.9.22"
+            }
+        }
+        plugins {
+            id 'org.gradle.toolchains.foojay-resolver-convention' version '0.8.0'
+        }
+        "#;
+        let settings = GradleSettings::parse_str(src);
+
+        let pinned: Vec<_> = settings
+            .plugin_management
+            .iter()
+            .map(|p| (p.id.as_id().unwrap(), p.version.as_deref()))
+            .collect();
+        assert_eq!(
+            pinned,
+            [
+                ("com.diffplug.spotless", Some("6.25.0")),
+                ("org.x.y", Some("1.0")),
+                ("org.jetbrains.kotlin.jvm", Some("1.9.22")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_programmatic_version_catalogs() {
+        let src = r#"
+        dependencyResolutionManagement {
+            versionCatalogs {
+                create("libs") {
+                    library("retrofit", "com.squareup.retrofit2", "retrofit").versionRef("retrofit")
+                    library("okhttp", "com.squareup.okhttp3:okhttp:4.12.0")
+                    plugin("kotlin-jvm", "org.jetbrains.kotlin.jvm").version("1.9.22")
+                    bundle("network", listOf("retrofit", "okhttp"))
+                    version("retrofit", "2.9.0")
+                }
+            }
+        }
+        "#;
+        let settings = GradleSettings::parse_str(src);
+
+        assert_eq!(settings.version_catalogs.len(), 1);
+        let libs = &settings.version_catalogs[0];
+        assert_eq!(libs.name, "libs");
+        assert_eq!(
+            libs.library("retrofit").unwrap().to_string(),
+            "com.squareup.retrofit2:retrofit:2.9.0"
+        );
+        assert_eq!(libs.library("okhttp").unwrap().name, "okhttp");
+        assert_eq!(
+            libs.plugin("kotlin.jvm").unwrap().version.as_deref(),
+            Some("1.9.22")
+        );
+        assert_eq!(libs.bundles["network"], vec!["retrofit", "okhttp"]);
+    }
+
+    #[test]
+    fn test_groovy_version_catalog_block() {
+        let src = r#"
+        dependencyResolutionManagement {
+            versionCatalogs {
+                testLibs {
+                    library('junit', 'junit:junit:4.13.2')
+                }
+            }
+        }
+        "#;
+        let settings = GradleSettings::parse_str(src);
+
+        let catalog = &settings.version_catalogs[0];
+        assert_eq!(catalog.name, "testLibs");
+        assert!(catalog.lookup("testLibs.junit").is_some());
+    }
+}
@@ -0,0 +1,301 @@
+use crate::analysis::Span;
+use crate::ast::{Call, Expr, ExprKind, Script, Statement, StatementKind};
+use crate::model::{DSLBlock, DSLValue, Operand};
+
+/// A string literal of a script, found by [`Script::string_literals`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct StringLiteral {
+    /// Names of the enclosing blocks and of the statement, the same path
+    /// [`DSLBlock::walk`] yields for it, e.g. `["android", "namespace"]`.
+    pub path: Vec<String>,
+    /// The named argument or chained call the literal is passed to, e.g.
+    /// `group` in `implementation group: 'g', name: 'a'` or `version` in
+    /// `id 'x' version '1.0'`.
+    pub argument: Option<String>,
+    /// Without its quotes.
+    pub value: String,
+    /// Includes the quotes.
+    pub span: Span,
+}
+
+impl Script {
+    /// Every string literal, including those inside expressions and call
+    /// arguments, in source order.
+    ///
+    /// ```rust
+    /// use uniparse_gradle::Script;
+    ///
+    /// let script = Script::parse("repositories {\n    maven { url 'https://repo.example.com' }\n}\n").unwrap();
+    /// let literal = &script.string_literals()[0];
+    /// assert_eq!(literal.path, ["repositories", "maven", "url"]);
+    /// assert_eq!(literal.value, "https://repo.example.com");
+    /// assert_eq!(literal.span.line, 2);
+    /// ```
+    pub fn string_literals(&self) -> Vec<StringLiteral> {
+        let mut out = Vec::new();
+        statements(&self.statements, &[], &mut out);
+        out
+    }
+}
+
+impl DSLBlock {
+    /// The values of every string literal with the path of the entry holding
+    /// it, like [`Script::string_literals`] but without positions, which
+    /// this model doesn't keep.
+    pub fn string_literals(&self) -> Vec<(Vec<&str>, &str)> {
+        let mut out = Vec::new();
+        for (path, value) in self.walk() {
+            value_strings(value, &mut |s| out.push((path.clone(), s)));
+        }
+        out
+    }
+}
+
+fn statements(list: &[Statement], prefix: &[String], out: &mut Vec<StringLiteral>) {
+    for statement in list {
+        match &statement.kind {
+            StatementKind::Assignment { target, value } => {
+                let path = child(prefix, &target.text);
+                expr(value, &path, None, out);
+            }
+            StatementKind::Call(call) => {
+                let path = child(prefix, &call.name.text);
+                call_args(call, &path, None, out);
+                let mut next = Some(call);
+                while let Some(call) = next {
+                    if let Some(block) = &call.closure {
+                        statements(&block.statements, &path, out);
+                    }
+                    next = call.chained.as_deref();
+                    if let Some(chained) = next {
+                        call_args(chained, &path, Some(&chained.name.text), out);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn call_args(call: &Call, path: &[String], argument: Option<&str>, out: &mut Vec<StringLiteral>) {
+    for arg in &call.args {
+        let argument = arg.name.as_ref().map(|n| n.text.as_str()).or(argument);
+        expr(&arg.value, path, argument, out);
+    }
+}
+
+fn expr(expr_: &Expr, path: &[String], argument: Option<&str>, out: &mut Vec<StringLiteral>) {
+    match &expr_.kind {
+        ExprKind::String(value) => out.push(StringLiteral {
+            path: path.to_vec(),
+            argument: argument.map(str::to_string),
+            value: value.clone(),
+            span: expr_.span.clone(),
+        }),
+        ExprKind::Binary { lhs, rhs, .. } => {
+            expr(lhs, path, argument, out);
+            expr(rhs, path, argument, out);
+        }
+        ExprKind::Call(call) => call_args(call, path, argument, out),
+        _ => {}
+    }
+}
+
+fn child(prefix: &[String], name: &str) -> Vec<String> {
+    let mut path = prefix.to_vec();
+    path.push(name.to_string());
+    path
+}
+
+/// Strings directly in `value`; nested blocks are visited by
+/// [`DSLBlock::walk`] itself.
+fn value_strings<'a>(value: &'a DSLValue, f: &mut impl FnMut(&'a str)) {
+    match value {
+        DSLValue::String(s) | DSLValue::Assignment(s) => f(s),
+        DSLValue::FunctionCall(args) => args.iter().for_each(|arg| value_strings(arg, f)),
+        DSLValue::MultiArgs(args) => {
+            // a HashMap: visit in a stable order
+            let mut args: Vec<_> = args.iter().collect();
+            args.sort_by_key(|(key, _)| (key.as_str() != "value", key.as_str()));
+            args.into_iter().for_each(|(_, arg)| value_strings(arg, f));
+        }
+        DSLValue::Expression(expression) => {
+            operand_strings(&expression.first, f);
+            for (_, operand) in &expression.rest {
+                operand_strings(operand, f);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn operand_strings<'a>(operand: &'a Operand, f: &mut impl FnMut(&'a str)) {
+    match operand {
+        Operand::String(s) => f(s),
+        Operand::Call { args, .. } => {
+            for arg in args {
+                operand_strings(&arg.first, f);
+                for (_, operand) in &arg.rest {
+                    operand_strings(operand, f);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SRC: &str = r#"plugins {
+    id 'com.android.application' version '8.2.0'
+}
+
+android {
+    namespace = "com.example.app"
+    signingConfigs {
+        release { storePassword = System.getenv("STORE_PASSWORD") ?: "hunter2" }
+    }
+}
+
+dependencies {
+    implementation group: 'com.squareup.okhttp3', name: 'okhttp'
+    implementation "org.jetbrains.kotlin:kotlin-stdlib:" + kotlinVersion
+}
+"#;
+
+    #[test]
+    fn test_script_string_literals() {
+        let script = Script::parse(SRC).unwrap();
+        let found: Vec<_> = script
+            .string_literals()
+            .into_iter()
+            .map(|l| {
+                (
+                    l.path.join("."),
+                    l.argument,
+                    l.value,
+                    (l.span.line, l.span.column),
+                )
+            })
+            .collect();
+        let expected = [
+            ("plugins.id", None, "com.android.application", (2, 8)),
+            ("plugins.id", Some("version"), "8.2.0", (2, 42)),
+            ("android.namespace", None, "com.example.app", (6, 17)),
+            (
+                "android.signingConfigs.release.storePassword",
+                None,
+                "STORE_PASSWORD",
+                (8, 49),
+            ),
+            (
+                "android.signingConfigs.release.storePassword",
+                None,
+                "hunter2",
+                (8, 70),
+            ),
+            (
+                "dependencies.implementation",
+                Some("group"),
+                "com.squareup.okhttp3",
+                (13, 27),
+            ),
+            (
+                "dependencies.implementation",
+                Some("name"),
+                "okhttp",
+                (13, 57),
+            ),
+            (
+                "dependencies.implementation",
+                None,
+                "org.jetbrains.kotlin:kotlin-stdlib:",
+                (14, 20),
+            ),
+        ];
+        assert_eq!(
+            found,
+            expected.map(|(path, argument, value, at)| (
+                path.to_string(),
+                argument.map(str::to_string),
+                value.to_string(),
+                at
+            ))
+        );
+
+        let span = &script.string_literals()[2].span;
+        assert_eq!(&SRC[span.byte_range.clone()], "\"com.example.app\"");
+    }
+
+    #[test]
+    fn test_block_string_literals() {
+        let block = DSLBlock::parse_str(SRC).unwrap();
+        let found: Vec<_> = block
+            .string_literals()
+            .into_iter()
+            .map(|(path, value)| (path.join("."), value))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("plugins.id".to_string(), "com.android.application"),
+                ("plugins.id".to_string(), "8.2.0"),
+                ("android.namespace".to_string(), "com.example.app"),
+                (
+                    "android.signingConfigs.release.storePassword".to_string(),
+                    "STORE_PASSWORD"
+                ),
+                (
+                    "android.signingConfigs.release.storePassword".to_string(),
+                    "hunter2"
+                ),
+                (
+                    "dependencies.implementation".to_string(),
+                    "com.squareup.okhttp3"
+                ),
+                ("dependencies.implementation".to_string(), "okhttp"),
+                (
+                    "dependencies.implementation".to_string(),
+                    "org.jetbrains.kotlin:kotlin-stdlib:"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_string_literals_in_if_branches() {
+        let src = "if (ci) {\n    dependencies { implementation 'g:a:1' }\n} else {\n    repositories { maven { url 'https://secret' } }\n}\n";
+        let from_block: Vec<_> = DSLBlock::parse_str(src)
+            .unwrap()
+            .string_literals()
+            .into_iter()
+            .map(|(path, value)| (path.join("."), value.to_string()))
+            .collect();
+        assert_eq!(
+            from_block,
+            [
+                (
+                    "if.dependencies.implementation".to_string(),
+                    "g:a:1".to_string()
+                ),
+                (
+                    "if.repositories.maven.url".to_string(),
+                    "https://secret".to_string()
+                ),
+            ]
+        );
+
+        let from_script: Vec<_> = Script::parse(src)
+            .unwrap()
+            .string_literals()
+            .into_iter()
+            .map(|l| (l.path.join("."), l.value))
+            .collect();
+        assert_eq!(from_script, from_block);
+    }
+}
@@ -0,0 +1,266 @@
+use crate::analysis::{mask_source, word_at};
+use crate::settings::string_args;
+use serde::{Deserialize, Serialize};
+
+/// How the `test` task of a build script is configured.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TestConfig {
+    /// Set by `useJUnitPlatform()`, `useJUnit()` or `useTestNG()`; `None`
+    /// means Gradle's default, JUnit 4.
+    pub framework: Option<TestFramework>,
+    /// Value of `maxParallelForks`.
+    pub max_parallel_forks: Option<Parallelism>,
+    /// Arguments passed with `jvmArgs`, in declaration order.
+    pub jvm_args: Vec<String>,
+    /// Value of `maxHeapSize`, e.g. `1g`.
+    pub max_heap_size: Option<String>,
+}
+
+/// Test engine selected for the `test` task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TestFramework {
+    /// `useJUnitPlatform()` (JUnit 5, Kotest, Spock 2, ...).
+    JUnitPlatform,
+    /// `useJUnit()`
+    JUnit4,
+    /// `useTestNG()`
+    TestNG,
+}
+
+/// Value of `maxParallelForks`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Parallelism {
+    /// A number literal, e.g. `4`.
+    Fixed(u32),
+    /// Computed at configuration time, e.g.
+    /// `Runtime.runtime.availableProcessors().intdiv(2)`; kept as written.
+    Expression(String),
+}
+
+impl TestConfig {
+    /// Collects the settings of every block configuring the `test` task:
+    /// `test { }`, `tasks.test { }`, `tasks.named("test") { }` and
+    /// `tasks.withType(Test) { }` in either DSL, as well as the default
+    /// `test` suite of `testing { suites { } }`.
+    ///
+    /// Works on the raw source like [`config_cache_issues`], so scripts the
+    /// [`DSLBlock`](crate::DSLBlock) parser can't model are covered too.
+    /// Returns `None` if the script doesn't configure the task. Later
+    /// settings override earlier ones; `jvmArgs` accumulate.
+    ///
+    /// [`config_cache_issues`]: crate::config_cache_issues
+    pub fn parse_str(source: &str) -> Option<Self> {
+        let masked = mask_source(source);
+        let mut config: Option<TestConfig> = None;
+        // headers of the enclosing blocks, and whether each configures `test`
+        let mut blocks: Vec<(String, bool)> = Vec::new();
+        let mut statement_start: Option<usize> = None;
+        let mut at_statement_start = true;
+
+        for (idx, ch) in masked.char_indices() {
+            if at_statement_start && !ch.is_whitespace() {
+                at_statement_start = false;
+                statement_start = Some(idx);
+
+                if blocks.iter().any(|(_, is_test)| *is_test) {
+                    let config = config.get_or_insert_with(TestConfig::default);
+                    config.apply(source, &masked, idx);
+                }
+            }
+
+            match ch {
+                '{' => {
+                    let header = statement_start.map_or("", |start| source[start..idx].trim());
+                    let parent = blocks.last().map(|(header, _)| header.as_str());
+                    let is_test = is_test_task(header, parent);
+                    if is_test {
+                        config.get_or_insert_with(TestConfig::default);
+                    }
+                    blocks.push((header.to_string(), is_test));
+                    at_statement_start = true;
+                }
+                '}' => {
+                    blocks.pop();
+                    at_statement_start = true;
+                }
+                '\n' | ';' => at_statement_start = true,
+                _ => {}
+            }
+        }
+
+        config
+    }
+
+    /// Applies the statement starting at `start`, if it is a setting we know.
+    fn apply(&mut self, source: &str, masked: &str, start: usize) {
+        let word = word_at(&masked[start..]);
+        let after = start + word.len();
+
+        match word {
+            "useJUnitPlatform" | "useJUnitJupiter" => {
+                self.framework = Some(TestFramework::JUnitPlatform)
+            }
+            "useJUnit" => self.framework = Some(TestFramework::JUnit4),
+            "useTestNG" => self.framework = Some(TestFramework::TestNG),
+            "maxParallelForks" => {
+                let value = statement_value(source, masked, after);
+                self.max_parallel_forks = Some(match value.parse() {
+                    Ok(n) => Parallelism::Fixed(n),
+                    Err(_) => Parallelism::Expression(value.to_string()),
+                });
+            }
+            "maxHeapSize" => {
+                if let Some(size) = string_args(source, masked, after).into_iter().next() {
+                    self.max_heap_size = Some(size);
+                }
+            }
+            "jvmArgs" | "jvmArgs.add" | "jvmArgs.addAll" => {
+                self.jvm_args.extend(string_args(source, masked, after))
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Whether a block header configures the `test` task. A plain `test { }` only
+/// does at the top level or under `tasks`/`suites`; inside `sourceSets` it is
+/// a source set.
+fn is_test_task(header: &str, parent: Option<&str>) -> bool {
+    let header: String = header
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| if c == '\'' { '"' } else { c })
+        .collect();
+    let header = header
+        .strip_suffix(".configureEach")
+        .or_else(|| header.strip_suffix(".configure"))
+        .unwrap_or(&header);
+
+    match header {
+        "test" => matches!(parent, None | Some("tasks" | "suites")),
+        "tasks.test"
+        | "tasks.named(\"test\")"
+        | "tasks.named<Test>(\"test\")"
+        | "tasks.getByName(\"test\")"
+        | "tasks.withType(Test)"
+        | "tasks.withType(Test::class)"
+        | "tasks.withType(Test::class.java)"
+        | "tasks.withType<Test>" => true,
+        _ => false,
+    }
+}
+
+/// The rest of the statement after a property name, without the `=` or
+/// parentheses around the whole value.
+fn statement_value<'a>(source: &'a str, masked: &str, from: usize) -> &'a str {
+    let end = masked[from..]
+        .find(['\n', ';'])
+        .map_or(masked.len(), |i| from + i);
+    let mut range = from..end;
+    let trim = |range: &mut std::ops::Range<usize>| {
+        let text = &masked[range.clone()];
+        range.start += text.len() - text.trim_start().len();
+        range.end -= text.len() - text.trim_end().len();
+    };
+
+    trim(&mut range);
+    if masked[range.clone()].starts_with('=') {
+        range.start += 1;
+        trim(&mut range);
+    }
+    if encloses(&masked[range.clone()]) {
+        range = range.start + 1..range.end - 1;
+        trim(&mut range);
+    }
+    // the unmasked source, so string literals survive
+    &source[range]
+}
+
+/// Whether `text` is a single parenthesised group such as `(4)`, as opposed
+/// to `(a) / (b)`.
+fn encloses(text: &str) -> bool {
+    let mut depth = 0usize;
+    for (i, c) in text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    return i == text.len() - 1 && i > 0;
+                }
+            }
+            _ if depth == 0 => return false,
+            _ => {}
+        }
+    }
+    false
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_groovy_test_block() {
+        let src = r#"
+        sourceSets {
+            test { java { srcDirs 'src/test/java' } }
+        }
+        test {
+            useJUnitPlatform()
+            maxParallelForks = 4
+            jvmArgs '-Xmx1g', '-XX:+UseG1GC'
+            // jvmArgs '-Xdisabled'
+            testLogging { events "failed" }
+        }
+        "#;
+        let config = TestConfig::parse_str(src).unwrap();
+
+        assert_eq!(config.framework, Some(TestFramework::JUnitPlatform));
+        assert_eq!(config.max_parallel_forks, Some(Parallelism::Fixed(4)));
+        assert_eq!(config.jvm_args, ["-Xmx1g", "-XX:+UseG1GC"]);
+        assert_eq!(config.max_heap_size, None);
+    }
+
+    #[test]
+    fn test_kotlin_task_configuration() {
+        let src = r#"
+        tasks.withType<Test> {
+            maxParallelForks = (Runtime.getRuntime().availableProcessors() / 2).coerceAtLeast(1)
+            maxHeapSize = "2g"
+        }
+        tasks.named<Test>("test") {
+            useTestNG()
+            jvmArgs("-Dfile.encoding=UTF-8")
+        }
+        tasks.named("integrationTest") { useJUnit() }
+        "#;
+        let config = TestConfig::parse_str(src).unwrap();
+
+        assert_eq!(config.framework, Some(TestFramework::TestNG));
+        assert_eq!(
+            config.max_parallel_forks,
+            Some(Parallelism::Expression(
+                "(Runtime.getRuntime().availableProcessors() / 2).coerceAtLeast(1)".into()
+            ))
+        );
+        assert_eq!(config.max_heap_size.as_deref(), Some("2g"));
+        assert_eq!(config.jvm_args, ["-Dfile.encoding=UTF-8"]);
+    }
+
+    #[test]
+    fn test_no_test_configuration() {
+        assert_eq!(TestConfig::parse_str("sourceSets { test { } }\n"), None);
+        assert_eq!(
+            TestConfig::parse_str("testing { suites { test { useJUnitJupiter() } } }"),
+            Some(TestConfig {
+                framework: Some(TestFramework::JUnitPlatform),
+                ..TestConfig::default()
+            })
+        );
+    }
+}
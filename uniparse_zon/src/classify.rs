@@ -0,0 +1,99 @@
+use std::fmt::{Display, Formatter};
+
+use crate::model::{ZonFile, ZonValue};
+
+/// What a `.zon` document looks like it is, as guessed by
+/// [`ZonFile::classify`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZonKind {
+    /// A `build.zig.zon` package manifest: an object with `.name` and
+    /// `.version`.
+    Manifest,
+    /// Flat settings read by a build script, e.g. through `@import`: an
+    /// object whose fields are all strings, numbers, booleans or enum
+    /// literals.
+    BuildOptions,
+    /// Anything else: lists, nested objects, bare values.
+    Data,
+}
+
+impl Display for ZonKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ZonKind::Manifest => "package manifest",
+            ZonKind::BuildOptions => "build options",
+            ZonKind::Data => "generic data",
+        })
+    }
+}
+
+impl ZonFile {
+    /// Guesses what the document is from its top-level shape, so callers
+    /// scanning arbitrary `.zon` files can skip those that aren't manifests
+    /// before [`ZonFile::as_manifest`] fails on them.
+    ///
+    /// ```rust
+    /// use uniparse_zon::{ZonFile, ZonKind};
+    ///
+    /// let options = ZonFile::parse_str(".{ .optimize = .ReleaseSafe, .strip = true }").unwrap();
+    /// assert_eq!(options.classify(), ZonKind::BuildOptions);
+    /// ```
+    pub fn classify(&self) -> ZonKind {
+        let ZonValue::Object(fields) = &self.data else {
+            return ZonKind::Data;
+        };
+        if fields.contains_key("name") && fields.contains_key("version") {
+            ZonKind::Manifest
+        } else if !fields.is_empty()
+            && fields
+                .values()
+                .all(|v| !matches!(v, ZonValue::List(_) | ZonValue::Object(_)))
+        {
+            ZonKind::BuildOptions
+        } else {
+            ZonKind::Data
+        }
+    }
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RootZon;
+
+    #[test]
+    fn test_classify() {
+        let cases = [
+            (
+                ".{ .name = .demo, .version = \"0.1.0\", .paths = .{\"\"} }",
+                ZonKind::Manifest,
+            ),
+            (
+                ".{ .target = \"x86_64-linux\", .lto = false, .jobs = 4 }",
+                ZonKind::BuildOptions,
+            ),
+            (".{ \"src\", \"build.zig\" }", ZonKind::Data),
+            (".{ .palette = .{ .bg = 0x000000 } }", ZonKind::Data),
+            (".{}", ZonKind::Data),
+            ("\"hello\"", ZonKind::Data),
+        ];
+        for (src, kind) in cases {
+            assert_eq!(ZonFile::parse_str(src).unwrap().classify(), kind, "{}", src);
+        }
+    }
+
+    #[test]
+    fn test_as_manifest_error_names_the_kind() {
+        let options = ZonFile::parse_str(".{ .lto = false }").unwrap();
+        let err = options.as_manifest().unwrap_err();
+        assert!(err.ends_with("(the document looks like build options, not a package manifest)"));
+
+        // other targets don't get the hint
+        let err = options.as_struct::<RootZon>().unwrap_err();
+        assert!(!err.contains("package manifest"), "{}", err);
+    }
+}
@@ -0,0 +1,28 @@
+//! The signatures this crate had before structured errors, kept as
+//! deprecated wrappers so callers can migrate one call site at a time. See
+//! [`uniparse_core::compat`] for the policy.
+
+use uniparse_core::compat::IntoLegacy;
+
+use crate::model::{ZonFile, ZonValue};
+
+/// [`ZonFile::parse_str`] with the error as its message.
+///
+/// ```rust
+/// #![allow(deprecated)]
+/// use uniparse_zon::compat;
+///
+/// let zon = compat::parse_str(r#".{ .name = "demo" }"#).unwrap();
+/// assert_eq!(zon.get(&["name"]).and_then(|v| v.as_str()), Some("demo"));
+/// assert_eq!(compat::parse_str(".{ .a = ? }"), Err("Unexpected character: ?".to_string()));
+/// ```
+#[deprecated(since = "0.1.0", note = "use `ZonFile::parse_str` instead")]
+pub fn parse_str(src: &str) -> Result<ZonFile, String> {
+    ZonFile::parse_str(src).into_legacy()
+}
+
+/// [`parse_zon`](crate::parse_zon) with the error as its message.
+#[deprecated(since = "0.1.0", note = "use `uniparse_zon::parse_zon` instead")]
+pub fn parse_zon(input: &str) -> Result<ZonValue, String> {
+    crate::parser::parse_zon(input).into_legacy()
+}
@@ -0,0 +1,233 @@
+//! Conversions between [`ZonValue`] and other configuration formats.
+//!
+//! Neither TOML nor YAML has enum literals, so they are written as strings
+//! and come back as [`ZonValue::String`]. Integers that don't fit the target
+//! format (such as 64-bit fingerprints in TOML) are written as their source
+//! text, the same way [`ZonValue::to_json`] does.
+
+use crate::model::{ParseError, ZonValue};
+use crate::number::ZonNumber;
+#[cfg(feature = "yaml")]
+use std::collections::HashMap;
+
+#[cfg(feature = "toml")]
+impl ZonValue {
+    pub fn to_toml(&self) -> toml::Value {
+        match self {
+            ZonValue::String(s) | ZonValue::EnumLiteral(s) => toml::Value::String(s.clone()),
+            ZonValue::Bool(b) => toml::Value::Boolean(*b),
+            ZonValue::Number(n) => match (n.as_i64(), n.is_float()) {
+                (Some(i), _) => toml::Value::Integer(i),
+                (None, true) => n
+                    .as_f64()
+                    .map_or_else(|| toml::Value::String(n.raw().into()), toml::Value::Float),
+                (None, false) => toml::Value::String(n.raw().to_string()),
+            },
+            ZonValue::List(items) => toml::Value::Array(items.iter().map(Self::to_toml).collect()),
+            ZonValue::Object(map) => {
+                toml::Value::Table(map.iter().map(|(k, v)| (k.clone(), v.to_toml())).collect())
+            }
+        }
+    }
+
+    /// Converts a TOML document; datetimes become strings.
+    ///
+    /// # Errors
+    /// Returns [`ParseError::UnsupportedValue`] for `nan` and `inf`, which
+    /// `.zon` number literals can't express.
+    pub fn from_toml(value: &toml::Value) -> Result<Self, ParseError> {
+        Ok(match value {
+            toml::Value::String(s) => ZonValue::String(s.clone()),
+            toml::Value::Integer(i) => ZonValue::Number(integer(*i)),
+            toml::Value::Float(f) => ZonValue::Number(float(*f)?),
+            toml::Value::Boolean(b) => ZonValue::Bool(*b),
+            toml::Value::Datetime(d) => ZonValue::String(d.to_string()),
+            toml::Value::Array(items) => ZonValue::List(
+                items
+                    .iter()
+                    .map(Self::from_toml)
+                    .collect::<Result<_, _>>()?,
+            ),
+            toml::Value::Table(table) => ZonValue::Object(
+                table
+                    .iter()
+                    .map(|(k, v)| Ok((k.clone(), Self::from_toml(v)?)))
+                    .collect::<Result<_, ParseError>>()?,
+            ),
+        })
+    }
+}
+
+#[cfg(feature = "yaml")]
+impl ZonValue {
+    pub fn to_yaml(&self) -> serde_yaml_ng::Value {
+        use serde_yaml_ng::Value;
+
+        match self {
+            ZonValue::String(s) | ZonValue::EnumLiteral(s) => Value::String(s.clone()),
+            ZonValue::Bool(b) => Value::Bool(*b),
+            ZonValue::Number(n) => {
+                if let Some(u) = n.as_u64() {
+                    Value::Number(u.into())
+                } else if let Some(i) = n.as_i64() {
+                    Value::Number(i.into())
+                } else {
+                    match n.as_f64().filter(|_| n.is_float()) {
+                        Some(f) => Value::Number(f.into()),
+                        None => Value::String(n.raw().to_string()),
+                    }
+                }
+            }
+            ZonValue::List(items) => Value::Sequence(items.iter().map(Self::to_yaml).collect()),
+            ZonValue::Object(map) => Value::Mapping(
+                map.iter()
+                    .map(|(k, v)| (Value::String(k.clone()), v.to_yaml()))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Converts a YAML document.
+    ///
+    /// # Errors
+    /// Returns [`ParseError::UnsupportedValue`] for `null`, `.nan`, `.inf`
+    /// and mapping keys that aren't strings, none of which `.zon` can
+    /// express.
+    pub fn from_yaml(value: &serde_yaml_ng::Value) -> Result<Self, ParseError> {
+        use serde_yaml_ng::Value;
+
+        Ok(match value {
+            Value::Null => return Err(ParseError::UnsupportedValue("null".into())),
+            Value::Bool(b) => ZonValue::Bool(*b),
+            Value::Number(n) => ZonValue::Number(match (n.as_u64(), n.as_i64(), n.as_f64()) {
+                (Some(u), _, _) => ZonNumber::from(u),
+                (None, Some(i), _) => integer(i),
+                (None, None, Some(f)) => float(f)?,
+                _ => return Err(ParseError::UnsupportedValue(n.to_string())),
+            }),
+            Value::String(s) => ZonValue::String(s.clone()),
+            Value::Sequence(items) => ZonValue::List(
+                items
+                    .iter()
+                    .map(Self::from_yaml)
+                    .collect::<Result<_, _>>()?,
+            ),
+            Value::Mapping(mapping) => {
+                let mut map = HashMap::new();
+                for (key, value) in mapping {
+                    let key = key.as_str().ok_or_else(|| {
+                        ParseError::UnsupportedValue(format!("non-string key {:?}", key))
+                    })?;
+                    map.insert(key.to_string(), Self::from_yaml(value)?);
+                }
+                ZonValue::Object(map)
+            }
+            Value::Tagged(tagged) => Self::from_yaml(&tagged.value)?,
+        })
+    }
+}
+
+fn integer(i: i64) -> ZonNumber {
+    i.to_string()
+        .parse()
+        .expect("formatted integers are valid literals")
+}
+
+fn float(f: f64) -> Result<ZonNumber, ParseError> {
+    if !f.is_finite() {
+        return Err(ParseError::UnsupportedValue(f.to_string()));
+    }
+    // `{:?}` keeps the `.0` so the literal stays a float
+    Ok(format!("{:?}", f)
+        .parse()
+        .expect("finite floats are valid literals"))
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ZonFile;
+
+    const MANIFEST: &str = r#".{
+        .name = .demo,
+        .version = "0.1.0",
+        .fingerprint = 0xA1B2C3D4E5F60718,
+        .paths = .{ "src", "build.zig" },
+        .dependencies = .{ .zlib = .{ .url = "https://x/zlib.tar.gz", .lazy = true } },
+    }"#;
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_toml_round_trip() {
+        let zon = ZonFile::parse_str(MANIFEST).unwrap().data;
+        let toml = zon.to_toml();
+
+        assert_eq!(toml["name"].as_str(), Some("demo"));
+        assert_eq!(toml["fingerprint"].as_str(), Some("0xA1B2C3D4E5F60718"));
+        assert_eq!(toml["dependencies"]["zlib"]["lazy"].as_bool(), Some(true));
+
+        let text = toml::to_string(&toml).unwrap();
+        let back = ZonValue::from_toml(&text.parse::<toml::Table>().unwrap().into()).unwrap();
+        assert_eq!(back.get_path(&["version"]), zon.get_path(&["version"]));
+        assert_eq!(back.get_path(&["paths"]), zon.get_path(&["paths"]));
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_from_toml_numbers() {
+        let value: toml::Value = "port = 8080\nratio = 1.5\n"
+            .parse::<toml::Table>()
+            .unwrap()
+            .into();
+        let zon = ZonValue::from_toml(&value).unwrap();
+        assert_eq!(zon.get_path(&["port"]).unwrap().to_string(), "8080");
+        assert_eq!(zon.get_path(&["ratio"]).unwrap().to_string(), "1.5");
+
+        for special in ["nan", "inf", "-inf"] {
+            let value: toml::Value = format!("x = [{}]\n", special)
+                .parse::<toml::Table>()
+                .unwrap()
+                .into();
+            assert!(matches!(
+                ZonValue::from_toml(&value),
+                Err(ParseError::UnsupportedValue(_))
+            ));
+        }
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_yaml_round_trip() {
+        let zon = ZonFile::parse_str(MANIFEST).unwrap().data;
+        let yaml = serde_yaml_ng::to_string(&zon.to_yaml()).unwrap();
+        assert!(yaml.contains("fingerprint: 11651590505119483672"));
+
+        let back = ZonValue::from_yaml(&serde_yaml_ng::from_str(&yaml).unwrap()).unwrap();
+        assert_eq!(
+            back.get_path(&["fingerprint"])
+                .and_then(ZonValue::as_number)
+                .and_then(|n| n.as_u64()),
+            Some(0xA1B2_C3D4_E5F6_0718)
+        );
+        assert_eq!(
+            back.get_path(&["dependencies"]),
+            zon.get_path(&["dependencies"])
+        );
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_from_yaml_rejects_null() {
+        for src in ["name: ~", "ratio: .nan", "ratio: -.inf"] {
+            let value: serde_yaml_ng::Value = serde_yaml_ng::from_str(src).unwrap();
+            assert!(matches!(
+                ZonValue::from_yaml(&value),
+                Err(ParseError::UnsupportedValue(_))
+            ));
+        }
+    }
+}
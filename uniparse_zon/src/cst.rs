@@ -0,0 +1,482 @@
+//! Lossless syntax tree of a `.zon` file.
+//!
+//! [`ZonFile`](crate::ZonFile) keeps only the data. [`ZonCst`] keeps every
+//! byte, comments included, on top of the [`uniparse_core`] tree types, and
+//! attaches comments to the fields below them so `///` doc comments can be
+//! read back per field.
+
+use std::fmt::{self, Display, Formatter};
+use std::ops::Range;
+
+use uniparse_core::{CstKind, GreenNodeBuilder, SyntaxNode, SyntaxToken, comments_above};
+
+use crate::model::ParseError;
+use crate::parser::{ZonToken, dot_key, parse_zon, tokenize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SyntaxKind {
+    /// The whole file.
+    File,
+    /// `.{ .key = value, ... }`
+    Object,
+    /// `.{ value, ... }`
+    List,
+    /// `.key = value,` with the comments attached to it.
+    Field,
+    /// One value of a list with its comma and attached comments.
+    Element,
+    /// `.key`, or an enum literal such as `.zigimg`.
+    DotKey,
+    Equals,
+    /// `.{` or `{`.
+    OpenBrace,
+    CloseBrace,
+    Comma,
+    /// A string literal, quotes included.
+    String,
+    Number,
+    Bool,
+    Whitespace,
+    Newline,
+    /// `// ...`
+    Comment,
+    /// `/// ...`, documenting the field below.
+    DocComment,
+    /// `//! ...`, documenting the whole file.
+    ContainerDocComment,
+}
+
+impl CstKind for SyntaxKind {
+    fn is_trivia(self) -> bool {
+        matches!(
+            self,
+            SyntaxKind::Whitespace
+                | SyntaxKind::Newline
+                | SyntaxKind::Comment
+                | SyntaxKind::DocComment
+                | SyntaxKind::ContainerDocComment
+        )
+    }
+
+    fn is_comment(self) -> bool {
+        matches!(
+            self,
+            SyntaxKind::Comment | SyntaxKind::DocComment | SyntaxKind::ContainerDocComment
+        )
+    }
+
+    fn is_newline(self) -> bool {
+        self == SyntaxKind::Newline
+    }
+}
+
+/// A `.zon` file as a lossless syntax tree.
+///
+/// ```rust
+/// use uniparse_zon::ZonCst;
+///
+/// let src = ".{\n    /// Bump together with build.zig.\n    .version = \"0.1.0\",\n}\n";
+/// let cst = ZonCst::parse(src).unwrap();
+/// assert_eq!(cst.to_string(), src);
+/// assert_eq!(
+///     cst.doc_comment(&["version"]).as_deref(),
+///     Some("Bump together with build.zig.")
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct ZonCst {
+    root: SyntaxNode<SyntaxKind>,
+}
+
+impl ZonCst {
+    /// Parses `source`, which must also be valid for
+    /// [`ZonFile::parse_str`](crate::ZonFile::parse_str). Printing the
+    /// result gives back `source` unchanged.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(len = source.len()), err)
+    )]
+    pub fn parse(source: &str) -> Result<Self, ParseError> {
+        parse_zon(source)?;
+        build(source)
+    }
+
+    pub fn syntax(&self) -> &SyntaxNode<SyntaxKind> {
+        &self.root
+    }
+
+    /// The doc comment of the field at `path`, one line per `///` line
+    /// without the slashes. The empty path gives the file's `//!` comment.
+    pub fn doc_comment(&self, path: &[&str]) -> Option<String> {
+        if path.is_empty() {
+            let lines: Vec<_> = self
+                .root
+                .tokens()
+                .filter(|t| t.kind() == SyntaxKind::ContainerDocComment)
+                .collect();
+            return join_doc(&lines);
+        }
+        self.doc_comments()
+            .into_iter()
+            .find(|(p, _)| p.iter().map(String::as_str).eq(path.iter().copied()))
+            .map(|(_, doc)| doc)
+    }
+
+    /// Every documented field with its path, in source order. List
+    /// elements appear in paths by index, e.g. `["items", "0", "name"]`.
+    pub fn doc_comments(&self) -> Vec<(Vec<String>, String)> {
+        self.root
+            .descendants()
+            .filter(|n| n.kind() == SyntaxKind::Field)
+            .filter_map(|field| {
+                let lines: Vec<_> = field
+                    .leading_comments()
+                    .into_iter()
+                    .filter(|t| t.kind() == SyntaxKind::DocComment)
+                    .collect();
+                Some((path_of(&field), join_doc(&lines)?))
+            })
+            .collect()
+    }
+}
+
+impl Display for ZonCst {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.root.fmt(f)
+    }
+}
+
+fn join_doc(lines: &[SyntaxToken<SyntaxKind>]) -> Option<String> {
+    if lines.is_empty() {
+        return None;
+    }
+    let text = lines
+        .iter()
+        .map(|t| {
+            let body = &t.text()[3..];
+            body.strip_prefix(' ').unwrap_or(body).trim_end()
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    Some(text)
+}
+
+/// Keys and list indices from the root down to `field`.
+fn path_of(field: &SyntaxNode<SyntaxKind>) -> Vec<String> {
+    let mut path: Vec<String> = field
+        .ancestors()
+        .filter_map(|node| match node.kind() {
+            SyntaxKind::Field => node
+                .tokens()
+                .find(|t| t.kind() == SyntaxKind::DotKey)
+                .map(|t| dot_key(t.text()).to_string()),
+            SyntaxKind::Element => {
+                let list = node.parent()?;
+                let index = list
+                    .children()
+                    .take_while(|sibling| *sibling != node)
+                    .filter(|sibling| sibling.kind() == SyntaxKind::Element)
+                    .count();
+                Some(index.to_string())
+            }
+            _ => None,
+        })
+        .collect();
+    path.reverse();
+    path
+}
+
+/// The tree of `source`, which tokenizes but may not parse.
+fn build(source: &str) -> Result<ZonCst, ParseError> {
+    let mut tree = TreeBuilder {
+        source,
+        leaves: leaves(source)?,
+        i: 0,
+        builder: GreenNodeBuilder::new(),
+    };
+    tree.builder.start_node(SyntaxKind::File);
+    tree.items(None);
+    tree.builder.finish_node();
+    Ok(ZonCst {
+        root: SyntaxNode::new_root(tree.builder.finish()),
+    })
+}
+
+type Leaf = (SyntaxKind, Range<usize>);
+
+/// Every token of `source` in order, trivia included.
+fn leaves(source: &str) -> Result<Vec<Leaf>, ParseError> {
+    let (tokens, spans) = tokenize(source)?;
+    let mut leaves = Vec::new();
+    let mut pos = 0;
+    for (token, range) in tokens.iter().zip(spans) {
+        trivia(source, pos..range.start, &mut leaves);
+        let kind = match token {
+            ZonToken::DotKey(_) => SyntaxKind::DotKey,
+            ZonToken::Equals => SyntaxKind::Equals,
+            ZonToken::OpenBrace => SyntaxKind::OpenBrace,
+            ZonToken::CloseBrace => SyntaxKind::CloseBrace,
+            ZonToken::String(_) => SyntaxKind::String,
+            ZonToken::Number(_) => SyntaxKind::Number,
+            ZonToken::Bool(_) => SyntaxKind::Bool,
+            ZonToken::Comma => SyntaxKind::Comma,
+            ZonToken::Invalid => {
+                let text = &source[range.clone()];
+                return Err(ParseError::UnexpectedToken(format!(
+                    "Unexpected `{}`",
+                    text
+                )));
+            }
+        };
+        pos = range.end;
+        leaves.push((kind, range));
+    }
+    trivia(source, pos..source.len(), &mut leaves);
+    Ok(leaves)
+}
+
+/// Splits a gap between tokens into whitespace, line breaks and comments.
+fn trivia(source: &str, range: Range<usize>, leaves: &mut Vec<Leaf>) {
+    let mut pos = range.start;
+    while pos < range.end {
+        let rest = &source[pos..range.end];
+        let (kind, len) = if rest.starts_with("//") {
+            let kind = if rest.starts_with("////") {
+                // four or more slashes are a plain comment in Zig
+                SyntaxKind::Comment
+            } else if rest.starts_with("///") {
+                SyntaxKind::DocComment
+            } else if rest.starts_with("//!") {
+                SyntaxKind::ContainerDocComment
+            } else {
+                SyntaxKind::Comment
+            };
+            let len = rest.find('\n').unwrap_or(rest.len());
+            (kind, rest[..len].trim_end_matches('\r').len())
+        } else if rest.starts_with('\n') {
+            (SyntaxKind::Newline, 1)
+        } else {
+            let len = rest
+                .find(|c: char| c == '\n' || !c.is_whitespace())
+                .unwrap_or(rest.len());
+            let first = rest.chars().next().map_or(1, char::len_utf8);
+            (SyntaxKind::Whitespace, len.max(first))
+        };
+        leaves.push((kind, pos..pos + len));
+        pos += len;
+    }
+}
+
+struct TreeBuilder<'a> {
+    source: &'a str,
+    leaves: Vec<Leaf>,
+    /// The next leaf to add.
+    i: usize,
+    builder: GreenNodeBuilder<SyntaxKind>,
+}
+
+impl TreeBuilder<'_> {
+    fn kind(&self, i: usize) -> Option<SyntaxKind> {
+        self.leaves.get(i).map(|(kind, _)| *kind)
+    }
+
+    fn leaf(&mut self) {
+        let (kind, range) = &self.leaves[self.i];
+        self.builder.token(*kind, &self.source[range.clone()]);
+        self.i += 1;
+    }
+
+    /// Index of the next leaf that isn't trivia, or the end.
+    fn next_significant(&self) -> usize {
+        (self.i..self.leaves.len())
+            .find(|&j| !self.leaves[j].0.is_trivia())
+            .unwrap_or(self.leaves.len())
+    }
+
+    fn leaves_until(&mut self, index: usize) {
+        while self.i < index {
+            self.leaf();
+        }
+    }
+
+    /// The items of a container up to its `}` (not consumed), or of the
+    /// file up to its end when `item_kind` is `None`.
+    fn items(&mut self, item_kind: Option<SyntaxKind>) {
+        loop {
+            let first = self.next_significant();
+            match self.kind(first) {
+                None => {
+                    self.leaves_until(first);
+                    return;
+                }
+                Some(SyntaxKind::CloseBrace) if item_kind.is_some() => return,
+                // a stray `}` after the file's value; `item` would stop at it
+                Some(SyntaxKind::CloseBrace) => {
+                    self.leaves_until(first + 1);
+                    continue;
+                }
+                _ => {}
+            }
+
+            let start = match item_kind {
+                Some(_) => comments_above(|j| self.kind(j), self.i, first),
+                None => first,
+            };
+            self.leaves_until(start);
+            if let Some(kind) = item_kind {
+                self.builder.start_node(kind);
+            }
+            self.item();
+            if item_kind.is_some() {
+                self.builder.finish_node();
+            }
+        }
+    }
+
+    /// One field or element: everything up to and including its comma, or
+    /// up to the container's `}`. A comment after the comma on the same
+    /// line belongs to the item.
+    fn item(&mut self) {
+        loop {
+            let next = self.next_significant();
+            match self.kind(next) {
+                None | Some(SyntaxKind::CloseBrace) => return,
+                Some(SyntaxKind::OpenBrace) => {
+                    self.leaves_until(next);
+                    self.container();
+                }
+                Some(SyntaxKind::Comma) => {
+                    self.leaves_until(next + 1);
+                    let comment = match self.kind(self.i) {
+                        Some(SyntaxKind::Whitespace) => self.i + 1,
+                        _ => self.i,
+                    };
+                    if self.kind(comment).is_some_and(CstKind::is_comment) {
+                        self.leaves_until(comment + 1);
+                    }
+                    return;
+                }
+                Some(_) => self.leaves_until(next + 1),
+            }
+        }
+    }
+
+    /// `.{ ... }` at the current leaf.
+    fn container(&mut self) {
+        let after_brace = (self.i + 1..self.leaves.len()).find(|&j| !self.leaves[j].0.is_trivia());
+        let second = after_brace
+            .and_then(|j| (j + 1..self.leaves.len()).find(|&k| !self.leaves[k].0.is_trivia()));
+        let is_object = match after_brace.and_then(|j| self.kind(j)) {
+            Some(SyntaxKind::CloseBrace) => true,
+            Some(SyntaxKind::DotKey) => {
+                second.and_then(|k| self.kind(k)) == Some(SyntaxKind::Equals)
+            }
+            _ => false,
+        };
+        let (node, item) = if is_object {
+            (SyntaxKind::Object, SyntaxKind::Field)
+        } else {
+            (SyntaxKind::List, SyntaxKind::Element)
+        };
+
+        self.builder.start_node(node);
+        self.leaf();
+        self.items(Some(item));
+        let close = self.next_significant();
+        self.leaves_until(close);
+        if self.kind(close) == Some(SyntaxKind::CloseBrace) {
+            self.leaf();
+        }
+        self.builder.finish_node();
+    }
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SRC: &str = r#"//! Package manifest for the demo app.
+//! Maintained by the platform team.
+.{
+    // not documentation
+    .name = .demo,
+    /// Bump together with build.zig.
+    ///
+    /// Follows semver.
+    .version = "0.1.0", // current
+    .dependencies = .{
+        /// Fork with the PNG fix; drop once upstream releases.
+        .zigimg = .{
+            .url = "https://example.com/zigimg.tar.gz",
+        },
+    },
+    .paths = .{
+        "build.zig",
+        // sources
+        "src",
+    },
+}
+"#;
+
+    #[test]
+    fn test_round_trip_and_doc_comments() {
+        let cst = ZonCst::parse(SRC).unwrap();
+        assert_eq!(cst.to_string(), SRC);
+
+        assert_eq!(
+            cst.doc_comment(&[]).as_deref(),
+            Some("Package manifest for the demo app.\nMaintained by the platform team.")
+        );
+        assert_eq!(
+            cst.doc_comment(&["version"]).as_deref(),
+            Some("Bump together with build.zig.\n\nFollows semver.")
+        );
+        assert_eq!(cst.doc_comment(&["name"]), None);
+        assert_eq!(
+            cst.doc_comments()
+                .into_iter()
+                .map(|(path, _)| path.join("."))
+                .collect::<Vec<_>>(),
+            ["version", "dependencies.zigimg"]
+        );
+    }
+
+    #[test]
+    fn test_comments_attach_to_items() {
+        let cst = ZonCst::parse(SRC).unwrap();
+        let fields: Vec<_> = cst
+            .syntax()
+            .descendants()
+            .filter(|n| n.kind() == SyntaxKind::Field)
+            .map(|n| n.to_string())
+            .collect();
+        assert_eq!(fields[0], "// not documentation\n    .name = .demo,");
+        assert!(fields[1].ends_with(".version = \"0.1.0\", // current"));
+
+        let elements: Vec<_> = cst
+            .syntax()
+            .descendants()
+            .filter(|n| n.kind() == SyntaxKind::Element)
+            .map(|n| n.to_string())
+            .collect();
+        assert_eq!(elements, ["\"build.zig\",", "// sources\n        \"src\","]);
+    }
+
+    #[test]
+    fn test_stray_close_brace() {
+        for src in [".{}}", ".{ .a = 1 }}", "1}"] {
+            assert!(parse_zon(src).is_err(), "{}", src);
+            assert!(ZonCst::parse(src).is_err(), "{}", src);
+            // the tree still covers the brace rather than stopping at it
+            assert_eq!(build(src).unwrap().to_string(), src);
+
+            let zon = crate::ZonFile::parse_str(".{ .a = 2 }").unwrap();
+            assert_eq!(zon.patch_source(src), zon.to_string_pretty());
+        }
+    }
+}
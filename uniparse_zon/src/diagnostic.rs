@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use uniparse_core::{DiagnosticCode, Severity};
+
+/// A location in `.zon` source text.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Span {
+    /// Line number (starting at 1).
+    pub line: usize,
+    /// Column number in characters (starting at 1).
+    pub column: usize,
+    /// Byte offsets into the source.
+    pub byte_range: Range<usize>,
+}
+
+impl Span {
+    pub(crate) fn from_range(source: &str, byte_range: Range<usize>) -> Self {
+        let before = &source[..byte_range.start];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let column = source[line_start..byte_range.start].chars().count() + 1;
+        Span {
+            line,
+            column,
+            byte_range,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DiagnosticKind {
+    /// An object literal sets the same key twice; only the last value is
+    /// kept.
+    DuplicateKey,
+    /// A character that can't start a token; reported by
+    /// [`ZonFile::parse_recovering`](crate::ZonFile::parse_recovering).
+    UnexpectedCharacter,
+    /// A bare identifier other than `true` or `false`; reported by
+    /// [`ZonFile::parse_recovering`](crate::ZonFile::parse_recovering).
+    UnknownIdentifier,
+    /// A token out of place, such as a missing `=` or `}`; reported by
+    /// [`ZonFile::parse_recovering`](crate::ZonFile::parse_recovering).
+    UnexpectedToken,
+}
+
+impl DiagnosticCode for DiagnosticKind {
+    fn code(&self) -> &'static str {
+        match self {
+            DiagnosticKind::DuplicateKey => "ZON008",
+            DiagnosticKind::UnexpectedCharacter => "ZON002",
+            DiagnosticKind::UnknownIdentifier => "ZON003",
+            DiagnosticKind::UnexpectedToken => "ZON004",
+        }
+    }
+}
+
+/// A problem found while parsing that still produced a value.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub kind: DiagnosticKind,
+    pub message: String,
+    /// Where the problem is, e.g. the second occurrence of a key.
+    pub span: Span,
+    /// Other locations involved, e.g. the first occurrence of a key.
+    pub related: Vec<Span>,
+}
+
+impl DiagnosticCode for Diagnostic {
+    fn code(&self) -> &'static str {
+        self.kind.code()
+    }
+
+    fn severity(&self) -> Severity {
+        self.kind.severity()
+    }
+}
@@ -0,0 +1,192 @@
+use crate::cst::{SyntaxKind, ZonCst};
+
+const INDENT: &str = "    ";
+
+impl ZonCst {
+    /// Lays the file out the way `zig fmt` writes hand-edited manifests:
+    /// one field or element per line, indented four spaces per level, with
+    /// trailing commas. Meant for reviewing generated or minified files.
+    ///
+    /// Only whitespace changes. Fields keep their order, literals are
+    /// written as in the source and comments are kept, trailing ones on the
+    /// line of the item before them. Empty objects stay `.{}`.
+    ///
+    /// ```rust
+    /// use uniparse_zon::ZonCst;
+    ///
+    /// let cst = ZonCst::parse(r#".{.name=.demo,.paths=.{"src"},.dependencies=.{}}"#).unwrap();
+    /// assert_eq!(
+    ///     cst.expand(),
+    ///     ".{\n    .name = .demo,\n    .paths = .{\n        \"src\",\n    },\n    .dependencies = .{},\n}\n"
+    /// );
+    /// ```
+    pub fn expand(&self) -> String {
+        let tokens: Vec<_> = self
+            .syntax()
+            .tokens()
+            .filter(|t| t.kind() != SyntaxKind::Whitespace)
+            .map(|t| (t.kind(), t.text().to_string()))
+            .collect();
+        let significant = |from: usize| {
+            tokens[from..]
+                .iter()
+                .map(|(kind, _)| *kind)
+                .find(|kind| *kind != SyntaxKind::Newline)
+        };
+
+        let mut out = Layout::default();
+        let mut i = 0;
+        while let Some((kind, text)) = tokens.get(i) {
+            i += 1;
+            match kind {
+                SyntaxKind::Newline => out.newlines += 1,
+                SyntaxKind::OpenBrace => {
+                    out.begin_item(text);
+                    if significant(i) == Some(SyntaxKind::CloseBrace) {
+                        let close = tokens[i..]
+                            .iter()
+                            .position(|(kind, _)| *kind == SyntaxKind::CloseBrace);
+                        i += close.unwrap_or_default() + 1;
+                        out.text.push('}');
+                        out.needs_comma = out.depth > 0;
+                        continue;
+                    }
+                    out.depth += 1;
+                    out.newline();
+                }
+                SyntaxKind::CloseBrace => {
+                    out.end_item();
+                    out.depth -= 1;
+                    out.begin_item("}");
+                    out.needs_comma = out.depth > 0;
+                }
+                SyntaxKind::Comma => {
+                    out.text.push(',');
+                    out.needs_comma = false;
+                }
+                SyntaxKind::Equals => {
+                    out.text.push_str(" = ");
+                    out.needs_comma = false;
+                }
+                SyntaxKind::DotKey if significant(i) == Some(SyntaxKind::Equals) => {
+                    out.begin_item(text);
+                    out.needs_comma = false;
+                }
+                SyntaxKind::DotKey | SyntaxKind::String | SyntaxKind::Number | SyntaxKind::Bool => {
+                    out.begin_item(text);
+                    out.needs_comma = true;
+                }
+                _ if out.newlines == 0 && !out.at_line_start() => {
+                    // a comment after an item on the same line
+                    if out.needs_comma {
+                        out.text.push(',');
+                        out.needs_comma = false;
+                    }
+                    out.text.push(' ');
+                    out.text.push_str(text);
+                    out.newline();
+                }
+                _ => {
+                    out.end_item();
+                    out.begin_item(text);
+                    out.newline();
+                }
+            }
+            if *kind != SyntaxKind::Newline {
+                out.newlines = 0;
+            }
+        }
+        out.newline();
+        out.text
+    }
+}
+
+#[derive(Default)]
+struct Layout {
+    text: String,
+    depth: usize,
+    /// Line breaks since the last token.
+    newlines: usize,
+    /// The last item is a value that has no comma yet.
+    needs_comma: bool,
+}
+
+impl Layout {
+    fn at_line_start(&self) -> bool {
+        self.text.is_empty() || self.text.ends_with('\n')
+    }
+
+    fn newline(&mut self) {
+        if !self.at_line_start() {
+            self.text.push('\n');
+        }
+    }
+
+    /// Closes the current line, adding the item's missing comma.
+    fn end_item(&mut self) {
+        if self.needs_comma {
+            self.text.push(',');
+            self.needs_comma = false;
+        }
+        self.newline();
+    }
+
+    /// Writes `text`, starting a new indented line if the previous item is
+    /// done.
+    fn begin_item(&mut self, text: &str) {
+        if !self.text.ends_with(" = ") {
+            self.end_item();
+            self.text.push_str(&INDENT.repeat(self.depth));
+        }
+        self.text.push_str(text);
+    }
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use crate::{ZonCst, ZonFile};
+
+    #[test]
+    fn test_expand_minified() {
+        let minified = r#".{.name=.demo,.version="0.1.0",.fingerprint=0x9a3c,.dependencies=.{.zigimg=.{.url="https://x/a.tar.gz",.hash="1220ab",.lazy=true}},.paths=.{"build.zig","src",}}"#;
+        let expanded = ZonCst::parse(minified).unwrap().expand();
+        assert_eq!(
+            expanded,
+            r#".{
+    .name = .demo,
+    .version = "0.1.0",
+    .fingerprint = 0x9a3c,
+    .dependencies = .{
+        .zigimg = .{
+            .url = "https://x/a.tar.gz",
+            .hash = "1220ab",
+            .lazy = true,
+        },
+    },
+    .paths = .{
+        "build.zig",
+        "src",
+    },
+}
+"#
+        );
+        assert_eq!(
+            ZonFile::parse_str(&expanded).unwrap(),
+            ZonFile::parse_str(minified).unwrap()
+        );
+        assert_eq!(ZonCst::parse(&expanded).unwrap().expand(), expanded);
+    }
+
+    #[test]
+    fn test_expand_odd_spacing_and_comments() {
+        let src = "//! generated\r\n.{ .name  =\t.demo ,.paths = .{ \"\" } , // keep\n\n   // owned by CI\n  .dependencies = .{\n\n},\n.minimum_zig_version=\"0.14.0\"}";
+        assert_eq!(
+            ZonCst::parse(src).unwrap().expand(),
+            "//! generated\n.{\n    .name = .demo,\n    .paths = .{\n        \"\",\n    }, // keep\n    // owned by CI\n    .dependencies = .{},\n    .minimum_zig_version = \"0.14.0\",\n}\n"
+        );
+    }
+}
@@ -0,0 +1,264 @@
+//! Zig package hashes (`.hash = "1220..."`) computed from tarball bytes.
+//!
+//! This mirrors `computeHash` in Zig's `Package/Fetch.zig`: every file kept
+//! after unpacking is hashed as `sha256(path ++ [0, 0] ++ contents)` (symlinks
+//! as `sha256(path ++ target)`), the per-file digests are concatenated in
+//! path order and hashed again, and the result is written as a SHA-256
+//! multihash in hex.
+
+use flate2::read::GzDecoder;
+use sha2::{Digest, Sha256};
+use std::io::{self, Read};
+
+use crate::model::{Dependency, ParseError, ZonFile, ZonValue};
+
+/// Multihash prefix for a 32 byte SHA-256 digest.
+const MULTIHASH_SHA256: &str = "1220";
+
+enum Kind {
+    File(Vec<u8>),
+    Link(Vec<u8>),
+}
+
+impl Dependency {
+    /// Computes the hash Zig expects in `.hash` for a package tarball
+    /// (`.tar` or `.tar.gz`).
+    ///
+    /// As in Zig, a single top-level directory is stripped and, when the
+    /// package has its own `build.zig.zon`, only files under its `.paths`
+    /// count.
+    ///
+    /// # Errors
+    /// Returns [`ParseError::Io`] if the bytes aren't a readable tarball.
+    pub fn hash_from_bytes(bytes: &[u8]) -> Result<String, ParseError> {
+        let mut files = read_tarball(bytes)?;
+        strip_root_dir(&mut files);
+
+        let paths = files
+            .iter()
+            .find(|(path, _)| path == "build.zig.zon")
+            .and_then(|(_, kind)| match kind {
+                Kind::File(content) => included_paths(content),
+                Kind::Link(_) => None,
+            });
+        if let Some(paths) = paths {
+            files.retain(|(path, _)| {
+                paths.iter().any(|p| {
+                    p.is_empty()
+                        || path == p
+                        || path
+                            .strip_prefix(p.as_str())
+                            .is_some_and(|rest| rest.starts_with('/'))
+                })
+            });
+        }
+
+        files.sort_by(|(a, _), (b, _)| a.as_bytes().cmp(b.as_bytes()));
+
+        let mut package = Sha256::new();
+        for (path, kind) in &files {
+            let mut file = Sha256::new();
+            file.update(path.as_bytes());
+            match kind {
+                // Zig hard-codes the executable bit to false
+                Kind::File(content) => {
+                    file.update([0, 0]);
+                    file.update(content);
+                }
+                Kind::Link(target) => file.update(target),
+            }
+            package.update(file.finalize());
+        }
+
+        let digest: String = package
+            .finalize()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        Ok(format!("{}{}", MULTIHASH_SHA256, digest))
+    }
+}
+
+impl ZonFile {
+    /// Hashes `tarball` with [`Dependency::hash_from_bytes`] and stores the
+    /// result as `.dependencies.<name>.hash`, returning it.
+    ///
+    /// # Errors
+    /// Returns [`ParseError::Io`] for an unreadable tarball and
+    /// [`ParseError::UnexpectedToken`] if `.dependencies` is not an object.
+    pub fn set_dependency_hash(
+        &mut self,
+        name: &str,
+        tarball: &[u8],
+    ) -> Result<String, ParseError> {
+        let hash = Dependency::hash_from_bytes(tarball)?;
+        self.set(
+            &["dependencies", name, "hash"],
+            ZonValue::String(hash.clone()),
+        )
+        .map_err(ParseError::UnexpectedToken)?;
+        Ok(hash)
+    }
+}
+
+/// Regular files and symlinks in the archive, with `/`-separated paths.
+fn read_tarball(bytes: &[u8]) -> Result<Vec<(String, Kind)>, ParseError> {
+    let reader: Box<dyn Read + '_> = if bytes.starts_with(&[0x1f, 0x8b]) {
+        Box::new(GzDecoder::new(bytes))
+    } else {
+        Box::new(bytes)
+    };
+
+    let mut archive = tar::Archive::new(reader);
+    let mut files = Vec::new();
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let path = String::from_utf8_lossy(&entry.path_bytes()).into_owned();
+        let path = path.trim_start_matches("./").to_string();
+
+        let kind = match entry.header().entry_type() {
+            tar::EntryType::Regular | tar::EntryType::Continuous => {
+                let mut content = Vec::new();
+                entry.read_to_end(&mut content)?;
+                Kind::File(content)
+            }
+            tar::EntryType::Symlink => {
+                let target = entry.link_name_bytes().ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::InvalidData, "symlink without target")
+                })?;
+                Kind::Link(target.into_owned())
+            }
+            // directories, pax headers and the like don't contribute
+            _ => continue,
+        };
+        files.push((path, kind));
+    }
+    Ok(files)
+}
+
+/// Removes the top-level directory GitHub-style tarballs wrap everything in.
+fn strip_root_dir(files: &mut [(String, Kind)]) {
+    let Some(root) = files
+        .first()
+        .and_then(|(path, _)| path.split_once('/'))
+        .map(|(root, _)| format!("{}/", root))
+    else {
+        return;
+    };
+
+    if files.iter().all(|(path, _)| path.starts_with(&root)) {
+        for (path, _) in files.iter_mut() {
+            path.drain(..root.len());
+        }
+    }
+}
+
+/// The `.paths` of a package manifest, or `None` if it can't be read.
+fn included_paths(manifest: &[u8]) -> Option<Vec<String>> {
+    let zon = ZonFile::parse_str(std::str::from_utf8(manifest).ok()?).ok()?;
+    match zon.get(&["paths"])? {
+        ZonValue::List(items) => items
+            .iter()
+            .map(|v| v.as_str().map(|p| p.trim_end_matches('/').to_string()))
+            .collect(),
+        ZonValue::String(path) => Some(vec![path.trim_end_matches('/').to_string()]),
+        _ => None,
+    }
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+
+    fn tarball(files: &[(&str, &str)], gzip: bool) -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        for (path, content) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, path, content.as_bytes())
+                .unwrap();
+        }
+        let tar = builder.into_inner().unwrap();
+        if !gzip {
+            return tar;
+        }
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        io::Write::write_all(&mut encoder, &tar).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_hash_matches_reference_vector() {
+        // computed independently with Python's hashlib
+        let bytes = tarball(&[("pkg/b.txt", "bee\n"), ("pkg/a.txt", "ay\n")], true);
+        assert_eq!(
+            Dependency::hash_from_bytes(&bytes).unwrap(),
+            "12206786c2581f428514f022b3fbaa908f0701623bf66146c78147d4f41ca81fb699"
+        );
+
+        // same tree without the wrapping directory, stored uncompressed
+        let flat = tarball(&[("a.txt", "ay\n"), ("b.txt", "bee\n")], false);
+        assert_eq!(
+            Dependency::hash_from_bytes(&flat).unwrap(),
+            Dependency::hash_from_bytes(&bytes).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_only_manifest_paths_are_hashed() {
+        let manifest =
+            r#".{ .name = "pkg", .version = "0.1.0", .paths = .{ "build.zig.zon", "src" } }"#;
+        let with_extra = tarball(
+            &[
+                ("pkg/build.zig.zon", manifest),
+                ("pkg/src/main.zig", "pub fn main() void {}\n"),
+                ("pkg/README.md", "ignored\n"),
+                ("pkg/srcfoo.txt", "also ignored\n"),
+            ],
+            true,
+        );
+        let minimal = tarball(
+            &[
+                ("pkg/build.zig.zon", manifest),
+                ("pkg/src/main.zig", "pub fn main() void {}\n"),
+            ],
+            true,
+        );
+        assert_eq!(
+            Dependency::hash_from_bytes(&with_extra).unwrap(),
+            Dependency::hash_from_bytes(&minimal).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_set_dependency_hash() {
+        let mut zon = ZonFile::parse_str(
+            r#".{ .name = "app", .dependencies = .{ .pkg = .{ .url = "https://x/pkg.tar.gz", .hash = "old" } } }"#,
+        )
+        .unwrap();
+        let hash = zon
+            .set_dependency_hash("pkg", &tarball(&[("a.txt", "ay\n")], true))
+            .unwrap();
+
+        assert!(hash.starts_with("1220") && hash.len() == 68);
+        assert_eq!(
+            zon.get(&["dependencies", "pkg", "hash"])
+                .and_then(ZonValue::as_str),
+            Some(hash.as_str())
+        );
+        assert!(matches!(
+            zon.set_dependency_hash("pkg", b"not a tarball"),
+            Err(ParseError::Io(_))
+        ));
+    }
+}
@@ -7,8 +7,36 @@
 //! assert_eq!(structured.name, "example");
 //! ```
 
+mod classify;
+pub mod compat;
+#[cfg(any(feature = "toml", feature = "yaml"))]
+mod convert;
+mod cst;
+mod diagnostic;
+mod expand;
+#[cfg(feature = "hash")]
+mod hash;
+#[cfg(feature = "macros")]
+mod macros;
 mod model;
+mod number;
 mod parser;
+mod patch;
+mod paths;
+mod syntax;
+mod version;
+mod workspace;
 
-pub use model::{Dependency, RootZon, ZonFile, ZonValue};
+pub use classify::ZonKind;
+pub use cst::{SyntaxKind, ZonCst};
+pub use diagnostic::{Diagnostic, DiagnosticKind, Span};
+pub use model::{Dependency, ParseError, RootZon, ZonFile, ZonValue};
+pub use number::{Radix, ZonNumber};
 pub use parser::parse_zon;
+pub use paths::PathIssue;
+pub use syntax::ZonSyntaxVersion;
+pub use uniparse_core::{
+    AsValueRef, CancellationToken, Capabilities, DiagnosticCode, Document, ParsedFile, Schema,
+    SchemaViolation, SemanticEq, Severity, SourceEdit, ValueKind, ValueRef, ViolationKind,
+};
+pub use workspace::{DependencyEdge, UnresolvedDependency, ZonPackage, ZonWorkspace};
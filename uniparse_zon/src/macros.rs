@@ -0,0 +1,179 @@
+/// Builds a [`ZonValue`](crate::ZonValue) from `.zon`-like syntax, the way
+/// `serde_json::json!` builds JSON values. Needs the `macros` feature.
+///
+/// - `.key = value, ...` is an object, written bare at the top level or as
+///   `.{ ... }` (or `{ ... }`) when nested. Keys that aren't identifiers are
+///   written `.@"key"`, as in Zig.
+/// - `[a, b]` and `.{ a, b }` are lists; `.{}` is an empty object.
+/// - `.name` is an enum literal.
+/// - Anything else is a Rust expression converted with `ZonValue::from`:
+///   string and number literals, `true`/`false`, variables, or a
+///   [`ZonNumber`](crate::ZonNumber) to keep a radix. Wrap longer
+///   expressions in parentheses.
+///
+/// ```rust
+/// use uniparse_zon::{ZonValue, zon};
+///
+/// let version = "0.1.0";
+/// let value = zon! {
+///     .name = .example,
+///     .version = version,
+///     .paths = ["build.zig", "src"],
+///     .dependencies = .{
+///         .@"zig-clap" = .{ .url = "https://example.com/clap.tar.gz", .lazy = true },
+///     },
+/// };
+///
+/// let ZonValue::Object(root) = &value else { unreachable!() };
+/// assert_eq!(root["name"], ZonValue::EnumLiteral("example".into()));
+/// assert_eq!(root["paths"], zon!(.{ "build.zig", "src" }));
+/// ```
+#[macro_export]
+macro_rules! zon {
+    // `.key = value, ...` into `$object`
+    (@object $object:ident ()) => {};
+    (@object $object:ident (. $key:ident = $($rest:tt)*)) => {
+        $crate::zon!(@entry $object (stringify!($key)) () ($($rest)*))
+    };
+    (@object $object:ident (. @ $key:literal = $($rest:tt)*)) => {
+        $crate::zon!(@entry $object ($key) () ($($rest)*))
+    };
+    (@object $object:ident ($($unexpected:tt)+)) => {
+        compile_error!(concat!(
+            "expected `.key = value`, found `",
+            stringify!($($unexpected)+),
+            "`"
+        ))
+    };
+
+    // the tokens of one value, up to the next top-level comma
+    (@entry $object:ident ($key:expr) ($($value:tt)+) (, $($rest:tt)*)) => {
+        $object.insert(::std::string::String::from($key), $crate::zon!($($value)+));
+        $crate::zon!(@object $object ($($rest)*));
+    };
+    (@entry $object:ident ($key:expr) ($($value:tt)+) ()) => {
+        $object.insert(::std::string::String::from($key), $crate::zon!($($value)+));
+    };
+    (@entry $object:ident ($key:expr) ($($value:tt)*) ($next:tt $($rest:tt)*)) => {
+        $crate::zon!(@entry $object ($key) ($($value)* $next) ($($rest)*))
+    };
+
+    // `a, b, ...` into a `vec![...]` of the values done so far
+    (@list [$($done:expr,)*] () ()) => {
+        ::std::vec![$($done,)*]
+    };
+    (@list [$($done:expr,)*] ($($value:tt)+) ()) => {
+        ::std::vec![$($done,)* $crate::zon!($($value)+)]
+    };
+    (@list [$($done:expr,)*] ($($value:tt)+) (, $($rest:tt)*)) => {
+        $crate::zon!(@list [$($done,)* $crate::zon!($($value)+),] () ($($rest)*))
+    };
+    (@list [$($done:expr,)*] ($($value:tt)*) ($next:tt $($rest:tt)*)) => {
+        $crate::zon!(@list [$($done,)*] ($($value)* $next) ($($rest)*))
+    };
+
+    () => {
+        $crate::ZonValue::Object(::std::collections::HashMap::new())
+    };
+    (. {}) => {
+        $crate::zon!()
+    };
+    (. { . $key:ident = $($rest:tt)* }) => {
+        $crate::zon!(. $key = $($rest)*)
+    };
+    (. { . @ $key:literal = $($rest:tt)* }) => {
+        $crate::zon!(. @ $key = $($rest)*)
+    };
+    (. { $($values:tt)* }) => {
+        $crate::zon!([$($values)*])
+    };
+    ({ $($entries:tt)* }) => {
+        $crate::zon!(. { $($entries)* })
+    };
+    ([ $($values:tt)* ]) => {
+        $crate::ZonValue::List($crate::zon!(@list [] () ($($values)*)))
+    };
+    (. $key:ident = $($rest:tt)*) => {{
+        let mut object = ::std::collections::HashMap::new();
+        $crate::zon!(@object object (. $key = $($rest)*));
+        $crate::ZonValue::Object(object)
+    }};
+    (. @ $key:literal = $($rest:tt)*) => {{
+        let mut object = ::std::collections::HashMap::new();
+        $crate::zon!(@object object (. @ $key = $($rest)*));
+        $crate::ZonValue::Object(object)
+    }};
+    (. $name:ident) => {
+        $crate::ZonValue::EnumLiteral(::std::string::String::from(stringify!($name)))
+    };
+    ($value:expr) => {
+        $crate::ZonValue::from($value)
+    };
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use crate::{Radix, ZonFile, ZonNumber, ZonValue};
+
+    #[test]
+    fn test_zon_matches_parsed_file() {
+        let hash = "1220aaaa";
+        let built = zon! {
+            .name = .example,
+            .version = "0.1.0",
+            .fingerprint = (ZonNumber::with_radix(0xa1b2, Radix::Hex)),
+            .minimum_zig_version = "0.14.0",
+            .paths = .{ "build.zig", "src" },
+            .dependencies = .{
+                .@"zig-clap" = .{
+                    .url = "https://example.com/clap.tar.gz",
+                    .hash = hash,
+                    .lazy = true,
+                },
+            },
+        };
+
+        let parsed = ZonFile::parse_str(
+            r#".{
+                .name = .example,
+                .version = "0.1.0",
+                .fingerprint = 0xa1b2,
+                .minimum_zig_version = "0.14.0",
+                .paths = .{ "build.zig", "src" },
+                .dependencies = .{
+                    .@"zig-clap" = .{
+                        .url = "https://example.com/clap.tar.gz",
+                        .hash = "1220aaaa",
+                        .lazy = true,
+                    },
+                },
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(built, parsed.data);
+    }
+
+    #[test]
+    fn test_zon_values() {
+        assert_eq!(zon!(), ZonValue::Object(Default::default()));
+        assert_eq!(zon!(.{}), zon!());
+        assert_eq!(zon!([]), ZonValue::List(Vec::new()));
+        assert_eq!(zon!(["a", "b",]), zon!(.{ "a", "b" }));
+        assert_eq!(zon!({ .a = 1 }), zon!(.{ .a = 1 }));
+        assert_eq!(zon!(-3).to_string(), "-3");
+        assert_eq!(zon!(1.5).to_string(), "1.5");
+        assert_eq!(zon!(2.0).to_string(), "2.0");
+        assert_eq!(zon!(false), ZonValue::Bool(false));
+        assert_eq!(
+            zon!([vec!["x"], .y]),
+            ZonValue::List(vec![
+                ZonValue::List(vec![ZonValue::String("x".into())]),
+                ZonValue::EnumLiteral("y".into()),
+            ])
+        );
+    }
+}
@@ -2,13 +2,27 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 use std::str::FromStr;
+use uniparse_core::{
+    AsValueRef, Capabilities, DiagnosticCode, ParsedFile, Schema, SchemaViolation, SemanticEq,
+    ValueRef, normalize_dependency_id,
+};
 
-use crate::parser::parse_zon;
+use crate::classify::ZonKind;
+use crate::diagnostic::{Diagnostic, DiagnosticKind, Span};
+use crate::number::ZonNumber;
+use crate::parser::{
+    DuplicateKey, field_name, parse_zon, parse_zon_recovering, parse_zon_with_duplicates, quote,
+};
+use crate::syntax::ZonSyntaxVersion;
 
 #[derive(Debug, Deserialize)]
 pub struct RootZon {
     pub name: String,
     pub version: String,
+    /// Oldest Zig release the package builds with, e.g. `0.14.0` or
+    /// `0.14.0-dev.2+abc`.
+    #[serde(default)]
+    pub minimum_zig_version: Option<String>,
     pub paths: Vec<String>,
     pub dependencies: HashMap<String, Dependency>,
 }
@@ -22,6 +36,10 @@ pub struct ZonFile {
 pub enum ZonValue {
     String(String),
     Bool(bool),
+    /// An enum literal such as `.zigimg`.
+    EnumLiteral(String),
+    /// A number literal, kept as written.
+    Number(ZonNumber),
     List(Vec<ZonValue>),
     Object(HashMap<String, ZonValue>),
 }
@@ -33,8 +51,72 @@ pub struct Dependency {
     pub lazy: Option<bool>,
 }
 
+impl Dependency {
+    /// The URL in canonical form (no scheme, lowercase host, no `.git`), for
+    /// matching against dependencies of other manifests.
+    pub fn dependency_id(&self) -> String {
+        normalize_dependency_id(&self.url)
+    }
+}
+
+/// Errors returned while reading or parsing `.zon` sources.
+#[derive(Debug, thiserror::Error)]
+pub enum ParseError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Unexpected character: {0}")]
+    UnexpectedCharacter(char),
+
+    #[error("Unknown identifier: {0}")]
+    UnknownIdentifier(String),
+
+    /// The token stream does not match the expected structure.
+    #[error("{0}")]
+    UnexpectedToken(String),
+
+    #[error("Missing required field: {0}")]
+    MissingField(&'static str),
+
+    /// The document uses a construct the targeted Zig version doesn't accept.
+    #[error("{construct} is not supported by Zig {version} manifests")]
+    UnsupportedSyntax {
+        construct: &'static str,
+        version: ZonSyntaxVersion,
+    },
+
+    /// A value from another format has no `.zon` representation.
+    #[error("Value cannot be represented in .zon: {0}")]
+    UnsupportedValue(String),
+
+    /// An object sets the same key twice; only raised by
+    /// [`ZonFile::parse_strict`].
+    #[error("Duplicate key `.{path}` at line {}, first set at line {}", second.line, first.line)]
+    DuplicateKey {
+        /// Dot-separated path of the key, e.g. `dependencies.zlib.url`.
+        path: String,
+        first: Span,
+        second: Span,
+    },
+}
+
+impl DiagnosticCode for ParseError {
+    fn code(&self) -> &'static str {
+        match self {
+            ParseError::Io(_) => "ZON001",
+            ParseError::UnexpectedCharacter(_) => "ZON002",
+            ParseError::UnknownIdentifier(_) => "ZON003",
+            ParseError::UnexpectedToken(_) => "ZON004",
+            ParseError::MissingField(_) => "ZON005",
+            ParseError::UnsupportedSyntax { .. } => "ZON006",
+            ParseError::UnsupportedValue(_) => "ZON007",
+            ParseError::DuplicateKey { .. } => "ZON008",
+        }
+    }
+}
+
 impl std::str::FromStr for ZonFile {
-    type Err = String;
+    type Err = ParseError;
 
     fn from_str(src: &str) -> Result<Self, Self::Err> {
         let parsed = parse_zon(src)?;
@@ -49,18 +131,84 @@ impl Display for ZonFile {
 }
 
 impl ZonFile {
-    pub fn parse_str(src: &str) -> Result<Self, String>
+    pub fn parse_str(src: &str) -> Result<Self, ParseError>
     where
         Self: Sized,
     {
         Self::from_str(src)
     }
 
+    /// Parses `src`, returning diagnostics for problems that don't stop
+    /// parsing, such as a key set twice in one object (the last value wins,
+    /// as with [`ZonFile::parse_str`]).
+    pub fn parse_with_diagnostics(src: &str) -> Result<(Self, Vec<Diagnostic>), ParseError> {
+        let (data, duplicates) = parse_zon_with_duplicates(src)?;
+        let diagnostics = duplicates.into_iter().map(duplicate_diagnostic).collect();
+        Ok((ZonFile { data }, diagnostics))
+    }
+
+    /// Parses `src` without giving up at the first syntax error, for
+    /// editors that show every problem at once. A malformed entry is
+    /// dropped up to the next `,` or `}`, and the rest of the document is
+    /// kept; the diagnostics hold every syntax error and duplicate key, in
+    /// source order.
+    ///
+    /// ```rust
+    /// use uniparse_zon::ZonFile;
+    ///
+    /// let (zon, diagnostics) =
+    ///     ZonFile::parse_recovering(".{ .name = ?, .version = \"1.0.0\", .lazy = yes }");
+    /// assert_eq!(zon.get(&["version"]).unwrap().as_str(), Some("1.0.0"));
+    /// assert!(zon.get(&["name"]).is_none());
+    /// assert_eq!(diagnostics.len(), 2);
+    /// ```
+    pub fn parse_recovering(src: &str) -> (Self, Vec<Diagnostic>) {
+        let recovered = parse_zon_recovering(src);
+        let mut diagnostics: Vec<Diagnostic> = recovered
+            .errors
+            .into_iter()
+            .map(|(err, span)| Diagnostic {
+                kind: match err {
+                    ParseError::UnexpectedCharacter(_) => DiagnosticKind::UnexpectedCharacter,
+                    ParseError::UnknownIdentifier(_) => DiagnosticKind::UnknownIdentifier,
+                    _ => DiagnosticKind::UnexpectedToken,
+                },
+                message: err.to_string(),
+                span,
+                related: Vec::new(),
+            })
+            .chain(recovered.duplicates.into_iter().map(duplicate_diagnostic))
+            .collect();
+        diagnostics.sort_by_key(|d| d.span.byte_range.start);
+        let data = recovered.value;
+        (ZonFile { data }, diagnostics)
+    }
+
+    /// Like [`ZonFile::parse_str`], but a duplicate key is an error rather
+    /// than silently overwriting the earlier value.
+    pub fn parse_strict(src: &str) -> Result<Self, ParseError> {
+        let (data, duplicates) = parse_zon_with_duplicates(src)?;
+        match duplicates.into_iter().next() {
+            Some(dup) => Err(ParseError::DuplicateKey {
+                path: dup.path,
+                first: dup.first,
+                second: dup.second,
+            }),
+            None => Ok(ZonFile { data }),
+        }
+    }
+
     pub fn to_string_pretty(&self) -> String {
         // format!("{:#?}", self.data)
         self.data.to_string()
     }
 
+    /// Renders only the value at `path`, e.g. the `dependencies` object of
+    /// a manifest, or `None` if there's nothing there.
+    pub fn to_string_pretty_at(&self, path: &[&str]) -> Option<String> {
+        self.data.to_string_pretty_at(path)
+    }
+
     pub fn set(&mut self, path: &[&str], value: ZonValue) -> Result<(), String> {
         let mut current = &mut self.data;
 
@@ -102,9 +250,9 @@ impl ZonFile {
 
         let mut current = &mut self.data;
 
-        for i in 0..path.len() - 1 {
+        for key in &path[..path.len() - 1] {
             current = match current {
-                ZonValue::Object(map) => map.get_mut(path[i]).ok_or("Path not found")?,
+                ZonValue::Object(map) => map.get_mut(*key).ok_or("Path not found")?,
                 _ => return Err("Intermediate value is not an object".into()),
             }
         }
@@ -118,30 +266,207 @@ impl ZonFile {
         }
     }
 
+    /// Deserializes the document, e.g. into a [`RootZon`].
     pub fn as_struct<T: for<'de> Deserialize<'de>>(&self) -> Result<T, String> {
         let json: serde_json::Value = self.data.to_json();
         serde_json::from_value(json).map_err(|e| format!("Deserialization error: {e}"))
     }
+
+    /// Deserializes the document as a package manifest. Errors on documents
+    /// that don't look like one say what they look like instead (see
+    /// [`ZonFile::classify`]).
+    pub fn as_manifest(&self) -> Result<RootZon, String> {
+        self.as_struct().map_err(|e| match self.classify() {
+            ZonKind::Manifest => e,
+            kind => format!("{e} (the document looks like {kind}, not a package manifest)"),
+        })
+    }
+}
+
+impl ParsedFile for ZonFile {
+    type Error = ParseError;
+    type Value = ZonValue;
+    type OwnedValue = ZonValue;
+
+    /// Through [`ZonCst`](crate::ZonCst) and [`ZonFile::patch_source`]
+    /// rather than this model, which drops comments.
+    const CAPABILITIES: Capabilities = Capabilities {
+        lossless_editing: true,
+        spans: true,
+        comments: true,
+    };
+
+    fn parse_str(src: &str) -> Result<Self, ParseError> {
+        ZonFile::parse_str(src)
+    }
+
+    fn to_string_pretty(&self) -> String {
+        ZonFile::to_string_pretty(self)
+    }
+
+    fn get(&self, path: &[&str]) -> Option<&ZonValue> {
+        ZonFile::get(self, path)
+    }
+
+    fn set(&mut self, path: &[&str], value: ZonValue) -> Result<(), String> {
+        ZonFile::set(self, path, value)
+    }
+
+    fn remove(&mut self, path: &[&str]) -> Result<(), String> {
+        ZonFile::remove(self, path)
+    }
+}
+
+impl SemanticEq for ZonFile {
+    fn semantic_eq(&self, other: &Self) -> bool {
+        self.data.semantic_eq(&other.data)
+    }
+}
+
+impl SemanticEq for ZonValue {
+    /// Object keys are unordered and numbers compare by value; list order is
+    /// significant.
+    fn semantic_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (ZonValue::Number(a), ZonValue::Number(b)) => a.semantic_eq(b),
+            (ZonValue::List(a), ZonValue::List(b)) => a[..].semantic_eq(&b[..]),
+            (ZonValue::Object(a), ZonValue::Object(b)) => {
+                a.len() == b.len()
+                    && a.iter()
+                        .all(|(key, value)| b.get(key).is_some_and(|v| value.semantic_eq(v)))
+            }
+            (a, b) => a == b,
+        }
+    }
+}
+
+impl AsValueRef for ZonValue {
+    /// Object keys come out sorted, as objects don't keep their source order.
+    fn as_value_ref(&self) -> ValueRef<'_> {
+        match self {
+            ZonValue::String(s) => ValueRef::Str(s),
+            ZonValue::Bool(b) => ValueRef::Bool(*b),
+            ZonValue::EnumLiteral(name) => ValueRef::Ident(name),
+            ZonValue::Number(n) => ValueRef::Number(n.raw()),
+            ZonValue::List(items) => {
+                ValueRef::Seq(items.iter().map(|v| v.as_value_ref()).collect())
+            }
+            ZonValue::Object(map) => {
+                let mut entries: Vec<_> = map
+                    .iter()
+                    .map(|(k, v)| (k.as_str(), v.as_value_ref()))
+                    .collect();
+                entries.sort_by(|a, b| a.0.cmp(b.0));
+                ValueRef::Map(entries)
+            }
+        }
+    }
 }
 
 impl std::fmt::Display for ZonValue {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.write_indented(f, 0)
+    }
+}
+
+impl From<&str> for ZonValue {
+    fn from(value: &str) -> Self {
+        ZonValue::String(value.to_string())
+    }
+}
+
+impl From<String> for ZonValue {
+    fn from(value: String) -> Self {
+        ZonValue::String(value)
+    }
+}
+
+impl From<bool> for ZonValue {
+    fn from(value: bool) -> Self {
+        ZonValue::Bool(value)
+    }
+}
+
+impl From<ZonNumber> for ZonValue {
+    fn from(value: ZonNumber) -> Self {
+        ZonValue::Number(value)
+    }
+}
+
+impl From<i32> for ZonValue {
+    fn from(value: i32) -> Self {
+        ZonValue::Number(ZonNumber::from_i64(value.into()))
+    }
+}
+
+impl From<i64> for ZonValue {
+    fn from(value: i64) -> Self {
+        ZonValue::Number(ZonNumber::from_i64(value))
+    }
+}
+
+impl From<u64> for ZonValue {
+    fn from(value: u64) -> Self {
+        ZonValue::Number(value.into())
+    }
+}
+
+impl From<f64> for ZonValue {
+    fn from(value: f64) -> Self {
+        ZonValue::Number(ZonNumber::from_f64(value))
+    }
+}
+
+impl<T: Into<ZonValue>> From<Vec<T>> for ZonValue {
+    fn from(values: Vec<T>) -> Self {
+        ZonValue::List(values.into_iter().map(Into::into).collect())
+    }
+}
+
+impl From<HashMap<String, ZonValue>> for ZonValue {
+    fn from(object: HashMap<String, ZonValue>) -> Self {
+        ZonValue::Object(object)
+    }
+}
+
+impl ZonValue {
+    /// Renders the value as it is written `depth` levels deep in a file.
+    pub(crate) fn to_string_at(&self, depth: usize) -> String {
+        struct At<'a>(&'a ZonValue, usize);
+        impl std::fmt::Display for At<'_> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                self.0.write_indented(f, self.1)
+            }
+        }
+        At(self, depth).to_string()
+    }
+
+    /// Writes the value with nested lists and objects indented one level
+    /// deeper than `depth`.
+    fn write_indented(&self, f: &mut std::fmt::Formatter<'_>, depth: usize) -> std::fmt::Result {
+        let indent = "    ".repeat(depth + 1);
         match self {
-            ZonValue::String(s) => write!(f, "\"{}\"", s),
+            ZonValue::String(s) => f.write_str(&quote(s)),
             ZonValue::Bool(b) => write!(f, "{}", b),
+            ZonValue::EnumLiteral(name) => write!(f, ".{}", field_name(name)),
+            ZonValue::Number(n) => write!(f, "{}", n),
             ZonValue::List(list) => {
                 writeln!(f, ".{{")?;
                 for val in list {
-                    writeln!(f, "    {},", val)?;
+                    write!(f, "{}", indent)?;
+                    val.write_indented(f, depth + 1)?;
+                    writeln!(f, ",")?;
                 }
-                write!(f, "}}")
+                write!(f, "{}}}", &indent[4..])
             }
             ZonValue::Object(map) => {
                 writeln!(f, ".{{")?;
                 for (k, v) in map {
-                    writeln!(f, "    .{} = {},", k, v)?;
+                    write!(f, "{}.{} = ", indent, field_name(k))?;
+                    v.write_indented(f, depth + 1)?;
+                    writeln!(f, ",")?;
                 }
-                write!(f, "}}")
+                write!(f, "{}}}", &indent[4..])
             }
         }
     }
@@ -152,6 +477,8 @@ impl ZonValue {
         match self {
             ZonValue::String(s) => serde_json::Value::String(s.clone()),
             ZonValue::Bool(b) => serde_json::Value::Bool(*b),
+            ZonValue::EnumLiteral(name) => serde_json::Value::String(name.clone()),
+            ZonValue::Number(n) => number_to_json(n),
             ZonValue::List(items) => {
                 serde_json::Value::Array(items.iter().map(|v| v.to_json()).collect())
             }
@@ -174,6 +501,30 @@ impl ZonValue {
         Some(current)
     }
 
+    /// Renders the value at `path` below this one in `.zon` syntax.
+    pub fn to_string_pretty_at(&self, path: &[&str]) -> Option<String> {
+        self.get_path(path).map(ZonValue::to_string)
+    }
+
+    /// Checks the value against a [`Schema`], e.g. a manifest convention
+    /// stricter than [`RootZon`]. Enum literals such as `.name = .demo`
+    /// have the kind [`ValueKind::Ident`](uniparse_core::ValueKind::Ident).
+    ///
+    /// ```rust
+    /// use uniparse_zon::{Schema, ValueKind, ZonFile};
+    ///
+    /// let zon = ZonFile::parse_str(r#".{ .name = .demo, .version = 1 }"#).unwrap();
+    /// let schema = Schema::default()
+    ///     .required("version", Schema::of(ValueKind::Str))
+    ///     .required("license", Schema::of(ValueKind::Str));
+    /// let violations = zon.data.validate_schema(&schema);
+    /// assert_eq!(violations[0].to_string(), "`license`: required key is missing");
+    /// assert_eq!(violations[1].to_string(), "`version`: expected string, found number");
+    /// ```
+    pub fn validate_schema(&self, schema: &Schema) -> Vec<SchemaViolation> {
+        schema.validate(&self.as_value_ref())
+    }
+
     pub fn set_path(&mut self, path: &[&str], value: ZonValue) -> Result<(), String> {
         if path.is_empty() {
             return Err("Path cannot be empty".into());
@@ -213,6 +564,20 @@ impl ZonValue {
         }
     }
 
+    pub fn as_enum_literal(&self) -> Option<&str> {
+        match self {
+            ZonValue::EnumLiteral(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    pub fn as_number(&self) -> Option<&ZonNumber> {
+        match self {
+            ZonValue::Number(n) => Some(n),
+            _ => None,
+        }
+    }
+
     #[allow(dead_code)]
     fn as_list(&self) -> Option<&Vec<ZonValue>> {
         match self {
@@ -230,6 +595,33 @@ impl ZonValue {
     }
 }
 
+fn duplicate_diagnostic(dup: DuplicateKey) -> Diagnostic {
+    Diagnostic {
+        kind: DiagnosticKind::DuplicateKey,
+        message: format!(
+            "Duplicate key `.{}`; the value at line {} is overwritten",
+            dup.path, dup.first.line
+        ),
+        span: dup.second,
+        related: vec![dup.first],
+    }
+}
+
+/// Maps a number literal to JSON, falling back to its source text for values
+/// JSON can't represent exactly.
+fn number_to_json(n: &ZonNumber) -> serde_json::Value {
+    if let Some(u) = n.as_u64() {
+        return serde_json::Value::from(u);
+    }
+    if let Some(i) = n.as_i64() {
+        return serde_json::Value::from(i);
+    }
+    match n.as_f64().filter(|_| n.is_float()) {
+        Some(f) => serde_json::Value::from(f),
+        None => serde_json::Value::String(n.raw().to_string()),
+    }
+}
+
 //===================================//
 // T E S T S                         //
 //===================================//
@@ -238,6 +630,7 @@ impl ZonValue {
 mod tests {
     use crate::model::{RootZon, ZonFile, ZonValue};
     use std::collections::HashMap;
+    use uniparse_core::{DiagnosticCode, SemanticEq};
 
     fn sample_zon() -> ZonFile {
         let input = r#"
@@ -304,6 +697,21 @@ mod tests {
         assert_eq!(zon.get(&["dependencies", "zigimg", "hash"]), None);
     }
 
+    #[test]
+    fn test_fingerprint_round_trip_keeps_hex() {
+        let zon =
+            ZonFile::parse_str(r#".{ .name = .demo, .fingerprint = 0xA1B2C3D4E5F60718 }"#).unwrap();
+        let written = zon.to_string_pretty();
+        assert!(written.contains(".fingerprint = 0xA1B2C3D4E5F60718,"));
+        assert_eq!(ZonFile::parse_str(&written).unwrap(), zon);
+
+        let fingerprint = zon.get(&["fingerprint"]).and_then(ZonValue::as_number);
+        assert_eq!(
+            fingerprint.and_then(|n| n.as_u64()),
+            Some(0xA1B2_C3D4_E5F6_0718)
+        );
+    }
+
     #[test]
     fn test_to_struct_conversion() {
         let zon = sample_zon();
@@ -313,6 +721,10 @@ mod tests {
         assert_eq!(root.version, "0.1.0");
         assert!(root.paths.contains(&"src".to_string()));
         assert!(root.dependencies.contains_key("zigimg"));
+        assert_eq!(
+            root.dependencies["zigimg"].dependency_id(),
+            "example.com/zigimg.tar.gz"
+        );
     }
 
     #[test]
@@ -348,6 +760,57 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_display_quotes_enum_literals() {
+        let zon = ZonFile::parse_str(r#".{ .name = .app, .version = .@"1.0.2" }"#).unwrap();
+        assert_eq!(
+            zon.get(&["version"]),
+            Some(&ZonValue::EnumLiteral("1.0.2".into()))
+        );
+        let written = zon.to_string_pretty();
+        assert!(written.contains(r#".version = .@"1.0.2","#));
+        assert_eq!(ZonFile::parse_str(&written).unwrap(), zon);
+    }
+
+    #[test]
+    fn test_to_string_pretty_at_subtree() {
+        let zon = sample_zon();
+        let deps = zon.to_string_pretty_at(&["dependencies"]).unwrap();
+
+        assert!(deps.starts_with(".{\n    .zigimg = .{\n"));
+        assert!(deps.contains("\n        .lazy = true,\n"));
+        assert!(deps.ends_with("\n    },\n}"));
+        assert!(!deps.contains(".name"));
+        assert_eq!(
+            Some(&ZonFile::parse_str(&deps).unwrap().data),
+            zon.get(&["dependencies"])
+        );
+
+        assert_eq!(
+            zon.to_string_pretty_at(&["version"]).as_deref(),
+            Some("\"0.1.0\"")
+        );
+        assert_eq!(zon.to_string_pretty_at(&["dependencies", "missing"]), None);
+    }
+
+    #[test]
+    fn test_get_ref_borrows_values() {
+        use uniparse_core::{ParsedFile, ValueRef};
+
+        let zon = sample_zon();
+        let paths = zon.get_ref(&["paths"]).unwrap();
+        assert_eq!(
+            paths,
+            ValueRef::Seq(vec![ValueRef::Str("src"), ValueRef::Str("README.md")])
+        );
+
+        let dep = zon.get_ref(&["dependencies", "zigimg"]).unwrap();
+        assert_eq!(
+            dep.get("url").and_then(ValueRef::as_str),
+            Some("https://example.com/zigimg.tar.gz")
+        );
+    }
+
     #[test]
     fn test_zonvalue_accessors() {
         let val = ZonValue::String("hello".into());
@@ -378,6 +841,24 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_parse_error_codes() {
+        use uniparse_core::DiagnosticCode;
+
+        assert_eq!(
+            ZonFile::parse_str(".{ .a = @x }").unwrap_err().code(),
+            "ZON002"
+        );
+        assert_eq!(
+            ZonFile::parse_str(".{ .a = nope }").unwrap_err().code(),
+            "ZON003"
+        );
+        assert_eq!(
+            ZonFile::parse_str(".{ .a = \"x\" ").unwrap_err().code(),
+            "ZON004"
+        );
+    }
+
     #[test]
     fn test_invalid_set_on_non_object() {
         let mut zon = ZonFile {
@@ -387,4 +868,178 @@ mod tests {
         let result = zon.set(&["foo"], ZonValue::Bool(true));
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_semantic_eq_ignores_key_order_and_radix() {
+        let a = ZonFile::parse_str(
+            r#".{ .name = .demo, .fingerprint = 0x10, .paths = .{ "src", "build.zig" } }"#,
+        )
+        .unwrap();
+        let b = ZonFile::parse_str(
+            r#".{ .paths = .{ "src", "build.zig" }, .fingerprint = 16, .name = .demo }"#,
+        )
+        .unwrap();
+        assert!(a.semantic_eq(&b));
+
+        let c = ZonFile::parse_str(
+            r#".{ .name = .demo, .fingerprint = 0x10, .paths = .{ "build.zig", "src" } }"#,
+        )
+        .unwrap();
+        assert!(!a.semantic_eq(&c));
+    }
+
+    #[test]
+    fn test_duplicate_keys_are_reported_with_both_spans() {
+        let src = ".{\n    .name = \"a\",\n    .deps = .{ .x = .{ .url = \"1\", .url = \"2\" } },\n    .name = \"b\",\n}";
+
+        let (zon, diagnostics) = ZonFile::parse_with_diagnostics(src).unwrap();
+        assert_eq!(zon.get(&["name"]).and_then(ZonValue::as_str), Some("b"));
+        assert_eq!(diagnostics.len(), 2);
+
+        let url = &diagnostics[0];
+        assert_eq!(url.code(), "ZON008");
+        assert!(url.message.contains("`.deps.x.url`"));
+        assert_eq!((url.related[0].line, url.related[0].column), (3, 24));
+        assert_eq!((url.span.line, url.span.column), (3, 36));
+        assert_eq!(&src[url.span.byte_range.clone()], ".url");
+
+        assert_eq!(diagnostics[1].span.line, 4);
+        assert_eq!(diagnostics[1].related[0].line, 2);
+
+        let err = ZonFile::parse_strict(src).unwrap_err();
+        assert_eq!(err.code(), "ZON008");
+        assert_eq!(
+            err.to_string(),
+            "Duplicate key `.deps.x.url` at line 3, first set at line 3"
+        );
+        assert!(ZonFile::parse_strict(".{ .a = 1, .b = 1 }").is_ok());
+    }
+
+    #[test]
+    fn test_parse_recovering_reports_every_error() {
+        let src = ".{
+    .name = .app,
+    .version \"0.1.0\",
+    .minimum_zig_version = 0.13.x,
+    .dependencies = .{
+        .a = .{ .url = \"a\", .lazy = yes },
+        .b = .{ .url = \"b\" },
+        .a = .{ .url = \"c\" },
+    },
+    .paths = .{ \"src\", 1, \"build.zig\" },
+";
+        let (zon, diagnostics) = ZonFile::parse_recovering(src);
+        let found: Vec<_> = diagnostics
+            .iter()
+            .map(|d| (d.code(), d.span.line, &src[d.span.byte_range.clone()]))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("ZON004", 3, "\"0.1.0\""),
+                ("ZON004", 4, "0.13.x"),
+                ("ZON003", 6, "yes"),
+                ("ZON008", 8, ".a"),
+                ("ZON004", 10, "1"),
+                ("ZON004", 11, ""),
+            ]
+        );
+
+        // the rest of the document is kept
+        assert!(zon.get(&["version"]).is_none());
+        assert_eq!(
+            zon.get(&["name"]),
+            Some(&ZonValue::EnumLiteral("app".to_string()))
+        );
+        assert_eq!(
+            zon.get(&["dependencies", "b", "url"])
+                .and_then(ZonValue::as_str),
+            Some("b")
+        );
+        assert_eq!(
+            zon.get(&["paths"]),
+            Some(&ZonValue::List(vec![
+                ZonValue::String("src".to_string()),
+                ZonValue::String("build.zig".to_string()),
+            ]))
+        );
+
+        // valid input recovers to the same value as parse_str
+        let (zon, diagnostics) = ZonFile::parse_recovering(&sample_zon().to_string_pretty());
+        assert!(diagnostics.is_empty());
+        assert_eq!(zon.data, sample_zon().data);
+        assert!(ZonFile::parse_str(src).is_err());
+    }
+
+    #[test]
+    fn test_shared_document_is_copy_on_write() {
+        use uniparse_core::{Document, ParsedFile};
+
+        let shared: Document<ZonFile> =
+            ParsedFile::parse_str(r#".{ .name = "demo", .version = "0.1.0" }"#).unwrap();
+        let worker = {
+            let doc = shared.clone();
+            std::thread::spawn(move || {
+                doc.get(&["name"])
+                    .and_then(ZonValue::as_str)
+                    .map(String::from)
+            })
+        };
+
+        let mut edited = shared.clone();
+        edited
+            .set(&["version"], ZonValue::String("0.2.0".into()))
+            .unwrap();
+
+        assert_eq!(worker.join().unwrap().as_deref(), Some("demo"));
+        assert_eq!(
+            shared.get(&["version"]).and_then(ZonValue::as_str),
+            Some("0.1.0")
+        );
+        assert_eq!(
+            edited.get(&["version"]).and_then(ZonValue::as_str),
+            Some("0.2.0")
+        );
+    }
+
+    #[test]
+    fn test_validate_schema_reports_paths() {
+        use uniparse_core::{Schema, ValueKind};
+
+        let zon = ZonFile::parse_str(
+            r#".{
+                .name = .demo,
+                .dependencies = .{
+                    .ok = .{ .url = "https://a", .hash = "1220aa" },
+                    .local = .{ .path = "../local" },
+                    .bad = .{ .url = "https://b", .hash = "1220bb", .lazy = "yes" },
+                },
+            }"#,
+        )
+        .unwrap();
+        let dependency = Schema::of(ValueKind::Map)
+            .required("hash", Schema::of(ValueKind::Str))
+            .field("lazy", Schema::of(ValueKind::Bool));
+        let mut dependencies = Schema::of(ValueKind::Map);
+        for name in ["bad", "local", "ok"] {
+            dependencies = dependencies.field(name, dependency.clone());
+        }
+        let schema = Schema::default()
+            .required("name", Schema::of(ValueKind::Ident))
+            .required("dependencies", dependencies);
+
+        let violations: Vec<_> = zon
+            .data
+            .validate_schema(&schema)
+            .iter()
+            .map(|v| (v.path.join("."), v.code()))
+            .collect();
+        assert_eq!(
+            violations,
+            [
+                ("dependencies.bad.lazy".to_string(), "SCHEMA002"),
+                ("dependencies.local.hash".to_string(), "SCHEMA001"),
+            ]
+        );
+    }
 }
@@ -0,0 +1,251 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use uniparse_core::SemanticEq;
+
+use crate::model::ParseError;
+
+/// Base of an integer literal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Radix {
+    Binary,
+    Octal,
+    Decimal,
+    Hex,
+}
+
+impl Radix {
+    fn base(self) -> u32 {
+        match self {
+            Radix::Binary => 2,
+            Radix::Octal => 8,
+            Radix::Decimal => 10,
+            Radix::Hex => 16,
+        }
+    }
+
+    fn prefix(self) -> &'static str {
+        match self {
+            Radix::Binary => "0b",
+            Radix::Octal => "0o",
+            Radix::Decimal => "",
+            Radix::Hex => "0x",
+        }
+    }
+}
+
+/// A number literal that remembers how it was written.
+///
+/// No arithmetic is offered: the point is that values such as
+/// `.fingerprint = 0xA1B2C3D4E5F60718` are written back exactly as they were
+/// read, while still being readable as integers.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ZonNumber {
+    raw: String,
+    radix: Radix,
+    float: bool,
+}
+
+impl ZonNumber {
+    /// Creates an integer literal written in `radix`, e.g. `0x1f` for
+    /// `ZonNumber::with_radix(31, Radix::Hex)`.
+    pub fn with_radix(value: u64, radix: Radix) -> Self {
+        let digits = match radix {
+            Radix::Binary => format!("{:b}", value),
+            Radix::Octal => format!("{:o}", value),
+            Radix::Decimal => value.to_string(),
+            Radix::Hex => format!("{:x}", value),
+        };
+        ZonNumber {
+            raw: format!("{}{}", radix.prefix(), digits),
+            radix,
+            float: false,
+        }
+    }
+
+    /// A decimal literal for `value`.
+    pub(crate) fn from_i64(value: i64) -> Self {
+        ZonNumber {
+            raw: value.to_string(),
+            radix: Radix::Decimal,
+            float: false,
+        }
+    }
+
+    /// A float literal for `value`, always written with a fraction or
+    /// exponent (`1.0`) so it reads back as a float.
+    pub(crate) fn from_f64(value: f64) -> Self {
+        ZonNumber {
+            raw: format!("{:?}", value),
+            radix: Radix::Decimal,
+            float: true,
+        }
+    }
+
+    /// The literal exactly as written in the source.
+    pub fn raw(&self) -> &str {
+        &self.raw
+    }
+
+    pub fn radix(&self) -> Radix {
+        self.radix
+    }
+
+    pub fn is_float(&self) -> bool {
+        self.float
+    }
+
+    /// The value as an unsigned integer, if it is a non-negative integer
+    /// that fits.
+    pub fn as_u64(&self) -> Option<u64> {
+        if self.float || self.raw.starts_with('-') {
+            return None;
+        }
+        u64::from_str_radix(&self.digits(), self.radix.base()).ok()
+    }
+
+    /// The value as a signed integer, if it is an integer that fits.
+    pub fn as_i64(&self) -> Option<i64> {
+        if self.float {
+            return None;
+        }
+        let magnitude = i128::from_str_radix(&self.digits(), self.radix.base()).ok()?;
+        let value = if self.raw.starts_with('-') {
+            -magnitude
+        } else {
+            magnitude
+        };
+        i64::try_from(value).ok()
+    }
+
+    /// The value as a float. Integers convert (possibly losing precision);
+    /// only decimal floats are supported.
+    pub fn as_f64(&self) -> Option<f64> {
+        if !self.float {
+            return self
+                .as_i64()
+                .map(|n| n as f64)
+                .or_else(|| self.as_u64().map(|n| n as f64));
+        }
+        self.raw.replace('_', "").parse().ok()
+    }
+
+    /// Digits without sign, radix prefix or `_` separators.
+    fn digits(&self) -> String {
+        let unsigned = self.raw.trim_start_matches('-');
+        unsigned[self.radix.prefix().len()..].replace('_', "")
+    }
+}
+
+impl SemanticEq for ZonNumber {
+    /// Compares by value, so `0x10`, `16` and `1_6` are equal. Literals that
+    /// don't fit any numeric type fall back to comparing their text.
+    fn semantic_eq(&self, other: &Self) -> bool {
+        if self.float || other.float {
+            return match (self.as_f64(), other.as_f64()) {
+                (Some(a), Some(b)) => a == b,
+                _ => self.raw == other.raw,
+            };
+        }
+        match (self.as_i64(), other.as_i64()) {
+            (Some(a), Some(b)) => a == b,
+            _ => match (self.as_u64(), other.as_u64()) {
+                (Some(a), Some(b)) => a == b,
+                _ => self.raw == other.raw,
+            },
+        }
+    }
+}
+
+impl FromStr for ZonNumber {
+    type Err = ParseError;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseError::UnexpectedToken(format!("Invalid number literal: {}", raw));
+        let unsigned = raw.strip_prefix('-').unwrap_or(raw);
+
+        let radix = match unsigned.get(..2) {
+            Some("0x") => Radix::Hex,
+            Some("0o") => Radix::Octal,
+            Some("0b") => Radix::Binary,
+            _ => Radix::Decimal,
+        };
+        let digits = &unsigned[radix.prefix().len()..];
+        if !digits.starts_with(|c: char| c.is_digit(radix.base())) {
+            return Err(invalid());
+        }
+
+        let float = radix == Radix::Decimal && digits.contains(['.', 'e', 'E']);
+        let valid = if float {
+            digits.replace('_', "").parse::<f64>().is_ok()
+        } else {
+            digits.chars().all(|c| c == '_' || c.is_digit(radix.base()))
+        };
+        if !valid {
+            return Err(invalid());
+        }
+
+        Ok(ZonNumber {
+            raw: raw.to_string(),
+            radix,
+            float,
+        })
+    }
+}
+
+impl From<u64> for ZonNumber {
+    fn from(value: u64) -> Self {
+        ZonNumber::with_radix(value, Radix::Decimal)
+    }
+}
+
+impl Display for ZonNumber {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.raw)
+    }
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_keeps_radix_and_text() {
+        let n: ZonNumber = "0xABCDEF0123456789".parse().unwrap();
+        assert_eq!(n.radix(), Radix::Hex);
+        assert_eq!(n.as_u64(), Some(0xABCD_EF01_2345_6789));
+        assert_eq!(n.to_string(), "0xABCDEF0123456789");
+
+        let n: ZonNumber = "-1_000".parse().unwrap();
+        assert_eq!(n.radix(), Radix::Decimal);
+        assert_eq!(n.as_i64(), Some(-1000));
+        assert_eq!(n.as_u64(), None);
+
+        let n: ZonNumber = "0b1010".parse().unwrap();
+        assert_eq!(n.as_u64(), Some(10));
+
+        let n: ZonNumber = "1.5e3".parse().unwrap();
+        assert!(n.is_float());
+        assert_eq!(n.as_f64(), Some(1500.0));
+        assert_eq!(n.as_i64(), None);
+    }
+
+    #[test]
+    fn test_invalid_literals() {
+        for raw in ["-", "0x", "12abc", "0b102", "1.2.3"] {
+            assert!(raw.parse::<ZonNumber>().is_err(), "{raw}");
+        }
+    }
+
+    #[test]
+    fn test_with_radix() {
+        assert_eq!(ZonNumber::with_radix(255, Radix::Hex).raw(), "0xff");
+        assert_eq!(ZonNumber::with_radix(8, Radix::Octal).raw(), "0o10");
+        assert_eq!(ZonNumber::from(42).raw(), "42");
+    }
+}
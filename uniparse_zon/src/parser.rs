@@ -1,42 +1,224 @@
 use std::collections::HashMap;
+use std::iter::Peekable;
+use std::ops::Range;
+use std::str::CharIndices;
 
-use crate::model::ZonValue;
+use crate::diagnostic::Span;
+use crate::model::{ParseError, ZonValue};
+use crate::number::ZonNumber;
 
 #[derive(Debug, Clone, PartialEq)]
-enum ZonToken {
+pub(crate) enum ZonToken {
     DotKey(String),
     Equals,
     OpenBrace,
     CloseBrace,
     String(String),
+    Number(ZonNumber),
     Bool(bool),
     Comma,
+    /// Source the tokenizer couldn't read, already reported as an error.
+    Invalid,
 }
 
-pub fn parse_zon(input: &str) -> Result<ZonValue, String> {
-    let tokens = tokenize(input)?;
-    // println!("TOKENS: {:#?}", tokens); // 👈 print token stream
-    let (val, _) = parse_value(&tokens, 0)?;
-    Ok(val)
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(len = input.len()), err)
+)]
+pub fn parse_zon(input: &str) -> Result<ZonValue, ParseError> {
+    parse_zon_with_duplicates(input).map(|(value, _)| value)
 }
 
-fn tokenize(input: &str) -> Result<Vec<ZonToken>, String> {
+/// A key set more than once in the same object.
+pub(crate) struct DuplicateKey {
+    pub path: String,
+    pub first: Span,
+    pub second: Span,
+}
+
+/// Parses `input`, also reporting duplicate keys, which don't prevent
+/// building a value: the last one wins.
+pub(crate) fn parse_zon_with_duplicates(
+    input: &str,
+) -> Result<(ZonValue, Vec<DuplicateKey>), ParseError> {
+    let (tokens, spans) = tokenize(input)?;
+    #[cfg(feature = "tracing")]
+    tracing::trace!(count = tokens.len(), ?tokens, "tokenized zon source");
+    let mut ctx = Context {
+        source: input,
+        spans,
+        path: Vec::new(),
+        duplicates: Vec::new(),
+        errors: None,
+    };
+    let (val, end) = parse_value(&tokens, 0, &mut ctx)?;
+    if let Some(err) = trailing(&tokens, end) {
+        return Err(err);
+    }
+    Ok((val, ctx.duplicates))
+}
+
+/// An error for tokens left after the document's value, e.g. a stray `}`.
+fn trailing(tokens: &[ZonToken], end: usize) -> Option<ParseError> {
+    tokens
+        .get(end)
+        .map(|token| ParseError::UnexpectedToken(format!("Expected end of input, got {:?}", token)))
+}
+
+/// What [`parse_zon_recovering`] found.
+pub(crate) struct Recovered {
+    pub value: ZonValue,
+    pub duplicates: Vec<DuplicateKey>,
+    /// Every syntax error, in source order, with where it was found.
+    pub errors: Vec<(ParseError, Span)>,
+}
+
+/// Parses `input` without stopping at the first syntax error: a bad
+/// character or identifier is skipped, and a malformed entry is dropped up
+/// to the next `,` or `}` of its object or list. An unclosed object keeps
+/// the entries read so far.
+pub(crate) fn parse_zon_recovering(input: &str) -> Recovered {
+    let mut errors = Vec::new();
+    let (tokens, spans) = tokenize_with(input, &mut errors);
+    let mut ctx = Context {
+        source: input,
+        spans,
+        path: Vec::new(),
+        duplicates: Vec::new(),
+        errors: Some(errors),
+    };
+    let value = match parse_value(&tokens, 0, &mut ctx) {
+        Ok((value, end)) => {
+            if let Some(err) = trailing(&tokens, end) {
+                ctx.record(err, end);
+            }
+            value
+        }
+        Err(e) => {
+            ctx.record(e, 0);
+            ZonValue::Object(HashMap::new())
+        }
+    };
+    let mut errors = ctx.errors.take().unwrap_or_default();
+    errors.sort_by_key(|(_, span)| span.byte_range.start);
+    Recovered {
+        value,
+        duplicates: ctx.duplicates,
+        errors,
+    }
+}
+
+/// State shared across the recursive descent.
+struct Context<'a> {
+    source: &'a str,
+    /// Byte range of each token, parallel to the token list.
+    spans: Vec<Range<usize>>,
+    /// Keys of the objects being parsed, outermost first.
+    path: Vec<String>,
+    duplicates: Vec<DuplicateKey>,
+    /// Syntax errors seen so far when recovering; `None` stops at the
+    /// first one.
+    errors: Option<Vec<(ParseError, Span)>>,
+}
+
+impl Context<'_> {
+    fn span(&self, token: usize) -> Span {
+        match self.spans.get(token) {
+            Some(range) => Span::from_range(self.source, range.clone()),
+            // past the last token: the end of the input
+            None => Span::from_range(self.source, self.source.len()..self.source.len()),
+        }
+    }
+
+    /// Keeps `err`, found at `token`, when recovering; otherwise hands it
+    /// back to stop parsing.
+    fn recover(&mut self, err: ParseError, token: usize) -> Result<(), ParseError> {
+        if self.errors.is_none() {
+            return Err(err);
+        }
+        self.record(err, token);
+        Ok(())
+    }
+
+    fn record(&mut self, err: ParseError, token: usize) {
+        let span = self.span(token);
+        if let Some(errors) = &mut self.errors
+            // an invalid token was reported by the tokenizer already
+            && !errors.iter().any(|(_, seen)| seen.byte_range == span.byte_range)
+        {
+            errors.push((err, span));
+        }
+    }
+}
+
+/// The index just past the entry starting at `i`: after its `,`, or at
+/// the `}` closing its object or list.
+fn skip_entry(tokens: &[ZonToken], mut i: usize) -> usize {
+    let mut depth = 0;
+    while let Some(token) = tokens.get(i) {
+        match token {
+            ZonToken::OpenBrace => depth += 1,
+            ZonToken::CloseBrace if depth == 0 => return i,
+            ZonToken::CloseBrace => depth -= 1,
+            ZonToken::Comma if depth == 0 => return i + 1,
+            _ => {}
+        }
+        i += 1;
+    }
+    i
+}
+
+pub(crate) fn tokenize(input: &str) -> Result<(Vec<ZonToken>, Vec<Range<usize>>), ParseError> {
+    let mut errors = Vec::new();
+    let (tokens, spans) = tokenize_with(input, &mut errors);
+    match errors.into_iter().next() {
+        Some((err, _)) => Err(err),
+        None => Ok((tokens, spans)),
+    }
+}
+
+/// Tokenizes `input`, skipping what can't be tokenized and adding an
+/// error for it to `errors`.
+fn tokenize_with(
+    input: &str,
+    errors: &mut Vec<(ParseError, Span)>,
+) -> (Vec<ZonToken>, Vec<Range<usize>>) {
     let mut tokens = Vec::new();
-    let mut chars = input.chars().peekable();
+    let mut spans = Vec::new();
+    let mut chars = input.char_indices().peekable();
+    // byte offset just past the last consumed character
+    let offset = |chars: &mut Peekable<CharIndices>| chars.peek().map_or(input.len(), |&(i, _)| i);
 
-    while let Some(&ch) = chars.peek() {
+    while let Some(&(start, ch)) = chars.peek() {
+        let count = tokens.len();
         match ch {
             '.' => {
                 chars.next(); // consume '.'
                 // NEW: check for `. {` as root-level object
-                if chars.peek() == Some(&'{') {
+                if matches!(chars.peek(), Some((_, '{'))) {
                     chars.next(); // consume '{'
                     tokens.push(ZonToken::OpenBrace);
+                    spans.push(start..offset(&mut chars));
+                    continue;
+                }
+
+                // `.@"key"`, for keys that aren't identifiers
+                if matches!(chars.peek(), Some((_, '@'))) {
+                    chars.next();
+                    if !matches!(chars.peek(), Some((_, '"'))) {
+                        let span = Span::from_range(input, start + 1..start + 2);
+                        errors.push((ParseError::UnexpectedCharacter('@'), span));
+                        tokens.push(ZonToken::Invalid);
+                    } else {
+                        chars.next();
+                        tokens.push(ZonToken::DotKey(string_body(&mut chars)));
+                    }
+                    spans.push(start..offset(&mut chars));
                     continue;
                 }
 
                 let mut key = String::new();
-                while let Some(&c) = chars.peek() {
+                while let Some(&(_, c)) = chars.peek() {
                     if c.is_alphanumeric() || c == '_' || c == '-' {
                         key.push(c);
                         chars.next();
@@ -70,21 +252,56 @@ fn tokenize(input: &str) -> Result<Vec<ZonToken>, String> {
             }
             '"' => {
                 chars.next(); // consume quote
-                let mut val = String::new();
-                while let Some(c) = chars.next() {
-                    if c == '"' {
-                        break;
-                    }
-                    val.push(c);
-                }
-                tokens.push(ZonToken::String(val));
+                tokens.push(ZonToken::String(string_body(&mut chars)));
             }
             c if c.is_whitespace() => {
                 chars.next(); // skip
             }
+            '/' => {
+                chars.next();
+                if !matches!(chars.peek(), Some((_, '/'))) {
+                    let span = Span::from_range(input, start..start + 1);
+                    errors.push((ParseError::UnexpectedCharacter('/'), span));
+                    tokens.push(ZonToken::Invalid);
+                } else {
+                    // line comment, skip until end of line
+                    for (_, c) in chars.by_ref() {
+                        if c == '\n' {
+                            break;
+                        }
+                    }
+                }
+            }
+            c if c.is_ascii_digit() || c == '-' => {
+                let mut raw = String::new();
+                while let Some(&(_, c)) = chars.peek() {
+                    let hex = raw.trim_start_matches('-').starts_with("0x");
+                    // the sign of a decimal exponent, as in `2.5e-1`
+                    let exponent_sign = matches!(c, '-' | '+') && !hex && raw.ends_with(['e', 'E']);
+                    if c.is_ascii_alphanumeric()
+                        || c == '_'
+                        || c == '.'
+                        || (c == '-' && raw.is_empty())
+                        || exponent_sign
+                    {
+                        raw.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                match raw.parse() {
+                    Ok(number) => tokens.push(ZonToken::Number(number)),
+                    Err(e) => {
+                        let span = Span::from_range(input, start..offset(&mut chars));
+                        errors.push((e, span));
+                        tokens.push(ZonToken::Invalid);
+                    }
+                }
+            }
             c if c.is_alphabetic() => {
                 let mut ident = String::new();
-                while let Some(&c) = chars.peek() {
+                while let Some(&(_, c)) = chars.peek() {
                     if c.is_alphanumeric() || c == '_' {
                         ident.push(c);
                         chars.next();
@@ -96,19 +313,92 @@ fn tokenize(input: &str) -> Result<Vec<ZonToken>, String> {
                 match ident.as_str() {
                     "true" => tokens.push(ZonToken::Bool(true)),
                     "false" => tokens.push(ZonToken::Bool(false)),
-                    _ => return Err(format!("Unknown identifier: {}", ident)),
+                    _ => {
+                        let span = Span::from_range(input, start..offset(&mut chars));
+                        errors.push((ParseError::UnknownIdentifier(ident), span));
+                        tokens.push(ZonToken::Invalid);
+                    }
                 }
             }
             _ => {
-                return Err(format!("Unexpected character: {}", ch));
+                chars.next();
+                let span = Span::from_range(input, start..offset(&mut chars));
+                errors.push((ParseError::UnexpectedCharacter(ch), span));
+                tokens.push(ZonToken::Invalid);
             }
         }
+
+        if tokens.len() > count {
+            spans.push(start..offset(&mut chars));
+        }
+    }
+
+    (tokens, spans)
+}
+
+/// The contents of a string literal whose opening quote was consumed, up to
+/// and including the closing one. `\"` and `\\` are unescaped; other escapes
+/// such as `\n` are kept as written.
+fn string_body(chars: &mut Peekable<CharIndices>) -> String {
+    let mut value = String::new();
+    while let Some((_, c)) = chars.next() {
+        if c == '"' {
+            break;
+        }
+        if c == '\\'
+            && let Some(&(_, escaped @ ('"' | '\\'))) = chars.peek()
+        {
+            chars.next();
+            value.push(escaped);
+            continue;
+        }
+        value.push(c);
     }
+    value
+}
+
+/// `text` as a string literal, escaping quotes and backslashes the way the
+/// tokenizer reads them back.
+pub(crate) fn quote(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('"');
+    for c in text.chars() {
+        if c == '"' || c == '\\' {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out.push('"');
+    out
+}
 
-    Ok(tokens)
+/// The key of a `.key` or `.@"key"` token's text.
+pub(crate) fn dot_key(text: &str) -> &str {
+    let key = text.strip_prefix('.').unwrap_or(text);
+    key.strip_prefix("@\"")
+        .map_or(key, |quoted| quoted.strip_suffix('"').unwrap_or(quoted))
 }
 
-fn parse_value(tokens: &[ZonToken], mut i: usize) -> Result<(ZonValue, usize), String> {
+/// `key` as written after the `.` of a field: bare if it's an identifier,
+/// `@"key"` otherwise.
+pub(crate) fn field_name(key: &str) -> String {
+    let mut chars = key.chars();
+    let identifier = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if identifier {
+        key.to_string()
+    } else {
+        format!("@{}", quote(key))
+    }
+}
+
+fn parse_value(
+    tokens: &[ZonToken],
+    mut i: usize,
+    ctx: &mut Context,
+) -> Result<(ZonValue, usize), ParseError> {
     match tokens.get(i) {
         Some(ZonToken::OpenBrace) => {
             i += 1;
@@ -128,15 +418,22 @@ fn parse_value(tokens: &[ZonToken], mut i: usize) -> Result<(ZonValue, usize), S
                             i += 1;
                         }
                     } else {
-                        return Err(format!("Expected string in list, got {:?}", tokens.get(i)));
+                        let err = ParseError::UnexpectedToken(format!(
+                            "Expected string in list, got {:?}",
+                            tokens.get(i)
+                        ));
+                        ctx.recover(err, i)?;
+                        i = skip_entry(tokens, i);
                     }
                 }
 
                 if tokens.get(i) != Some(&ZonToken::CloseBrace) {
-                    return Err(format!(
+                    let err = ParseError::UnexpectedToken(format!(
                         "Expected closing '}}' for list, got {:?}",
                         tokens.get(i)
                     ));
+                    ctx.recover(err, i)?;
+                    return Ok((ZonValue::List(list), i));
                 }
 
                 return Ok((ZonValue::List(list), i + 1));
@@ -144,16 +441,42 @@ fn parse_value(tokens: &[ZonToken], mut i: usize) -> Result<(ZonValue, usize), S
 
             // ✅ Parse object as before
             let mut object = HashMap::new();
+            // token index of each key, to point at both sides of a duplicate
+            let mut seen: HashMap<String, usize> = HashMap::new();
 
             while i < tokens.len() && !matches!(tokens[i], ZonToken::CloseBrace) {
                 match &tokens[i] {
                     ZonToken::DotKey(key) => {
+                        let key_token = i;
                         i += 1;
                         if tokens.get(i) != Some(&ZonToken::Equals) {
-                            return Err(format!("Expected '=' after key '{}'", key));
+                            let err = ParseError::UnexpectedToken(format!(
+                                "Expected '=' after key '{}'",
+                                key
+                            ));
+                            ctx.recover(err, i)?;
+                            i = skip_entry(tokens, i);
+                            continue;
                         }
                         i += 1;
-                        let (val, next) = parse_value(tokens, i)?;
+                        ctx.path.push(key.clone());
+                        let (val, next) = match parse_value(tokens, i, ctx) {
+                            Ok(parsed) => parsed,
+                            Err(err) => {
+                                ctx.path.pop();
+                                ctx.recover(err, i)?;
+                                i = skip_entry(tokens, i);
+                                continue;
+                            }
+                        };
+                        if let Some(first) = seen.insert(key.clone(), key_token) {
+                            ctx.duplicates.push(DuplicateKey {
+                                path: ctx.path.join("."),
+                                first: ctx.span(first),
+                                second: ctx.span(key_token),
+                            });
+                        }
+                        ctx.path.pop();
                         object.insert(key.clone(), val);
                         i = next;
 
@@ -161,15 +484,24 @@ fn parse_value(tokens: &[ZonToken], mut i: usize) -> Result<(ZonValue, usize), S
                             i += 1;
                         }
                     }
-                    _ => return Err(format!("Expected .key, got {:?}", tokens.get(i))),
+                    _ => {
+                        let err = ParseError::UnexpectedToken(format!(
+                            "Expected .key, got {:?}",
+                            tokens.get(i)
+                        ));
+                        ctx.recover(err, i)?;
+                        i = skip_entry(tokens, i);
+                    }
                 }
             }
 
             if tokens.get(i) != Some(&ZonToken::CloseBrace) {
-                return Err(format!(
+                let err = ParseError::UnexpectedToken(format!(
                     "Expected closing '}}' for object, got {:?}",
                     tokens.get(i)
                 ));
+                ctx.recover(err, i)?;
+                return Ok((ZonValue::Object(object), i));
             }
 
             Ok((ZonValue::Object(object), i + 1))
@@ -207,12 +539,19 @@ fn parse_value(tokens: &[ZonToken], mut i: usize) -> Result<(ZonValue, usize), S
 
         Some(ZonToken::Bool(b)) => Ok((ZonValue::Bool(*b), i + 1)),
 
+        Some(ZonToken::Number(n)) => Ok((ZonValue::Number(n.clone()), i + 1)),
+
         Some(ZonToken::DotKey(k)) if k == "true" || k == "false" => {
             let val = k == "true";
             Ok((ZonValue::Bool(val), i + 1))
         }
 
-        _ => Err(format!("Unexpected token at {}", i)),
+        Some(ZonToken::DotKey(k)) => Ok((ZonValue::EnumLiteral(k.clone()), i + 1)),
+
+        _ => Err(ParseError::UnexpectedToken(format!(
+            "Unexpected token at {}",
+            i
+        ))),
     }
 }
 
@@ -294,6 +633,42 @@ fn test_parse_single_string_value() {
     }
 }
 
+#[test]
+fn test_parse_enum_literal_and_number() {
+    let input = r#".{ .name = .zigimg, .fingerprint = 0xd1b2c3a4e5f60718, .count = 42 }"#;
+    let result = parse_zon(input).unwrap();
+
+    assert_eq!(
+        result.get_path(&["name"]),
+        Some(&ZonValue::EnumLiteral("zigimg".into()))
+    );
+    assert_eq!(
+        result.get_path(&["fingerprint"]),
+        Some(&ZonValue::Number("0xd1b2c3a4e5f60718".parse().unwrap()))
+    );
+    assert_eq!(
+        result.get_path(&["count"]),
+        Some(&ZonValue::Number(42.into()))
+    );
+}
+
+#[test]
+fn test_parse_float_exponents() {
+    let input = r#".{ .a = 1e3, .b = 2.5E-1, .c = -1.5e+2, .d = 0x1e }"#;
+    let result = parse_zon(input).unwrap();
+    let number = |key| match result.get_path(&[key]) {
+        Some(ZonValue::Number(n)) => n.clone(),
+        other => panic!("Expected a number, got {:?}", other),
+    };
+
+    assert_eq!(number("a").as_f64(), Some(1000.0));
+    assert_eq!(number("b").as_f64(), Some(0.25));
+    assert_eq!(number("c").raw(), "-1.5e+2");
+    assert_eq!(number("c").as_f64(), Some(-150.0));
+    assert_eq!(number("d").as_u64(), Some(30));
+    assert!(parse_zon(".{ .a = 0x1e-1 }").is_err());
+}
+
 #[test]
 fn test_error_invalid_char() {
     let input = r#".{ .bad = @nope, }"#;
@@ -301,6 +676,62 @@ fn test_error_invalid_char() {
     assert!(result.is_err());
 }
 
+#[test]
+fn test_quoted_keys() {
+    let input = r#".{ .@"zig-clap" = .{ .lazy = true }, .@"two words" = 1 }"#;
+    let result = parse_zon(input).unwrap();
+    assert_eq!(
+        result.get_path(&["zig-clap", "lazy"]),
+        Some(&ZonValue::Bool(true))
+    );
+    assert!(result.get_path(&["two words"]).is_some());
+
+    let written = result.to_string_at(0);
+    assert!(written.contains(r#".@"zig-clap" = .{"#), "{}", written);
+    assert_eq!(parse_zon(&written).unwrap(), result);
+
+    assert!(matches!(
+        parse_zon(".{ .@key = 1 }"),
+        Err(ParseError::UnexpectedCharacter('@'))
+    ));
+}
+
+#[test]
+fn test_strings_escape_quotes_and_backslashes() {
+    let input = r#".{ .path = "C:\\zig \"dev\"\n", .@"a\"b" = 1 }"#;
+    let result = parse_zon(input).unwrap();
+    assert_eq!(
+        result.get_path(&["path"]).and_then(|v| v.as_str()),
+        Some(r#"C:\zig "dev"\n"#)
+    );
+    assert!(result.get_path(&["a\"b"]).is_some());
+
+    let written = result.to_string_at(0);
+    assert!(
+        written.contains(r#".path = "C:\\zig \"dev\"\\n","#),
+        "{}",
+        written
+    );
+    assert!(written.contains(r#".@"a\"b" = 1,"#), "{}", written);
+    assert_eq!(parse_zon(&written).unwrap(), result);
+}
+
+#[test]
+fn test_line_comments_are_skipped() {
+    let input = r#"
+    // package manifest
+    .{
+        .name = "zig", // trailing comment
+    }
+    "#;
+    let result = parse_zon(input).unwrap();
+
+    assert_eq!(
+        result.get_path(&["name"]).and_then(|v| v.as_str()),
+        Some("zig")
+    );
+}
+
 #[test]
 fn test_error_missing_closing() {
     let input = r#".{ .key = "value" "#; // missing closing brace
@@ -0,0 +1,270 @@
+use std::collections::HashMap;
+use std::ops::Range;
+
+use uniparse_core::{CstKind, SourceEdit, SyntaxElement, SyntaxNode, whole_lines};
+
+use crate::cst::{SyntaxKind, ZonCst};
+use crate::model::{ZonFile, ZonValue};
+use crate::parser::{dot_key, field_name};
+
+type Node = SyntaxNode<SyntaxKind>;
+type Element = SyntaxElement<SyntaxKind>;
+type Edit = (Range<usize>, String);
+
+impl ZonFile {
+    /// Writes this file's data into `original`, the source it was parsed
+    /// from, re-serializing only what changed.
+    ///
+    /// Changed values are replaced where they stand, removed fields are
+    /// deleted with their comments and new fields are added at the end of
+    /// their object, sorted by key. Everything else, comments and layout
+    /// included, stays byte for byte as it was. Objects written on one line
+    /// and lists whose length changed are re-serialized whole. If
+    /// `original` doesn't parse, the result is [`ZonFile::to_string_pretty`].
+    ///
+    /// ```rust
+    /// use uniparse_zon::{ZonFile, ZonValue};
+    ///
+    /// let src = ".{\n    // bumped by CI\n    .version = \"0.1.0\",\n    .name = .demo,\n}\n";
+    /// let mut zon = ZonFile::parse_str(src).unwrap();
+    /// zon.set(&["version"], ZonValue::String("0.2.0".into())).unwrap();
+    /// assert_eq!(
+    ///     zon.patch_source(src),
+    ///     ".{\n    // bumped by CI\n    .version = \"0.2.0\",\n    .name = .demo,\n}\n"
+    /// );
+    /// ```
+    pub fn patch_source(&self, original: &str) -> String {
+        SourceEdit::apply(original, &self.patch_edits(original))
+    }
+
+    /// The edits [`ZonFile::patch_source`] makes, in source order, e.g. to
+    /// send to an editor. If `original` doesn't parse, that is a single edit
+    /// replacing all of it.
+    pub fn patch_edits(&self, original: &str) -> Vec<SourceEdit> {
+        let (Ok(cst), Ok(old)) = (ZonCst::parse(original), ZonFile::parse_str(original)) else {
+            return vec![SourceEdit::new(0..original.len(), self.to_string_pretty())];
+        };
+        let Some(root) = value_of(cst.syntax()) else {
+            return vec![SourceEdit::new(0..original.len(), self.to_string_pretty())];
+        };
+
+        let mut edits = Vec::new();
+        patch(original, &root, &old.data, &self.data, 0, &mut edits);
+        edits.sort_by_key(|(range, _)| (range.start, range.end));
+        edits
+            .into_iter()
+            .map(|(range, text)| SourceEdit::new(range, text))
+            .collect()
+    }
+}
+
+/// Edits turning `element`, which holds `old` and sits `depth` levels deep,
+/// into `new`.
+fn patch(
+    src: &str,
+    element: &Element,
+    old: &ZonValue,
+    new: &ZonValue,
+    depth: usize,
+    edits: &mut Vec<Edit>,
+) {
+    if old == new {
+        return;
+    }
+    match (element, old, new) {
+        (SyntaxElement::Node(node), ZonValue::Object(old), ZonValue::Object(new))
+            if patch_object(src, node, old, new, depth, edits) => {}
+        (SyntaxElement::Node(node), ZonValue::List(old), ZonValue::List(new))
+            if old.len() == new.len() =>
+        {
+            let elements = node.children().filter(|n| n.kind() == SyntaxKind::Element);
+            for (item, (old, new)) in elements.zip(old.iter().zip(new)) {
+                if let Some(value) = value_of(&item) {
+                    patch(src, &value, old, new, depth + 1, edits);
+                }
+            }
+        }
+        _ => edits.push((element.text_range(), new.to_string_at(depth))),
+    }
+}
+
+/// Patches the fields of `node` one by one. Returns `false`, leaving
+/// `edits` alone, when fields are added or removed in an object that isn't
+/// laid out one field per line.
+fn patch_object(
+    src: &str,
+    node: &Node,
+    old: &HashMap<String, ZonValue>,
+    new: &HashMap<String, ZonValue>,
+    depth: usize,
+    edits: &mut Vec<Edit>,
+) -> bool {
+    let mut added: Vec<_> = new.iter().filter(|(k, _)| !old.contains_key(*k)).collect();
+    let reshaped = !added.is_empty() || old.keys().any(|k| !new.contains_key(k));
+    let close = node.text_range().end - 1;
+    let close_line = src[..close].rfind('\n').map(|i| i + 1);
+    let close_line = match close_line {
+        Some(start) if src[start..close].trim().is_empty() => start,
+        _ if reshaped => return false,
+        _ => close,
+    };
+
+    let mut last_kept = None;
+    for field in node.children().filter(|n| n.kind() == SyntaxKind::Field) {
+        let Some(key) = field
+            .tokens()
+            .find(|t| t.kind() == SyntaxKind::DotKey)
+            .map(|t| dot_key(t.text()).to_string())
+        else {
+            continue;
+        };
+        match (old.get(&key), new.get(&key)) {
+            (_, None) => edits.push((whole_lines(src, field.text_range()), String::new())),
+            (Some(old), Some(new)) => {
+                if let Some(value) = value_of(&field) {
+                    patch(src, &value, old, new, depth + 1, edits);
+                }
+                last_kept = Some(field);
+            }
+            (None, Some(_)) => {}
+        }
+    }
+
+    if added.is_empty() {
+        return true;
+    }
+    added.sort_by_key(|(k, _)| k.as_str());
+    if let Some(field) = last_kept
+        && !field.tokens().any(|t| t.kind() == SyntaxKind::Comma)
+        && let Some(value) = value_of(&field)
+    {
+        let end = value.text_range().end;
+        edits.push((end..end, ",".to_string()));
+    }
+    let indent = "    ".repeat(depth + 1);
+    let text = added
+        .into_iter()
+        .map(|(k, v)| {
+            let value = v.to_string_at(depth + 1);
+            format!("{}.{} = {},\n", indent, field_name(k), value)
+        })
+        .collect();
+    edits.push((close_line..close_line, text));
+    true
+}
+
+/// The value of a file, field or list element.
+fn value_of(node: &Node) -> Option<Element> {
+    let mut children = node
+        .children_with_tokens()
+        .into_iter()
+        .filter(|e| !e.kind().is_trivia());
+    if node.kind() == SyntaxKind::Field {
+        children.find(|e| e.kind() == SyntaxKind::Equals)?;
+    }
+    children.next()
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SRC: &str = r#"//! Demo package.
+.{
+    .name = .demo,
+    // bumped by CI
+    .version = "0.1.0",
+    .fingerprint = 0x9a3c,
+    .dependencies = .{
+        /// Fork with the PNG fix.
+        .zigimg = .{
+            .url = "https://example.com/zigimg.tar.gz",
+            .hash = "1220ab",
+        },
+        .zlib = .{ .url = "https://example.com/zlib.tar.gz", .hash = "1220cd" },
+    },
+    .paths = .{ "build.zig", "src" },
+}
+"#;
+
+    #[test]
+    fn test_patch_source_unchanged_is_identical() {
+        let zon = ZonFile::parse_str(SRC).unwrap();
+        assert_eq!(zon.patch_source(SRC), SRC);
+    }
+
+    #[test]
+    fn test_patch_source_only_touches_changes() {
+        let mut zon = ZonFile::parse_str(SRC).unwrap();
+        zon.set(&["version"], ZonValue::String("0.2.0".into()))
+            .unwrap();
+        zon.set(
+            &["dependencies", "zlib", "hash"],
+            ZonValue::String("1220ef".into()),
+        )
+        .unwrap();
+        zon.remove(&["dependencies", "zigimg"]).unwrap();
+        zon.set(&["minimum_zig_version"], ZonValue::String("0.14.0".into()))
+            .unwrap();
+        zon.set(
+            &["paths"],
+            ZonValue::List(vec![ZonValue::String("src".into())]),
+        )
+        .unwrap();
+
+        let patched = zon.patch_source(SRC);
+        assert_eq!(
+            patched,
+            r#"//! Demo package.
+.{
+    .name = .demo,
+    // bumped by CI
+    .version = "0.2.0",
+    .fingerprint = 0x9a3c,
+    .dependencies = .{
+        .zlib = .{ .url = "https://example.com/zlib.tar.gz", .hash = "1220ef" },
+    },
+    .paths = .{
+        "src",
+    },
+    .minimum_zig_version = "0.14.0",
+}
+"#
+        );
+        assert_eq!(ZonFile::parse_str(&patched).unwrap(), zon);
+    }
+
+    #[test]
+    fn test_patch_source_adds_missing_comma_and_falls_back() {
+        let src = ".{\n    .name = .demo\n}";
+        let mut zon = ZonFile::parse_str(src).unwrap();
+        zon.set(&["version"], ZonValue::String("1.0.0".into()))
+            .unwrap();
+        assert_eq!(
+            zon.patch_source(src),
+            ".{\n    .name = .demo,\n    .version = \"1.0.0\",\n}"
+        );
+
+        // a single-line object gaining a field is rewritten whole
+        let src = ".{ .name = .demo }";
+        assert_eq!(zon.patch_source(src), zon.to_string_pretty());
+        assert_eq!(zon.patch_source("not zon"), zon.to_string_pretty());
+    }
+
+    #[test]
+    fn test_patch_edits() {
+        let src = ".{\n    .name = .demo,\n    .version = \"0.1.0\",\n}\n";
+        let mut zon = ZonFile::parse_str(src).unwrap();
+        zon.set(&["version"], ZonValue::String("0.2.0".into()))
+            .unwrap();
+        assert_eq!(zon.patch_edits(src), [SourceEdit::new(37..44, "\"0.2.0\"")]);
+        assert_eq!(
+            zon.patch_edits("not zon"),
+            [SourceEdit::new(0..7, zon.to_string_pretty())]
+        );
+    }
+}
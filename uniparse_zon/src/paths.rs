@@ -0,0 +1,203 @@
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use uniparse_core::DiagnosticCode;
+
+use crate::model::{RootZon, ZonFile, ZonValue};
+
+/// A problem with an entry of a manifest's `.paths`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathIssue {
+    /// The entry doesn't exist relative to the package root.
+    Missing(String),
+    /// The entry is listed more than once (ignoring `./` and trailing `/`).
+    Duplicate(String),
+}
+
+impl DiagnosticCode for PathIssue {
+    fn code(&self) -> &'static str {
+        match self {
+            PathIssue::Missing(_) => "ZON009",
+            PathIssue::Duplicate(_) => "ZON010",
+        }
+    }
+}
+
+impl Display for PathIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PathIssue::Missing(path) => write!(f, "`{}` listed in .paths does not exist", path),
+            PathIssue::Duplicate(path) => {
+                write!(f, "`{}` is listed in .paths more than once", path)
+            }
+        }
+    }
+}
+
+impl RootZon {
+    /// Checks every `.paths` entry against the package directory, the way
+    /// `zig build` would find them when packaging.
+    ///
+    /// Entries are reported in the order they are listed. An empty entry
+    /// stands for the whole package and always exists.
+    pub fn validate_paths(&self, root_dir: impl AsRef<Path>) -> Vec<PathIssue> {
+        let root_dir = root_dir.as_ref();
+        let mut seen: Vec<&str> = Vec::new();
+        let mut issues = Vec::new();
+
+        for path in &self.paths {
+            let normalized = normalize(path);
+            if seen.contains(&normalized) {
+                issues.push(PathIssue::Duplicate(path.clone()));
+                continue;
+            }
+            seen.push(normalized);
+
+            if !normalized.is_empty() && !root_dir.join(normalized).exists() {
+                issues.push(PathIssue::Missing(path.clone()));
+            }
+        }
+        issues
+    }
+}
+
+impl ZonFile {
+    /// Adds `path` to `.paths` unless an equivalent entry is already there
+    /// (ignoring `./` and trailing `/`), creating the list if needed. The
+    /// list is left sorted and without duplicates. Returns whether it
+    /// changed.
+    pub fn add_path(&mut self, path: &str) -> Result<bool, String> {
+        if self.get(&["paths"]).is_none() {
+            self.set(&["paths"], ZonValue::List(Vec::new()))?;
+        }
+        self.edit_paths(|paths| paths.push(path.to_string()))
+    }
+
+    /// Removes every entry of `.paths` equivalent to `path`, leaving the
+    /// list sorted and without duplicates. Returns whether it changed.
+    pub fn remove_path(&mut self, path: &str) -> Result<bool, String> {
+        if self.get(&["paths"]).is_none() {
+            return Ok(false);
+        }
+        let target = normalize(path);
+        self.edit_paths(|paths| paths.retain(|p| normalize(p) != target))
+    }
+
+    /// Applies `edit` to the `.paths` strings, then sorts and dedupes them,
+    /// keeping the first spelling of each entry. An empty `.{}`, which ZON
+    /// can't tell apart from an empty list, counts as one.
+    fn edit_paths(&mut self, edit: impl FnOnce(&mut Vec<String>)) -> Result<bool, String> {
+        let list = match self.get(&["paths"]) {
+            Some(ZonValue::List(list)) => list.as_slice(),
+            Some(ZonValue::Object(map)) if map.is_empty() => &[],
+            _ => return Err("`.paths` is not a list".into()),
+        };
+        let before = list
+            .iter()
+            .map(|v| v.as_str().map(str::to_string))
+            .collect::<Option<Vec<_>>>()
+            .ok_or("`.paths` contains a value that is not a string")?;
+
+        let mut paths = before.clone();
+        edit(&mut paths);
+        let mut seen: Vec<String> = Vec::new();
+        paths.retain(|p| {
+            let normalized = normalize(p).to_string();
+            let new = !seen.contains(&normalized);
+            seen.push(normalized);
+            new
+        });
+        paths.sort_by(|a, b| normalize(a).cmp(normalize(b)));
+
+        if paths == before {
+            return Ok(false);
+        }
+        let list = paths.into_iter().map(ZonValue::String).collect();
+        self.set(&["paths"], ZonValue::List(list))?;
+        Ok(true)
+    }
+}
+
+fn normalize(path: &str) -> &str {
+    let path = path.trim_start_matches("./").trim_end_matches('/');
+    if path == "." { "" } else { path }
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_validate_paths_reports_missing_and_duplicates() {
+        let dir = std::env::temp_dir().join("uniparse_zon_validate_paths");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::write(dir.join("build.zig"), "").unwrap();
+
+        let zon = ZonFile::parse_str(
+            r#".{
+                .name = "pkg",
+                .version = "0.1.0",
+                .paths = .{ "build.zig", "src/", "LICENSE", "./src", "" },
+                .dependencies = .{},
+            }"#,
+        )
+        .unwrap();
+        let root: RootZon = zon.as_struct().unwrap();
+        let issues = root.validate_paths(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            issues,
+            [
+                PathIssue::Missing("LICENSE".into()),
+                PathIssue::Duplicate("./src".into()),
+            ]
+        );
+        assert_eq!(issues[0].code(), "ZON009");
+        assert_eq!(
+            issues[1].to_string(),
+            "`./src` is listed in .paths more than once"
+        );
+    }
+
+    #[test]
+    fn test_add_and_remove_paths_keep_list_sorted() {
+        let mut zon =
+            ZonFile::parse_str(r#".{ .name = "pkg", .paths = .{ "src", "build.zig", "./src/" } }"#)
+                .unwrap();
+        let paths = |zon: &ZonFile| -> Vec<String> {
+            let Some(ZonValue::List(list)) = zon.get(&["paths"]) else {
+                panic!("Expected a list");
+            };
+            list.iter()
+                .map(|v| v.as_str().unwrap().to_string())
+                .collect()
+        };
+
+        assert_eq!(zon.add_path("LICENSE"), Ok(true));
+        assert_eq!(paths(&zon), ["LICENSE", "build.zig", "src"]);
+        assert_eq!(zon.add_path("./LICENSE"), Ok(false));
+
+        assert_eq!(zon.remove_path("src/"), Ok(true));
+        assert_eq!(zon.remove_path("src"), Ok(false));
+        assert_eq!(paths(&zon), ["LICENSE", "build.zig"]);
+
+        let mut empty = ZonFile::parse_str(r#".{ .name = "pkg" }"#).unwrap();
+        assert_eq!(empty.remove_path("src"), Ok(false));
+        assert_eq!(empty.add_path("build.zig.zon"), Ok(true));
+        assert_eq!(paths(&empty), ["build.zig.zon"]);
+
+        let mut braces = ZonFile::parse_str(r#".{ .name = "pkg", .paths = .{} }"#).unwrap();
+        assert_eq!(braces.remove_path("src"), Ok(false));
+        assert_eq!(braces.add_path("src"), Ok(true));
+        assert_eq!(paths(&braces), ["src"]);
+
+        let mut bad = ZonFile::parse_str(r#".{ .paths = "src" }"#).unwrap();
+        assert!(bad.add_path("src").is_err());
+    }
+}
@@ -0,0 +1,186 @@
+use std::fmt::{Display, Formatter};
+
+use crate::model::{ParseError, ZonFile, ZonValue};
+
+/// The `build.zig.zon` dialect accepted or emitted by a given Zig release.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ZonSyntaxVersion {
+    /// Zig 0.11 – 0.13: `.name` is a string and there is no `.fingerprint`.
+    Zig0_11,
+    /// Zig 0.14 and later: `.name` is an enum literal and `.fingerprint` is
+    /// required.
+    Zig0_14,
+}
+
+impl ZonSyntaxVersion {
+    /// Picks the dialect matching a Zig version string such as `0.13.0` or
+    /// `0.14.0-dev.2+abc`.
+    pub fn from_zig_version(version: &str) -> Option<Self> {
+        let mut parts = version.split(['.', '-', '+']);
+        let major: u32 = parts.next()?.parse().ok()?;
+        let minor: u32 = parts.next()?.parse().ok()?;
+
+        Some(if (major, minor) >= (0, 14) {
+            ZonSyntaxVersion::Zig0_14
+        } else {
+            ZonSyntaxVersion::Zig0_11
+        })
+    }
+
+    /// Guesses the dialect a manifest was written for.
+    ///
+    /// A `.fingerprint` field or an enum literal `.name` means 0.14; otherwise
+    /// `.minimum_zig_version` is consulted, defaulting to the older dialect.
+    pub fn detect(data: &ZonValue) -> Self {
+        let uses_new_syntax = data.get_path(&["fingerprint"]).is_some()
+            || matches!(data.get_path(&["name"]), Some(ZonValue::EnumLiteral(_)));
+        if uses_new_syntax {
+            return ZonSyntaxVersion::Zig0_14;
+        }
+
+        data.get_path(&["minimum_zig_version"])
+            .and_then(ZonValue::as_str)
+            .and_then(Self::from_zig_version)
+            .unwrap_or(ZonSyntaxVersion::Zig0_11)
+    }
+}
+
+impl Display for ZonSyntaxVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ZonSyntaxVersion::Zig0_11 => write!(f, "0.11"),
+            ZonSyntaxVersion::Zig0_14 => write!(f, "0.14"),
+        }
+    }
+}
+
+impl ZonFile {
+    /// Parses `src` and checks that it only uses constructs accepted by
+    /// `version`.
+    pub fn parse_with_version(src: &str, version: ZonSyntaxVersion) -> Result<Self, ParseError> {
+        let zon = Self::parse_str(src)?;
+        zon.check_syntax(version)?;
+        Ok(zon)
+    }
+
+    /// The dialect this document appears to be written for.
+    pub fn syntax_version(&self) -> ZonSyntaxVersion {
+        ZonSyntaxVersion::detect(&self.data)
+    }
+
+    /// Verifies the manifest fields follow the rules of `version`.
+    pub fn check_syntax(&self, version: ZonSyntaxVersion) -> Result<(), ParseError> {
+        let unsupported =
+            |construct: &'static str| ParseError::UnsupportedSyntax { construct, version };
+
+        match (version, self.get(&["name"])) {
+            (ZonSyntaxVersion::Zig0_11, Some(ZonValue::EnumLiteral(_))) => {
+                return Err(unsupported("enum literal `.name`"));
+            }
+            (ZonSyntaxVersion::Zig0_14, Some(ZonValue::String(_))) => {
+                return Err(unsupported("string `.name`"));
+            }
+            _ => {}
+        }
+
+        let has_fingerprint = self.get(&["fingerprint"]).is_some();
+        match version {
+            ZonSyntaxVersion::Zig0_11 if has_fingerprint => Err(unsupported("`.fingerprint`")),
+            ZonSyntaxVersion::Zig0_14 if !has_fingerprint => {
+                Err(ParseError::MissingField("fingerprint"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Rewrites version-specific fields so the document is emitted in the
+    /// `version` dialect.
+    ///
+    /// Converting down drops `.fingerprint`. Converting up turns a string
+    /// `.name` into an enum literal (replacing `-` with `_`, as Zig requires a
+    /// bare identifier), but cannot invent a fingerprint: set one with
+    /// [`ZonFile::set`] before writing the manifest.
+    pub fn convert_to(&mut self, version: ZonSyntaxVersion) {
+        let ZonValue::Object(map) = &mut self.data else {
+            return;
+        };
+
+        match version {
+            ZonSyntaxVersion::Zig0_11 => {
+                map.remove("fingerprint");
+                if let Some(ZonValue::EnumLiteral(name)) = map.get("name") {
+                    let name = ZonValue::String(name.clone());
+                    map.insert("name".into(), name);
+                }
+            }
+            ZonSyntaxVersion::Zig0_14 => {
+                if let Some(ZonValue::String(name)) = map.get("name") {
+                    let name = ZonValue::EnumLiteral(name.replace('-', "_"));
+                    map.insert("name".into(), name);
+                }
+            }
+        }
+    }
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OLD: &str = r#".{ .name = "demo", .version = "0.1.0", .paths = .{ "" } }"#;
+    const NEW: &str =
+        r#".{ .name = .demo, .version = "0.1.0", .fingerprint = 0xabc, .paths = .{ "" } }"#;
+
+    #[test]
+    fn test_detect_version() {
+        assert_eq!(
+            ZonFile::parse_str(OLD).unwrap().syntax_version(),
+            ZonSyntaxVersion::Zig0_11
+        );
+        assert_eq!(
+            ZonFile::parse_str(NEW).unwrap().syntax_version(),
+            ZonSyntaxVersion::Zig0_14
+        );
+
+        let pinned = r#".{ .name = "demo", .minimum_zig_version = "0.14.0-dev.2+abc" }"#;
+        assert_eq!(
+            ZonFile::parse_str(pinned).unwrap().syntax_version(),
+            ZonSyntaxVersion::Zig0_14
+        );
+    }
+
+    #[test]
+    fn test_parse_with_version_rejects_other_dialect() {
+        assert!(ZonFile::parse_with_version(OLD, ZonSyntaxVersion::Zig0_11).is_ok());
+        assert!(ZonFile::parse_with_version(NEW, ZonSyntaxVersion::Zig0_14).is_ok());
+
+        let err = ZonFile::parse_with_version(NEW, ZonSyntaxVersion::Zig0_11).unwrap_err();
+        assert!(matches!(err, ParseError::UnsupportedSyntax { .. }));
+
+        let err = ZonFile::parse_with_version(OLD, ZonSyntaxVersion::Zig0_14).unwrap_err();
+        assert!(matches!(err, ParseError::UnsupportedSyntax { .. }));
+    }
+
+    #[test]
+    fn test_convert_between_dialects() {
+        let mut zon = ZonFile::parse_str(NEW).unwrap();
+        zon.convert_to(ZonSyntaxVersion::Zig0_11);
+        assert_eq!(zon.get(&["name"]), Some(&ZonValue::String("demo".into())));
+        assert!(zon.get(&["fingerprint"]).is_none());
+        assert!(zon.check_syntax(ZonSyntaxVersion::Zig0_11).is_ok());
+
+        zon.convert_to(ZonSyntaxVersion::Zig0_14);
+        assert_eq!(
+            zon.get(&["name"]),
+            Some(&ZonValue::EnumLiteral("demo".into()))
+        );
+        assert!(matches!(
+            zon.check_syntax(ZonSyntaxVersion::Zig0_14),
+            Err(ParseError::MissingField("fingerprint"))
+        ));
+    }
+}
@@ -0,0 +1,129 @@
+use std::cmp::Ordering;
+
+use uniparse_core::compare_prerelease;
+
+use crate::model::RootZon;
+
+impl RootZon {
+    /// Whether `.minimum_zig_version` asks for Zig `version` or newer.
+    ///
+    /// Versions are compared with semver precedence, so dev builds such as
+    /// `0.14.0-dev.2+abc` come before the `0.14.0` release and after
+    /// `0.13.0`; build metadata after `+` is ignored. Returns `false` when
+    /// the manifest has no minimum or either version can't be read.
+    ///
+    /// ```rust
+    /// use uniparse_zon::{RootZon, ZonFile};
+    ///
+    /// let zon = ZonFile::parse_str(
+    ///     r#".{ .name = "demo", .version = "0.1.0", .minimum_zig_version = "0.14.0-dev.2+abc",
+    ///          .paths = .{ "" }, .dependencies = .{} }"#,
+    /// )
+    /// .unwrap();
+    /// let root: RootZon = zon.as_struct().unwrap();
+    /// assert!(root.requires_zig_at_least("0.13.0"));
+    /// assert!(!root.requires_zig_at_least("0.14.0"));
+    /// ```
+    pub fn requires_zig_at_least(&self, version: &str) -> bool {
+        self.minimum_zig_version
+            .as_deref()
+            .and_then(|minimum| compare_zig_versions(minimum, version))
+            .is_some_and(Ordering::is_ge)
+    }
+}
+
+/// Orders two Zig version strings, or `None` if either isn't a version.
+pub(crate) fn compare_zig_versions(a: &str, b: &str) -> Option<Ordering> {
+    let (core_a, pre_a) = split(a)?;
+    let (core_b, pre_b) = split(b)?;
+
+    Some(
+        core_a
+            .cmp(&core_b)
+            .then_with(|| compare_prerelease(pre_a, pre_b)),
+    )
+}
+
+/// `(major, minor, patch)` and the pre-release part of a version.
+type Parts<'a> = ((u64, u64, u64), Option<&'a str>);
+
+/// Splits `0.14.0-dev.2+abc` into `(0, 14, 0)` and `Some("dev.2")`.
+fn split(version: &str) -> Option<Parts<'_>> {
+    let version = version.trim();
+    let version = version.split('+').next().unwrap_or_default();
+    let (core, pre) = match version.split_once('-') {
+        Some((core, pre)) => (core, Some(pre)),
+        None => (version, None),
+    };
+
+    let mut parts = core.split('.').map(|p| p.parse::<u64>().ok());
+    let major = parts.next()??;
+    let minor = parts.next()??;
+    let patch = parts.next().unwrap_or(Some(0))?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some(((major, minor, patch), pre))
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn root(minimum: Option<&str>) -> RootZon {
+        RootZon {
+            name: "demo".into(),
+            version: "0.1.0".into(),
+            minimum_zig_version: minimum.map(String::from),
+            paths: Vec::new(),
+            dependencies: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_compare_zig_versions() {
+        let mut versions = [
+            "0.14.0",
+            "0.14.0-dev.10+def",
+            "0.13.0",
+            "0.14.0-dev.2+abc",
+            "0.14.1",
+            "0.14.0-dev.2+fff",
+        ];
+        versions.sort_by(|a, b| compare_zig_versions(a, b).unwrap());
+        assert_eq!(
+            versions,
+            [
+                "0.13.0",
+                "0.14.0-dev.2+abc",
+                "0.14.0-dev.2+fff",
+                "0.14.0-dev.10+def",
+                "0.14.0",
+                "0.14.1",
+            ]
+        );
+        assert_eq!(
+            compare_zig_versions("0.14", "0.14.0"),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(compare_zig_versions("master", "0.14.0"), None);
+    }
+
+    #[test]
+    fn test_requires_zig_at_least() {
+        let dev = root(Some("0.14.0-dev.2+abc"));
+        assert!(dev.requires_zig_at_least("0.13.0"));
+        assert!(dev.requires_zig_at_least("0.14.0-dev.1+aaa"));
+        assert!(dev.requires_zig_at_least("0.14.0-dev.2"));
+        assert!(!dev.requires_zig_at_least("0.14.0"));
+
+        assert!(root(Some("0.14.0")).requires_zig_at_least("0.14.0"));
+        assert!(!root(None).requires_zig_at_least("0.11.0"));
+        assert!(!root(Some("0.14.0")).requires_zig_at_least("latest"));
+    }
+}
@@ -0,0 +1,361 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use uniparse_core::{CancellationToken, walk_files};
+
+use crate::model::{ParseError, ZonFile, ZonValue};
+
+const MANIFEST_NAME: &str = "build.zig.zon";
+
+/// A set of `build.zig.zon` manifests discovered under a directory, e.g. a
+/// vendored dependency tree or the zig package cache.
+#[derive(Debug, Default)]
+pub struct ZonWorkspace {
+    pub packages: Vec<ZonPackage>,
+    /// Manifests that were found but could not be parsed.
+    pub failures: Vec<(PathBuf, ParseError)>,
+}
+
+/// A single parsed manifest within a [`ZonWorkspace`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZonPackage {
+    pub name: String,
+    pub version: Option<String>,
+    /// Path of the `build.zig.zon` file.
+    pub manifest: PathBuf,
+    pub dependencies: Vec<DependencyEdge>,
+}
+
+/// A dependency declared by a package, with the metadata found in its manifest.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DependencyEdge {
+    pub name: String,
+    pub url: Option<String>,
+    pub hash: Option<String>,
+    pub path: Option<String>,
+    pub lazy: bool,
+}
+
+/// A dependency that does not match any package found during the scan.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnresolvedDependency {
+    pub package: String,
+    pub dependency: String,
+}
+
+impl ZonWorkspace {
+    /// Recursively finds and parses every `build.zig.zon` under `dir`,
+    /// without following symbolic links to directories.
+    ///
+    /// Manifests that fail to parse are recorded in `failures` instead of
+    /// aborting the scan.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(dir = %dir.as_ref().display()))
+    )]
+    pub fn scan(dir: impl AsRef<Path>) -> Result<Self, ParseError> {
+        Self::scan_cancellable(dir, &CancellationToken::default())
+    }
+
+    /// Like [`ZonWorkspace::scan`], checking `cancel` before each directory
+    /// and manifest and returning [`ParseError::Io`] with
+    /// [`ErrorKind::Interrupted`](std::io::ErrorKind::Interrupted) once it
+    /// is cancelled.
+    pub fn scan_cancellable(
+        dir: impl AsRef<Path>,
+        cancel: &CancellationToken,
+    ) -> Result<Self, ParseError> {
+        let mut manifests = Vec::new();
+        find_manifests(dir.as_ref(), cancel, &mut manifests)?;
+        manifests.sort();
+
+        let mut workspace = ZonWorkspace::default();
+        for manifest in manifests {
+            cancel.check()?;
+            let parsed = fs::read_to_string(&manifest)
+                .map_err(ParseError::from)
+                .and_then(|src| ZonFile::parse_str(&src))
+                .and_then(|zon| ZonPackage::from_zon(&zon, manifest.clone()));
+
+            match parsed {
+                Ok(package) => workspace.packages.push(package),
+                Err(e) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!(manifest = %manifest.display(), error = %e, "manifest failed to parse");
+                    workspace.failures.push((manifest, e));
+                }
+            }
+        }
+
+        Ok(workspace)
+    }
+
+    /// Looks up a package by its declared name.
+    pub fn package(&self, name: &str) -> Option<&ZonPackage> {
+        self.packages.iter().find(|p| p.name == name)
+    }
+
+    /// Resolves a dependency edge to a scanned package.
+    ///
+    /// Packages in the zig cache live in a directory named after their hash, so
+    /// a matching directory name is preferred over a matching package name.
+    pub fn resolve(&self, edge: &DependencyEdge) -> Option<&ZonPackage> {
+        self.resolve_index(edge).map(|i| &self.packages[i])
+    }
+
+    fn resolve_index(&self, edge: &DependencyEdge) -> Option<usize> {
+        edge.hash
+            .as_deref()
+            .and_then(|hash| {
+                self.packages
+                    .iter()
+                    .position(|p| p.dir_name() == Some(hash))
+            })
+            .or_else(|| self.packages.iter().position(|p| p.name == edge.name))
+    }
+
+    /// Name → dependency names graph of all scanned packages.
+    pub fn graph(&self) -> BTreeMap<&str, Vec<&str>> {
+        self.packages
+            .iter()
+            .map(|p| {
+                let deps = p
+                    .dependencies
+                    .iter()
+                    .map(|d| self.resolve(d).map_or(d.name.as_str(), |r| r.name.as_str()))
+                    .collect();
+                (p.name.as_str(), deps)
+            })
+            .collect()
+    }
+
+    /// Dependencies that could not be matched to any scanned package.
+    pub fn unresolved(&self) -> Vec<UnresolvedDependency> {
+        self.packages
+            .iter()
+            .flat_map(|p| {
+                p.dependencies
+                    .iter()
+                    .filter(|d| self.resolve(d).is_none())
+                    .map(|d| UnresolvedDependency {
+                        package: p.name.clone(),
+                        dependency: d.name.clone(),
+                    })
+            })
+            .collect()
+    }
+
+    /// Dependency cycles in the graph, each listed as the package names along
+    /// the cycle starting from the first one reached.
+    pub fn cycles(&self) -> Vec<Vec<String>> {
+        #[derive(Clone, Copy, PartialEq)]
+        enum State {
+            Unvisited,
+            InProgress,
+            Done,
+        }
+
+        fn visit(
+            ws: &ZonWorkspace,
+            idx: usize,
+            state: &mut [State],
+            stack: &mut Vec<usize>,
+            cycles: &mut Vec<Vec<String>>,
+        ) {
+            state[idx] = State::InProgress;
+            stack.push(idx);
+
+            for edge in &ws.packages[idx].dependencies {
+                let Some(target) = ws.resolve_index(edge) else {
+                    continue;
+                };
+                match state[target] {
+                    State::Unvisited => visit(ws, target, state, stack, cycles),
+                    State::InProgress => {
+                        let start = stack.iter().position(|&i| i == target).unwrap();
+                        cycles.push(
+                            stack[start..]
+                                .iter()
+                                .map(|&i| ws.packages[i].name.clone())
+                                .collect(),
+                        );
+                    }
+                    State::Done => {}
+                }
+            }
+
+            stack.pop();
+            state[idx] = State::Done;
+        }
+
+        let mut state = vec![State::Unvisited; self.packages.len()];
+        let mut cycles = Vec::new();
+
+        for idx in 0..self.packages.len() {
+            if state[idx] == State::Unvisited {
+                visit(self, idx, &mut state, &mut Vec::new(), &mut cycles);
+            }
+        }
+
+        cycles
+    }
+}
+
+impl ZonPackage {
+    fn from_zon(zon: &ZonFile, manifest: PathBuf) -> Result<Self, ParseError> {
+        let name = zon
+            .get(&["name"])
+            .and_then(|v| v.as_str().or_else(|| v.as_enum_literal()))
+            .ok_or(ParseError::MissingField("name"))?
+            .to_string();
+        let version = zon
+            .get(&["version"])
+            .and_then(ZonValue::as_str)
+            .map(str::to_string);
+
+        let mut dependencies = Vec::new();
+        if let Some(ZonValue::Object(deps)) = zon.get(&["dependencies"]) {
+            for (dep_name, dep) in deps {
+                let field = |key: &str| dep.get_path(&[key]).and_then(ZonValue::as_str);
+                dependencies.push(DependencyEdge {
+                    name: dep_name.clone(),
+                    url: field("url").map(str::to_string),
+                    hash: field("hash").map(str::to_string),
+                    path: field("path").map(str::to_string),
+                    lazy: dep.get_path(&["lazy"]).and_then(ZonValue::as_bool) == Some(true),
+                });
+            }
+        }
+        dependencies.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(ZonPackage {
+            name,
+            version,
+            manifest,
+            dependencies,
+        })
+    }
+
+    fn dir_name(&self) -> Option<&str> {
+        self.manifest.parent()?.file_name()?.to_str()
+    }
+}
+
+fn find_manifests(
+    dir: &Path,
+    cancel: &CancellationToken,
+    out: &mut Vec<PathBuf>,
+) -> Result<(), ParseError> {
+    walk_files(
+        dir,
+        cancel,
+        |_| true,
+        |path| {
+            if path.file_name().and_then(|n| n.to_str()) == Some(MANIFEST_NAME) {
+                out.push(path);
+            }
+        },
+    )?;
+    Ok(())
+}
+
+//===================================//
+// T E S T S                         //
+//===================================//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_manifest(root: &Path, dir: &str, src: &str) {
+        let dir = root.join(dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(MANIFEST_NAME), src).unwrap();
+    }
+
+    fn manifest(name: &str, deps: &[(&str, &str)]) -> String {
+        let deps: String = deps
+            .iter()
+            .map(|(dep, hash)| {
+                format!(".{dep} = .{{ .url = \"https://example.com/{dep}.tar.gz\", .hash = \"{hash}\" }},")
+            })
+            .collect();
+        format!(".{{ .name = \"{name}\", .version = \"0.1.0\", .dependencies = .{{ {deps} }} }}")
+    }
+
+    #[test]
+    fn test_scan_builds_graph_and_reports_unresolved() {
+        let root = std::env::temp_dir().join("uniparse_zon_scan_graph");
+        let _ = fs::remove_dir_all(&root);
+        write_manifest(
+            &root,
+            "app",
+            &manifest("app", &[("zigimg", "h1"), ("missing", "h2")]),
+        );
+        write_manifest(&root, "p/h1", &manifest("zigimg", &[]));
+        write_manifest(&root, "broken", ".{ .name = ");
+
+        let ws = ZonWorkspace::scan(&root).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(ws.packages.len(), 2);
+        assert_eq!(ws.failures.len(), 1);
+        assert_eq!(ws.graph()["app"], vec!["missing", "zigimg"]);
+
+        let app = ws.package("app").unwrap();
+        let zigimg = app
+            .dependencies
+            .iter()
+            .find(|d| d.name == "zigimg")
+            .unwrap();
+        assert_eq!(
+            zigimg.url.as_deref(),
+            Some("https://example.com/zigimg.tar.gz")
+        );
+        assert_eq!(ws.resolve(zigimg).map(|p| p.name.as_str()), Some("zigimg"));
+
+        assert_eq!(
+            ws.unresolved(),
+            vec![UnresolvedDependency {
+                package: "app".into(),
+                dependency: "missing".into(),
+            }]
+        );
+        assert!(ws.cycles().is_empty());
+    }
+
+    #[test]
+    fn test_scan_detects_cycles() {
+        let root = std::env::temp_dir().join("uniparse_zon_scan_cycles");
+        let _ = fs::remove_dir_all(&root);
+        write_manifest(&root, "a", &manifest("a", &[("b", "hb")]));
+        write_manifest(&root, "b", &manifest("b", &[("a", "ha")]));
+
+        let ws = ZonWorkspace::scan(&root).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(ws.cycles(), vec![vec!["a".to_string(), "b".to_string()]]);
+    }
+
+    #[test]
+    fn test_scan_stops_once_cancelled() {
+        let root = std::env::temp_dir().join("uniparse_zon_scan_cancelled");
+        let _ = fs::remove_dir_all(&root);
+        write_manifest(&root, "a", &manifest("a", &[]));
+
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let result = ZonWorkspace::scan_cancellable(&root, &cancel);
+        fs::remove_dir_all(&root).unwrap();
+
+        match result {
+            Err(ParseError::Io(e)) => assert_eq!(e.kind(), std::io::ErrorKind::Interrupted),
+            other => panic!("Expected an interrupted scan, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_scan_missing_dir_errors() {
+        assert!(ZonWorkspace::scan("does/not/exist").is_err());
+    }
+}